@@ -415,6 +415,10 @@ impl sc_cli::CliConfiguration for NormalizedRunCmd {
 		self.base.disable_grandpa()
 	}
 
+	fn slashing_protection(&self) -> sc_cli::Result<Option<PathBuf>> {
+		self.base.slashing_protection()
+	}
+
 	fn rpc_max_connections(&self) -> sc_cli::Result<u32> {
 		self.base.rpc_max_connections()
 	}
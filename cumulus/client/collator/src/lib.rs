@@ -26,9 +26,15 @@ use sp_core::traits::SpawnNamed;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
 use cumulus_client_consensus_common::ParachainConsensus;
-use polkadot_node_primitives::{CollationGenerationConfig, CollationResult, MaybeCompressedPoV};
+use polkadot_node_primitives::{
+	CollationDropReason, CollationEvent, CollationGenerationConfig, CollationResult,
+	MaybeCompressedPoV,
+};
 use polkadot_node_subsystem::messages::{CollationGenerationMessage, CollatorProtocolMessage};
-use polkadot_overseer::Handle as OverseerHandle;
+use polkadot_overseer::{
+	prometheus::{self, CounterVec, Opts, PrometheusError, U64},
+	Handle as OverseerHandle,
+};
 use polkadot_primitives::{CollatorPair, Id as ParaId};
 
 use codec::{Decode, Encode};
@@ -39,9 +45,14 @@ use crate::service::CollatorService;
 
 pub mod service;
 
+pub use polkadot_overseer::prometheus::Registry;
+
 /// The logging target.
 const LOG_TARGET: &str = "cumulus-collator";
 
+/// The number of collation events buffered for [`log_collation_events`].
+const COLLATION_EVENTS_CHANNEL_SIZE: usize = 64;
+
 /// The implementation of the Cumulus `Collator`.
 ///
 /// Note that this implementation is soon to be deprecated and removed, and it is suggested to
@@ -261,6 +272,151 @@ pub async fn initialize_collator_subsystems(
 		.await;
 }
 
+/// Counters of the collation events received by [`log_collation_events`].
+#[derive(Clone)]
+struct CollationEventMetrics {
+	events: CounterVec<U64>,
+	dropped: CounterVec<U64>,
+}
+
+impl CollationEventMetrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			events: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_collation_events_total",
+						"Number of events in the lifecycle of the collations built by this node",
+					),
+					&["event"],
+				)?,
+				registry,
+			)?,
+			dropped: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_collations_dropped_total",
+						"Number of collations built by this node that were dropped",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	fn on_event(&self, event: &CollationEvent) {
+		let name = match event {
+			CollationEvent::Advertised { .. } => "advertised",
+			CollationEvent::Fetched { .. } => "fetched",
+			CollationEvent::Seconded { .. } => "seconded",
+			CollationEvent::Backed { .. } => "backed",
+			CollationEvent::Included { .. } => "included",
+			CollationEvent::Dropped { .. } => "dropped",
+		};
+		self.events.with_label_values(&[name]).inc();
+
+		if let CollationEvent::Dropped { reason, .. } = event {
+			let reason = match reason {
+				CollationDropReason::NoValidationData => "no_validation_data",
+				CollationDropReason::PoVTooLarge { .. } => "pov_too_large",
+				CollationDropReason::CandidateConstructionFailed(_) =>
+					"candidate_construction_failed",
+				CollationDropReason::RelayParentOutOfView => "relay_parent_out_of_view",
+				CollationDropReason::TooManyCollations => "too_many_collations",
+				CollationDropReason::NotAssignedToCore => "not_assigned_to_core",
+				CollationDropReason::NoBackingGroup => "no_backing_group",
+				CollationDropReason::AvailabilityTimedOut => "availability_timed_out",
+				CollationDropReason::BackedOnAbandonedFork => "backed_on_abandoned_fork",
+			};
+			self.dropped.with_label_values(&[reason]).inc();
+		}
+	}
+}
+
+/// Log the lifecycle of the collations built by this node.
+///
+/// The events are reported by the collation subsystems of the relay chain node, see
+/// [`CollationGenerationMessage::SubscribeCollationEvents`]. Dropped collations are logged as
+/// warnings, so that missed slots can be traced back to their cause.
+///
+/// If a Prometheus registry is passed, the events are also counted by kind in
+/// `cumulus_collation_events_total`, and the dropped collations by reason in
+/// `cumulus_collations_dropped_total`.
+pub fn log_collation_events(
+	mut overseer_handle: OverseerHandle,
+	prometheus_registry: Option<&Registry>,
+) -> Result<impl Future<Output = ()> + Send + 'static, PrometheusError> {
+	let metrics = prometheus_registry.map(CollationEventMetrics::register).transpose()?;
+
+	Ok(async move {
+		let (tx, events) = futures::channel::mpsc::channel(COLLATION_EVENTS_CHANNEL_SIZE);
+		overseer_handle
+			.send_msg(CollationGenerationMessage::SubscribeCollationEvents(tx), "CollationEvents")
+			.await;
+
+		handle_collation_events(events, metrics).await
+	})
+}
+
+async fn handle_collation_events(
+	mut events: impl Stream<Item = CollationEvent> + Unpin,
+	metrics: Option<CollationEventMetrics>,
+) {
+	while let Some(event) = events.next().await {
+		if let Some(metrics) = &metrics {
+			metrics.on_event(&event);
+		}
+
+		match event {
+			CollationEvent::Advertised { relay_parent, candidate_hash } => tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?candidate_hash,
+				"Collation advertised to validators.",
+			),
+			CollationEvent::Fetched { relay_parent, candidate_hash, group_index } =>
+				tracing::debug!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?candidate_hash,
+					?group_index,
+					"Collation fetched by a validator.",
+				),
+			CollationEvent::Seconded { relay_parent, candidate_hash, validator_index } =>
+				tracing::debug!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?candidate_hash,
+					?validator_index,
+					"Collation seconded.",
+				),
+			CollationEvent::Backed { relay_parent, candidate_hash, relay_block } => tracing::info!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?candidate_hash,
+				?relay_block,
+				"Collation backed on the relay chain.",
+			),
+			CollationEvent::Included { relay_parent, candidate_hash, relay_block } =>
+				tracing::info!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?candidate_hash,
+					?relay_block,
+					"Collation included on the relay chain.",
+				),
+			CollationEvent::Dropped { relay_parent, candidate_hash, reason } => tracing::warn!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?candidate_hash,
+				?reason,
+				"Collation dropped.",
+			),
+		}
+	}
+}
+
 /// Parameters for [`start_collator`].
 pub struct StartCollatorParams<Block: BlockT, RA, BS, Spawner> {
 	pub para_id: ParaId,
@@ -344,12 +500,12 @@ mod tests {
 	};
 	use cumulus_test_relay_sproof_builder::RelayStateSproofBuilder;
 	use cumulus_test_runtime::{Block, Header};
-	use futures::{channel::mpsc, executor::block_on, StreamExt};
+	use futures::{channel::mpsc, executor::block_on, stream, StreamExt};
 	use polkadot_node_primitives::CollationGenerationConfig;
 	use polkadot_node_subsystem::messages::CollationGenerationMessage;
 	use polkadot_node_subsystem_test_helpers::ForwardSubsystem;
 	use polkadot_overseer::{dummy::dummy_overseer_builder, HeadSupportsParachains};
-	use polkadot_primitives::HeadData;
+	use polkadot_primitives::{CandidateHash, HeadData};
 	use sp_consensus::BlockOrigin;
 	use sp_core::{testing::TaskExecutor, Pair};
 	use sp_runtime::traits::BlakeTwo256;
@@ -475,4 +631,47 @@ mod tests {
 			.unwrap_err()
 			.contains("Trie lookup error: Database missing expected key"));
 	}
+
+	#[test]
+	fn collation_events_are_counted() {
+		let registry = Registry::new();
+		let metrics = CollationEventMetrics::register(&registry).unwrap();
+
+		let relay_parent = PHash::repeat_byte(1);
+		let candidate_hash = CandidateHash(PHash::repeat_byte(2));
+		let relay_block = PHash::repeat_byte(3);
+		let events = vec![
+			CollationEvent::Advertised { relay_parent, candidate_hash },
+			CollationEvent::Backed { relay_parent, candidate_hash, relay_block },
+			CollationEvent::Included { relay_parent, candidate_hash, relay_block },
+			CollationEvent::Dropped {
+				relay_parent,
+				candidate_hash: Some(candidate_hash),
+				reason: CollationDropReason::AvailabilityTimedOut,
+			},
+			CollationEvent::Dropped {
+				relay_parent,
+				candidate_hash: None,
+				reason: CollationDropReason::NoBackingGroup,
+			},
+			CollationEvent::Dropped {
+				relay_parent,
+				candidate_hash: None,
+				reason: CollationDropReason::NoBackingGroup,
+			},
+		];
+		block_on(handle_collation_events(stream::iter(events), Some(metrics.clone())));
+
+		let events = |event: &str| metrics.events.with_label_values(&[event]).get();
+		assert_eq!(events("advertised"), 1);
+		assert_eq!(events("fetched"), 0);
+		assert_eq!(events("backed"), 1);
+		assert_eq!(events("included"), 1);
+		assert_eq!(events("dropped"), 3);
+
+		let dropped = |reason: &str| metrics.dropped.with_label_values(&[reason]).get();
+		assert_eq!(dropped("availability_timed_out"), 1);
+		assert_eq!(dropped("no_backing_group"), 2);
+		assert_eq!(dropped("too_many_collations"), 0);
+	}
 }
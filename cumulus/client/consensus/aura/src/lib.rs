@@ -30,7 +30,7 @@ use cumulus_primitives_core::{relay_chain::Hash as PHash, PersistedValidationDat
 
 use futures::lock::Mutex;
 use sc_client_api::{backend::AuxStore, BlockOf};
-use sc_consensus::BlockImport;
+use sc_consensus::{BlockImport, SlashingProtection};
 use sc_consensus_slots::{BackoffAuthoringBlocksStrategy, SimpleSlotWorker, SlotInfo};
 use sc_telemetry::TelemetryHandle;
 use sp_api::ProvideRuntimeApi;
@@ -102,6 +102,7 @@ pub struct BuildAuraConsensusParams<PF, BI, CIDP, Client, BS, SO> {
 	pub telemetry: Option<TelemetryHandle>,
 	pub block_proposal_slot_portion: SlotProportion,
 	pub max_block_proposal_slot_portion: Option<SlotProportion>,
+	pub slashing_protection: Option<SlashingProtection>,
 }
 
 impl<B, CIDP> AuraConsensus<B, CIDP, ()>
@@ -127,6 +128,7 @@ where
 			telemetry,
 			block_proposal_slot_portion,
 			max_block_proposal_slot_portion,
+			slashing_protection,
 		}: BuildAuraConsensusParams<PF, BI, CIDP, Client, BS, SO>,
 	) -> Box<dyn ParachainConsensus<B>>
 	where
@@ -162,6 +164,7 @@ where
 				block_proposal_slot_portion,
 				max_block_proposal_slot_portion,
 				compatibility_mode: sc_consensus_aura::CompatibilityMode::None,
+				slashing_protection,
 			},
 		);
 
@@ -254,6 +254,7 @@ async fn new_minimal_relay_chain<Block: BlockT, Network: NetworkBackend<RelayBlo
 		spawner: task_manager.spawn_handle(),
 		is_parachain_node: IsParachainNode::Collator(collator_pair),
 		overseer_message_channel_capacity_override: None,
+		overseer_message_tracing: None,
 		req_protocol_names: request_protocol_names,
 		peerset_protocol_names,
 		notification_services,
@@ -19,6 +19,7 @@
 //! Provides functions for starting a collator node or a normal full node.
 
 use cumulus_client_cli::CollatorOptions;
+use cumulus_client_collator::Registry;
 use cumulus_client_consensus_common::ParachainConsensus;
 use cumulus_client_network::{AssumeSybilResistance, RequireSecondedInBlockAnnounce};
 use cumulus_client_pov_recovery::{PoVRecovery, RecoveryDelayRange, RecoveryHandle};
@@ -106,6 +107,7 @@ pub struct StartRelayChainTasksParams<'a, Block: BlockT, Client, RCInterface> {
 	pub relay_chain_slot_duration: Duration,
 	pub recovery_handle: Box<dyn RecoveryHandle>,
 	pub sync_service: Arc<SyncingService<Block>>,
+	pub prometheus_registry: Option<&'a Registry>,
 }
 
 /// Parameters given to [`start_full_node`].
@@ -177,6 +179,7 @@ where
 		relay_chain_slot_duration,
 		recovery_handle,
 		sync_service,
+		prometheus_registry: None,
 	})?;
 
 	#[allow(deprecated)]
@@ -214,6 +217,7 @@ pub fn start_relay_chain_tasks<Block, Client, Backend, RCInterface>(
 		relay_chain_slot_duration,
 		recovery_handle,
 		sync_service,
+		prometheus_registry,
 	}: StartRelayChainTasksParams<Block, Client, RCInterface>,
 ) -> sc_service::error::Result<()>
 where
@@ -243,6 +247,17 @@ where
 		.spawn_essential_handle()
 		.spawn_blocking("cumulus-consensus", None, consensus);
 
+	if matches!(da_recovery_profile, DARecoveryProfile::Collator) {
+		let overseer_handle = relay_chain_interface
+			.overseer_handle()
+			.map_err(|e| sc_service::Error::Application(Box::new(e)))?;
+		task_manager.spawn_handle().spawn(
+			"cumulus-collation-events",
+			None,
+			cumulus_client_collator::log_collation_events(overseer_handle, prometheus_registry)?,
+		);
+	}
+
 	let da_recovery_profile = match da_recovery_profile {
 		DARecoveryProfile::Collator => {
 			// We want that collators wait at maximum the relay chain slot duration before starting
@@ -327,6 +342,7 @@ where
 		recovery_handle,
 		sync_service,
 		da_recovery_profile: DARecoveryProfile::FullNode,
+		prometheus_registry: None,
 	})
 }
 
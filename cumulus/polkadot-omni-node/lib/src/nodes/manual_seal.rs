@@ -133,6 +133,10 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 				}
 			});
 
+		// The time starts at zero and only moves on `engine_setTimestamp` and
+		// `engine_increaseTime`.
+		let time_control = sc_consensus_manual_seal::TimeControl::starting_at(0);
+		let time_for_cidp = time_control.clone();
 		let client_for_cidp = client.clone();
 		let params = sc_consensus_manual_seal::ManualSealParams {
 			block_import: client.clone(),
@@ -150,6 +154,7 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 				let current_para_block_head =
 					Some(polkadot_primitives::HeadData(current_para_head.encode()));
 				let client_for_xcm = client_for_cidp.clone();
+				let timestamp = time_for_cidp.inherent_data_provider();
 				async move {
 					use sp_runtime::traits::UniqueSaturatedInto;
 
@@ -171,16 +176,22 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 						additional_key_values: None,
 					};
 					Ok((
-						// This is intentional, as the runtime that we expect to run against this
-						// will never receive the aura-related inherents/digests, and providing
-						// real timestamps would cause aura <> timestamp checking to fail.
-						sp_timestamp::InherentDataProvider::new(sp_timestamp::Timestamp::new(0)),
+						// The time doesn't follow the system time on purpose, as the runtime that
+						// we expect to run against this will never receive the aura-related
+						// inherents/digests, and providing real timestamps would cause
+						// aura <> timestamp checking to fail. Runtimes doing this check fail to
+						// build blocks once the time is moved away from zero.
+						timestamp,
 						mocked_parachain,
 					))
 				}
 			},
 		};
-		let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
+		let controls = sc_consensus_manual_seal::ManualSealControls::new()
+			.with_time_control(time_control)
+			.with_revert(backend.clone(), transaction_pool.clone());
+		let authorship_future =
+			sc_consensus_manual_seal::run_manual_seal_with_controls(params, controls);
 		task_manager.spawn_essential_handle().spawn_blocking(
 			"manual-seal",
 			None,
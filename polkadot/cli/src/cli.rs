@@ -56,6 +56,15 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Inspect, export or pin candidates in the availability store of a stopped node.
+	AvailabilityStore(AvailabilityStoreCmd),
+
+	/// Validate a candidate with the PVF workers, like validators would, and report the outcome.
+	ValidateCandidate(ValidateCandidateCmd),
+
+	/// Inspect or repair the dispute coordinator database of a stopped node.
+	DisputeCoordinator(DisputeCoordinatorCmd),
 }
 
 #[allow(missing_docs)]
@@ -115,6 +124,21 @@ pub struct RunCmd {
 	#[arg(long)]
 	pub overseer_channel_capacity_override: Option<usize>,
 
+	/// Trace every message exchanged between subsystems.
+	///
+	/// Messages are logged with their origin, destination and latency under the
+	/// `parachain::overseer-trace` target at trace level. Subsystems which have messages queued
+	/// but haven't received any for `--overseer-stall-timeout` seconds are reported as warnings.
+	/// Expensive, only meant for debugging.
+	#[arg(long)]
+	pub overseer_message_tracing: bool,
+
+	/// Seconds a subsystem may not receive its queued messages before being reported as stalled.
+	///
+	/// Only used with `--overseer-message-tracing`.
+	#[arg(long, value_name = "SECONDS", default_value_t = 30)]
+	pub overseer_stall_timeout: u64,
+
 	/// Path to the directory where auxiliary worker binaries reside.
 	///
 	/// If not specified, the main binary's directory is searched first, then
@@ -152,6 +176,233 @@ pub struct RunCmd {
 	/// explicitly advised to.
 	#[arg(long)]
 	pub enable_approval_voting_parallel: bool,
+
+	/// Keep the availability data of a parachain for a different number of hours after
+	/// finality.
+	///
+	/// Format is `PARA_ID=HOURS`, can be passed multiple times. `HOURS` must be greater than zero.
+	/// Data of other parachains is kept for 25 hours.
+	#[arg(long, value_name = "PARA_ID=HOURS", value_parser = parse_availability_retention)]
+	pub availability_retention: Vec<(u32, u64)>,
+}
+
+fn parse_availability_retention(s: &str) -> Result<(u32, u64), String> {
+	let (para_id, hours) = s
+		.split_once('=')
+		.ok_or_else(|| format!("expected `PARA_ID=HOURS`, got `{}`", s))?;
+	let para_id = para_id.parse().map_err(|e| format!("invalid para id `{}`: {}", para_id, e))?;
+	let hours: u64 = hours
+		.parse()
+		.map_err(|e| format!("invalid number of hours `{}`: {}", hours, e))?;
+
+	if hours == 0 {
+		return Err("the number of hours must be greater than zero".into())
+	}
+	if hours.checked_mul(60 * 60).is_none() {
+		return Err(format!("{} hours is too long a retention period", hours))
+	}
+
+	Ok((para_id, hours))
+}
+
+/// The `availability-store` command.
+///
+/// The node must not be running while this command is used: it opens the database directly, which
+/// a running node keeps locked. A running node cannot be asked to pin a candidate, use
+/// `--availability-retention` to keep the data of a parachain for longer instead.
+#[derive(Debug, Parser)]
+pub struct AvailabilityStoreCmd {
+	#[allow(missing_docs)]
+	#[command(subcommand)]
+	pub action: AvailabilityStoreAction,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+/// What to do with a candidate in the availability store.
+#[derive(Debug, clap::Subcommand)]
+pub enum AvailabilityStoreAction {
+	/// Show what the store knows about a candidate.
+	Info {
+		/// The candidate hash.
+		#[arg(long)]
+		candidate: sp_core::H256,
+	},
+
+	/// Write the SCALE encoded `AvailableData` of a candidate to a file.
+	Export {
+		/// The candidate hash.
+		#[arg(long)]
+		candidate: sp_core::H256,
+
+		/// The file to write to.
+		#[arg(long, value_name = "PATH")]
+		output: PathBuf,
+
+		/// Reconstruct the data from the stored chunks, even if it is stored in full.
+		#[arg(long)]
+		reconstruct: bool,
+	},
+
+	/// Keep the data of a candidate until it is unpinned.
+	///
+	/// Only works on a stopped node. The pin is honoured once the node is started again, but a
+	/// candidate that was pruned in the meantime cannot be pinned anymore.
+	Pin {
+		/// The candidate hash.
+		#[arg(long)]
+		candidate: sp_core::H256,
+	},
+
+	/// Let a pinned candidate be pruned again.
+	Unpin {
+		/// The candidate hash.
+		#[arg(long)]
+		candidate: sp_core::H256,
+	},
+}
+
+impl sc_cli::CliConfiguration for AvailabilityStoreCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The `dispute-coordinator` command.
+///
+/// The node must not be running while this command is used.
+#[derive(Debug, Parser)]
+pub struct DisputeCoordinatorCmd {
+	#[allow(missing_docs)]
+	#[command(subcommand)]
+	pub action: DisputeCoordinatorAction,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+/// What to do with the dispute coordinator database.
+#[derive(Debug, clap::Subcommand)]
+pub enum DisputeCoordinatorAction {
+	/// Show the earliest session and a summary of the recent disputes.
+	Info,
+
+	/// List the recent disputes and their status.
+	Disputes {
+		/// Only list disputes of this session.
+		#[arg(long)]
+		session: Option<u32>,
+	},
+
+	/// Show the votes on a candidate, or list the candidates with votes if no candidate is given.
+	Votes {
+		/// The session of the candidate.
+		#[arg(long)]
+		session: u32,
+
+		/// The candidate hash.
+		#[arg(long)]
+		candidate: Option<sp_core::H256>,
+	},
+
+	/// Show the spam slots validators would occupy once the node starts.
+	SpamSlots,
+
+	/// Delete all votes and disputes of a session.
+	DropSession {
+		/// The session to delete.
+		#[arg(long)]
+		session: u32,
+	},
+
+	/// Replace the recent disputes with the disputes known on chain at the best block.
+	///
+	/// Stored votes are kept, and so are unconcluded disputes only known locally.
+	Rebuild {
+		/// Also drop the unconcluded disputes that are not known on chain.
+		#[arg(long)]
+		drop_local: bool,
+	},
+}
+
+impl sc_cli::CliConfiguration for DisputeCoordinatorCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The `validate-candidate` command.
+///
+/// Runs the candidate through the same preparation and execution workers, with the same timeouts,
+/// as the candidate validation subsystem of a validator. Reports how long preparation and execution
+/// took and their peak memory usage.
+#[derive(Debug, Parser)]
+pub struct ValidateCandidateCmd {
+	/// The validation code of the parachain, compressed or not.
+	#[arg(long, value_name = "PATH")]
+	pub validation_code: PathBuf,
+
+	/// The SCALE encoded `PersistedValidationData` the candidate was built on.
+	#[arg(long, value_name = "PATH")]
+	pub persisted_validation_data: PathBuf,
+
+	/// The SCALE encoded `PoV` of the candidate.
+	#[arg(long, value_name = "PATH")]
+	pub pov: PathBuf,
+
+	/// The SCALE encoded `ExecutorParams` of the session the candidate is validated in.
+	///
+	/// They change the timeouts and the limits of the workers, so they must be the ones of the
+	/// session, as returned by the `session_executor_params` runtime API of the relay chain.
+	#[arg(long, value_name = "PATH")]
+	pub executor_params: PathBuf,
+
+	/// Validate the candidate like an approval checker rather than a backing validator.
+	///
+	/// This uses the longer approval execution timeout and retries executions that may have
+	/// failed for transient reasons.
+	#[arg(long)]
+	pub approval: bool,
+
+	/// The directory to store prepared artifacts in.
+	///
+	/// A temporary directory is used if not given. Artifacts already in the directory are
+	/// reused, in which case the preparation is not measured.
+	#[arg(long, value_name = "PATH")]
+	pub artifacts_path: Option<PathBuf>,
+
+	/// Path to the directory where auxiliary worker binaries reside.
+	///
+	/// If not specified, the main binary's directory is searched first, then
+	/// `/usr/lib/polkadot` is searched.
+	#[arg(long, value_name = "PATH")]
+	pub workers_path: Option<PathBuf>,
+
+	/// Run the workers outside of Secure Validator Mode.
+	///
+	/// Like a validator, the workers are run in Secure Validator Mode by default, which fails if
+	/// the required security features are missing on this machine.
+	#[arg(long = "insecure-validator-i-know-what-i-do")]
+	pub insecure_validator: bool,
 }
 
 #[allow(missing_docs)]
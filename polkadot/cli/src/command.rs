@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand, NODE_VERSION};
+use crate::cli::{
+	AvailabilityStoreAction, AvailabilityStoreCmd, Cli, DisputeCoordinatorAction,
+	DisputeCoordinatorCmd, Subcommand, ValidateCandidateCmd, NODE_VERSION,
+};
 use frame_benchmarking_cli::{
 	BenchmarkCmd, ExtrinsicFactory, SubstrateRemarkBuilder, SUBSTRATE_REFERENCE_HARDWARE,
 };
@@ -28,12 +31,13 @@ use polkadot_service::{
 #[cfg(feature = "pyroscope")]
 use pyroscope_pprofrs::{pprof_backend, PprofConfig};
 use sc_cli::SubstrateCli;
-use sp_core::crypto::Ss58AddressFormatRegistry;
+use sp_core::{crypto::Ss58AddressFormatRegistry, hexdisplay::HexDisplay, Decode, Encode};
 use sp_keyring::Sr25519Keyring;
 
 pub use crate::error::Error;
 #[cfg(feature = "pyroscope")]
 use std::net::ToSocketAddrs;
+use std::time::Duration;
 
 type Result<T> = std::result::Result<T, Error>;
 
@@ -229,12 +233,27 @@ where
 				overseer_message_channel_capacity_override: cli
 					.run
 					.overseer_channel_capacity_override,
+				overseer_message_tracing: cli
+					.run
+					.overseer_message_tracing
+					.then(|| Duration::from_secs(cli.run.overseer_stall_timeout)),
 				malus_finality_delay: maybe_malus_finality_delay,
 				hwbench,
 				execute_workers_max_num: cli.run.execute_workers_max_num,
 				prepare_workers_hard_max_num: cli.run.prepare_workers_hard_max_num,
 				prepare_workers_soft_max_num: cli.run.prepare_workers_soft_max_num,
 				enable_approval_voting_parallel: cli.run.enable_approval_voting_parallel,
+				availability_retention: cli
+					.run
+					.availability_retention
+					.iter()
+					.map(|(para_id, hours)| {
+						(
+							polkadot_service::ParaId::from(*para_id),
+							Duration::from_secs(hours.saturating_mul(60 * 60)),
+						)
+					})
+					.collect(),
 			},
 		)
 		.map(|full| full.task_manager)?;
@@ -450,6 +469,7 @@ pub fn run() -> Result<()> {
 					cmd.run(&config, SUBSTRATE_REFERENCE_HARDWARE.clone())
 						.map_err(Error::SubstrateCli)
 				}),
+				BenchmarkCmd::Compare(cmd) => cmd.run().map_err(Error::SubstrateCli),
 				// NOTE: this allows the Polkadot client to leniently implement
 				// new benchmark commands.
 				#[allow(unreachable_patterns)]
@@ -461,6 +481,18 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run::<polkadot_service::Block>(&config))?)
 		},
+		Some(Subcommand::AvailabilityStore(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| run_availability_store_cmd(cmd, &config.database))?)
+		},
+		Some(Subcommand::ValidateCandidate(cmd)) => {
+			sc_cli::LoggerBuilder::new("").init()?;
+			run_validate_candidate_cmd(cmd)
+		},
+		Some(Subcommand::DisputeCoordinator(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| run_dispute_coordinator_cmd(cmd, config))?)
+		},
 	}?;
 
 	#[cfg(feature = "pyroscope")]
@@ -470,3 +502,239 @@ pub fn run() -> Result<()> {
 	}
 	Ok(())
 }
+
+fn application_error(err: impl std::error::Error + Send + Sync + 'static) -> sc_cli::Error {
+	sc_cli::Error::Application(Box::new(err))
+}
+
+fn run_availability_store_cmd(
+	cmd: &AvailabilityStoreCmd,
+	database: &polkadot_service::DatabaseSource,
+) -> sc_cli::Result<()> {
+	let unknown = |candidate| {
+		sc_cli::Error::Input(format!("Candidate {:?} is not in the availability store", candidate))
+	};
+
+	let store = polkadot_service::open_availability_store(database).map_err(application_error)?;
+
+	match &cmd.action {
+		AvailabilityStoreAction::Info { candidate } => {
+			let info = store
+				.candidate_info(&polkadot_service::CandidateHash(*candidate))
+				.map_err(application_error)?
+				.ok_or_else(|| unknown(candidate))?;
+
+			println!("Status: {:?}", info.status);
+			println!("Data available: {}", info.data_available);
+			println!("Chunks stored: {}/{}", info.chunks_stored, info.n_validators);
+			println!("Pinned: {}", info.pinned);
+		},
+		AvailabilityStoreAction::Export { candidate, output, reconstruct } => {
+			let candidate_hash = polkadot_service::CandidateHash(*candidate);
+			let stored = if *reconstruct {
+				None
+			} else {
+				store.available_data(&candidate_hash).map_err(application_error)?
+			};
+			let data = match stored {
+				Some(data) => data,
+				None => store
+					.reconstruct_available_data(&candidate_hash)
+					.map_err(application_error)?
+					.ok_or_else(|| unknown(candidate))?,
+			};
+
+			std::fs::write(output, data.encode())?;
+			info!(
+				"Exported the available data of candidate {:?} to {}",
+				candidate,
+				output.display()
+			);
+		},
+		AvailabilityStoreAction::Pin { candidate } => {
+			if !store
+				.pin(&polkadot_service::CandidateHash(*candidate))
+				.map_err(application_error)?
+			{
+				return Err(unknown(candidate))
+			}
+			info!("Pinned candidate {:?}", candidate);
+		},
+		AvailabilityStoreAction::Unpin { candidate } => {
+			if store
+				.unpin(&polkadot_service::CandidateHash(*candidate))
+				.map_err(application_error)?
+			{
+				info!("Unpinned candidate {:?}", candidate);
+			} else {
+				info!("Candidate {:?} was not pinned", candidate);
+			}
+		},
+	}
+
+	Ok(())
+}
+
+fn run_validate_candidate_cmd(cmd: &ValidateCandidateCmd) -> Result<()> {
+	fn decode_file<T: Decode>(path: &std::path::Path) -> Result<T> {
+		let encoded = std::fs::read(path).map_err(sc_cli::Error::from)?;
+		T::decode(&mut &encoded[..])
+			.map_err(|e| Error::Other(format!("Failed to decode {}: {}", path.display(), e)))
+	}
+
+	let request = polkadot_service::DryRunRequest {
+		validation_code: polkadot_service::ValidationCode(
+			std::fs::read(&cmd.validation_code).map_err(sc_cli::Error::from)?,
+		),
+		persisted_validation_data: decode_file(&cmd.persisted_validation_data)?,
+		pov: decode_file(&cmd.pov)?,
+		executor_params: decode_file(&cmd.executor_params)?,
+		kind: if cmd.approval {
+			polkadot_service::DryRunKind::Approval
+		} else {
+			polkadot_service::DryRunKind::Backing
+		},
+	};
+
+	let report = sc_cli::build_runtime().map_err(sc_cli::Error::from)?.block_on(
+		polkadot_service::dry_run_candidate_validation(
+			request,
+			cmd.workers_path.clone(),
+			Some(NODE_VERSION.to_string()),
+			!cmd.insecure_validator,
+			cmd.artifacts_path.clone(),
+		),
+	)?;
+
+	let print_time = |what, time: Option<Duration>, timeout: Duration| match time {
+		Some(time) => println!("{}: {:?} (timeout {:?})", what, time, timeout),
+		None => println!("{}: skipped (timeout {:?})", what, timeout),
+	};
+	print_time("Preparation", report.preparation_time, report.prepare_timeout);
+	print_time("Execution", report.execution_time, report.exec_timeout);
+	let print_memory = |what, kb: Option<u64>| match kb {
+		Some(kb) => println!("Peak {} memory: {} KiB", what, kb),
+		None => println!("Peak {} memory: not measured", what),
+	};
+	print_memory("preparation", report.peak_preparation_memory_kb);
+	print_memory("execution", report.peak_execution_memory_kb);
+
+	let invalid = |reason: String| -> Result<()> {
+		Err(Error::Other(format!("The candidate is invalid: {}", reason)))
+	};
+	match report.outcome {
+		polkadot_service::DryRunOutcome::Valid(commitments) => {
+			println!("Result: valid");
+			println!("Head data: 0x{}", HexDisplay::from(&commitments.head_data.0));
+			println!(
+				"Upward messages: {} ({} bytes)",
+				commitments.upward_messages.len(),
+				commitments.upward_messages.iter().map(|m| m.len()).sum::<usize>(),
+			);
+			println!("Horizontal messages: {}", commitments.horizontal_messages.len());
+			for message in commitments.horizontal_messages.iter() {
+				println!("  to {}: {} bytes", message.recipient, message.data.len());
+			}
+			println!("Processed downward messages: {}", commitments.processed_downward_messages);
+			println!("HRMP watermark: {}", commitments.hrmp_watermark);
+			if let Some(code) = commitments.new_validation_code {
+				println!("New validation code: {} bytes", code.0.len());
+			}
+			Ok(())
+		},
+		polkadot_service::DryRunOutcome::PoVTooLarge(size) =>
+			invalid(format!("the PoV is too large ({} bytes)", size)),
+		polkadot_service::DryRunOutcome::PreparationFailed(error) => invalid(error.to_string()),
+		polkadot_service::DryRunOutcome::ExecutionFailed(error) => invalid(error.to_string()),
+	}
+}
+
+fn run_dispute_coordinator_cmd(
+	cmd: &DisputeCoordinatorCmd,
+	mut config: polkadot_service::Configuration,
+) -> sc_cli::Result<()> {
+	let store =
+		polkadot_service::open_dispute_store(&config.database).map_err(application_error)?;
+
+	match &cmd.action {
+		DisputeCoordinatorAction::Info => {
+			let earliest_session = store.earliest_session().map_err(application_error)?;
+			let recent_disputes = store.recent_disputes().map_err(application_error)?;
+			let unconfirmed = recent_disputes
+				.values()
+				.filter(|status| !status.is_confirmed_concluded())
+				.count();
+			let concluded_for =
+				recent_disputes.values().filter(|status| status.has_concluded_for()).count();
+			let concluded_against =
+				recent_disputes.values().filter(|status| status.has_concluded_against()).count();
+
+			println!("Earliest session: {:?}", earliest_session);
+			println!("Recent disputes: {}", recent_disputes.len());
+			println!("Unconfirmed: {}", unconfirmed);
+			println!("Concluded for: {}", concluded_for);
+			println!("Concluded against: {}", concluded_against);
+		},
+		DisputeCoordinatorAction::Disputes { session } => {
+			for ((dispute_session, candidate_hash), status) in
+				store.recent_disputes().map_err(application_error)?
+			{
+				if session.is_some_and(|session| session != dispute_session) {
+					continue
+				}
+				println!("{}\t{:?}\t{:?}", dispute_session, candidate_hash, status);
+			}
+		},
+		DisputeCoordinatorAction::Votes { session, candidate: None } => {
+			for candidate_hash in
+				store.candidates_with_votes(*session).map_err(application_error)?
+			{
+				println!("{:?}", candidate_hash);
+			}
+		},
+		DisputeCoordinatorAction::Votes { session, candidate: Some(candidate) } => {
+			let votes = store
+				.candidate_votes(*session, &polkadot_service::CandidateHash(*candidate))
+				.map_err(application_error)?
+				.ok_or_else(|| {
+					sc_cli::Error::Input(format!(
+						"No votes on candidate {:?} in session {}",
+						candidate, session
+					))
+				})?;
+
+			println!("Para: {}", votes.candidate_receipt.descriptor.para_id());
+			println!("Relay parent: {:?}", votes.candidate_receipt.descriptor.relay_parent());
+			for (validator, (kind, _)) in votes.valid.raw() {
+				println!("Valid\t{}\t{:?}", validator.0, kind);
+			}
+			for (validator, (kind, _)) in &votes.invalid {
+				println!("Invalid\t{}\t{:?}", validator.0, kind);
+			}
+		},
+		DisputeCoordinatorAction::SpamSlots => {
+			for ((session, validator), slots) in store.spam_slots().map_err(application_error)? {
+				println!("{}\t{}\t{}", session, validator.0, slots);
+			}
+		},
+		DisputeCoordinatorAction::DropSession { session } => {
+			let dropped = store.delete_session(*session).map_err(application_error)?;
+			info!("Deleted the votes and {} disputes of session {}", dropped, session);
+		},
+		DisputeCoordinatorAction::Rebuild { drop_local } => {
+			let (client, _, _, _) =
+				polkadot_service::new_chain_ops(&mut config).map_err(application_error)?;
+			let on_chain =
+				polkadot_service::on_chain_disputes(&client).map_err(application_error)?;
+			let summary = store
+				.rebuild_recent_disputes(on_chain, *drop_local)
+				.map_err(application_error)?;
+			info!(
+				"Restored {} disputes from chain, kept {} and dropped {} disputes only known locally",
+				summary.restored, summary.kept, summary.dropped
+			);
+		},
+	}
+
+	Ok(())
+}
@@ -0,0 +1,78 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use polkadot_node_primitives::{AvailableData, BlockData, PoV};
+use std::{sync::Arc, time::Duration};
+
+const N_VALIDATORS: [usize; 3] = [300, 500, 1000];
+
+const KB: usize = 1024;
+const MB: usize = 1024 * KB;
+
+const POV_SIZES: [usize; 2] = [5 * MB, 10 * MB];
+
+fn available_data(pov_size: usize) -> AvailableData {
+	AvailableData {
+		pov: Arc::new(PoV { block_data: BlockData((0..=255u8).cycle().take(pov_size).collect()) }),
+		validation_data: Default::default(),
+	}
+}
+
+fn construct(c: &mut Criterion) {
+	for pov_size in POV_SIZES {
+		let data = available_data(pov_size);
+
+		let mut group = c.benchmark_group(format!("construct_{}mib", pov_size / MB));
+		group.throughput(Throughput::Bytes(pov_size as u64));
+		for n_validators in N_VALIDATORS {
+			group.bench_with_input(
+				BenchmarkId::new("in_memory", n_validators),
+				&n_validators,
+				|b, &n| {
+					b.iter(|| {
+						let chunks = polkadot_erasure_coding::obtain_chunks_v1(n, &data).unwrap();
+						polkadot_erasure_coding::branches(&chunks).root()
+					});
+				},
+			);
+			group.bench_with_input(
+				BenchmarkId::new("streaming", n_validators),
+				&n_validators,
+				|b, &n| {
+					b.iter(|| {
+						polkadot_erasure_coding::obtain_chunks_streaming(n, &data).unwrap().root()
+					});
+				},
+			);
+		}
+		group.finish();
+	}
+}
+
+fn criterion_config() -> Criterion {
+	Criterion::default()
+		.sample_size(10)
+		.warm_up_time(Duration::from_millis(200))
+		.measurement_time(Duration::from_secs(3))
+}
+
+criterion_group!(
+	name = streaming;
+	config = criterion_config();
+	targets = construct,
+);
+criterion_main!(streaming);
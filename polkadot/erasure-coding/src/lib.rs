@@ -23,6 +23,9 @@
 //! Each of n validators stores their piece of data. We assume `n = 3f + k`, `0 < k ≤ 3`.
 //! f is the maximum number of faulty validators in the system.
 //! The data is coded so any f+1 chunks can be used to reconstruct the full data.
+//!
+//! [`StreamingEncoder`] produces the same chunks and roots while processing large payloads in
+//! fixed-size segments. Only its chunk hashing is vectorised.
 
 use codec::{Decode, Encode};
 use polkadot_node_primitives::{AvailableData, Proof};
@@ -36,6 +39,10 @@ use thiserror::Error;
 
 use novelpoly::{CodeParams, WrappedShard};
 
+mod stream;
+
+pub use stream::{obtain_chunks_streaming, ErasureChunks, StreamingEncoder, DEFAULT_SEGMENT_SIZE};
+
 // we are limited to the field order of GF(2^16), which is 65536
 const MAX_VALIDATORS: usize = novelpoly::f2e16::FIELD_SIZE;
 
@@ -290,22 +297,28 @@ pub fn branches<'a, I: 'a>(chunks: &'a [I]) -> Branches<'a, I>
 where
 	I: AsRef<[u8]>,
 {
+	let (trie_storage, root) =
+		chunk_trie(chunks.as_ref().iter().map(|chunk| BlakeTwo256::hash(chunk.as_ref())));
+
+	Branches { trie_storage, root, chunks, current_pos: 0 }
+}
+
+/// Construct the trie mapping each chunk's index to the given chunk hash.
+fn chunk_trie(chunk_hashes: impl IntoIterator<Item = H256>) -> (MemoryDB<Blake2Hasher>, H256) {
 	let mut trie_storage: MemoryDB<Blake2Hasher> = MemoryDB::default();
 	let mut root = H256::default();
 
-	// construct trie mapping each chunk's index to its hash.
 	{
 		let mut trie = TrieDBMutBuilder::new(&mut trie_storage, &mut root).build();
-		for (i, chunk) in chunks.as_ref().iter().enumerate() {
+		for (i, chunk_hash) in chunk_hashes.into_iter().enumerate() {
 			(i as u32).using_encoded(|encoded_index| {
-				let chunk_hash = BlakeTwo256::hash(chunk.as_ref());
 				trie.insert(encoded_index, chunk_hash.as_ref())
 					.expect("a fresh trie stored in memory cannot have errors loading nodes; qed");
 			})
 		}
 	}
 
-	Branches { trie_storage, root, chunks, current_pos: 0 }
+	(trie_storage, root)
 }
 
 /// Verify a merkle branch, yielding the chunk hash meant to be present at that
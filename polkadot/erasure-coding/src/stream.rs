@@ -0,0 +1,308 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Streaming erasure coding.
+//!
+//! [`obtain_chunks`](crate::obtain_chunks) and [`branches`](crate::branches) need the encoding of
+//! the whole payload in memory, and hash every chunk in a second pass once all chunks are known.
+//! The types in this module work on segments of a fixed size instead. [`StreamingEncoder`] is a
+//! [`codec::Output`], so a value can be encoded straight into it: every full segment is erasure
+//! coded right away, and the resulting symbols are appended to the chunks and fed to the chunk
+//! hashers.
+//!
+//! The code operates on independent runs of `2 * k` payload bytes, each of which contributes one
+//! 2-byte symbol to every chunk. Segment sizes are rounded to a multiple of that run length, so
+//! chunks, proofs and roots are byte-for-byte the same as those of the non-streaming functions.
+//!
+//! Chunk hashes are computed with `blake2b_simd::many`, which picks an AVX2, SSE4.1 or portable
+//! implementation at runtime and hashes several chunks at once. This is the only vectorised part:
+//! the Reed-Solomon encoding and reconstruction of each segment still go through the scalar
+//! implementation of `novelpoly`, so the streaming encoder saves memory and the second hashing
+//! pass, not the cost of the code itself.
+//!
+//! The node keeps using [`obtain_chunks_v1`](crate::obtain_chunks_v1) and
+//! [`branches`](crate::branches) for now.
+
+use crate::{chunk_trie, code_params, Branches, Error};
+use codec::{Encode, Output};
+use novelpoly::{CodeParams, WrappedShard};
+use polkadot_primitives::Hash as H256;
+use sp_core::Blake2Hasher;
+use sp_trie::MemoryDB;
+
+/// Amount of payload bytes erasure coded at once, unless configured otherwise.
+pub const DEFAULT_SEGMENT_SIZE: usize = 1024 * 1024;
+
+/// Number of symbols each chunk gains per segment of (roughly) `segment_size` payload bytes.
+fn symbols_per_segment(params: &CodeParams, segment_size: usize) -> usize {
+	(segment_size / (params.k() * 2)).max(1)
+}
+
+fn chunk_hasher() -> blake2b_simd::State {
+	blake2b_simd::Params::new().hash_length(32).to_state()
+}
+
+/// Erasure codes a payload segment by segment, as it is being written.
+pub struct StreamingEncoder {
+	params: CodeParams,
+	segment_size: usize,
+	pending: Vec<u8>,
+	chunks: Vec<Vec<u8>>,
+	hashers: Vec<blake2b_simd::State>,
+}
+
+impl StreamingEncoder {
+	/// Create an encoder producing one chunk per validator, using [`DEFAULT_SEGMENT_SIZE`].
+	pub fn new(n_validators: usize) -> Result<Self, Error> {
+		Self::with_segment_size(n_validators, DEFAULT_SEGMENT_SIZE)
+	}
+
+	/// Create an encoder producing one chunk per validator.
+	///
+	/// `segment_size` is rounded down to a multiple of the run length of the code, but never
+	/// below a single run.
+	pub fn with_segment_size(n_validators: usize, segment_size: usize) -> Result<Self, Error> {
+		let params = code_params(n_validators)?;
+		let segment_size = symbols_per_segment(&params, segment_size) * params.k() * 2;
+
+		Ok(StreamingEncoder {
+			params,
+			segment_size,
+			pending: Vec::with_capacity(segment_size),
+			chunks: Vec::new(),
+			hashers: Vec::new(),
+		})
+	}
+
+	/// Append bytes to the payload.
+	pub fn feed(&mut self, mut bytes: &[u8]) {
+		if !self.pending.is_empty() {
+			let missing = self.segment_size - self.pending.len();
+			let (head, tail) = bytes.split_at(missing.min(bytes.len()));
+			self.pending.extend_from_slice(head);
+			bytes = tail;
+
+			if self.pending.len() < self.segment_size {
+				return
+			}
+
+			let pending = std::mem::take(&mut self.pending);
+			self.encode_segment(&pending);
+			self.pending = pending;
+			self.pending.clear();
+		}
+
+		// Full segments don't need to be copied into `pending` first.
+		let mut segments = bytes.chunks_exact(self.segment_size);
+		for segment in &mut segments {
+			self.encode_segment(segment);
+		}
+		self.pending.extend_from_slice(segments.remainder());
+	}
+
+	fn encode_segment(&mut self, segment: &[u8]) {
+		let shards: Vec<Vec<u8>> = self
+			.params
+			.make_encoder()
+			.encode::<WrappedShard>(segment)
+			.expect(
+				"Segment non-empty, shard sizes are uniform, and validator numbers checked; qed",
+			)
+			.into_iter()
+			.map(|shard| shard.into_inner())
+			.collect();
+
+		if self.hashers.is_empty() {
+			self.hashers = vec![chunk_hasher(); shards.len()];
+		}
+		blake2b_simd::many::update_many(
+			self.hashers.iter_mut().zip(shards.iter().map(|shard| &shard[..])),
+		);
+
+		if self.chunks.is_empty() {
+			self.chunks = shards;
+		} else {
+			for (chunk, shard) in self.chunks.iter_mut().zip(shards) {
+				chunk.extend_from_slice(&shard);
+			}
+		}
+	}
+
+	/// Encode whatever is left of the payload and build the merkle trie over the chunks.
+	pub fn finish(mut self) -> Result<ErasureChunks, Error> {
+		if !self.pending.is_empty() {
+			let pending = std::mem::take(&mut self.pending);
+			self.encode_segment(&pending);
+		}
+
+		if self.chunks.is_empty() {
+			return Err(Error::BadPayload)
+		}
+
+		let (trie_storage, root) = chunk_trie(
+			self.hashers.iter().map(|hasher| H256::from_slice(hasher.finalize().as_bytes())),
+		);
+
+		Ok(ErasureChunks { chunks: self.chunks, trie_storage, root })
+	}
+}
+
+impl Output for StreamingEncoder {
+	fn write(&mut self, bytes: &[u8]) {
+		self.feed(bytes)
+	}
+}
+
+/// The chunks of an erasure-coded value along with the merkle trie built over them.
+pub struct ErasureChunks {
+	chunks: Vec<Vec<u8>>,
+	trie_storage: MemoryDB<Blake2Hasher>,
+	root: H256,
+}
+
+impl ErasureChunks {
+	/// Get the trie root, also known as the erasure root.
+	pub fn root(&self) -> H256 {
+		self.root
+	}
+
+	/// Get the chunks, one for each validator.
+	pub fn chunks(&self) -> &[Vec<u8>] {
+		&self.chunks
+	}
+
+	/// Iterate over the merkle branches and chunk data, as [`branches`](crate::branches) does.
+	pub fn branches(&self) -> Branches<'_, Vec<u8>> {
+		Branches {
+			trie_storage: self.trie_storage.clone(),
+			root: self.root,
+			chunks: &self.chunks,
+			current_pos: 0,
+		}
+	}
+
+	/// Consume `self`, returning the chunks.
+	pub fn into_chunks(self) -> Vec<Vec<u8>> {
+		self.chunks
+	}
+}
+
+/// Obtain erasure-coded chunks along with their merkle trie, one chunk for each validator.
+///
+/// Equivalent to [`obtain_chunks`](crate::obtain_chunks) followed by
+/// [`branches`](crate::branches), without holding the encoded `data` in memory at once.
+pub fn obtain_chunks_streaming<T: Encode>(
+	n_validators: usize,
+	data: &T,
+) -> Result<ErasureChunks, Error> {
+	let mut encoder = StreamingEncoder::new(n_validators)?;
+	data.encode_to(&mut encoder);
+	encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{branches, obtain_chunks, obtain_chunks_v1, MAX_VALIDATORS};
+	use polkadot_node_primitives::{AvailableData, BlockData, PoV};
+	use quickcheck::{Arbitrary, Gen, QuickCheck};
+	use std::sync::Arc;
+
+	#[derive(Clone, Debug)]
+	struct Case {
+		available_data: AvailableData,
+		n_validators: usize,
+		segment_size: usize,
+	}
+
+	impl Arbitrary for Case {
+		fn arbitrary(g: &mut Gen) -> Self {
+			let pov_len = (u32::arbitrary(g) % (256 * 1024)).max(1);
+			let pov = (0..pov_len).map(|_| u8::arbitrary(g)).collect();
+
+			Case {
+				available_data: AvailableData {
+					pov: Arc::new(PoV { block_data: BlockData(pov) }),
+					validation_data: Default::default(),
+				},
+				n_validators: (u16::arbitrary(g) % 1024).max(2) as usize,
+				segment_size: (u32::arbitrary(g) % (64 * 1024)) as usize,
+			}
+		}
+	}
+
+	fn encode(case: &Case) -> ErasureChunks {
+		let mut encoder =
+			StreamingEncoder::with_segment_size(case.n_validators, case.segment_size).unwrap();
+		case.available_data.encode_to(&mut encoder);
+		encoder.finish().unwrap()
+	}
+
+	#[test]
+	fn streaming_chunks_and_proofs_match() {
+		fn property(case: Case) {
+			let expected = obtain_chunks(case.n_validators, &case.available_data).unwrap();
+			let expected_branches = branches(&expected);
+
+			let encoded = encode(&case);
+			assert_eq!(encoded.chunks(), &expected[..]);
+			assert_eq!(encoded.root(), expected_branches.root());
+			assert!(encoded.branches().eq(expected_branches));
+		}
+
+		QuickCheck::new().tests(20).quickcheck(property as fn(Case))
+	}
+
+	#[test]
+	fn streaming_matches_obtain_chunks_v1_for_any_validator_count() {
+		fn property(n_validators: u16, pov: Vec<u8>) {
+			// Cover the whole supported range, from 2 to `MAX_VALIDATORS` validators.
+			let n_validators = 2 + n_validators as usize % (MAX_VALIDATORS - 1);
+			let available_data = AvailableData {
+				pov: Arc::new(PoV { block_data: BlockData(pov) }),
+				validation_data: Default::default(),
+			};
+
+			let expected = obtain_chunks_v1(n_validators, &available_data).unwrap();
+			let encoded = obtain_chunks_streaming(n_validators, &available_data).unwrap();
+			assert_eq!(encoded.chunks(), &expected[..]);
+			assert_eq!(encoded.root(), branches(&expected).root());
+		}
+
+		QuickCheck::new().tests(20).quickcheck(property as fn(u16, Vec<u8>))
+	}
+
+	#[test]
+	fn byte_wise_writes_match() {
+		let available_data = AvailableData {
+			pov: Arc::new(PoV { block_data: BlockData((0..=255).cycle().take(10_000).collect()) }),
+			validation_data: Default::default(),
+		};
+		let expected = obtain_chunks(10, &available_data).unwrap();
+
+		let mut encoder = StreamingEncoder::with_segment_size(10, 100).unwrap();
+		for byte in available_data.encode() {
+			encoder.push_byte(byte);
+		}
+		assert_eq!(encoder.finish().unwrap().into_chunks(), expected);
+	}
+
+	#[test]
+	fn empty_payload_is_rejected() {
+		let encoder = StreamingEncoder::new(10).unwrap();
+		assert_eq!(encoder.finish().err(), Some(Error::BadPayload));
+	}
+}
@@ -35,8 +35,8 @@ use codec::Encode;
 use error::{Error, Result};
 use futures::{channel::oneshot, future::FutureExt, select};
 use polkadot_node_primitives::{
-	AvailableData, Collation, CollationGenerationConfig, CollationSecondedSignal, PoV,
-	SubmitCollationParams,
+	AvailableData, Collation, CollationDropReason, CollationEvent, CollationEventSubscribers,
+	CollationGenerationConfig, CollationSecondedSignal, PoV, SubmitCollationParams,
 };
 use polkadot_node_subsystem::{
 	messages::{CollationGenerationMessage, CollatorProtocolMessage, RuntimeApiMessage},
@@ -77,6 +77,7 @@ const LOG_TARGET: &'static str = "parachain::collation-generation";
 pub struct CollationGenerationSubsystem {
 	config: Option<Arc<CollationGenerationConfig>>,
 	session_info_cache: SessionInfoCache,
+	collation_events: CollationEventSubscribers,
 	metrics: Metrics,
 }
 
@@ -84,7 +85,12 @@ pub struct CollationGenerationSubsystem {
 impl CollationGenerationSubsystem {
 	/// Create a new instance of the `CollationGenerationSubsystem`.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { config: None, metrics, session_info_cache: SessionInfoCache::new() }
+		Self {
+			config: None,
+			metrics,
+			session_info_cache: SessionInfoCache::new(),
+			collation_events: Default::default(),
+		}
 	}
 
 	/// Run this subsystem
@@ -157,6 +163,14 @@ impl CollationGenerationSubsystem {
 
 				false
 			},
+			Ok(FromOrchestra::Communication {
+				msg: CollationGenerationMessage::SubscribeCollationEvents(sender),
+			}) => {
+				self.collation_events.subscribe(sender.clone());
+				ctx.send_message(CollatorProtocolMessage::SubscribeCollationEvents(sender))
+					.await;
+				false
+			},
 			Ok(FromOrchestra::Signal(OverseerSignal::BlockFinalized(..))) => false,
 			Err(err) => {
 				gum::error!(
@@ -206,6 +220,11 @@ impl CollationGenerationSubsystem {
 					our_para = %config.para_id,
 					"No validation data for para - does it exist at this relay-parent?",
 				);
+				self.collation_events.notify(CollationEvent::Dropped {
+					relay_parent,
+					candidate_hash: None,
+					reason: CollationDropReason::NoValidationData,
+				});
 				return Ok(())
 			},
 		};
@@ -231,7 +250,7 @@ impl CollationGenerationSubsystem {
 			session_index,
 		};
 
-		construct_and_distribute_receipt(
+		if let Err(err) = construct_and_distribute_receipt(
 			collation,
 			config.key.clone(),
 			ctx.sender(),
@@ -240,7 +259,15 @@ impl CollationGenerationSubsystem {
 			session_info.v2_receipts,
 			&transpose_claim_queue(claim_queue),
 		)
-		.await?;
+		.await
+		{
+			self.collation_events.notify(CollationEvent::Dropped {
+				relay_parent,
+				candidate_hash: None,
+				reason: drop_reason(&err),
+			});
+			return Err(err)
+		}
 
 		Ok(())
 	}
@@ -337,6 +364,7 @@ impl CollationGenerationSubsystem {
 
 		let task_config = config.clone();
 		let metrics = self.metrics.clone();
+		let mut collation_events = self.collation_events.clone();
 		let mut task_sender = ctx.sender().clone();
 
 		ctx.spawn(
@@ -389,6 +417,11 @@ impl CollationGenerationSubsystem {
 							"Failed to construct and distribute collation: {}",
 							err
 						);
+						collation_events.notify(CollationEvent::Dropped {
+							relay_parent,
+							candidate_hash: None,
+							reason: drop_reason(&err),
+						});
 						return
 					}
 
@@ -469,6 +502,15 @@ struct PreparedCollation {
 	session_index: SessionIndex,
 }
 
+/// The reason reported to subscribers for a collation which failed to be distributed.
+fn drop_reason(err: &Error) -> CollationDropReason {
+	match err {
+		Error::POVSizeExceeded(size, max_size) =>
+			CollationDropReason::PoVTooLarge { size: *size, max_size: *max_size },
+		err => CollationDropReason::CandidateConstructionFailed(err.to_string()),
+	}
+}
+
 /// Takes a prepared collation, along with its context, and produces a candidate receipt
 /// which is distributed to validators.
 async fn construct_and_distribute_receipt(
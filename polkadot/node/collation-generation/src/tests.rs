@@ -206,6 +206,66 @@ fn submit_collation_leads_to_distribution() {
 	});
 }
 
+#[test]
+fn collation_without_validation_data_is_reported_dropped() {
+	let relay_parent = Hash::repeat_byte(0);
+	let para_id = ParaId::from(5);
+	let (events_tx, mut events_rx) = futures::channel::mpsc::channel(8);
+
+	test_harness(|mut virtual_overseer| async move {
+		helpers::initialize_collator(&mut virtual_overseer, para_id).await;
+
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: CollationGenerationMessage::SubscribeCollationEvents(events_tx),
+			})
+			.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::CollatorProtocol(CollatorProtocolMessage::SubscribeCollationEvents(_))
+		);
+
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: CollationGenerationMessage::SubmitCollation(SubmitCollationParams {
+					relay_parent,
+					collation: test_collation(),
+					parent_head: dummy_head_data(),
+					validation_code_hash: ValidationCodeHash::from(Hash::repeat_byte(42)),
+					result_sender: None,
+					core_index: CoreIndex(0),
+				}),
+			})
+			.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				hash,
+				RuntimeApiRequest::PersistedValidationData(id, OccupiedCoreAssumption::TimedOut, tx),
+			)) => {
+				assert_eq!(hash, relay_parent);
+				assert_eq!(id, para_id);
+				let _ = tx.send(Ok(None));
+			}
+		);
+
+		assert_matches!(
+			events_rx.next().timeout(TIMEOUT).await,
+			Some(Some(CollationEvent::Dropped {
+				relay_parent: dropped_at,
+				candidate_hash: None,
+				reason: CollationDropReason::NoValidationData,
+			})) => {
+				assert_eq!(dropped_at, relay_parent);
+			}
+		);
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn distribute_collation_only_for_assigned_para_id_at_offset_0() {
 	let activated_hash: Hash = [1; 32].into();
@@ -45,8 +45,8 @@ use polkadot_overseer::{OverseerSignal, Priority, SubsystemSender, TimeoutExt};
 use polkadot_primitives::{CandidateIndex, Hash, ValidatorIndex, ValidatorSignature};
 use rand::SeedableRng;
 
-use sc_keystore::LocalKeystore;
 use sp_consensus::SyncOracle;
+use sp_keystore::KeystorePtr;
 
 use futures::{channel::oneshot, prelude::*, StreamExt};
 pub use metrics::Metrics;
@@ -83,10 +83,10 @@ fn prio_right<'a>(_val: &'a mut ()) -> PollNext {
 
 /// The approval voting parallel subsystem.
 pub struct ApprovalVotingParallelSubsystem {
-	/// `LocalKeystore` is needed for assignment keys, but not necessarily approval keys.
+	/// Keystore holding the assignment and approval keys.
 	///
-	/// We do a lot of VRF signing and need the keys to have low latency.
-	keystore: Arc<LocalKeystore>,
+	/// We do a lot of VRF signing, a remote signer should be reachable with low latency.
+	keystore: KeystorePtr,
 	db_config: DatabaseConfig,
 	slot_duration_millis: u64,
 	db: Arc<dyn Database>,
@@ -102,7 +102,7 @@ impl ApprovalVotingParallelSubsystem {
 	pub fn with_config(
 		config: Config,
 		db: Arc<dyn Database>,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
 		spawner: impl overseer::gen::Spawner + 'static + Clone,
@@ -124,7 +124,7 @@ impl ApprovalVotingParallelSubsystem {
 	pub fn with_config_and_clock(
 		config: Config,
 		db: Arc<dyn Database>,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
 		clock: Arc<dyn Clock + Send + Sync>,
@@ -0,0 +1,204 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replays relay chain blocks through the approval checking logic for every combination of the
+//! given parameters, and prints the resulting finality lag and message counts.
+//!
+//! Parameters accepting several comma separated values are swept, e.g.
+//! `approval-voting-simulator --needed-approvals 20,30 --no-show-rate 0.01,0.05`.
+
+use clap::Parser;
+use polkadot_node_core_approval_voting::simulator::{
+	simulate, SimulationConfig, SimulationError, SimulationReport,
+};
+use std::time::Duration;
+
+#[derive(Debug, Parser)]
+#[command(about, rename_all = "kebab-case")]
+struct Cli {
+	/// The number of validators.
+	#[arg(long, default_value_t = 300)]
+	validators: u32,
+
+	/// The number of cores with a candidate included in every block.
+	#[arg(long, default_value_t = 50)]
+	cores: u32,
+
+	/// The number of relay chain blocks to simulate per parameter set.
+	#[arg(long, default_value_t = 200)]
+	blocks: u32,
+
+	/// The number of approvals needed for a candidate.
+	#[arg(long, value_delimiter = ',', default_value = "30")]
+	needed_approvals: Vec<u32>,
+
+	/// The number of delay tranches.
+	#[arg(long, value_delimiter = ',', default_value = "89")]
+	delay_tranches: Vec<u32>,
+
+	/// The width of the zeroth delay tranche.
+	#[arg(long, value_delimiter = ',', default_value = "0")]
+	zeroth_delay_tranche_width: Vec<u32>,
+
+	/// The number of `RelayVRFModulo` samples.
+	#[arg(long, value_delimiter = ',', default_value = "6")]
+	relay_vrf_modulo_samples: Vec<u32>,
+
+	/// The number of slots after which an assignment without approval is a no-show.
+	#[arg(long, value_delimiter = ',', default_value = "2")]
+	no_show_slots: Vec<u32>,
+
+	/// The probability that a triggered assignment is never followed by an approval.
+	#[arg(long, value_delimiter = ',', default_value = "0.01")]
+	no_show_rate: Vec<f64>,
+
+	/// The time in milliseconds it takes a message to reach the other validators.
+	#[arg(long, value_delimiter = ',', default_value = "500")]
+	latency_ms: Vec<u64>,
+
+	/// The time in milliseconds it takes a validator to recover and validate a candidate.
+	#[arg(long, default_value_t = 2000)]
+	check_time_ms: u64,
+
+	/// Use v1 assignments, which are not coalesced into a single certificate.
+	#[arg(long)]
+	v1_assignments: bool,
+
+	/// The maximum number of approvals sent in a single message.
+	#[arg(long, default_value_t = 1)]
+	max_approval_coalesce_count: u32,
+
+	/// The seed of the simulation.
+	#[arg(long, default_value_t = 0)]
+	seed: u64,
+
+	/// Print the results as CSV.
+	#[arg(long)]
+	csv: bool,
+}
+
+impl Cli {
+	/// Every combination of the swept parameters.
+	fn configs(&self) -> Vec<SimulationConfig> {
+		let base = SimulationConfig {
+			n_validators: self.validators,
+			n_cores: self.cores,
+			enable_v2_assignments: !self.v1_assignments,
+			max_approval_coalesce_count: self.max_approval_coalesce_count,
+			approval_check_time: Duration::from_millis(self.check_time_ms),
+			n_blocks: self.blocks,
+			seed: self.seed,
+			..Default::default()
+		};
+
+		let configs = sweep(vec![base], &self.needed_approvals, |config, value| {
+			config.needed_approvals = value
+		});
+		let configs =
+			sweep(configs, &self.delay_tranches, |config, value| config.n_delay_tranches = value);
+		let configs = sweep(configs, &self.zeroth_delay_tranche_width, |config, value| {
+			config.zeroth_delay_tranche_width = value
+		});
+		let configs = sweep(configs, &self.relay_vrf_modulo_samples, |config, value| {
+			config.relay_vrf_modulo_samples = value
+		});
+		let configs =
+			sweep(configs, &self.no_show_slots, |config, value| config.no_show_slots = value);
+		let configs =
+			sweep(configs, &self.no_show_rate, |config, value| config.no_show_rate = value);
+		sweep(configs, &self.latency_ms, |config, value| {
+			config.network_latency = Duration::from_millis(value)
+		})
+	}
+}
+
+/// Combine every config with every value of a parameter.
+fn sweep<T: Copy>(
+	configs: Vec<SimulationConfig>,
+	values: &[T],
+	apply: impl Fn(&mut SimulationConfig, T),
+) -> Vec<SimulationConfig> {
+	let apply = &apply;
+	configs
+		.iter()
+		.flat_map(|config| {
+			values.iter().map(move |&value| {
+				let mut config = config.clone();
+				apply(&mut config, value);
+				config
+			})
+		})
+		.collect()
+}
+
+const COLUMNS: &[&str] = &[
+	"needed_approvals",
+	"delay_tranches",
+	"zeroth_width",
+	"samples",
+	"no_show_slots",
+	"no_show_rate",
+	"latency_ms",
+	"lag_p50_ms",
+	"lag_p90_ms",
+	"lag_p99_ms",
+	"lag_max_ms",
+	"unapproved",
+	"max_tranche",
+	"assignments_per_block",
+	"approvals_per_block",
+	"no_shows",
+];
+
+fn row(config: &SimulationConfig, report: &SimulationReport) -> Vec<String> {
+	let lag = |fraction| {
+		report
+			.finality_lag_percentile(fraction)
+			.map_or_else(|| "-".to_string(), |lag| lag.as_millis().to_string())
+	};
+
+	vec![
+		config.needed_approvals.to_string(),
+		config.n_delay_tranches.to_string(),
+		config.zeroth_delay_tranche_width.to_string(),
+		config.relay_vrf_modulo_samples.to_string(),
+		config.no_show_slots.to_string(),
+		config.no_show_rate.to_string(),
+		config.network_latency.as_millis().to_string(),
+		lag(0.5),
+		lag(0.9),
+		lag(0.99),
+		lag(1.0),
+		report.unapproved_blocks().to_string(),
+		report.max_triggered_tranche.to_string(),
+		format!("{:.1}", report.assignment_messages_per_block()),
+		format!("{:.1}", report.approval_messages_per_block()),
+		report.no_shows.to_string(),
+	]
+}
+
+fn main() -> Result<(), SimulationError> {
+	let cli = Cli::parse();
+
+	let separator = if cli.csv { "," } else { "\t" };
+	println!("{}", COLUMNS.join(separator));
+	for config in cli.configs() {
+		let report = simulate(&config)?;
+		println!("{}", row(&config, &report).join(separator));
+	}
+
+	Ok(())
+}
@@ -24,20 +24,20 @@ pub use polkadot_node_primitives::approval::criteria::{
 use polkadot_node_primitives::approval::{
 	self as approval_types,
 	v1::{AssignmentCert, AssignmentCertKind, DelayTranche, RelayVRFStory},
-	v2::{
-		AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, VrfPreOutput, VrfProof, VrfSignature,
-	},
+	v2::{AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, VrfPreOutput, VrfSignature},
 };
 
 use polkadot_primitives::{
-	AssignmentPair, CandidateHash, CoreIndex, GroupIndex, IndexedVec, ValidatorIndex,
+	AssignmentId, CandidateHash, CoreIndex, GroupIndex, IndexedVec, ValidatorIndex,
 };
 use rand::{seq::SliceRandom, SeedableRng};
 use rand_chacha::ChaCha20Rng;
-use sc_keystore::LocalKeystore;
-use sp_application_crypto::ByteArray;
+use sp_application_crypto::{
+	sr25519::vrf::{VrfSignData, VrfTranscript},
+	AppCrypto, ByteArray,
+};
+use sp_keystore::Keystore;
 
-use merlin::Transcript;
 use schnorrkel::vrf::VRFInOut;
 
 use std::{
@@ -66,30 +66,31 @@ impl From<OurAssignment> for crate::approval_db::v2::OurAssignment {
 
 // Combines the relay VRF story with a sample number if any.
 fn relay_vrf_modulo_transcript_inner(
-	mut transcript: Transcript,
+	label: &'static [u8],
 	relay_vrf_story: RelayVRFStory,
 	sample: Option<u32>,
-) -> Transcript {
-	transcript.append_message(b"RC-VRF", &relay_vrf_story.0);
+) -> VrfTranscript {
+	let sample = sample.map(|sample| sample.encode());
+	let mut data: Vec<(&'static [u8], &[u8])> = vec![(&b"RC-VRF"[..], &relay_vrf_story.0[..])];
 
-	if let Some(sample) = sample {
-		sample.using_encoded(|s| transcript.append_message(b"sample", s));
+	if let Some(sample) = &sample {
+		data.push((&b"sample"[..], &sample[..]));
 	}
 
-	transcript
+	VrfTranscript::new(label, &data)
 }
 
-fn relay_vrf_modulo_transcript_v1(relay_vrf_story: RelayVRFStory, sample: u32) -> Transcript {
+fn relay_vrf_modulo_transcript_v1(relay_vrf_story: RelayVRFStory, sample: u32) -> VrfTranscript {
 	relay_vrf_modulo_transcript_inner(
-		Transcript::new(approval_types::v1::RELAY_VRF_MODULO_CONTEXT),
+		approval_types::v1::RELAY_VRF_MODULO_CONTEXT,
 		relay_vrf_story,
 		Some(sample),
 	)
 }
 
-fn relay_vrf_modulo_transcript_v2(relay_vrf_story: RelayVRFStory) -> Transcript {
+fn relay_vrf_modulo_transcript_v2(relay_vrf_story: RelayVRFStory) -> VrfTranscript {
 	relay_vrf_modulo_transcript_inner(
-		Transcript::new(approval_types::v2::RELAY_VRF_MODULO_CONTEXT),
+		approval_types::v2::RELAY_VRF_MODULO_CONTEXT,
 		relay_vrf_story,
 		None,
 	)
@@ -161,11 +162,14 @@ fn relay_vrf_modulo_core(vrf_in_out: &VRFInOut, n_cores: u32) -> CoreIndex {
 	CoreIndex(random_core)
 }
 
-fn relay_vrf_delay_transcript(relay_vrf_story: RelayVRFStory, core_index: CoreIndex) -> Transcript {
-	let mut t = Transcript::new(approval_types::v1::RELAY_VRF_DELAY_CONTEXT);
-	t.append_message(b"RC-VRF", &relay_vrf_story.0);
-	core_index.0.using_encoded(|s| t.append_message(b"core", s));
-	t
+fn relay_vrf_delay_transcript(
+	relay_vrf_story: RelayVRFStory,
+	core_index: CoreIndex,
+) -> VrfTranscript {
+	VrfTranscript::new(
+		approval_types::v1::RELAY_VRF_DELAY_CONTEXT,
+		&[(b"RC-VRF", &relay_vrf_story.0), (b"core", &core_index.0.encode())],
+	)
 }
 
 fn relay_vrf_delay_tranche(
@@ -183,10 +187,67 @@ fn relay_vrf_delay_tranche(
 	wide_tranche.saturating_sub(zeroth_delay_tranche_width)
 }
 
-fn assigned_core_transcript(core_index: CoreIndex) -> Transcript {
-	let mut t = Transcript::new(approval_types::v1::ASSIGNED_CORE_CONTEXT);
-	core_index.0.using_encoded(|s| t.append_message(b"core", s));
-	t
+fn assigned_core_transcript(core_index: CoreIndex) -> VrfTranscript {
+	VrfTranscript::new(
+		approval_types::v1::ASSIGNED_CORE_CONTEXT,
+		&[(b"core", &core_index.0.encode())],
+	)
+}
+
+/// Signs the assignments with the assignment key held by a keystore, which may be a remote signer.
+struct AssignmentSigner<'a> {
+	keystore: &'a dyn Keystore,
+	public: AssignmentId,
+}
+
+impl AssignmentSigner<'_> {
+	/// Sign `transcript` if `check` returns the extra data to sign along for its VRF output, like
+	/// `schnorrkel::Keypair::vrf_sign_extra_after_check`.
+	///
+	/// The VRF output is computed from the pre-output given by the keystore, the signature is only
+	/// requested afterwards.
+	fn vrf_sign_extra_after_check(
+		&self,
+		transcript: VrfTranscript,
+		check: impl FnOnce(&VRFInOut) -> Option<VrfTranscript>,
+	) -> Option<(VRFInOut, VrfSignature)> {
+		let pre_output = self
+			.keystore
+			.sr25519_vrf_pre_output(AssignmentId::ID, self.public.as_ref(), &transcript)
+			.map_err(|e| gum::warn!(target: LOG_TARGET, "Encountered keystore error: {:?}", e))
+			.ok()
+			.flatten()?;
+		let vrf_in_out = self.vrf_in_out(&pre_output, &transcript)?;
+		let extra = check(&vrf_in_out)?;
+		let signature = self.sign(&VrfSignData::new(transcript).with_extra(extra))?;
+
+		// The assignment must be claimed with the output it was computed from.
+		(signature.pre_output == pre_output).then_some((vrf_in_out, signature))
+	}
+
+	/// Sign `transcript`, without extra data.
+	fn vrf_sign(&self, transcript: VrfTranscript) -> Option<(VRFInOut, VrfSignature)> {
+		let signature = self.sign(&VrfSignData::new(transcript.clone()))?;
+		let vrf_in_out = self.vrf_in_out(&signature.pre_output, &transcript)?;
+		Some((vrf_in_out, signature))
+	}
+
+	fn sign(&self, data: &VrfSignData) -> Option<VrfSignature> {
+		self.keystore
+			.sr25519_vrf_sign(AssignmentId::ID, self.public.as_ref(), data)
+			.map_err(|e| gum::warn!(target: LOG_TARGET, "Encountered keystore error: {:?}", e))
+			.ok()
+			.flatten()
+	}
+
+	fn vrf_in_out(
+		&self,
+		pre_output: &VrfPreOutput,
+		transcript: &VrfTranscript,
+	) -> Option<VRFInOut> {
+		let public = schnorrkel::PublicKey::from_bytes(self.public.as_slice()).ok()?;
+		pre_output.0.attach_input_hash(&public, transcript.0.clone()).ok()
+	}
 }
 
 pub struct RealAssignmentCriteria;
@@ -194,7 +255,7 @@ pub struct RealAssignmentCriteria;
 impl AssignmentCriteria for RealAssignmentCriteria {
 	fn compute_assignments(
 		&self,
-		keystore: &LocalKeystore,
+		keystore: &dyn Keystore,
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
@@ -235,7 +296,7 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 ///
 /// This will not assign to anything the local validator was part of the backing group for.
 pub fn compute_assignments(
-	keystore: &LocalKeystore,
+	keystore: &dyn Keystore,
 	relay_vrf_story: RelayVRFStory,
 	config: &Config,
 	leaving_cores: impl IntoIterator<Item = (CandidateHash, CoreIndex, GroupIndex)> + Clone,
@@ -256,18 +317,13 @@ pub fn compute_assignments(
 		return HashMap::new()
 	}
 
-	let (index, assignments_key): (ValidatorIndex, AssignmentPair) = {
+	let (index, assignments_key): (ValidatorIndex, AssignmentId) = {
+		let our_keys = keystore.sr25519_public_keys(AssignmentId::ID);
 		let key = config.assignment_keys.iter().enumerate().find_map(|(i, p)| {
-			match keystore.key_pair(p) {
-				Ok(Some(pair)) => Some((ValidatorIndex(i as _), pair)),
-				Ok(None) => None,
-				Err(sc_keystore::Error::Unavailable) => None,
-				Err(sc_keystore::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => None,
-				Err(e) => {
-					gum::warn!(target: LOG_TARGET, "Encountered keystore error: {:?}", e);
-					None
-				},
-			}
+			our_keys
+				.iter()
+				.any(|key| key.as_slice() == p.as_slice())
+				.then(|| (ValidatorIndex(i as _), p.clone()))
 		});
 
 		match key {
@@ -292,15 +348,14 @@ pub fn compute_assignments(
 		"Assigning to candidates from different backing groups"
 	);
 
-	let assignments_key: &sp_application_crypto::sr25519::Pair = assignments_key.as_ref();
-	let assignments_key: &schnorrkel::Keypair = assignments_key.as_ref();
+	let signer = AssignmentSigner { keystore, public: assignments_key };
 
 	let mut assignments = HashMap::new();
 
 	// First run `RelayVRFModulo` for each sample.
 	if enable_v2_assignments {
 		compute_relay_vrf_modulo_assignments_v2(
-			&assignments_key,
+			&signer,
 			index,
 			config,
 			relay_vrf_story.clone(),
@@ -309,7 +364,7 @@ pub fn compute_assignments(
 		);
 	} else {
 		compute_relay_vrf_modulo_assignments_v1(
-			&assignments_key,
+			&signer,
 			index,
 			config,
 			relay_vrf_story.clone(),
@@ -320,7 +375,7 @@ pub fn compute_assignments(
 
 	// Then run `RelayVRFDelay` once for the whole block.
 	compute_relay_vrf_delay_assignments(
-		&assignments_key,
+		&signer,
 		index,
 		config,
 		relay_vrf_story,
@@ -332,7 +387,7 @@ pub fn compute_assignments(
 }
 
 fn compute_relay_vrf_modulo_assignments_v1(
-	assignments_key: &schnorrkel::Keypair,
+	signer: &AssignmentSigner<'_>,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
@@ -346,7 +401,7 @@ fn compute_relay_vrf_modulo_assignments_v1(
 			// Extra scope to ensure borrowing instead of moving core
 			// into closure.
 			let core = &mut core;
-			assignments_key.vrf_sign_extra_after_check(
+			signer.vrf_sign_extra_after_check(
 				relay_vrf_modulo_transcript_v1(relay_vrf_story.clone(), rvm_sample),
 				|vrf_in_out| {
 					*core = relay_vrf_modulo_core(&vrf_in_out, config.n_cores);
@@ -370,15 +425,12 @@ fn compute_relay_vrf_modulo_assignments_v1(
 			)
 		};
 
-		if let Some((vrf_in_out, vrf_proof, _)) = maybe_assignment {
+		if let Some((_, vrf)) = maybe_assignment {
 			// Sanity: `core` is always initialized to non-default here, as the closure above
 			// has been executed.
 			let cert = AssignmentCert {
 				kind: AssignmentCertKind::RelayVRFModulo { sample: rvm_sample },
-				vrf: VrfSignature {
-					pre_output: VrfPreOutput(vrf_in_out.to_preout()),
-					proof: VrfProof(vrf_proof),
-				},
+				vrf,
 			};
 
 			// All assignments of type RelayVRFModulo have tranche 0.
@@ -392,14 +444,15 @@ fn compute_relay_vrf_modulo_assignments_v1(
 	}
 }
 
-fn assigned_cores_transcript(core_bitfield: &CoreBitfield) -> Transcript {
-	let mut t = Transcript::new(approval_types::v2::ASSIGNED_CORE_CONTEXT);
-	core_bitfield.using_encoded(|s| t.append_message(b"cores", s));
-	t
+fn assigned_cores_transcript(core_bitfield: &CoreBitfield) -> VrfTranscript {
+	VrfTranscript::new(
+		approval_types::v2::ASSIGNED_CORE_CONTEXT,
+		&[(b"cores", &core_bitfield.encode())],
+	)
 }
 
 fn compute_relay_vrf_modulo_assignments_v2(
-	assignments_key: &schnorrkel::Keypair,
+	signer: &AssignmentSigner<'_>,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
@@ -411,7 +464,7 @@ fn compute_relay_vrf_modulo_assignments_v2(
 
 	let maybe_assignment = {
 		let assigned_cores = &mut assigned_cores;
-		assignments_key.vrf_sign_extra_after_check(
+		signer.vrf_sign_extra_after_check(
 			relay_vrf_modulo_transcript_v2(relay_vrf_story.clone()),
 			|vrf_in_out| {
 				*assigned_cores = relay_vrf_modulo_cores(
@@ -445,7 +498,7 @@ fn compute_relay_vrf_modulo_assignments_v2(
 		)
 	};
 
-	if let Some(assignment) = maybe_assignment.map(|(vrf_in_out, vrf_proof, _)| {
+	if let Some(assignment) = maybe_assignment.map(|(_, vrf)| {
 		let assignment_bitfield: CoreBitfield = assigned_cores
 			.clone()
 			.try_into()
@@ -455,10 +508,7 @@ fn compute_relay_vrf_modulo_assignments_v2(
 			kind: AssignmentCertKindV2::RelayVRFModuloCompact {
 				core_bitfield: assignment_bitfield.clone(),
 			},
-			vrf: VrfSignature {
-				pre_output: VrfPreOutput(vrf_in_out.to_preout()),
-				proof: VrfProof(vrf_proof),
-			},
+			vrf,
 		};
 
 		// All assignments of type RelayVRFModulo have tranche 0.
@@ -471,7 +521,7 @@ fn compute_relay_vrf_modulo_assignments_v2(
 }
 
 fn compute_relay_vrf_delay_assignments(
-	assignments_key: &schnorrkel::Keypair,
+	signer: &AssignmentSigner<'_>,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
@@ -479,8 +529,11 @@ fn compute_relay_vrf_delay_assignments(
 	assignments: &mut HashMap<CoreIndex, OurAssignment>,
 ) {
 	for (candidate_hash, core) in leaving_cores {
-		let (vrf_in_out, vrf_proof, _) =
-			assignments_key.vrf_sign(relay_vrf_delay_transcript(relay_vrf_story.clone(), core));
+		let Some((vrf_in_out, vrf)) =
+			signer.vrf_sign(relay_vrf_delay_transcript(relay_vrf_story.clone(), core))
+		else {
+			continue
+		};
 
 		let tranche = relay_vrf_delay_tranche(
 			&vrf_in_out,
@@ -490,10 +543,7 @@ fn compute_relay_vrf_delay_assignments(
 
 		let cert = AssignmentCertV2 {
 			kind: AssignmentCertKindV2::RelayVRFDelay { core_index: core },
-			vrf: VrfSignature {
-				pre_output: VrfPreOutput(vrf_in_out.to_preout()),
-				proof: VrfProof(vrf_proof),
-			},
+			vrf,
 		};
 
 		let our_assignment = OurAssignment::new(cert, tranche, validator_index, false);
@@ -591,10 +641,10 @@ pub(crate) fn check_assignment_cert(
 
 			let (vrf_in_out, _) = public
 				.vrf_verify_extra(
-					relay_vrf_modulo_transcript_v2(relay_vrf_story),
+					relay_vrf_modulo_transcript_v2(relay_vrf_story).0,
 					&vrf_pre_output.0,
 					&vrf_proof.0,
-					assigned_cores_transcript(core_bitfield),
+					assigned_cores_transcript(core_bitfield).0,
 				)
 				.map_err(|_| InvalidAssignment(Reason::VRFModuloOutputMismatch))?;
 
@@ -640,10 +690,10 @@ pub(crate) fn check_assignment_cert(
 
 			let (vrf_in_out, _) = public
 				.vrf_verify_extra(
-					relay_vrf_modulo_transcript_v1(relay_vrf_story, *sample),
+					relay_vrf_modulo_transcript_v1(relay_vrf_story, *sample).0,
 					&vrf_pre_output.0,
 					&vrf_proof.0,
-					assigned_core_transcript(CoreIndex(first_claimed_core_index)),
+					assigned_core_transcript(CoreIndex(first_claimed_core_index)).0,
 				)
 				.map_err(|_| InvalidAssignment(Reason::VRFModuloOutputMismatch))?;
 
@@ -678,7 +728,7 @@ pub(crate) fn check_assignment_cert(
 
 			let (vrf_in_out, _) = public
 				.vrf_verify(
-					relay_vrf_delay_transcript(relay_vrf_story, *core_index),
+					relay_vrf_delay_transcript(relay_vrf_story, *core_index).0,
 					&vrf_pre_output.0,
 					&vrf_proof.0,
 				)
@@ -719,6 +769,7 @@ mod tests {
 	use super::*;
 	use crate::import::tests::garbage_vrf_signature;
 	use polkadot_primitives::{AssignmentId, Hash, ASSIGNMENT_KEY_TYPE_ID};
+	use sc_keystore::LocalKeystore;
 	use sp_application_crypto::sr25519;
 	use sp_core::crypto::Pair as PairT;
 	use sp_keyring::sr25519::Keyring as Sr25519Keyring;
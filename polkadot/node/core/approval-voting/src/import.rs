@@ -49,8 +49,8 @@ use polkadot_primitives::{
 	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt},
 	BlockNumber, CandidateHash, ConsensusLog, CoreIndex, GroupIndex, Hash, Header, SessionIndex,
 };
-use sc_keystore::LocalKeystore;
 use sp_consensus_slots::Slot;
+use sp_keystore::Keystore;
 
 use bitvec::order::Lsb0 as BitOrderLsb0;
 use futures::{channel::oneshot, prelude::*};
@@ -83,7 +83,7 @@ struct ImportedBlockInfo {
 struct ImportedBlockInfoEnv<'a> {
 	runtime_info: &'a mut RuntimeInfo,
 	assignment_criteria: &'a (dyn AssignmentCriteria + Send + Sync),
-	keystore: &'a LocalKeystore,
+	keystore: &'a dyn Keystore,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -623,6 +623,7 @@ pub(crate) mod tests {
 		IndexedVec, NodeFeatures, SessionInfo, ValidatorId, ValidatorIndex,
 	};
 	use polkadot_primitives_test_helpers::{dummy_candidate_receipt_v2, dummy_hash};
+	use sc_keystore::LocalKeystore;
 	use schnellru::{ByLength, LruMap};
 	pub(crate) use sp_consensus_babe::{
 		digests::{CompatibleDigestItem, PreDigest, SecondaryVRFPreDigest},
@@ -684,7 +685,7 @@ pub(crate) mod tests {
 	impl AssignmentCriteria for MockAssignmentCriteria {
 		fn compute_assignments(
 			&self,
-			_keystore: &LocalKeystore,
+			_keystore: &dyn Keystore,
 			_relay_vrf_story: polkadot_node_primitives::approval::v1::RelayVRFStory,
 			_config: &criteria::Config,
 			_leaving_cores: Vec<(
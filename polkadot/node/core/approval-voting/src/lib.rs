@@ -54,13 +54,12 @@ use polkadot_node_subsystem_util::{
 use polkadot_primitives::{
 	vstaging::CandidateReceiptV2 as CandidateReceipt, ApprovalVoteMultipleCandidates,
 	ApprovalVotingParams, BlockNumber, CandidateHash, CandidateIndex, CoreIndex, ExecutorParams,
-	GroupIndex, Hash, SessionIndex, SessionInfo, ValidatorId, ValidatorIndex, ValidatorPair,
-	ValidatorSignature,
+	GroupIndex, Hash, SessionIndex, SessionInfo, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
-use sc_keystore::LocalKeystore;
-use sp_application_crypto::Pair;
+use sp_application_crypto::AppCrypto;
 use sp_consensus::SyncOracle;
 use sp_consensus_slots::Slot;
+use sp_keystore::{Keystore, KeystorePtr};
 use std::time::Instant;
 
 // The max number of blocks we keep track of assignments gathering times. Normally,
@@ -102,12 +101,14 @@ pub mod criteria;
 mod import;
 mod ops;
 mod persisted_entries;
+#[cfg(feature = "simulator")]
+pub mod simulator;
 
 use crate::{
 	approval_checking::{Check, TranchesToApproveResult},
 	approval_db::common::{Config as DatabaseConfig, DbBackend},
 	backend::{Backend, OverlayedBackend},
-	criteria::InvalidAssignmentReason,
+	criteria::{InvalidAssignmentReason, OurAssignment},
 	persisted_entries::OurApproval,
 };
 
@@ -154,10 +155,10 @@ enum Mode {
 
 /// The approval voting subsystem.
 pub struct ApprovalVotingSubsystem {
-	/// `LocalKeystore` is needed for assignment keys, but not necessarily approval keys.
+	/// Keystore holding the assignment and approval keys.
 	///
-	/// We do a lot of VRF signing and need the keys to have low latency.
-	keystore: Arc<LocalKeystore>,
+	/// We do a lot of VRF signing, a remote signer should be reachable with low latency.
+	keystore: KeystorePtr,
 	db_config: DatabaseConfig,
 	slot_duration_millis: u64,
 	db: Arc<dyn Database>,
@@ -480,7 +481,7 @@ impl ApprovalVotingSubsystem {
 	pub fn with_config(
 		config: Config,
 		db: Arc<dyn Database>,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
 		spawner: Arc<dyn overseer::gen::Spawner + 'static>,
@@ -500,7 +501,7 @@ impl ApprovalVotingSubsystem {
 	pub fn with_config_and_clock(
 		config: Config,
 		db: Arc<dyn Database>,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
 		clock: Arc<dyn Clock + Send + Sync>,
@@ -828,7 +829,7 @@ where
 }
 
 struct State {
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	slot_duration_millis: u64,
 	clock: Arc<dyn Clock + Send + Sync>,
 	assignment_criteria: Box<dyn AssignmentCriteria + Send + Sync>,
@@ -1373,7 +1374,7 @@ pub async fn start_approval_worker<
 	to_approval_distr: ADSender,
 	config: Config,
 	db: Arc<dyn Database>,
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	metrics: Metrics,
 	spawner: Arc<dyn overseer::gen::Spawner + 'static>,
@@ -3096,28 +3097,47 @@ fn should_trigger_assignment(
 ) -> bool {
 	match approval_entry.our_assignment() {
 		None => false,
-		Some(ref assignment) if assignment.triggered() => false,
-		Some(ref assignment) if assignment.tranche() == 0 => true,
-		Some(ref assignment) => {
-			match required_tranches {
-				RequiredTranches::All => !approval_checking::check_approval(
-					&candidate_entry,
-					&approval_entry,
-					RequiredTranches::All,
-				)
-				// when all are required, we are just waiting for the first 1/3+
-				.is_approved(Tick::max_value()),
-				RequiredTranches::Pending { maximum_broadcast, clock_drift, .. } => {
-					let drifted_tranche_now =
-						tranche_now.saturating_sub(clock_drift as DelayTranche);
-					assignment.tranche() <= maximum_broadcast &&
-						assignment.tranche() <= drifted_tranche_now
-				},
-				RequiredTranches::Exact { .. } => {
-					// indicates that no new assignments are needed at the moment.
-					false
-				},
-			}
+		Some(assignment) => assignment_should_trigger(
+			assignment,
+			approval_entry,
+			candidate_entry,
+			required_tranches,
+			tranche_now,
+		),
+	}
+}
+
+/// Whether the given assignment should be triggered, based on the assignments and approvals
+/// already known for the candidate.
+fn assignment_should_trigger(
+	assignment: &OurAssignment,
+	approval_entry: &ApprovalEntry,
+	candidate_entry: &CandidateEntry,
+	required_tranches: RequiredTranches,
+	tranche_now: DelayTranche,
+) -> bool {
+	if assignment.triggered() {
+		return false
+	}
+	if assignment.tranche() == 0 {
+		return true
+	}
+
+	match required_tranches {
+		RequiredTranches::All => !approval_checking::check_approval(
+			&candidate_entry,
+			&approval_entry,
+			RequiredTranches::All,
+		)
+		// when all are required, we are just waiting for the first 1/3+
+		.is_approved(Tick::max_value()),
+		RequiredTranches::Pending { maximum_broadcast, clock_drift, .. } => {
+			let drifted_tranche_now = tranche_now.saturating_sub(clock_drift as DelayTranche);
+			assignment.tranche() <= maximum_broadcast && assignment.tranche() <= drifted_tranche_now
+		},
+		RequiredTranches::Exact { .. } => {
+			// indicates that no new assignments are needed at the moment.
+			false
 		},
 	}
 }
@@ -3814,16 +3834,18 @@ async fn maybe_create_signature<
 
 // Sign an approval vote. Fails if the key isn't present in the store.
 fn sign_approval(
-	keystore: &LocalKeystore,
+	keystore: &dyn Keystore,
 	public: &ValidatorId,
 	candidate_hashes: &[CandidateHash],
 	session_index: SessionIndex,
 ) -> Option<ValidatorSignature> {
-	let key = keystore.key_pair::<ValidatorPair>(public).ok().flatten()?;
-
 	let payload = ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session_index);
 
-	Some(key.sign(&payload[..]))
+	keystore
+		.sr25519_sign(ValidatorId::ID, public.as_ref(), &payload[..])
+		.ok()
+		.flatten()
+		.map(Into::into)
 }
 
 /// Send `IssueLocalStatement` to dispute-coordinator.
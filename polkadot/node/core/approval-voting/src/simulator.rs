@@ -0,0 +1,606 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline simulation of approval checking.
+//!
+//! Changes to the approval parameters of a session (`needed_approvals`, `n_delay_tranches`,
+//! `no_show_slots`, `relay_vrf_modulo_samples`, ...) are hard to evaluate on a live network. The
+//! simulator replays relay chain blocks under a configurable validator set, no-show rate and
+//! network latency, using the real assignment criteria to compute the assignments of every
+//! validator and the real tranche counting to decide when assignments are triggered and when
+//! candidates are approved.
+//!
+//! The network is modelled as a single view shared by all validators: an assignment or approval
+//! is seen by everyone after the configured latency.
+
+use crate::{
+	approval_checking::{self, RequiredTranches},
+	approval_db::v2::Bitfield,
+	assignment_should_trigger,
+	criteria::{self, OurAssignment},
+	persisted_entries::{ApprovalEntry, CandidateEntry},
+	APPROVAL_DELAY,
+};
+use polkadot_node_primitives::approval::{
+	time::{Tick, TICK_DURATION_MILLIS},
+	v1::{DelayTranche, RelayVRFStory},
+	v2::AssignmentCertKindV2,
+};
+use polkadot_primitives::{
+	vstaging::{CandidateDescriptorV2, CandidateReceiptV2 as CandidateReceipt},
+	AssignmentId, CandidateHash, CoreIndex, GroupIndex, Hash, Id as ParaId, IndexedVec,
+	ValidatorIndex, ASSIGNMENT_KEY_TYPE_ID,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sc_keystore::LocalKeystore;
+use sp_keystore::Keystore;
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	time::Duration,
+};
+
+/// How many times the no-show duration a candidate may stay unapproved before the simulation of
+/// its block is given up on.
+const MAX_NO_SHOW_PERIODS: Tick = 16;
+
+/// The parameters of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationConfig {
+	/// The number of validators in the session.
+	pub n_validators: u32,
+	/// The number of cores with a candidate included in every relay chain block.
+	pub n_cores: u32,
+	/// The number of approvals needed for a candidate without no-shows.
+	pub needed_approvals: u32,
+	/// The number of delay tranches.
+	pub n_delay_tranches: u32,
+	/// The width of the zeroth delay tranche.
+	pub zeroth_delay_tranche_width: u32,
+	/// The number of `RelayVRFModulo` samples.
+	pub relay_vrf_modulo_samples: u32,
+	/// The number of slots after which an assignment without an approval is a no-show.
+	pub no_show_slots: u32,
+	/// Whether validators use v2 assignments, which coalesce their tranche zero assignments into
+	/// a single certificate.
+	pub enable_v2_assignments: bool,
+	/// The maximum number of approvals a validator sends in a single message.
+	pub max_approval_coalesce_count: u32,
+	/// The probability that a triggered assignment is never followed by an approval.
+	pub no_show_rate: f64,
+	/// The time it takes an assignment or approval to reach the other validators.
+	pub network_latency: Duration,
+	/// The time it takes a validator to recover and validate a candidate.
+	pub approval_check_time: Duration,
+	/// The duration of a relay chain slot.
+	pub slot_duration: Duration,
+	/// The number of relay chain blocks to simulate.
+	pub n_blocks: u32,
+	/// The seed of all randomness used by the simulation.
+	pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+	fn default() -> Self {
+		Self {
+			n_validators: 300,
+			n_cores: 50,
+			needed_approvals: 30,
+			n_delay_tranches: 89,
+			zeroth_delay_tranche_width: 0,
+			relay_vrf_modulo_samples: 6,
+			no_show_slots: 2,
+			enable_v2_assignments: true,
+			max_approval_coalesce_count: 1,
+			no_show_rate: 0.01,
+			network_latency: Duration::from_millis(500),
+			approval_check_time: Duration::from_secs(2),
+			slot_duration: Duration::from_secs(6),
+			n_blocks: 200,
+			seed: 0,
+		}
+	}
+}
+
+/// A simulation could not be run.
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SimulationError {
+	/// Every core needs a backing group with at least one validator.
+	#[error("{n_cores} cores need at least as many validators, got {n_validators}")]
+	NotEnoughValidators {
+		/// The configured number of validators.
+		n_validators: u32,
+		/// The configured number of cores.
+		n_cores: u32,
+	},
+	/// A configuration value is out of range.
+	#[error("invalid simulation config: {0}")]
+	InvalidConfig(&'static str),
+}
+
+/// The results of a simulation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationReport {
+	/// For every simulated block, the time from its slot starting until it and all of its
+	/// ancestors were approved, or `None` if that didn't happen within the simulated time.
+	pub finality_lags: Vec<Option<Duration>>,
+	/// For every simulated block, the time from its slot starting until its own candidates were
+	/// approved, or `None` if that didn't happen within the simulated time.
+	pub approval_lags: Vec<Option<Duration>>,
+	/// The number of assignment messages sent.
+	pub assignment_messages: u64,
+	/// The number of approval messages sent.
+	pub approval_messages: u64,
+	/// The number of triggered assignments which were never followed by an approval.
+	pub no_shows: u64,
+	/// The highest delay tranche that was triggered.
+	pub max_triggered_tranche: DelayTranche,
+}
+
+impl SimulationReport {
+	/// The number of blocks which were not approved within the simulated time.
+	///
+	/// Only the candidates of the block itself are considered. A block following an unapproved
+	/// one is approved when its own candidates are, even though it can't be finalized.
+	pub fn unapproved_blocks(&self) -> usize {
+		self.approval_lags.iter().filter(|lag| lag.is_none()).count()
+	}
+
+	/// The finality lag below which the given fraction (between 0 and 1) of blocks fall.
+	///
+	/// Unapproved blocks count as having an infinite lag, for which `None` is returned.
+	pub fn finality_lag_percentile(&self, fraction: f64) -> Option<Duration> {
+		if self.finality_lags.is_empty() {
+			return None
+		}
+
+		let mut lags = self.finality_lags.clone();
+		// `None` sorts first, move unapproved blocks to the end instead.
+		lags.sort_by_key(|lag| (lag.is_none(), *lag));
+		let index = ((lags.len() as f64 * fraction).ceil() as usize).clamp(1, lags.len()) - 1;
+		lags[index]
+	}
+
+	/// The average number of assignment messages sent per block.
+	pub fn assignment_messages_per_block(&self) -> f64 {
+		self.assignment_messages as f64 / self.finality_lags.len().max(1) as f64
+	}
+
+	/// The average number of approval messages sent per block.
+	pub fn approval_messages_per_block(&self) -> f64 {
+		self.approval_messages as f64 / self.finality_lags.len().max(1) as f64
+	}
+}
+
+/// Replay `config.n_blocks` relay chain blocks and report how long they took to be approved.
+pub fn simulate(config: &SimulationConfig) -> Result<SimulationReport, SimulationError> {
+	Simulation::new(config)?.run()
+}
+
+enum Event {
+	Assignment { core: usize, validator: ValidatorIndex, tranche: DelayTranche },
+	Approval { core: usize, validator: ValidatorIndex },
+}
+
+struct BlockOutcome {
+	approved_after: Option<Tick>,
+	assignment_messages: u64,
+	approval_messages: u64,
+	no_shows: u64,
+	max_triggered_tranche: DelayTranche,
+}
+
+struct Simulation<'a> {
+	config: &'a SimulationConfig,
+	keystores: Vec<LocalKeystore>,
+	criteria_config: criteria::Config,
+	latency: Tick,
+	check_time: Tick,
+	no_show_duration: Tick,
+	ticks_per_slot: Tick,
+}
+
+impl<'a> Simulation<'a> {
+	fn new(config: &'a SimulationConfig) -> Result<Self, SimulationError> {
+		if config.n_cores == 0 || config.n_validators < config.n_cores {
+			return Err(SimulationError::NotEnoughValidators {
+				n_validators: config.n_validators,
+				n_cores: config.n_cores,
+			})
+		}
+		if config.n_delay_tranches == 0 {
+			return Err(SimulationError::InvalidConfig("at least one delay tranche is needed"))
+		}
+		if config.max_approval_coalesce_count == 0 {
+			return Err(SimulationError::InvalidConfig("approvals must be sent in some message"))
+		}
+		if !(0.0..=1.0).contains(&config.no_show_rate) {
+			return Err(SimulationError::InvalidConfig("the no-show rate must be between 0 and 1"))
+		}
+		let ticks_per_slot = config.slot_duration.as_millis() as Tick / TICK_DURATION_MILLIS;
+		if ticks_per_slot == 0 {
+			return Err(SimulationError::InvalidConfig("slots must be at least one tick long"))
+		}
+
+		let mut assignment_keys = Vec::with_capacity(config.n_validators as usize);
+		let keystores = (0..config.n_validators)
+			.map(|index| {
+				let keystore = LocalKeystore::in_memory();
+				let public = keystore
+					.sr25519_generate_new(
+						ASSIGNMENT_KEY_TYPE_ID,
+						Some(&format!("//Validator{}", index)),
+					)
+					.expect("in-memory keystores can always store keys; qed");
+				assignment_keys.push(AssignmentId::from(public));
+				keystore
+			})
+			.collect();
+
+		// Contiguous backing groups, one per core.
+		let group_size = config.n_validators.div_ceil(config.n_cores);
+		let validator_groups = (0..config.n_validators)
+			.map(ValidatorIndex)
+			.collect::<Vec<_>>()
+			.chunks(group_size as usize)
+			.map(|group| group.to_vec())
+			.collect::<Vec<_>>();
+
+		Ok(Self {
+			config,
+			keystores,
+			criteria_config: criteria::Config {
+				assignment_keys,
+				validator_groups: IndexedVec::from(validator_groups),
+				n_cores: config.n_cores,
+				zeroth_delay_tranche_width: config.zeroth_delay_tranche_width,
+				relay_vrf_modulo_samples: config.relay_vrf_modulo_samples,
+				n_delay_tranches: config.n_delay_tranches,
+			},
+			latency: to_ticks(config.network_latency),
+			check_time: to_ticks(config.approval_check_time),
+			no_show_duration: config.no_show_slots as Tick * ticks_per_slot,
+			ticks_per_slot,
+		})
+	}
+
+	fn run(&self) -> Result<SimulationReport, SimulationError> {
+		let mut rng = ChaCha20Rng::seed_from_u64(self.config.seed);
+		let mut report = SimulationReport::default();
+		// The tick at which all blocks simulated so far were approved.
+		let mut chain_approved_at = Some(0);
+
+		for block_number in 0..self.config.n_blocks {
+			let block_tick = block_number as Tick * self.ticks_per_slot;
+			let outcome = self.simulate_block(block_number, &mut rng);
+			let to_lag = |ticks: Tick| Duration::from_millis(ticks * TICK_DURATION_MILLIS);
+
+			report.approval_lags.push(outcome.approved_after.map(to_lag));
+			chain_approved_at = chain_approved_at
+				.zip(outcome.approved_after)
+				.map(|(chain, block)| chain.max(block_tick + block));
+			report
+				.finality_lags
+				.push(chain_approved_at.map(|tick| to_lag(tick - block_tick)));
+			report.assignment_messages += outcome.assignment_messages;
+			report.approval_messages += outcome.approval_messages;
+			report.no_shows += outcome.no_shows;
+			report.max_triggered_tranche =
+				report.max_triggered_tranche.max(outcome.max_triggered_tranche);
+		}
+
+		Ok(report)
+	}
+
+	/// Compute the assignments of every validator for a block, in parallel.
+	fn compute_assignments(
+		&self,
+		relay_vrf_story: &RelayVRFStory,
+		leaving_cores: &[(CandidateHash, CoreIndex, GroupIndex)],
+	) -> Vec<HashMap<CoreIndex, OurAssignment>> {
+		let n_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+		let chunk_size = self.keystores.len().div_ceil(n_threads);
+
+		std::thread::scope(|scope| {
+			let handles = self
+				.keystores
+				.chunks(chunk_size)
+				.map(|keystores| {
+					scope.spawn(move || {
+						keystores
+							.iter()
+							.map(|keystore| {
+								criteria::compute_assignments(
+									keystore,
+									relay_vrf_story.clone(),
+									&self.criteria_config,
+									leaving_cores.iter().cloned(),
+									self.config.enable_v2_assignments,
+								)
+							})
+							.collect::<Vec<_>>()
+					})
+				})
+				.collect::<Vec<_>>();
+
+			handles
+				.into_iter()
+				.flat_map(|handle| handle.join().expect("computing assignments doesn't panic; qed"))
+				.collect()
+		})
+	}
+
+	fn simulate_block(&self, block_number: u32, rng: &mut ChaCha20Rng) -> BlockOutcome {
+		let n_validators = self.config.n_validators as usize;
+		let n_groups = self.criteria_config.validator_groups.len() as u32;
+		let relay_vrf_story = RelayVRFStory(rng.gen());
+		let leaving_cores = (0..self.config.n_cores)
+			.map(|core| {
+				// Rotate the backing groups over the cores from block to block.
+				let group = GroupIndex((core + block_number) % n_groups);
+				(CandidateHash(Hash::from(rng.gen::<[u8; 32]>())), CoreIndex(core), group)
+			})
+			.collect::<Vec<_>>();
+
+		// The untriggered assignments to every core.
+		let mut assignments = vec![Vec::new(); leaving_cores.len()];
+		for (validator, validator_assignments) in self
+			.compute_assignments(&relay_vrf_story, &leaving_cores)
+			.into_iter()
+			.enumerate()
+		{
+			for (core, assignment) in validator_assignments {
+				assignments[core.0 as usize].push((ValidatorIndex(validator as u32), assignment));
+			}
+		}
+
+		let mut candidates = leaving_cores
+			.iter()
+			.map(|(_, core, group)| {
+				(
+					ApprovalEntry::new(
+						Vec::new(),
+						*group,
+						None,
+						None,
+						Bitfield::repeat(false, n_validators),
+						false,
+					),
+					dummy_candidate_entry(*core, n_validators),
+				)
+			})
+			.collect::<Vec<_>>();
+		let mut approved_at = vec![None; candidates.len()];
+
+		let mut events = BTreeMap::<Tick, Vec<Event>>::new();
+		// Assignment messages already sent, a compact certificate covers several cores.
+		let mut assignment_messages = HashSet::new();
+		// The number of approvals every validator has ready to send at a tick.
+		let mut pending_approvals = HashMap::<(Tick, ValidatorIndex), u32>::new();
+		let mut no_shows = 0;
+		let mut max_triggered_tranche = 0;
+
+		let max_tick = self.config.n_delay_tranches as Tick +
+			self.latency +
+			self.check_time +
+			MAX_NO_SHOW_PERIODS * self.no_show_duration;
+		for tick in 0..=max_tick {
+			for event in events.remove(&tick).into_iter().flatten() {
+				match event {
+					Event::Assignment { core, validator, tranche } =>
+						candidates[core].0.import_assignment(tranche, validator, tick),
+					Event::Approval { core, validator } => {
+						let _ = candidates[core].1.mark_approval(validator);
+					},
+				}
+			}
+
+			let tranche_now = tick as DelayTranche;
+			for (core, (approval_entry, candidate_entry)) in candidates.iter().enumerate() {
+				if approved_at[core].is_some() {
+					continue
+				}
+
+				let required_tranches = approval_checking::tranches_to_approve(
+					approval_entry,
+					candidate_entry.approvals(),
+					tranche_now,
+					0,
+					self.no_show_duration,
+					self.config.needed_approvals as usize,
+				)
+				.required_tranches;
+				let check = approval_checking::check_approval(
+					candidate_entry,
+					approval_entry,
+					required_tranches.clone(),
+				);
+				if check.is_approved(tick.saturating_sub(APPROVAL_DELAY)) {
+					approved_at[core] = Some(tick);
+					continue
+				}
+
+				for (validator, assignment) in assignments[core].iter_mut() {
+					if !assignment_should_trigger(
+						assignment,
+						approval_entry,
+						candidate_entry,
+						required_tranches.clone(),
+						tranche_now,
+					) {
+						continue
+					}
+					assignment.mark_triggered();
+					max_triggered_tranche = max_triggered_tranche.max(assignment.tranche());
+
+					let message = match assignment.cert().kind {
+						AssignmentCertKindV2::RelayVRFModuloCompact { .. } => None,
+						_ => Some(core),
+					};
+					let _ = assignment_messages.insert((*validator, message));
+					events.entry(tick + self.latency).or_default().push(Event::Assignment {
+						core,
+						validator: *validator,
+						tranche: assignment.tranche(),
+					});
+
+					if rng.gen_bool(self.config.no_show_rate) {
+						no_shows += 1;
+					} else {
+						let sent_at = tick + self.check_time;
+						*pending_approvals.entry((sent_at, *validator)).or_default() += 1;
+						events
+							.entry(sent_at + self.latency)
+							.or_default()
+							.push(Event::Approval { core, validator: *validator });
+					}
+				}
+			}
+
+			if approved_at.iter().all(Option::is_some) {
+				break
+			}
+		}
+
+		let coalesce = self.config.max_approval_coalesce_count;
+		BlockOutcome {
+			approved_after: approved_at
+				.into_iter()
+				.try_fold(0, |latest, approved| approved.map(|approved| latest.max(approved))),
+			assignment_messages: assignment_messages.len() as u64,
+			approval_messages: pending_approvals
+				.values()
+				.map(|approvals| approvals.div_ceil(coalesce) as u64)
+				.sum(),
+			no_shows,
+			max_triggered_tranche,
+		}
+	}
+}
+
+fn to_ticks(duration: Duration) -> Tick {
+	(duration.as_millis() as Tick).div_ceil(TICK_DURATION_MILLIS)
+}
+
+/// Candidate entries only need a receipt to be persisted, which the simulation never does.
+fn dummy_candidate_entry(core: CoreIndex, n_validators: usize) -> CandidateEntry {
+	let descriptor = CandidateDescriptorV2::new(
+		ParaId::from(core.0),
+		Hash::zero(),
+		core,
+		0,
+		Hash::zero(),
+		Hash::zero(),
+		Hash::zero(),
+		Hash::zero(),
+		Hash::zero().into(),
+	);
+
+	CandidateEntry {
+		candidate: CandidateReceipt { descriptor, commitments_hash: Hash::zero() },
+		session: 0,
+		block_assignments: BTreeMap::new(),
+		approvals: Bitfield::repeat(false, n_validators),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn small_config() -> SimulationConfig {
+		SimulationConfig {
+			n_validators: 30,
+			n_cores: 3,
+			needed_approvals: 5,
+			n_delay_tranches: 20,
+			relay_vrf_modulo_samples: 2,
+			no_show_rate: 0.0,
+			n_blocks: 8,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn honest_validators_approve_every_block() {
+		let config = small_config();
+		let report = simulate(&config).unwrap();
+
+		assert_eq!(report.finality_lags.len(), 8);
+		assert_eq!(report.unapproved_blocks(), 0);
+		// Without unapproved blocks, every block is finalized once its own candidates are.
+		assert_eq!(report.finality_lags, report.approval_lags);
+		assert_eq!(report.no_shows, 0);
+		// Nothing can be approved before an approval was checked and reached the others.
+		let earliest = config.approval_check_time + config.network_latency;
+		assert!(report.finality_lags.iter().all(|lag| lag.unwrap() >= earliest));
+		assert!(report.approval_messages >= 8 * 3 * 5);
+	}
+
+	#[test]
+	fn no_shows_delay_finality() {
+		let honest = simulate(&small_config()).unwrap();
+		let flaky = simulate(&SimulationConfig { no_show_rate: 0.5, ..small_config() }).unwrap();
+
+		assert!(flaky.no_shows > 0);
+		assert!(
+			flaky.finality_lag_percentile(0.5).unwrap() >
+				honest.finality_lag_percentile(1.0).unwrap()
+		);
+	}
+
+	#[test]
+	fn v2_assignments_need_fewer_messages() {
+		let v1 =
+			simulate(&SimulationConfig { enable_v2_assignments: false, ..small_config() }).unwrap();
+		let v2 = simulate(&small_config()).unwrap();
+
+		assert!(v2.assignment_messages < v1.assignment_messages);
+	}
+
+	#[test]
+	fn unapproved_blocks_are_the_slowest() {
+		let report = SimulationReport {
+			finality_lags: vec![
+				Some(Duration::from_secs(3)),
+				None,
+				Some(Duration::from_secs(1)),
+				Some(Duration::from_secs(2)),
+			],
+			approval_lags: vec![
+				Some(Duration::from_secs(3)),
+				None,
+				Some(Duration::from_secs(1)),
+				Some(Duration::from_secs(2)),
+			],
+			..Default::default()
+		};
+
+		assert_eq!(report.unapproved_blocks(), 1);
+		assert_eq!(report.finality_lag_percentile(0.0), Some(Duration::from_secs(1)));
+		assert_eq!(report.finality_lag_percentile(0.5), Some(Duration::from_secs(2)));
+		assert_eq!(report.finality_lag_percentile(0.75), Some(Duration::from_secs(3)));
+		assert_eq!(report.finality_lag_percentile(1.0), None);
+	}
+
+	#[test]
+	fn every_core_needs_a_backing_group() {
+		assert_eq!(
+			simulate(&SimulationConfig { n_validators: 2, n_cores: 3, ..small_config() }),
+			Err(SimulationError::NotEnoughValidators { n_validators: 2, n_cores: 3 }),
+		);
+	}
+}
@@ -51,6 +51,7 @@ use std::{cmp::max, time::Duration};
 
 use assert_matches::assert_matches;
 use parking_lot::Mutex;
+use sc_keystore::LocalKeystore;
 use sp_keyring::sr25519::Keyring as Sr25519Keyring;
 use sp_keystore::Keystore;
 use std::{
@@ -236,7 +237,7 @@ where
 {
 	fn compute_assignments(
 		&self,
-		_keystore: &LocalKeystore,
+		_keystore: &dyn Keystore,
 		_relay_vrf_story: polkadot_node_primitives::approval::v1::RelayVRFStory,
 		_config: &criteria::Config,
 		_leaving_cores: Vec<(
@@ -48,13 +48,17 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_util as util;
 use polkadot_primitives::{
 	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt},
-	BlockNumber, CandidateHash, ChunkIndex, CoreIndex, Hash, Header, NodeFeatures, ValidatorIndex,
+	BlockNumber, CandidateHash, ChunkIndex, CoreIndex, Hash, Header, Id as ParaId, NodeFeatures,
+	ValidatorIndex,
 };
 use util::availability_chunks::availability_chunk_indices;
 
 mod metrics;
 pub use self::metrics::*;
 
+mod offline;
+pub use self::offline::{CandidateInfo, CandidateStatus, OfflineStore};
+
 #[cfg(test)]
 mod tests;
 
@@ -67,6 +71,8 @@ const CHUNK_PREFIX: &[u8; 5] = b"chunk";
 const META_PREFIX: &[u8; 4] = b"meta";
 const UNFINALIZED_PREFIX: &[u8; 11] = b"unfinalized";
 const PRUNE_BY_TIME_PREFIX: &[u8; 13] = b"prune_by_time";
+const RETAIN_FOR_PREFIX: &[u8; 10] = b"retain_for";
+const PINNED_PREFIX: &[u8; 6] = b"pinned";
 
 // We have some keys we want to map to empty values because existence of the key is enough. We use
 // this because rocksdb doesn't support empty values.
@@ -260,6 +266,49 @@ fn delete_meta(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
 	tx.delete(config.col_meta, &key[..])
 }
 
+fn load_retention(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	hash: &CandidateHash,
+) -> Result<Option<Duration>, Error> {
+	let key = (RETAIN_FOR_PREFIX, hash).encode();
+
+	query_inner::<u64>(db, config.col_meta, &key).map(|secs| secs.map(Duration::from_secs))
+}
+
+fn write_retention(
+	tx: &mut DBTransaction,
+	config: &Config,
+	hash: &CandidateHash,
+	keep_for: Duration,
+) {
+	let key = (RETAIN_FOR_PREFIX, hash).encode();
+
+	tx.put_vec(config.col_meta, &key, keep_for.as_secs().encode());
+}
+
+fn delete_retention(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (RETAIN_FOR_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..])
+}
+
+fn is_pinned(db: &Arc<dyn Database>, config: &Config, hash: &CandidateHash) -> Result<bool, Error> {
+	let key = (PINNED_PREFIX, hash).encode();
+
+	Ok(db.get(config.col_meta, &key)?.is_some())
+}
+
+fn write_pin(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (PINNED_PREFIX, hash).encode();
+
+	tx.put(config.col_meta, &key, TOMBSTONE_VALUE);
+}
+
+fn delete_pin(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (PINNED_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..])
+}
+
 fn delete_unfinalized_height(tx: &mut DBTransaction, config: &Config, block_number: BlockNumber) {
 	let prefix = (UNFINALIZED_PREFIX, BEBlockNumber(block_number)).encode();
 	tx.delete_prefix(config.col_meta, &prefix);
@@ -419,6 +468,9 @@ struct PruningConfig {
 	/// How long finalized data should be kept.
 	keep_finalized_for: Duration,
 
+	/// Per-parachain overrides of `keep_finalized_for`.
+	keep_finalized_for_para: HashMap<ParaId, Duration>,
+
 	/// How often to perform data pruning.
 	pruning_interval: Duration,
 }
@@ -428,6 +480,7 @@ impl Default for PruningConfig {
 		Self {
 			keep_unavailable_for: KEEP_UNAVAILABLE_FOR,
 			keep_finalized_for: KEEP_FINALIZED_FOR,
+			keep_finalized_for_para: HashMap::new(),
 			pruning_interval: PRUNING_INTERVAL,
 		}
 	}
@@ -505,6 +558,16 @@ impl AvailabilityStoreSubsystem {
 			finalized_number: None,
 		}
 	}
+
+	/// Keep the data of candidates of the given parachains for a different amount of time after
+	/// finality than the default.
+	///
+	/// Applies to candidates included from now on, what is already in the store keeps the
+	/// retention it was included with.
+	pub fn with_para_retention(mut self, keep_finalized_for: HashMap<ParaId, Duration>) -> Self {
+		self.pruning_config.keep_finalized_for_para = keep_finalized_for;
+		self
+	}
 }
 
 /// We keep the hashes and numbers of all unfinalized
@@ -858,6 +921,12 @@ fn note_block_included(
 				},
 			};
 
+			if let Some(keep_for) =
+				pruning_config.keep_finalized_for_para.get(&candidate.descriptor.para_id())
+			{
+				write_retention(db_transaction, config, &candidate_hash, *keep_for);
+			}
+
 			write_unfinalized_block_contains(
 				db_transaction,
 				config,
@@ -1053,14 +1122,12 @@ fn update_blocks_at_finalized_height(
 
 			meta.state = State::Finalized(now.into());
 
+			let keep_for = load_retention(&subsystem.db, &subsystem.config, &candidate_hash)?
+				.unwrap_or(subsystem.pruning_config.keep_finalized_for);
+
 			// Write the meta and a pruning record.
 			write_meta(db_transaction, &subsystem.config, &candidate_hash, &meta);
-			write_pruning_key(
-				db_transaction,
-				&subsystem.config,
-				now + subsystem.pruning_config.keep_finalized_for,
-				&candidate_hash,
-			);
+			write_pruning_key(db_transaction, &subsystem.config, now + keep_for, &candidate_hash);
 		} else {
 			meta.state = match meta.state {
 				State::Finalized(_) => continue,   // sanity.
@@ -1368,14 +1435,25 @@ fn prune_all(db: &Arc<dyn Database>, config: &Config, now: Duration) -> Result<(
 
 	for r in iter {
 		let (k, _v) = r?;
-		tx.delete(config.col_meta, &k[..]);
 
 		let (_, candidate_hash) = match decode_pruning_key(&k[..]) {
 			Ok(m) => m,
-			Err(_) => continue, // sanity
+			Err(_) => {
+				tx.delete(config.col_meta, &k[..]);
+				continue // sanity
+			},
 		};
 
+		// Pinned candidates keep their pruning key, so that they get pruned on the first run
+		// after being unpinned.
+		if is_pinned(db, config, &candidate_hash)? {
+			gum::trace!(target: LOG_TARGET, ?candidate_hash, "Not pruning pinned candidate");
+			continue
+		}
+
+		tx.delete(config.col_meta, &k[..]);
 		delete_meta(&mut tx, config, &candidate_hash);
+		delete_retention(&mut tx, config, &candidate_hash);
 
 		// Clean up all attached data of the candidate.
 		if let Some(meta) = load_meta(db, config, &candidate_hash)? {
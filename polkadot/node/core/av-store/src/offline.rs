@@ -0,0 +1,161 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Access to the availability store of a node that is not running.
+//!
+//! Useful for debugging: inspecting what the store knows about a candidate, exporting its data and
+//! keeping it around past the usual retention period.
+
+use super::*;
+
+/// Where a candidate is in its lifecycle, as far as the availability store is concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandidateStatus {
+	/// The candidate was backed but isn't included in any known block.
+	Unavailable,
+	/// The candidate is included in the given number of unfinalized blocks.
+	Unfinalized(usize),
+	/// The candidate is included in a finalized block.
+	Finalized,
+}
+
+/// What the availability store knows about a candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateInfo {
+	/// The status of the candidate.
+	pub status: CandidateStatus,
+	/// Whether the full `AvailableData` is stored.
+	pub data_available: bool,
+	/// The number of stored chunks.
+	pub chunks_stored: usize,
+	/// The number of validators the candidate was erasure coded for.
+	pub n_validators: usize,
+	/// Whether the candidate is exempt from pruning.
+	pub pinned: bool,
+}
+
+/// The availability store database, opened outside of the subsystem.
+///
+/// Must not be used on a database that a running node has open.
+pub struct OfflineStore {
+	db: Arc<dyn Database>,
+	config: Config,
+}
+
+impl OfflineStore {
+	/// Wrap an already opened database.
+	pub fn new(db: Arc<dyn Database>, config: Config) -> Self {
+		Self { db, config }
+	}
+
+	/// Get what the store knows about a candidate, or `None` if it is unknown.
+	pub fn candidate_info(
+		&self,
+		candidate_hash: &CandidateHash,
+	) -> Result<Option<CandidateInfo>, Error> {
+		let meta = match load_meta(&self.db, &self.config, candidate_hash)? {
+			None => return Ok(None),
+			Some(meta) => meta,
+		};
+
+		let status = match meta.state {
+			State::Unavailable(_) => CandidateStatus::Unavailable,
+			State::Unfinalized(_, blocks) => CandidateStatus::Unfinalized(blocks.len()),
+			State::Finalized(_) => CandidateStatus::Finalized,
+		};
+
+		Ok(Some(CandidateInfo {
+			status,
+			data_available: meta.data_available,
+			chunks_stored: meta.chunks_stored.count_ones(),
+			n_validators: meta.chunks_stored.len(),
+			pinned: is_pinned(&self.db, &self.config, candidate_hash)?,
+		}))
+	}
+
+	/// Get the `AvailableData` of a candidate, if it is stored in full.
+	pub fn available_data(
+		&self,
+		candidate_hash: &CandidateHash,
+	) -> Result<Option<AvailableData>, Error> {
+		load_available_data(&self.db, &self.config, candidate_hash)
+	}
+
+	/// Reconstruct the `AvailableData` of a candidate from the chunks in the store.
+	///
+	/// Returns `None` if the candidate is unknown and an erasure coding error if there are not
+	/// enough chunks.
+	pub fn reconstruct_available_data(
+		&self,
+		candidate_hash: &CandidateHash,
+	) -> Result<Option<AvailableData>, Error> {
+		let meta = match load_meta(&self.db, &self.config, candidate_hash)? {
+			None => return Ok(None),
+			Some(meta) => meta,
+		};
+
+		let mut chunks = Vec::with_capacity(meta.chunks_stored.count_ones());
+		for (i, stored) in meta.chunks_stored.iter().enumerate() {
+			if !*stored {
+				continue
+			}
+
+			if let Some(chunk) =
+				load_chunk(&self.db, &self.config, candidate_hash, ValidatorIndex(i as _))?
+			{
+				chunks.push(chunk);
+			}
+		}
+
+		let data = polkadot_erasure_coding::reconstruct_v1(
+			meta.chunks_stored.len(),
+			chunks.iter().map(|chunk| (&chunk.chunk[..], chunk.index.0 as usize)),
+		)?;
+
+		Ok(Some(data))
+	}
+
+	/// Exempt a candidate from pruning.
+	///
+	/// Returns `false` if the candidate is unknown.
+	pub fn pin(&self, candidate_hash: &CandidateHash) -> Result<bool, Error> {
+		if load_meta(&self.db, &self.config, candidate_hash)?.is_none() {
+			return Ok(false)
+		}
+
+		let mut tx = DBTransaction::new();
+		write_pin(&mut tx, &self.config, candidate_hash);
+		self.db.write(tx)?;
+
+		Ok(true)
+	}
+
+	/// Allow a pinned candidate to be pruned again. If its retention period is over, it is pruned
+	/// on the next pruning run of the subsystem.
+	///
+	/// Returns `false` if the candidate wasn't pinned.
+	pub fn unpin(&self, candidate_hash: &CandidateHash) -> Result<bool, Error> {
+		if !is_pinned(&self.db, &self.config, candidate_hash)? {
+			return Ok(false)
+		}
+
+		let mut tx = DBTransaction::new();
+		delete_pin(&mut tx, &self.config, candidate_hash);
+		self.db.write(tx)?;
+
+		Ok(true)
+	}
+}
@@ -96,6 +96,7 @@ impl Default for TestState {
 		let pruning_config = PruningConfig {
 			keep_unavailable_for: Duration::from_secs(1),
 			keep_finalized_for: Duration::from_secs(2),
+			keep_finalized_for_para: HashMap::new(),
 			pruning_interval: Duration::from_millis(250),
 		};
 
@@ -899,6 +900,194 @@ fn stored_data_kept_until_finalized() {
 	});
 }
 
+#[test]
+fn para_retention_override_is_used_after_finality() {
+	let store = test_store();
+	let mut test_state = TestState::default();
+	let para_id = ParaId::from(2000);
+	let keep_para_for = test_state.pruning_config.keep_finalized_for * 5;
+	test_state.pruning_config.keep_finalized_for_para.insert(para_id, keep_para_for);
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+
+		let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+		let candidate =
+			TestCandidateBuilder { para_id, pov_hash: pov.hash(), ..Default::default() }.build();
+		let candidate_hash = candidate.hash();
+
+		let available_data = AvailableData {
+			pov: Arc::new(pov),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		send_available_data(&mut virtual_overseer, candidate_hash, n_validators, &available_data)
+			.await;
+
+		let parent = Hash::repeat_byte(2);
+		let block_number = 10;
+
+		let a_leaf = import_leaf(
+			&mut virtual_overseer,
+			parent,
+			block_number,
+			vec![candidate_included(candidate)],
+			(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect(),
+		)
+		.await;
+
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::BlockFinalized(a_leaf, block_number),
+		)
+		.await;
+
+		// Past the default retention, but not the one of the para.
+		test_state.clock.inc(test_state.pruning_config.keep_finalized_for * 2);
+		test_state.wait_for_pruning().await;
+
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, candidate_hash).await.unwrap(),
+			available_data,
+		);
+		assert!(has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators, true).await);
+
+		test_state.clock.inc(keep_para_for);
+		test_state.wait_for_pruning().await;
+
+		assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+		assert!(has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators, false).await);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn pinned_candidate_is_pruned_only_after_unpinning() {
+	let store = test_store();
+	let test_state = TestState::default();
+	let offline = OfflineStore::new(store.clone(), TEST_CONFIG);
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+		let n_validators = 10;
+
+		let available_data = AvailableData {
+			pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		assert!(!offline.pin(&candidate_hash).unwrap());
+
+		send_available_data(&mut virtual_overseer, candidate_hash, n_validators, &available_data)
+			.await;
+
+		assert!(offline.pin(&candidate_hash).unwrap());
+
+		// Never included, so it would normally be gone by now.
+		test_state.clock.inc(test_state.pruning_config.keep_unavailable_for * 10);
+		test_state.wait_for_pruning().await;
+
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, candidate_hash).await.unwrap(),
+			available_data,
+		);
+		assert!(offline.candidate_info(&candidate_hash).unwrap().unwrap().pinned);
+
+		assert!(offline.unpin(&candidate_hash).unwrap());
+		assert!(!offline.unpin(&candidate_hash).unwrap());
+		test_state.wait_for_pruning().await;
+
+		assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+		assert!(offline.candidate_info(&candidate_hash).unwrap().is_none());
+		virtual_overseer
+	});
+}
+
+#[test]
+fn offline_store_reconstructs_from_chunks() {
+	let store = test_store();
+	let offline = OfflineStore::new(store.clone(), TEST_CONFIG);
+
+	let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+	let n_validators = 10;
+	let threshold = polkadot_erasure_coding::recovery_threshold(n_validators).unwrap();
+
+	let available_data = AvailableData {
+		pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+		validation_data: TestState::default().persisted_validation_data,
+	};
+	let chunks = polkadot_erasure_coding::obtain_chunks_v1(n_validators, &available_data).unwrap();
+
+	assert!(offline.candidate_info(&candidate_hash).unwrap().is_none());
+	assert!(offline.reconstruct_available_data(&candidate_hash).unwrap().is_none());
+
+	// Store chunks of the last validators only, one short of the threshold.
+	let mut chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators];
+	with_tx(&store, |tx| {
+		for i in (n_validators - threshold + 1)..n_validators {
+			let chunk = ErasureChunk {
+				chunk: chunks[i].clone(),
+				index: ChunkIndex(i as _),
+				proof: Proof::try_from(vec![vec![0]]).unwrap(),
+			};
+			super::write_chunk(tx, &TEST_CONFIG, &candidate_hash, ValidatorIndex(i as _), &chunk);
+			chunks_stored.set(i, true);
+		}
+		super::write_meta(
+			tx,
+			&TEST_CONFIG,
+			&candidate_hash,
+			&CandidateMeta {
+				data_available: false,
+				chunks_stored: chunks_stored.clone(),
+				state: State::Finalized(BETimestamp(0)),
+			},
+		);
+	});
+
+	assert_eq!(
+		offline.candidate_info(&candidate_hash).unwrap(),
+		Some(CandidateInfo {
+			status: CandidateStatus::Finalized,
+			data_available: false,
+			chunks_stored: threshold - 1,
+			n_validators,
+			pinned: false,
+		}),
+	);
+	assert!(offline.available_data(&candidate_hash).unwrap().is_none());
+	assert_matches!(
+		offline.reconstruct_available_data(&candidate_hash),
+		Err(Error::Erasure(polkadot_erasure_coding::Error::NotEnoughChunks))
+	);
+
+	let i = n_validators - threshold;
+	with_tx(&store, |tx| {
+		let chunk = ErasureChunk {
+			chunk: chunks[i].clone(),
+			index: ChunkIndex(i as _),
+			proof: Proof::try_from(vec![vec![0]]).unwrap(),
+		};
+		super::write_chunk(tx, &TEST_CONFIG, &candidate_hash, ValidatorIndex(i as _), &chunk);
+		chunks_stored.set(i, true);
+		super::write_meta(
+			tx,
+			&TEST_CONFIG,
+			&candidate_hash,
+			&CandidateMeta {
+				data_available: false,
+				chunks_stored,
+				state: State::Finalized(BETimestamp(0)),
+			},
+		);
+	});
+
+	assert_eq!(
+		offline.reconstruct_available_data(&candidate_hash).unwrap().unwrap(),
+		available_data,
+	);
+}
+
 #[test]
 fn we_dont_miss_anything_if_import_notifications_are_missed() {
 	let store = test_store();
@@ -1233,6 +1422,31 @@ async fn query_available_data(
 	rx.await.unwrap()
 }
 
+async fn send_available_data(
+	virtual_overseer: &mut VirtualOverseer,
+	candidate_hash: CandidateHash,
+	n_validators: u32,
+	available_data: &AvailableData,
+) {
+	let chunks =
+		polkadot_erasure_coding::obtain_chunks_v1(n_validators as _, available_data).unwrap();
+	let branches = polkadot_erasure_coding::branches(chunks.as_ref());
+
+	let (tx, rx) = oneshot::channel();
+	let msg = AvailabilityStoreMessage::StoreAvailableData {
+		candidate_hash,
+		n_validators,
+		available_data: available_data.clone(),
+		tx,
+		node_features: NodeFeatures::EMPTY,
+		core_index: CoreIndex(1),
+		expected_erasure_root: branches.root(),
+	};
+
+	overseer_send(virtual_overseer, msg).await;
+	rx.await.unwrap().unwrap();
+}
+
 async fn query_chunk(
 	virtual_overseer: &mut VirtualOverseer,
 	candidate_hash: CandidateHash,
@@ -0,0 +1,254 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Validating a candidate outside of a running node.
+//!
+//! A dry run drives the same PVF host and workers as the subsystem, deriving the preparation and
+//! execution timeouts from the given [`ExecutorParams`] exactly like the subsystem does. This
+//! makes it possible to find out why a candidate is (or is not) accepted by validators without
+//! submitting it to a network.
+
+use super::{
+	pvf_exec_timeout, pvf_host_config, pvf_prep_timeout, Config, ValidationBackend, LOG_TARGET,
+	PVF_APPROVAL_EXECUTION_RETRY_DELAY,
+};
+use codec::Encode;
+use futures::{future::Either, prelude::*};
+use polkadot_node_core_pvf::{PrepareError, PrepareJobKind, PvfPrepData, ValidationError};
+use polkadot_node_metrics::metrics::{prometheus, Metrics as _};
+use polkadot_node_primitives::PoV;
+use polkadot_node_subsystem::{messages::PvfExecKind, SubsystemError, SubsystemResult};
+use polkadot_primitives::{
+	CandidateCommitments, ExecutorParams, Hash, PersistedValidationData, PvfPrepKind,
+	ValidationCode,
+};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// Metrics the preparation workers report their memory usage with, in kilobytes.
+const PREPARATION_MEMORY_METRICS: &[&str] = &[
+	"polkadot_pvf_preparation_max_rss",
+	"polkadot_pvf_preparation_max_resident",
+	"polkadot_pvf_preparation_max_allocated",
+	"polkadot_pvf_preparation_peak_tracked_allocation",
+];
+
+/// Metrics the execution workers report their memory usage with, in kilobytes.
+const EXECUTION_MEMORY_METRICS: &[&str] = &["polkadot_pvf_execution_max_rss"];
+
+/// The context a candidate is validated in, which determines the execution timeout and whether
+/// failed executions are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DryRunKind {
+	/// Validate the candidate like a backing validator.
+	Backing,
+	/// Validate the candidate like an approval checker or a dispute participant.
+	Approval,
+}
+
+impl DryRunKind {
+	fn exec_kind(self) -> PvfExecKind {
+		match self {
+			// The relay parent is only used to drop backing jobs once it goes out of scope, which
+			// never happens since the host of a dry run doesn't see any leaves.
+			DryRunKind::Backing => PvfExecKind::Backing(Hash::zero()),
+			DryRunKind::Approval => PvfExecKind::Approval,
+		}
+	}
+}
+
+/// A candidate to be validated by [`dry_run`].
+#[derive(Debug, Clone)]
+pub struct DryRunRequest {
+	/// The validation code of the parachain.
+	pub validation_code: ValidationCode,
+	/// The persisted validation data the candidate was built on.
+	pub persisted_validation_data: PersistedValidationData,
+	/// The proof of validity of the candidate.
+	pub pov: PoV,
+	/// The executor parameters of the session the candidate is validated in.
+	pub executor_params: ExecutorParams,
+	/// The context to validate the candidate in.
+	pub kind: DryRunKind,
+}
+
+/// The outcome of validating a candidate.
+#[derive(Debug)]
+pub enum DryRunOutcome {
+	/// The candidate is valid and produced these commitments.
+	Valid(CandidateCommitments),
+	/// The encoded PoV is larger than the `max_pov_size` of the persisted validation data.
+	PoVTooLarge(u64),
+	/// The validation code could not be prepared.
+	PreparationFailed(PrepareError),
+	/// The validation code was prepared, but validating the candidate failed.
+	ExecutionFailed(ValidationError),
+}
+
+/// What happened during a dry run.
+#[derive(Debug)]
+pub struct DryRunReport {
+	/// The outcome of the validation.
+	pub outcome: DryRunOutcome,
+	/// The preparation timeout derived from the executor parameters.
+	pub prepare_timeout: Duration,
+	/// The execution timeout derived from the executor parameters.
+	pub exec_timeout: Duration,
+	/// How long preparing the validation code took, including queueing and spawning a worker.
+	pub preparation_time: Option<Duration>,
+	/// How long executing the validation code took, including queueing, spawning a worker and
+	/// any retries.
+	pub execution_time: Option<Duration>,
+	/// The peak memory usage of the preparation worker in kilobytes.
+	///
+	/// It is only measured on platforms supported by the memory tracker. This is `None` if a
+	/// prepared artifact was reused.
+	pub peak_preparation_memory_kb: Option<u64>,
+	/// The peak resident set size of the execution job in kilobytes.
+	///
+	/// It is only measured on Linux, and only for successful executions.
+	pub peak_execution_memory_kb: Option<u64>,
+}
+
+/// Start a PVF validation host with the given configuration and validate a single candidate with
+/// it.
+///
+/// The artifacts cache of the configuration should be a fresh directory, otherwise a cached
+/// artifact may be executed without preparing the validation code again.
+pub async fn dry_run(config: Config, request: DryRunRequest) -> SubsystemResult<DryRunReport> {
+	let registry = prometheus::Registry::new();
+	let pvf_metrics = polkadot_node_core_pvf::Metrics::register(Some(&registry))?;
+	let (validation_host, task) =
+		polkadot_node_core_pvf::start(pvf_host_config(config), pvf_metrics).await?;
+
+	let report =
+		match future::select(task.boxed(), dry_run_with_backend(validation_host, request).boxed())
+			.await
+		{
+			Either::Left(((), _)) =>
+				return Err(SubsystemError::Context(
+					"the validation host stopped unexpectedly".into(),
+				)),
+			Either::Right((report, _)) => report,
+		};
+
+	Ok(DryRunReport {
+		peak_preparation_memory_kb: peak_memory_kb(&registry, PREPARATION_MEMORY_METRICS),
+		peak_execution_memory_kb: peak_memory_kb(&registry, EXECUTION_MEMORY_METRICS),
+		..report
+	})
+}
+
+/// Validates the candidate with the given backend, preparing the validation code up front so
+/// that preparation and execution can be timed separately.
+pub(crate) async fn dry_run_with_backend(
+	mut validation_backend: impl ValidationBackend + Send,
+	DryRunRequest { validation_code, persisted_validation_data, pov, executor_params, kind }: DryRunRequest,
+) -> DryRunReport {
+	let exec_kind = kind.exec_kind();
+	let prepare_timeout = pvf_prep_timeout(&executor_params, PvfPrepKind::Prepare);
+	let exec_timeout = pvf_exec_timeout(&executor_params, exec_kind.into());
+	let report = |outcome, preparation_time, execution_time| DryRunReport {
+		outcome,
+		prepare_timeout,
+		exec_timeout,
+		preparation_time,
+		execution_time,
+		peak_preparation_memory_kb: None,
+		peak_execution_memory_kb: None,
+	};
+
+	let encoded_pov_size = pov.encoded_size();
+	if encoded_pov_size > persisted_validation_data.max_pov_size as usize {
+		return report(DryRunOutcome::PoVTooLarge(encoded_pov_size as u64), None, None)
+	}
+
+	let pvf = PvfPrepData::from_code(
+		validation_code.0.clone(),
+		executor_params.clone(),
+		prepare_timeout,
+		PrepareJobKind::Compilation,
+	);
+
+	let started = Instant::now();
+	let prepared = validation_backend.precheck_pvf(pvf.clone()).await;
+	let preparation_time = Some(started.elapsed());
+	if let Err(error) = prepared {
+		gum::debug!(target: LOG_TARGET, ?error, "Dry run failed to prepare the validation code");
+		return report(DryRunOutcome::PreparationFailed(error), preparation_time, None)
+	}
+
+	let persisted_validation_data = Arc::new(persisted_validation_data);
+	let pov = Arc::new(pov);
+	let started = Instant::now();
+	let result = match kind {
+		DryRunKind::Backing =>
+			validation_backend
+				.validate_candidate(
+					pvf,
+					exec_timeout,
+					persisted_validation_data,
+					pov,
+					exec_kind.into(),
+					exec_kind,
+				)
+				.await,
+		DryRunKind::Approval =>
+			validation_backend
+				.validate_candidate_with_retry(
+					validation_code.0,
+					exec_timeout,
+					persisted_validation_data,
+					pov,
+					executor_params,
+					PVF_APPROVAL_EXECUTION_RETRY_DELAY,
+					exec_kind.into(),
+					exec_kind,
+				)
+				.await,
+	};
+	let execution_time = Some(started.elapsed());
+
+	let outcome = match result {
+		Ok(res) => DryRunOutcome::Valid(CandidateCommitments {
+			head_data: res.head_data,
+			upward_messages: res.upward_messages,
+			horizontal_messages: res.horizontal_messages,
+			new_validation_code: res.new_validation_code,
+			processed_downward_messages: res.processed_downward_messages,
+			hrmp_watermark: res.hrmp_watermark,
+		}),
+		Err(error) => DryRunOutcome::ExecutionFailed(error),
+	};
+
+	report(outcome, preparation_time, execution_time)
+}
+
+/// The highest memory usage any of the given memory metrics observed.
+fn peak_memory_kb(registry: &prometheus::Registry, metrics: &[&str]) -> Option<u64> {
+	registry
+		.gather()
+		.iter()
+		.filter(|family| metrics.contains(&family.get_name()))
+		.flat_map(|family| family.get_metric())
+		.map(|metric| metric.get_histogram())
+		.filter(|histogram| histogram.get_sample_count() > 0)
+		.map(|histogram| histogram.get_sample_sum() as u64)
+		.max()
+		.filter(|kb| *kb > 0)
+}
@@ -74,7 +74,9 @@ use std::{
 
 use async_trait::async_trait;
 
+mod dry_run;
 mod metrics;
+pub use self::dry_run::{dry_run, DryRunKind, DryRunOutcome, DryRunReport, DryRunRequest};
 use self::metrics::Metrics;
 
 #[cfg(test)]
@@ -240,12 +242,7 @@ where
 	}
 }
 
-#[overseer::contextbounds(CandidateValidation, prefix = self::overseer)]
-async fn run<Context>(
-	mut ctx: Context,
-	keystore: KeystorePtr,
-	metrics: Metrics,
-	pvf_metrics: polkadot_node_core_pvf::Metrics,
+fn pvf_host_config(
 	Config {
 		artifacts_cache_path,
 		node_version,
@@ -256,21 +253,29 @@ async fn run<Context>(
 		pvf_prepare_workers_soft_max_num,
 		pvf_prepare_workers_hard_max_num,
 	}: Config,
-) -> SubsystemResult<()> {
-	let (validation_host, task) = polkadot_node_core_pvf::start(
-		polkadot_node_core_pvf::Config::new(
-			artifacts_cache_path,
-			node_version,
-			secure_validator_mode,
-			prep_worker_path,
-			exec_worker_path,
-			pvf_execute_workers_max_num,
-			pvf_prepare_workers_soft_max_num,
-			pvf_prepare_workers_hard_max_num,
-		),
-		pvf_metrics,
+) -> polkadot_node_core_pvf::Config {
+	polkadot_node_core_pvf::Config::new(
+		artifacts_cache_path,
+		node_version,
+		secure_validator_mode,
+		prep_worker_path,
+		exec_worker_path,
+		pvf_execute_workers_max_num,
+		pvf_prepare_workers_soft_max_num,
+		pvf_prepare_workers_hard_max_num,
 	)
-	.await?;
+}
+
+#[overseer::contextbounds(CandidateValidation, prefix = self::overseer)]
+async fn run<Context>(
+	mut ctx: Context,
+	keystore: KeystorePtr,
+	metrics: Metrics,
+	pvf_metrics: polkadot_node_core_pvf::Metrics,
+	config: Config,
+) -> SubsystemResult<()> {
+	let (validation_host, task) =
+		polkadot_node_core_pvf::start(pvf_host_config(config), pvf_metrics).await?;
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
 	let mut tasks = FuturesUnordered::new();
@@ -2215,3 +2215,150 @@ fn maybe_prepare_validation_does_not_prepare_already_prepared_pvfs() {
 	assert!(state.is_next_session_authority);
 	assert_eq!(state.already_prepared_code_hashes.len(), 3);
 }
+
+struct MockDryRunBackend {
+	precheck_result: Result<(), PrepareError>,
+	validation_results: Vec<Result<WasmValidationResult, ValidationError>>,
+	precheck_call_count: usize,
+	validate_call_count: usize,
+}
+
+impl MockDryRunBackend {
+	fn new(
+		precheck_result: Result<(), PrepareError>,
+		validation_results: Vec<Result<WasmValidationResult, ValidationError>>,
+	) -> Self {
+		Self { precheck_result, validation_results, precheck_call_count: 0, validate_call_count: 0 }
+	}
+}
+
+#[async_trait]
+impl<'a> ValidationBackend for &'a mut MockDryRunBackend {
+	async fn validate_candidate(
+		&mut self,
+		pvf: PvfPrepData,
+		_timeout: Duration,
+		_pvd: Arc<PersistedValidationData>,
+		_pov: Arc<PoV>,
+		_prepare_priority: polkadot_node_core_pvf::Priority,
+		_exec_kind: PvfExecKind,
+	) -> Result<WasmValidationResult, ValidationError> {
+		assert_eq!(pvf.prep_timeout(), DEFAULT_LENIENT_PREPARATION_TIMEOUT);
+		let result = self.validation_results[self.validate_call_count].clone();
+		self.validate_call_count += 1;
+
+		result
+	}
+
+	async fn precheck_pvf(&mut self, _pvf: PvfPrepData) -> Result<(), PrepareError> {
+		self.precheck_call_count += 1;
+		self.precheck_result.clone()
+	}
+
+	async fn heads_up(&mut self, _active_pvfs: Vec<PvfPrepData>) -> Result<(), String> {
+		unreachable!()
+	}
+
+	async fn update_active_leaves(
+		&mut self,
+		_update: ActiveLeavesUpdate,
+		_ancestors: Vec<Hash>,
+	) -> Result<(), String> {
+		unreachable!()
+	}
+}
+
+fn dry_run_request(kind: DryRunKind) -> DryRunRequest {
+	DryRunRequest {
+		validation_code: ValidationCode(vec![2; 16]),
+		persisted_validation_data: PersistedValidationData {
+			max_pov_size: 1024,
+			..Default::default()
+		},
+		pov: PoV { block_data: BlockData(vec![1; 32]) },
+		executor_params: ExecutorParams::default(),
+		kind,
+	}
+}
+
+#[test]
+fn dry_run_reports_commitments_of_valid_candidate() {
+	let validation_result = WasmValidationResult {
+		head_data: HeadData(vec![1, 1, 1]),
+		new_validation_code: None,
+		upward_messages: vec![vec![3; 4]].try_into().unwrap(),
+		horizontal_messages: Default::default(),
+		processed_downward_messages: 2,
+		hrmp_watermark: 0,
+	};
+	let mut backend = MockDryRunBackend::new(Ok(()), vec![Ok(validation_result.clone())]);
+
+	let report = executor::block_on(dry_run::dry_run_with_backend(
+		&mut backend,
+		dry_run_request(DryRunKind::Backing),
+	));
+
+	assert_eq!(report.prepare_timeout, DEFAULT_LENIENT_PREPARATION_TIMEOUT);
+	assert_eq!(report.exec_timeout, DEFAULT_BACKING_EXECUTION_TIMEOUT);
+	assert!(report.preparation_time.is_some());
+	assert!(report.execution_time.is_some());
+	assert_matches!(report.outcome, DryRunOutcome::Valid(commitments) => {
+		assert_eq!(commitments.head_data, validation_result.head_data);
+		assert_eq!(commitments.upward_messages, validation_result.upward_messages);
+		assert_eq!(commitments.processed_downward_messages, 2);
+	});
+	assert_eq!((backend.precheck_call_count, backend.validate_call_count), (1, 1));
+}
+
+#[test]
+fn dry_run_retries_like_approval_checkers() {
+	let mut backend = MockDryRunBackend::new(
+		Ok(()),
+		vec![
+			Err(ValidationError::PossiblyInvalid(PossiblyInvalidError::AmbiguousWorkerDeath)),
+			Err(ValidationError::Invalid(WasmInvalidCandidate::HardTimeout)),
+		],
+	);
+
+	let report = executor::block_on(dry_run::dry_run_with_backend(
+		&mut backend,
+		dry_run_request(DryRunKind::Approval),
+	));
+
+	assert_eq!(report.exec_timeout, DEFAULT_APPROVAL_EXECUTION_TIMEOUT);
+	assert_matches!(
+		report.outcome,
+		DryRunOutcome::ExecutionFailed(ValidationError::Invalid(WasmInvalidCandidate::HardTimeout))
+	);
+	assert_eq!(backend.validate_call_count, 2);
+}
+
+#[test]
+fn dry_run_does_not_execute_unprepared_code() {
+	let mut backend =
+		MockDryRunBackend::new(Err(PrepareError::Prevalidation("bad code".into())), vec![]);
+
+	let report = executor::block_on(dry_run::dry_run_with_backend(
+		&mut backend,
+		dry_run_request(DryRunKind::Backing),
+	));
+
+	assert_matches!(
+		report.outcome,
+		DryRunOutcome::PreparationFailed(PrepareError::Prevalidation(_))
+	);
+	assert!(report.execution_time.is_none());
+	assert_eq!(backend.validate_call_count, 0);
+}
+
+#[test]
+fn dry_run_rejects_oversized_pov() {
+	let mut backend = MockDryRunBackend::new(Ok(()), vec![]);
+	let mut request = dry_run_request(DryRunKind::Backing);
+	request.persisted_validation_data.max_pov_size = 8;
+
+	let report = executor::block_on(dry_run::dry_run_with_backend(&mut backend, request));
+
+	assert_matches!(report.outcome, DryRunOutcome::PoVTooLarge(_));
+	assert_eq!(backend.precheck_call_count, 0);
+}
@@ -116,12 +116,12 @@ impl DbBackend {
 impl Backend for DbBackend {
 	/// Load the earliest session, if any.
 	fn load_earliest_session(&self) -> FatalResult<Option<SessionIndex>> {
-		load_earliest_session(&*self.inner, &self.config)
+		load_earliest_session(&*self.inner, &self.config).map_err(FatalError::DbReadFailed)
 	}
 
 	/// Load the recent disputes, if any.
 	fn load_recent_disputes(&self) -> FatalResult<Option<RecentDisputes>> {
-		load_recent_disputes(&*self.inner, &self.config)
+		load_recent_disputes(&*self.inner, &self.config).map_err(FatalError::DbReadFailed)
 	}
 
 	/// Load the candidate votes for the specific session-candidate pair, if any.
@@ -131,6 +131,7 @@ impl Backend for DbBackend {
 		candidate_hash: &CandidateHash,
 	) -> FatalResult<Option<CandidateVotes>> {
 		load_candidate_votes(&*self.inner, &self.config, session, candidate_hash)
+			.map_err(FatalError::DbReadFailed)
 	}
 
 	/// Atomically writes the list of operations, with later operations taking precedence over
@@ -291,27 +292,70 @@ pub(crate) fn load_candidate_votes(
 	config: &ColumnConfiguration,
 	session: SessionIndex,
 	candidate_hash: &CandidateHash,
-) -> FatalResult<Option<CandidateVotes>> {
+) -> Result<Option<CandidateVotes>> {
 	load_decode(db, config.col_dispute_data, &candidate_votes_key(session, candidate_hash))
-		.map_err(|e| FatalError::DbReadFailed(e))
 }
 
 /// Load the earliest session, if any.
 pub(crate) fn load_earliest_session(
 	db: &dyn Database,
 	config: &ColumnConfiguration,
-) -> FatalResult<Option<SessionIndex>> {
+) -> Result<Option<SessionIndex>> {
 	load_decode(db, config.col_dispute_data, EARLIEST_SESSION_KEY)
-		.map_err(|e| FatalError::DbReadFailed(e))
 }
 
 /// Load the recent disputes, if any.
 pub(crate) fn load_recent_disputes(
 	db: &dyn Database,
 	config: &ColumnConfiguration,
-) -> FatalResult<Option<RecentDisputes>> {
+) -> Result<Option<RecentDisputes>> {
 	load_decode(db, config.col_dispute_data, RECENT_DISPUTES_KEY)
-		.map_err(|e| FatalError::DbReadFailed(e))
+}
+
+/// Load the hashes of all candidates with votes in the given session.
+pub(crate) fn load_session_candidates(
+	db: &dyn Database,
+	config: &ColumnConfiguration,
+	session: SessionIndex,
+) -> Result<Vec<CandidateHash>> {
+	let prefix = candidate_votes_session_prefix(session);
+	db.iter_with_prefix(config.col_dispute_data, &prefix)
+		.map(|entry| {
+			let (key, _) = entry?;
+			CandidateHash::decode(&mut &key[prefix.len()..]).map_err(Into::into)
+		})
+		.collect()
+}
+
+/// Overwrite the recent disputes.
+pub(crate) fn write_recent_disputes(
+	db: &dyn Database,
+	config: &ColumnConfiguration,
+	recent_disputes: &RecentDisputes,
+) -> Result<()> {
+	let mut tx = DBTransaction::new();
+	tx.put_vec(config.col_dispute_data, RECENT_DISPUTES_KEY, recent_disputes.encode());
+	db.write(tx).map_err(Into::into)
+}
+
+/// Delete all votes and recent disputes of a session.
+///
+/// Returns the number of deleted recent disputes.
+pub(crate) fn delete_session(
+	db: &dyn Database,
+	config: &ColumnConfiguration,
+	session: SessionIndex,
+) -> Result<usize> {
+	let mut recent_disputes = load_recent_disputes(db, config)?.unwrap_or_default();
+	let n_disputes = recent_disputes.len();
+	recent_disputes.retain(|(dispute_session, _), _| *dispute_session != session);
+
+	let mut tx = DBTransaction::new();
+	tx.delete_prefix(config.col_dispute_data, &candidate_votes_session_prefix(session));
+	tx.put_vec(config.col_dispute_data, RECENT_DISPUTES_KEY, recent_disputes.encode());
+	db.write(tx)?;
+
+	Ok(n_disputes - recent_disputes.len())
 }
 
 /// Maybe prune data in the DB based on the provided session index.
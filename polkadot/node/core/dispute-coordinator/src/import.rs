@@ -36,9 +36,10 @@ use polkadot_node_subsystem_util::runtime::RuntimeInfo;
 use polkadot_primitives::{
 	vstaging::CandidateReceiptV2 as CandidateReceipt, CandidateHash, DisputeStatement,
 	ExecutorParams, Hash, IndexedVec, SessionIndex, SessionInfo, ValidDisputeStatementKind,
-	ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
+	ValidatorId, ValidatorIndex, ValidatorSignature,
 };
-use sc_keystore::LocalKeystore;
+use sp_application_crypto::{AppCrypto, ByteArray};
+use sp_keystore::Keystore;
 
 use crate::LOG_TARGET;
 
@@ -63,7 +64,7 @@ impl<'a> CandidateEnvironment<'a> {
 	///
 	/// Return: `None` in case session is outside of session window.
 	pub async fn new<Context>(
-		keystore: &LocalKeystore,
+		keystore: &dyn Keystore,
 		ctx: &mut Context,
 		runtime_info: &'a mut RuntimeInfo,
 		session_index: SessionIndex,
@@ -637,12 +638,13 @@ impl ImportResult {
 ///
 /// That is all `ValidatorIndex`es we have private keys for. Usually this will only be one.
 fn find_controlled_validator_indices(
-	keystore: &LocalKeystore,
+	keystore: &dyn Keystore,
 	validators: &IndexedVec<ValidatorIndex, ValidatorId>,
 ) -> HashSet<ValidatorIndex> {
+	let local_keys = keystore.sr25519_public_keys(ValidatorId::ID);
 	let mut controlled = HashSet::new();
 	for (index, validator) in validators.iter().enumerate() {
-		if keystore.key_pair::<ValidatorPair>(validator).ok().flatten().is_none() {
+		if !local_keys.iter().any(|key| key.as_slice() == validator.as_slice()) {
 			continue
 		}
 
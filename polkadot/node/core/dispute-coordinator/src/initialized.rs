@@ -16,18 +16,13 @@
 
 //! Dispute coordinator subsystem in initialized state (after first active leaf is received).
 
-use std::{
-	collections::{BTreeMap, VecDeque},
-	sync::Arc,
-};
+use std::collections::{BTreeMap, VecDeque};
 
 use futures::{
 	channel::{mpsc, oneshot},
 	FutureExt, StreamExt,
 };
 
-use sc_keystore::LocalKeystore;
-
 use polkadot_node_primitives::{
 	disputes::ValidCandidateVotes, CandidateVotes, DisputeStatus, SignedDisputeStatement,
 	Timestamp, DISPUTE_WINDOW,
@@ -50,6 +45,7 @@ use polkadot_primitives::{
 	SessionIndex, ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
 };
 use schnellru::{LruMap, UnlimitedCompact};
+use sp_keystore::KeystorePtr;
 
 use crate::{
 	db,
@@ -93,7 +89,7 @@ pub struct InitialData {
 /// statements for validity, we cannot query orderings, we have no valid `SessionInfo`,
 /// ...
 pub(crate) struct Initialized {
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	runtime_info: RuntimeInfo,
 	/// We have the onchain state of disabled validators as well as the offchain
 	/// state that is based on the lost disputes.
@@ -1489,9 +1485,8 @@ impl Initialized {
 				continue
 			}
 
-			let keystore = self.keystore.clone() as Arc<_>;
 			let res = SignedDisputeStatement::sign_explicit(
-				&keystore,
+				&self.keystore,
 				valid,
 				candidate_hash,
 				session,
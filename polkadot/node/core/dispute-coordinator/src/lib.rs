@@ -31,7 +31,6 @@ use error::FatalError;
 use futures::FutureExt;
 
 use gum::CandidateHash;
-use sc_keystore::LocalKeystore;
 
 use polkadot_node_primitives::{
 	CandidateVotes, DisputeMessage, DisputeMessageCheckError, SignedDisputeStatement,
@@ -48,6 +47,7 @@ use polkadot_node_subsystem_util::{
 use polkadot_primitives::{
 	vstaging::ScrapedOnChainVotes, DisputeStatement, SessionIndex, SessionInfo, ValidatorIndex,
 };
+use sp_keystore::KeystorePtr;
 
 use crate::{
 	error::{FatalResult, Result},
@@ -109,6 +109,13 @@ mod metrics;
 /// Status tracking of disputes (`DisputeStatus`).
 mod status;
 
+/// Inspection and repair of the database of a node that is not running.
+mod offline;
+pub use self::{
+	db::v1::Error as DbError,
+	offline::{OfflineStore, RebuildSummary},
+};
+
 use crate::status::Clock;
 
 #[cfg(test)]
@@ -120,7 +127,7 @@ pub(crate) const LOG_TARGET: &str = "parachain::dispute-coordinator";
 pub struct DisputeCoordinatorSubsystem {
 	config: Config,
 	store: Arc<dyn Database>,
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	metrics: Metrics,
 	approval_voting_parallel_enabled: bool,
 }
@@ -163,7 +170,7 @@ impl DisputeCoordinatorSubsystem {
 	pub fn new(
 		store: Arc<dyn Database>,
 		config: Config,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		metrics: Metrics,
 		approval_voting_parallel_enabled: bool,
 	) -> Self {
@@ -0,0 +1,365 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Access to the dispute coordinator database of a node that is not running.
+//!
+//! Useful for operators to see what the dispute coordinator has stored and to repair stale or
+//! corrupted state without deleting the whole database.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use polkadot_node_primitives::{CandidateVotes, DisputeStatus, Timestamp};
+use polkadot_node_subsystem_util::database::Database;
+use polkadot_primitives::{
+	byzantine_threshold, supermajority_threshold, BlockNumber, CandidateHash, DisputeState,
+	SessionIndex, ValidatorIndex,
+};
+
+use crate::{
+	db::v1::{self, ColumnConfiguration, RecentDisputes},
+	status::{Clock, SystemClock},
+	Config,
+};
+
+/// What [`OfflineStore::rebuild_recent_disputes`] changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebuildSummary {
+	/// The number of disputes taken over from the chain.
+	pub restored: usize,
+	/// The number of unconcluded disputes only known locally, which were kept.
+	pub kept: usize,
+	/// The number of disputes only known locally, which were dropped.
+	pub dropped: usize,
+}
+
+/// The dispute coordinator database, opened outside of the subsystem.
+///
+/// Must not be used on a database that a running node has open.
+pub struct OfflineStore {
+	db: Arc<dyn Database>,
+	config: ColumnConfiguration,
+}
+
+impl OfflineStore {
+	/// Wrap an already opened database.
+	pub fn new(db: Arc<dyn Database>, config: Config) -> Self {
+		Self { db, config: config.column_config() }
+	}
+
+	/// The oldest session the database keeps votes for, if any.
+	pub fn earliest_session(&self) -> v1::Result<Option<SessionIndex>> {
+		v1::load_earliest_session(&*self.db, &self.config)
+	}
+
+	/// All disputes that have not been pruned yet, with their status.
+	pub fn recent_disputes(&self) -> v1::Result<RecentDisputes> {
+		Ok(v1::load_recent_disputes(&*self.db, &self.config)?.unwrap_or_default())
+	}
+
+	/// The candidates we have votes for in the given session.
+	pub fn candidates_with_votes(&self, session: SessionIndex) -> v1::Result<Vec<CandidateHash>> {
+		v1::load_session_candidates(&*self.db, &self.config, session)
+	}
+
+	/// The votes on a candidate, if any.
+	pub fn candidate_votes(
+		&self,
+		session: SessionIndex,
+		candidate_hash: &CandidateHash,
+	) -> v1::Result<Option<CandidateVotes>> {
+		Ok(v1::load_candidate_votes(&*self.db, &self.config, session, candidate_hash)?
+			.map(Into::into))
+	}
+
+	/// The spam slots every validator would occupy once the subsystem starts, per session.
+	///
+	/// Spam slots are not persisted, but recovered from unconfirmed disputes on startup. The
+	/// subsystem additionally frees the slots of disputes about candidates it sees included on
+	/// chain, which can't be known here, so this is an upper bound.
+	pub fn spam_slots(&self) -> v1::Result<BTreeMap<(SessionIndex, ValidatorIndex), u32>> {
+		let mut slots = BTreeMap::new();
+		for ((session, candidate_hash), status) in self.recent_disputes()? {
+			if status != DisputeStatus::Active {
+				continue
+			}
+
+			let votes = match self.candidate_votes(session, &candidate_hash)? {
+				Some(votes) => votes,
+				None => continue,
+			};
+			for validator in votes.voted_indices() {
+				*slots.entry((session, validator)).or_default() += 1;
+			}
+		}
+
+		Ok(slots)
+	}
+
+	/// Delete all votes and disputes of a session.
+	///
+	/// Returns the number of deleted disputes.
+	pub fn delete_session(&self, session: SessionIndex) -> v1::Result<usize> {
+		v1::delete_session(&*self.db, &self.config, session)
+	}
+
+	/// Replace the recent disputes with the disputes known on chain, as returned by the
+	/// `disputes` runtime API.
+	///
+	/// Disputes which already concluded locally the same way keep their conclusion time, other
+	/// concluded disputes count as concluded from now on. Disputes only known locally are kept if
+	/// they haven't concluded yet, as they may not have reached the chain, unless `drop_local` is
+	/// set. Stored votes are left untouched, as the chain doesn't have the signatures to restore
+	/// them.
+	pub fn rebuild_recent_disputes(
+		&self,
+		on_chain: impl IntoIterator<Item = (SessionIndex, CandidateHash, DisputeState<BlockNumber>)>,
+		drop_local: bool,
+	) -> v1::Result<RebuildSummary> {
+		let now = SystemClock.now();
+		let mut local = self.recent_disputes()?;
+
+		let mut rebuilt: RecentDisputes = on_chain
+			.into_iter()
+			.map(|(session, candidate_hash, state)| {
+				let key = (session, candidate_hash);
+				let status = match (on_chain_status(&state, now), local.remove(&key)) {
+					(
+						DisputeStatus::ConcludedFor(_),
+						Some(local @ DisputeStatus::ConcludedFor(_)),
+					) => local,
+					(
+						DisputeStatus::ConcludedAgainst(_),
+						Some(local @ DisputeStatus::ConcludedAgainst(_)),
+					) => local,
+					(status, _) => status,
+				};
+				(key, status)
+			})
+			.collect();
+
+		let restored = rebuilt.len();
+
+		let (kept, dropped): (Vec<_>, Vec<_>) = local
+			.into_iter()
+			.partition(|(_, status)| !drop_local && status.concluded_at().is_none());
+		let kept_count = kept.len();
+		rebuilt.extend(kept);
+
+		v1::write_recent_disputes(&*self.db, &self.config, &rebuilt)?;
+
+		Ok(RebuildSummary { restored, kept: kept_count, dropped: dropped.len() })
+	}
+}
+
+/// The status of a dispute as the dispute coordinator would track it, given its on-chain state.
+fn on_chain_status(state: &DisputeState<BlockNumber>, now: Timestamp) -> DisputeStatus {
+	let n_validators = state.validators_for.len();
+
+	if state.concluded_at.is_some() {
+		return if state.validators_against.count_ones() >= supermajority_threshold(n_validators) {
+			DisputeStatus::ConcludedAgainst(now)
+		} else {
+			DisputeStatus::ConcludedFor(now)
+		}
+	}
+
+	let participants = state
+		.validators_for
+		.iter()
+		.zip(state.validators_against.iter())
+		.filter(|(valid, invalid)| **valid || **invalid)
+		.count();
+	if participants > byzantine_threshold(n_validators) {
+		DisputeStatus::Confirmed
+	} else {
+		DisputeStatus::Active
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		backend::{Backend, OverlayedBackend},
+		db::v1::{CandidateVotes, DbBackend},
+		metrics::Metrics,
+	};
+	use polkadot_primitives::{Hash, InvalidDisputeStatementKind, ValidDisputeStatementKind};
+	use polkadot_primitives_test_helpers::{
+		dummy_candidate_receipt_v2, dummy_hash, dummy_signature,
+	};
+
+	fn make_store() -> (DbBackend, OfflineStore) {
+		let db = kvdb_memorydb::create(1);
+		let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[0]);
+		let db: Arc<dyn Database> = Arc::new(db);
+		let config = Config { col_dispute_data: 0 };
+		(
+			DbBackend::new(db.clone(), config.column_config(), Metrics::default()),
+			OfflineStore::new(db, config),
+		)
+	}
+
+	fn votes(valid: &[u32], invalid: &[u32]) -> CandidateVotes {
+		CandidateVotes {
+			candidate_receipt: dummy_candidate_receipt_v2(dummy_hash()),
+			valid: valid
+				.iter()
+				.map(|i| {
+					(ValidDisputeStatementKind::Explicit, ValidatorIndex(*i), dummy_signature())
+				})
+				.collect(),
+			invalid: invalid
+				.iter()
+				.map(|i| {
+					(InvalidDisputeStatementKind::Explicit, ValidatorIndex(*i), dummy_signature())
+				})
+				.collect(),
+		}
+	}
+
+	fn dispute_state(valid: &[bool], invalid: &[bool], concluded: bool) -> DisputeState {
+		DisputeState {
+			validators_for: valid.iter().copied().collect(),
+			validators_against: invalid.iter().copied().collect(),
+			start: 1,
+			concluded_at: concluded.then_some(2),
+		}
+	}
+
+	#[test]
+	fn inspect_disputes_and_spam_slots() {
+		let (mut backend, store) = make_store();
+		let active = CandidateHash(Hash::repeat_byte(1));
+		let confirmed = CandidateHash(Hash::repeat_byte(2));
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_earliest_session(3);
+		overlay_db.write_recent_disputes(
+			vec![((4, active), DisputeStatus::Active), ((4, confirmed), DisputeStatus::Confirmed)]
+				.into_iter()
+				.collect(),
+		);
+		overlay_db.write_candidate_votes(4, active, votes(&[0], &[1, 2]));
+		overlay_db.write_candidate_votes(4, confirmed, votes(&[0, 3], &[1]));
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert_eq!(store.earliest_session().unwrap(), Some(3));
+		assert_eq!(store.recent_disputes().unwrap().len(), 2);
+		assert_eq!(store.candidates_with_votes(4).unwrap(), vec![active, confirmed]);
+		assert!(store.candidates_with_votes(5).unwrap().is_empty());
+		assert_eq!(store.candidate_votes(4, &active).unwrap().unwrap().invalid.len(), 2);
+
+		// Only the unconfirmed dispute occupies spam slots.
+		assert_eq!(
+			store.spam_slots().unwrap(),
+			vec![
+				((4, ValidatorIndex(0)), 1),
+				((4, ValidatorIndex(1)), 1),
+				((4, ValidatorIndex(2)), 1)
+			]
+			.into_iter()
+			.collect(),
+		);
+	}
+
+	#[test]
+	fn delete_session_keeps_other_sessions() {
+		let (mut backend, store) = make_store();
+		let candidate_a = CandidateHash(Hash::repeat_byte(1));
+		let candidate_b = CandidateHash(Hash::repeat_byte(2));
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_recent_disputes(
+			vec![
+				((4, candidate_a), DisputeStatus::Active),
+				((5, candidate_b), DisputeStatus::Active),
+			]
+			.into_iter()
+			.collect(),
+		);
+		overlay_db.write_candidate_votes(4, candidate_a, votes(&[0], &[1]));
+		overlay_db.write_candidate_votes(4, candidate_b, votes(&[0], &[]));
+		overlay_db.write_candidate_votes(5, candidate_b, votes(&[0], &[1]));
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert_eq!(store.delete_session(4).unwrap(), 1);
+
+		assert!(store.candidates_with_votes(4).unwrap().is_empty());
+		assert_eq!(store.candidates_with_votes(5).unwrap(), vec![candidate_b]);
+		assert_eq!(
+			store.recent_disputes().unwrap().into_keys().collect::<Vec<_>>(),
+			vec![(5, candidate_b)],
+		);
+	}
+
+	#[test]
+	fn rebuild_takes_over_on_chain_disputes() {
+		let (mut backend, store) = make_store();
+		let local_only = CandidateHash(Hash::repeat_byte(1));
+		let concluded_for = CandidateHash(Hash::repeat_byte(2));
+		let concluded_against = CandidateHash(Hash::repeat_byte(3));
+		let confirmed = CandidateHash(Hash::repeat_byte(4));
+		let active = CandidateHash(Hash::repeat_byte(5));
+		let local_concluded = CandidateHash(Hash::repeat_byte(6));
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_recent_disputes(
+			vec![
+				((4, local_only), DisputeStatus::Active),
+				((4, local_concluded), DisputeStatus::ConcludedFor(10)),
+				((4, concluded_for), DisputeStatus::ConcludedFor(10)),
+				((4, concluded_against), DisputeStatus::ConcludedFor(10)),
+			]
+			.into_iter()
+			.collect(),
+		);
+		overlay_db.write_candidate_votes(4, local_only, votes(&[0], &[1]));
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		let on_chain = vec![
+			(4, concluded_for, dispute_state(&[true, true, true, false], &[false; 4], true)),
+			(4, concluded_against, dispute_state(&[false; 4], &[true, true, true, false], true)),
+			(4, confirmed, dispute_state(&[true, true, false, false], &[false; 4], false)),
+			(4, active, dispute_state(&[true, false, false, false], &[false; 4], false)),
+		];
+		let summary = store.rebuild_recent_disputes(on_chain.clone(), false).unwrap();
+		assert_eq!(summary, RebuildSummary { restored: 4, kept: 1, dropped: 1 });
+
+		let recent_disputes = store.recent_disputes().unwrap();
+		assert_eq!(recent_disputes.len(), 5);
+		// Unconcluded disputes only known locally are kept.
+		assert_eq!(recent_disputes[&(4, local_only)], DisputeStatus::Active);
+		assert!(!recent_disputes.contains_key(&(4, local_concluded)));
+		// The local conclusion time is kept, if the outcome agrees.
+		assert_eq!(recent_disputes[&(4, concluded_for)], DisputeStatus::ConcludedFor(10));
+		assert!(recent_disputes[&(4, concluded_against)].has_concluded_against());
+		assert_eq!(recent_disputes[&(4, confirmed)], DisputeStatus::Confirmed);
+		assert_eq!(recent_disputes[&(4, active)], DisputeStatus::Active);
+
+		// Votes are not touched.
+		assert!(store.candidate_votes(4, &local_only).unwrap().is_some());
+
+		// Unconcluded local disputes are only dropped on request.
+		let summary = store.rebuild_recent_disputes(on_chain, true).unwrap();
+		assert_eq!(summary, RebuildSummary { restored: 4, kept: 0, dropped: 1 });
+		assert!(!store.recent_disputes().unwrap().contains_key(&(4, local_only)));
+		assert!(store.candidate_votes(4, &local_only).unwrap().is_some());
+	}
+}
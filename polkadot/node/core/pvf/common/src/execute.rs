@@ -37,6 +37,8 @@ pub struct WorkerResponse {
 	pub duration: Duration,
 	/// The uncompressed PoV size.
 	pub pov_size: u32,
+	/// The maximum resident set size of the job process in kilobytes, if it could be measured.
+	pub max_rss: Option<u64>,
 }
 
 /// An error occurred in the worker process.
@@ -32,6 +32,12 @@ pub use sp_tracing;
 
 const LOG_TARGET: &str = "parachain::pvf-common";
 
+/// The version of `wasmtime` the node was built with, if the build could determine it.
+///
+/// Compiled artifacts are only valid for the exact compiler that produced them, so this is part
+/// of the artifact file names. Without it, artifacts are not reused across restarts.
+pub const WASMTIME_VERSION: Option<&str> = option_env!("SUBSTRATE_WASMTIME_VERSION");
+
 use codec::{Decode, Encode};
 use std::{
 	io::{self, Read, Write},
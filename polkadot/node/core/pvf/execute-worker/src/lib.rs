@@ -225,6 +225,7 @@ pub fn worker_entrypoint(
 									job_response: JobResponse::PoVDecompressionFailure,
 									duration: Duration::ZERO,
 									pov_size: 0,
+									max_rss: None,
 								}),
 								worker_info,
 							)?;
@@ -597,6 +598,11 @@ fn handle_parent_process(
 		Err(errno) => return Ok(Err(internal_error_from_errno("getrusage after", errno))),
 	};
 
+	// `ru_maxrss` of the children is the maximum over all the terminated children, it only tells
+	// the peak of the current child if it's larger than before. It's in kilobytes on Linux only.
+	let max_rss = (cfg!(target_os = "linux") && usage_after.max_rss() > usage_before.max_rss())
+		.then(|| usage_after.max_rss() as u64);
+
 	// Using `getrusage` is needed to check whether child has timedout since we cannot rely on
 	// child to report its own time.
 	// As `getrusage` returns resource usage from all terminated child processes,
@@ -629,7 +635,7 @@ fn handle_parent_process(
 						))));
 					}
 
-					Ok(Ok(WorkerResponse { job_response, pov_size, duration: cpu_tv }))
+					Ok(Ok(WorkerResponse { job_response, pov_size, duration: cpu_tv, max_rss }))
 				},
 				Err(job_error) => {
 					gum::warn!(
@@ -18,7 +18,9 @@
 //!
 //! # Lifecycle of an artifact
 //!
-//! 1. During node start-up, we prune all the cached artifacts, if any.
+//! 1. During node start-up, we scan the cache directory for artifacts of previous runs. Artifacts
+//!    that were compiled by the same node and `wasmtime` version and whose checksum still matches
+//!    their contents are kept as [`ArtifactState::Prepared`]. Any other artifact is removed.
 //!
 //! 2. In order to be executed, a PVF should be prepared first. This means that artifacts should
 //!    have an [`ArtifactState::Prepared`] entry for that artifact in the table. If not, the
@@ -34,8 +36,9 @@
 //!
 //! 3. The pool gets an available worker and instructs it to work on the given PVF. The worker
 //!    starts compilation. When the worker finishes successfully, it writes the serialized artifact
-//!    into a temporary file and notifies the host that it's done. The host atomically moves
-//!    (renames) the temporary file to the destination filename of the artifact.
+//!    into a temporary file and notifies the host that it's done, along with the checksum of the
+//!    artifact. The host atomically moves (renames) the temporary file to the destination filename
+//!    of the artifact, which encodes the artifact ID, the versions and the checksum.
 //!
 //! 4. If the worker concluded successfully or returned an error, then the pool notifies the queue.
 //!    In both cases, the queue reports to the host that the result is ready.
@@ -54,15 +57,17 @@
 //!    older by a predefined parameter. This process is run very rarely (say, once a day). Once the
 //!    artifact is expired it is removed from disk eagerly atomically.
 
-use crate::{host::PrecheckResultSender, worker_interface::WORKER_DIR_PREFIX};
+use crate::{host::PrecheckResultSender, worker_interface::WORKER_DIR_PREFIX, LOG_TARGET};
 use always_assert::always;
-use polkadot_node_core_pvf_common::{error::PrepareError, pvf::PvfPrepData};
+use polkadot_node_core_pvf_common::{error::PrepareError, pvf::PvfPrepData, WASMTIME_VERSION};
+use polkadot_node_primitives::NODE_VERSION;
 use polkadot_parachain_primitives::primitives::ValidationCodeHash;
-use polkadot_primitives::ExecutorParamsPrepHash;
+use polkadot_primitives::{ExecutorParamsPrepHash, Hash};
 use std::{
 	collections::HashMap,
 	fs,
 	path::{Path, PathBuf},
+	str::FromStr as _,
 	time::{Duration, SystemTime},
 };
 
@@ -72,19 +77,85 @@ const ARTIFACT_EXTENSION: &str = "pvf";
 /// The prefix that artifacts used to start with under the old naming scheme.
 const ARTIFACT_OLD_PREFIX: &str = "wasmtime_";
 
-pub fn generate_artifact_path(cache_path: &Path) -> PathBuf {
-	let file_name = {
+/// The number of hex characters of the artifact checksum kept in the file name.
+///
+/// The full checksum would push the file name over the limit of common file systems.
+const ARTIFACT_CHECKSUM_LEN: usize = 32;
+
+/// The prefix of the artifact file names, made of the versions of the node and of `wasmtime`.
+///
+/// Artifacts compiled by another version are never reused.
+fn artifact_prefix() -> String {
+	format!("polkadot_v{}_wasmtime_v{}", NODE_VERSION, WASMTIME_VERSION.unwrap_or("unknown"))
+}
+
+/// Returns a fresh path for the artifact with the given ID and checksum.
+///
+/// The file name ends with a random nonce, so that a re-prepared artifact never takes the path of
+/// one that is still waiting to be removed.
+pub fn generate_artifact_path(
+	cache_path: &Path,
+	artifact_id: &ArtifactId,
+	checksum: &str,
+) -> PathBuf {
+	let nonce = {
 		use array_bytes::Hex;
 		use rand::RngCore;
-		let mut bytes = [0u8; 64];
+		let mut bytes = [0u8; 8];
 		rand::thread_rng().fill_bytes(&mut bytes);
-		bytes.hex("0x")
+		bytes.hex("")
 	};
+	let checksum = checksum.get(..ARTIFACT_CHECKSUM_LEN).unwrap_or(checksum);
+	let file_name = format!(
+		"{}_{:#x}_{:#x}_{}_{}",
+		artifact_prefix(),
+		artifact_id.code_hash,
+		artifact_id.executor_params_prep_hash,
+		checksum,
+		nonce,
+	);
 	let mut artifact_path = cache_path.join(file_name);
 	artifact_path.set_extension(ARTIFACT_EXTENSION);
 	artifact_path
 }
 
+/// Parses the file name of an artifact of the current versions into its ID and checksum.
+fn parse_artifact_file_name(file_name: &str) -> Option<(ArtifactId, &str)> {
+	let file_name = file_name
+		.strip_suffix(ARTIFACT_EXTENSION)?
+		.strip_suffix('.')?
+		.strip_prefix(&artifact_prefix())?
+		.strip_prefix('_')?;
+	let [code_hash, prep_hash, checksum, _nonce] = file_name.split('_').collect::<Vec<_>>()[..]
+	else {
+		return None
+	};
+	if checksum.len() != ARTIFACT_CHECKSUM_LEN {
+		return None
+	}
+	let code_hash = Hash::from_str(code_hash).ok()?.into();
+	let prep_hash = ExecutorParamsPrepHash::from_hash(Hash::from_str(prep_hash).ok()?);
+	Some((ArtifactId::new(code_hash, prep_hash), checksum))
+}
+
+/// Checks a file in the cache directory and returns the ID and size of the artifact it contains,
+/// if it can be reused.
+fn verify_artifact(path: &Path) -> Option<(ArtifactId, u64)> {
+	let file_name = path.file_name()?.to_str()?;
+	let (artifact_id, checksum) = parse_artifact_file_name(file_name)?;
+	let bytes = fs::read(path).ok()?;
+	let actual = blake3::hash(&bytes).to_hex();
+	if !actual.as_str().starts_with(checksum) {
+		gum::warn!(
+			target: LOG_TARGET,
+			path = %path.display(),
+			"cached artifact does not match its checksum",
+		);
+		return None
+	}
+	Some((artifact_id, bytes.len() as u64))
+}
+
 /// Identifier of an artifact. Encodes a code hash of the PVF and a hash of preparation-related
 ///  executor parameter set.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -212,27 +283,84 @@ impl Artifacts {
 		self.inner.keys().cloned().collect()
 	}
 
-	/// Create an empty table and the cache directory on-disk if it doesn't exist.
-	pub async fn new(cache_path: &Path) -> Self {
+	/// Create the cache directory on-disk if it doesn't exist and build the table from the valid
+	/// artifacts found in it.
+	///
+	/// If the node was built without knowing the version of `wasmtime`, an artifact can't be told
+	/// apart from one compiled by another version, so all of them are discarded.
+	///
+	/// Returns the table and the number of artifacts that were discarded.
+	pub async fn new(cache_path: &Path) -> (Self, usize) {
 		// Make sure that the cache path directory and all its parents are created.
 		let _ = tokio::fs::create_dir_all(cache_path).await;
 
-		// Delete any leftover artifacts and worker dirs from previous runs. We don't delete the
-		// entire cache directory in case the user made a mistake and set it to e.g. their home
-		// directory. This is a best-effort to do clean-up, so ignore any errors.
+		let reuse = WASMTIME_VERSION.is_some();
+		if !reuse {
+			gum::warn!(
+				target: LOG_TARGET,
+				"the node was built without the version of wasmtime, cached artifacts are not reused",
+			);
+		}
+
+		// Checksumming the artifacts reads the whole cache, so keep it off the async runtime.
+		let cache_path = cache_path.to_owned();
+		tokio::task::spawn_blocking(move || Self::load(&cache_path, reuse))
+			.await
+			.unwrap_or_else(|_| (Self { inner: HashMap::new() }, 0))
+	}
+
+	fn load(cache_path: &Path, reuse: bool) -> (Self, usize) {
+		let mut artifacts = Self { inner: HashMap::new() };
+		let mut discarded = 0;
+		let now = SystemTime::now();
+
+		// Delete any leftover worker dirs and unusable artifacts from previous runs. We don't
+		// delete the entire cache directory in case the user made a mistake and set it to e.g.
+		// their home directory. This is a best-effort to do clean-up, so ignore any errors.
 		for entry in fs::read_dir(cache_path).into_iter().flatten().flatten() {
 			let path = entry.path();
 			let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else { continue };
-			if path.is_dir() && file_name.starts_with(WORKER_DIR_PREFIX) {
-				let _ = fs::remove_dir_all(path);
-			} else if path.extension().map_or(false, |ext| ext == ARTIFACT_EXTENSION) ||
-				file_name.starts_with(ARTIFACT_OLD_PREFIX)
+			if path.is_dir() {
+				if file_name.starts_with(WORKER_DIR_PREFIX) {
+					let _ = fs::remove_dir_all(path);
+				}
+				continue
+			}
+			if !path.extension().map_or(false, |ext| ext == ARTIFACT_EXTENSION) &&
+				!file_name.starts_with(ARTIFACT_OLD_PREFIX)
 			{
-				let _ = fs::remove_file(path);
+				continue
+			}
+
+			match reuse.then(|| verify_artifact(&path)).flatten() {
+				Some((artifact_id, size)) if !artifacts.inner.contains_key(&artifact_id) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						validation_code_hash = ?artifact_id.code_hash,
+						"reusing cached artifact {}",
+						path.display(),
+					);
+					artifacts.inner.insert(
+						artifact_id,
+						ArtifactState::Prepared { path, last_time_needed: now, size },
+					);
+				},
+				_ => {
+					let _ = fs::remove_file(path);
+					discarded += 1;
+				},
 			}
 		}
 
-		Self { inner: HashMap::new() }
+		(artifacts, discarded)
+	}
+
+	/// Returns the number of artifacts that are ready to be used by the executor.
+	pub fn prepared_count(&self) -> usize {
+		self.inner
+			.values()
+			.filter(|state| matches!(state, ArtifactState::Prepared { .. }))
+			.count()
 	}
 
 	/// Returns the state of the given artifact by its ID.
@@ -329,8 +457,30 @@ mod tests {
 
 	use super::*;
 
+	const CHECKSUM: &str = "00112233445566778899aabbccddeeff";
+
+	fn write_artifact(cache_path: &Path, artifact_id: &ArtifactId, contents: &[u8]) -> PathBuf {
+		let checksum = blake3::hash(contents).to_hex();
+		let path = generate_artifact_path(cache_path, artifact_id, checksum.as_str());
+		fs::write(&path, contents).unwrap();
+		path
+	}
+
+	#[test]
+	fn artifact_file_name_roundtrip() {
+		let artifact_id = artifact_id(1);
+		let path = generate_artifact_path(Path::new("/cache"), &artifact_id, CHECKSUM);
+		let file_name = path.file_name().unwrap().to_str().unwrap();
+
+		assert!(file_name.len() < 255);
+		assert_eq!(parse_artifact_file_name(file_name), Some((artifact_id.clone(), CHECKSUM)));
+		assert_eq!(parse_artifact_file_name(&file_name.replace(NODE_VERSION, "0.0.0")), None);
+		assert_eq!(parse_artifact_file_name(file_name.trim_end_matches(".pvf")), None);
+		assert_ne!(path, generate_artifact_path(Path::new("/cache"), &artifact_id, CHECKSUM));
+	}
+
 	#[tokio::test]
-	async fn cache_cleared_on_startup() {
+	async fn cache_cleaned_on_startup() {
 		let tempdir = tempfile::tempdir().unwrap();
 		let cache_path = tempdir.path();
 
@@ -344,7 +494,7 @@ mod tests {
 		fs::write(cache_path.join("polkadot_..."), "test").unwrap();
 		fs::create_dir(cache_path.join("worker-prepare-test")).unwrap();
 
-		let artifacts = Artifacts::new(cache_path).await;
+		let (artifacts, discarded) = Artifacts::new(cache_path).await;
 
 		let entries: Vec<String> = fs::read_dir(&cache_path)
 			.unwrap()
@@ -355,6 +505,59 @@ mod tests {
 		assert!(entries.contains(&String::from("polkadot_...")));
 		assert!(entries.contains(&String::from("worker-prepare-test")));
 		assert_eq!(artifacts.len(), 0);
+		assert_eq!(discarded, 2);
+	}
+
+	#[tokio::test]
+	async fn valid_artifacts_reused_on_startup() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path();
+
+		let valid = write_artifact(cache_path, &artifact_id(1), b"artifact 1");
+		let corrupted = write_artifact(cache_path, &artifact_id(2), b"artifact 2");
+		fs::write(&corrupted, b"corrupted").unwrap();
+		let duplicate = write_artifact(cache_path, &artifact_id(3), b"artifact 3");
+		write_artifact(cache_path, &artifact_id(3), b"artifact 3");
+		let other_version = cache_path.join(
+			duplicate
+				.file_name()
+				.unwrap()
+				.to_str()
+				.unwrap()
+				.replace(&artifact_prefix(), "polkadot_v0.0.0_wasmtime_v0.0.0-other"),
+		);
+		fs::write(&other_version, b"artifact 3").unwrap();
+
+		let (mut artifacts, discarded) = Artifacts::load(cache_path, true);
+
+		assert_eq!(discarded, 3);
+		assert_eq!(artifacts.prepared_count(), 2);
+		let mut ids = artifacts.artifact_ids();
+		ids.sort();
+		let mut expected = vec![artifact_id(1), artifact_id(3)];
+		expected.sort();
+		assert_eq!(ids, expected);
+		assert_matches::assert_matches!(
+			artifacts.artifact_state_mut(&artifact_id(1)),
+			Some(ArtifactState::Prepared { path, size: 10, .. }) if *path == valid
+		);
+		assert!(!corrupted.exists());
+		assert!(!other_version.exists());
+		assert_eq!(fs::read_dir(cache_path).unwrap().count(), 2);
+	}
+
+	#[test]
+	fn artifacts_not_reused_without_wasmtime_version() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path();
+
+		let valid = write_artifact(cache_path, &artifact_id(1), b"artifact 1");
+
+		let (artifacts, discarded) = Artifacts::load(cache_path, false);
+
+		assert_eq!(discarded, 1);
+		assert_eq!(artifacts.len(), 0);
+		assert!(!valid.exists());
 	}
 
 	#[tokio::test]
@@ -363,14 +566,14 @@ mod tests {
 		let tempdir = tempfile::tempdir().unwrap();
 		let cache_path = tempdir.path();
 
-		let path1 = generate_artifact_path(cache_path);
-		let path2 = generate_artifact_path(cache_path);
-		let path3 = generate_artifact_path(cache_path);
 		let artifact_id1 = artifact_id(1);
 		let artifact_id2 = artifact_id(2);
 		let artifact_id3 = artifact_id(3);
+		let path1 = generate_artifact_path(cache_path, &artifact_id1, CHECKSUM);
+		let path2 = generate_artifact_path(cache_path, &artifact_id2, CHECKSUM);
+		let path3 = generate_artifact_path(cache_path, &artifact_id3, CHECKSUM);
 
-		let mut artifacts = Artifacts::new(cache_path).await;
+		let (mut artifacts, _) = Artifacts::new(cache_path).await;
 		let cleanup_config = ArtifactsCleanupConfig::new(1500, Duration::from_secs(0));
 
 		artifacts.insert_prepared(
@@ -408,14 +611,14 @@ mod tests {
 		let tempdir = tempfile::tempdir().unwrap();
 		let cache_path = tempdir.path();
 
-		let path1 = generate_artifact_path(cache_path);
-		let path2 = generate_artifact_path(cache_path);
-		let path3 = generate_artifact_path(cache_path);
 		let artifact_id1 = artifact_id(1);
 		let artifact_id2 = artifact_id(2);
 		let artifact_id3 = artifact_id(3);
+		let path1 = generate_artifact_path(cache_path, &artifact_id1, CHECKSUM);
+		let path2 = generate_artifact_path(cache_path, &artifact_id2, CHECKSUM);
+		let path3 = generate_artifact_path(cache_path, &artifact_id3, CHECKSUM);
 
-		let mut artifacts = Artifacts::new(cache_path).await;
+		let (mut artifacts, _) = Artifacts::new(cache_path).await;
 		let cleanup_config = ArtifactsCleanupConfig::new(1500, Duration::from_secs(12));
 
 		artifacts.insert_prepared(
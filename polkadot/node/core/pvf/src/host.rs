@@ -250,7 +250,15 @@ pub async fn start(
 	gum::debug!(target: LOG_TARGET, ?config, "starting PVF validation host");
 
 	// Make sure the cache is initialized before doing anything else.
-	let artifacts = Artifacts::new(&config.cache_path).await;
+	let (artifacts, discarded) = Artifacts::new(&config.cache_path).await;
+	let reused = artifacts.prepared_count();
+	gum::info!(
+		target: LOG_TARGET,
+		%reused,
+		%discarded,
+		"loaded PVF artifacts cached by a previous run",
+	);
+	metrics.on_artifacts_loaded(reused, discarded);
 
 	// Run checks for supported security features once per host startup. If some checks fail, warn
 	// if Secure Validator Mode is disabled and return an error otherwise.
@@ -948,14 +956,14 @@ async fn handle_artifact_removal(
 	} else {
 		// if we haven't found the artifact by its id,
 		// it has been probably removed
-		// anyway with the randomness of the artifact name
+		// anyway with the nonce in the artifact name
 		// it is safe to ignore
 		return Ok(());
 	};
 	reply_to
 		.send(())
 		.expect("the execute queue waits for the artifact remove confirmation; qed");
-	// Thanks to the randomness of the artifact name (see
+	// Thanks to the random nonce in the artifact name (see
 	// `artifacts::generate_artifact_path`) there is no issue with any name conflict on
 	// future repreparation.
 	// So we can confirm the artifact removal already
@@ -1237,8 +1245,8 @@ pub(crate) mod tests {
 		let mut builder = Builder::default();
 		builder.cleanup_pulse_interval = Duration::from_millis(100);
 		builder.cleanup_config = ArtifactsCleanupConfig::new(1024, Duration::from_secs(0));
-		let path1 = generate_artifact_path(cache_path);
-		let path2 = generate_artifact_path(cache_path);
+		let path1 = generate_artifact_path(cache_path, &artifact_id(1), "checksum");
+		let path2 = generate_artifact_path(cache_path, &artifact_id(2), "checksum");
 		builder.artifacts.insert_prepared(artifact_id(1), path1.clone(), mock_now, 1024);
 		builder.artifacts.insert_prepared(artifact_id(2), path2.clone(), mock_now, 1024);
 		let mut test = builder.build();
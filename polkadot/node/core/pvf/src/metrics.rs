@@ -107,6 +107,15 @@ impl Metrics {
 		}
 	}
 
+	/// Observe the maximum resident set size of an execution job, in kilobytes.
+	#[allow(unused_variables)]
+	pub(crate) fn observe_execution_max_rss(&self, max_rss: u64) {
+		#[cfg(target_os = "linux")]
+		if let Some(metrics) = &self.0 {
+			metrics.execution_max_rss.observe(max_rss as f64);
+		}
+	}
+
 	pub(crate) fn observe_code_size(&self, code_size: usize) {
 		if let Some(metrics) = &self.0 {
 			metrics.code_size.observe(code_size as f64);
@@ -122,6 +131,17 @@ impl Metrics {
 		}
 	}
 
+	/// Report the artifacts found in the cache directory on startup.
+	pub(crate) fn on_artifacts_loaded(&self, reused: usize, discarded: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.cached_artifacts.with_label_values(&["reused"]).inc_by(reused as u64);
+			metrics
+				.cached_artifacts
+				.with_label_values(&["discarded"])
+				.inc_by(discarded as u64);
+		}
+	}
+
 	/// When preparation pipeline concluded working on an item.
 	pub(crate) fn on_execute_kind(&self, kind: PvfExecKind) {
 		if let Some(metrics) = &self.0 {
@@ -144,6 +164,8 @@ struct MetricsInner {
 	execution_queued_time: prometheus::Histogram,
 	#[cfg(target_os = "linux")]
 	preparation_max_rss: prometheus::Histogram,
+	#[cfg(target_os = "linux")]
+	execution_max_rss: prometheus::Histogram,
 	// Max. allocated memory, tracked by Jemallocator, polling-based
 	#[cfg(any(target_os = "linux", feature = "jemalloc-allocator"))]
 	preparation_max_allocated: prometheus::Histogram,
@@ -155,6 +177,7 @@ struct MetricsInner {
 	pov_size: prometheus::HistogramVec,
 	code_size: prometheus::Histogram,
 	exec_kind_selected: prometheus::CounterVec<prometheus::U64>,
+	cached_artifacts: prometheus::CounterVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -311,6 +334,19 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			#[cfg(target_os = "linux")]
+			execution_max_rss: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_max_rss",
+						"ru_maxrss (maximum resident set size) observed for execution (in kilobytes)",
+					).buckets(
+						prometheus::exponential_buckets(8192.0, 2.0, 10)
+							.expect("arguments are always valid; qed"),
+					),
+				)?,
+				registry,
+			)?,
 			#[cfg(any(target_os = "linux", feature = "jemalloc-allocator"))]
 			preparation_max_resident: prometheus::register(
 				prometheus::Histogram::with_opts(
@@ -388,6 +424,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			cached_artifacts: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_pvf_cached_artifacts",
+						"Artifacts of a previous run found in the cache on startup, by whether they were reused or discarded",
+					),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(inner)))
 	}
@@ -17,7 +17,7 @@
 //! Host interface to the prepare worker.
 
 use crate::{
-	artifacts::generate_artifact_path,
+	artifacts::{generate_artifact_path, ArtifactId},
 	metrics::Metrics,
 	worker_interface::{
 		clear_worker_dir_path, framed_recv, framed_send, spawn_with_program_path, IdleWorker,
@@ -163,6 +163,7 @@ pub async fn start_work(
 						IdleWorker { stream, pid, worker_dir },
 						prepare_worker_result,
 						pid,
+						ArtifactId::from_pvf_prep_data(&pvf),
 						tmp_artifact_file,
 						&cache_path,
 						preparation_timeout,
@@ -202,14 +203,13 @@ async fn handle_response(
 	worker: IdleWorker,
 	result: PrepareWorkerResult,
 	worker_pid: u32,
+	artifact_id: ArtifactId,
 	tmp_file: PathBuf,
 	cache_path: &Path,
 	preparation_timeout: Duration,
 ) -> Outcome {
-	// TODO: Add `checksum` to `ArtifactPathId`. See:
-	//       https://github.com/paritytech/polkadot-sdk/issues/2399
 	let PrepareWorkerSuccess {
-		checksum: _,
+		checksum,
 		stats: PrepareStats { cpu_time_elapsed, memory_stats, observed_wasm_code_len },
 	} = match result.clone() {
 		Ok(result) => result,
@@ -248,11 +248,10 @@ async fn handle_response(
 		},
 	};
 
-	// The file name should uniquely identify the artifact even across restarts. In case the cache
-	// for some reason is not cleared correctly, we cannot
-	// accidentally execute an artifact compiled under a different wasmtime version, host
-	// environment, etc.
-	let artifact_path = generate_artifact_path(cache_path);
+	// The file name identifies the artifact across restarts. It encodes the node and wasmtime
+	// versions, so we cannot accidentally execute an artifact compiled under a different wasmtime
+	// version, and the checksum, so that a corrupted artifact is not picked up on startup.
+	let artifact_path = generate_artifact_path(cache_path, &artifact_id, &checksum);
 
 	gum::debug!(
 		target: LOG_TARGET,
@@ -58,6 +58,45 @@ async fn execute_good_block_on_parent() {
 	assert_eq!(new_head.post_state, hash_state(512));
 }
 
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn execute_reports_max_rss() {
+	use polkadot_node_metrics::metrics::{prometheus, Metrics as _};
+
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+	let block_data = BlockData { state: 0, add: 512 };
+	let pvd = PersistedValidationData {
+		parent_head: GenericHeadData(parent_head.encode()),
+		relay_parent_number: 1u32,
+		relay_parent_storage_root: H256::default(),
+		max_pov_size: 4096 * 1024,
+	};
+	let pov = PoV { block_data: GenericBlockData(block_data.encode()) };
+
+	let registry = prometheus::Registry::new();
+	let metrics = polkadot_node_core_pvf::Metrics::register(Some(&registry)).unwrap();
+	let host = TestHost::new_with_config_and_metrics(|_| (), metrics).await;
+
+	host.validate_candidate(
+		test_parachain_adder::wasm_binary_unwrap(),
+		pvd,
+		pov,
+		Default::default(),
+		H256::default(),
+	)
+	.await
+	.unwrap();
+
+	let max_rss = registry
+		.gather()
+		.into_iter()
+		.find(|family| family.get_name() == "polkadot_pvf_execution_max_rss")
+		.unwrap();
+	let histogram = max_rss.get_metric()[0].get_histogram();
+	assert_eq!(histogram.get_sample_count(), 1);
+	assert!(histogram.get_sample_sum() > 0.0);
+}
+
 #[tokio::test]
 async fn execute_good_chain_on_parent() {
 	let mut parent_hash = [0; 32];
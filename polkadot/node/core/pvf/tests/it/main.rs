@@ -56,6 +56,13 @@ impl TestHost {
 	}
 
 	async fn new_with_config<F>(f: F) -> Self
+	where
+		F: FnOnce(&mut Config),
+	{
+		Self::new_with_config_and_metrics(f, Metrics::default()).await
+	}
+
+	async fn new_with_config_and_metrics<F>(f: F, metrics: Metrics) -> Self
 	where
 		F: FnOnce(&mut Config),
 	{
@@ -73,7 +80,7 @@ impl TestHost {
 			2,
 		);
 		f(&mut config);
-		let (host, task) = start(config, Metrics::default()).await.unwrap();
+		let (host, task) = start(config, metrics).await.unwrap();
 		let _ = tokio::task::spawn(task);
 		Self { cache_dir, host: Mutex::new(host) }
 	}
@@ -465,7 +472,7 @@ async fn corrupted_prepared_artifact_does_not_dispute() {
 }
 
 #[tokio::test]
-async fn cache_cleared_on_startup() {
+async fn cache_reused_on_startup() {
 	// Don't drop this host, it owns the `TempDir` which gets cleared on drop.
 	let host = TestHost::new().await;
 
@@ -478,7 +485,23 @@ async fn cache_cleared_on_startup() {
 	let cache_dir = host.cache_dir.path().to_owned();
 	assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 2);
 
-	// Start a new host, previous artifact should be cleared.
+	// Start a new host, the worker dir should be cleared but the artifact kept.
+	let host = TestHost::new_with_config(|cfg| {
+		cfg.cache_path = cache_dir.clone();
+	})
+	.await;
+	let artifact_path = std::fs::read_dir(&cache_dir).unwrap().next().unwrap().unwrap().path();
+	assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+	// The artifact is reused, so no preparation takes place.
+	let _stats = host
+		.precheck_pvf(test_parachain_halt::wasm_binary_unwrap(), Default::default())
+		.await
+		.unwrap();
+	assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+	// Start a new host after corrupting the artifact, it should be cleared.
+	std::fs::write(&artifact_path, b"corrupted").unwrap();
 	let _host = TestHost::new_with_config(|cfg| {
 		cfg.cache_path = cache_dir.clone();
 	})
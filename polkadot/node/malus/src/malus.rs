@@ -42,6 +42,12 @@ enum NemesisVariant {
 	DisputeFinalizedCandidates(DisputeFinalizedCandidatesOptions),
 	/// Spam many request statements instead of sending a single one.
 	SpamStatementRequests(SpamStatementRequestsOptions),
+	/// Refuse to serve erasure chunks of backed candidates.
+	WithholdAvailability(WithholdAvailabilityOptions),
+	/// Drop own approval votes and become a no-show.
+	WithholdApprovals(WithholdApprovalsOptions),
+	/// Issue conflicting `Seconded` statements next to the honest ones.
+	EquivocateStatements(EquivocateStatementsOptions),
 }
 
 #[derive(Debug, Parser)]
@@ -105,6 +111,25 @@ impl MalusCli {
 
 				polkadot_cli::run_node(cli, SpamStatementRequests { spam_factor }, finality_delay)?
 			},
+			NemesisVariant::WithholdAvailability(opts) => {
+				let WithholdAvailabilityOptions { percentage, cli } = opts;
+
+				polkadot_cli::run_node(cli, WithholdAvailability { percentage }, finality_delay)?
+			},
+			NemesisVariant::WithholdApprovals(opts) => {
+				let WithholdApprovalsOptions { percentage, cli } = opts;
+
+				polkadot_cli::run_node(cli, WithholdApprovals { percentage }, finality_delay)?
+			},
+			NemesisVariant::EquivocateStatements(opts) => {
+				let EquivocateStatementsOptions { percentage, equivocations, cli } = opts;
+
+				polkadot_cli::run_node(
+					cli,
+					EquivocateStatements { percentage, equivocations },
+					finality_delay,
+				)?
+			},
 		}
 		Ok(())
 	}
@@ -244,4 +269,72 @@ mod tests {
 			assert!(opts.cli.run.base.bob);
 		});
 	}
+
+	#[test]
+	fn withhold_availability_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"withhold-availability",
+			"--percentage",
+			"50",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::WithholdAvailability(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 50);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	fn withhold_approvals_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"withhold-approvals",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::WithholdApprovals(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 100);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	fn equivocate_statements_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"equivocate-statements",
+			"--equivocations",
+			"3",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::EquivocateStatements(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 100);
+			assert_eq!(opts.equivocations, 3);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	fn validate_range_for_equivocations() {
+		assert!(MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"equivocate-statements",
+			"--equivocations",
+			"0",
+			"--bob",
+		]))
+		.is_err());
+	}
 }
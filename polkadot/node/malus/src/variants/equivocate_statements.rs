@@ -0,0 +1,356 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that equivocates on `Seconded` statements.
+//!
+//! This malus variant seconds candidates like an honest node. Whenever it is asked to second a
+//! candidate, it additionally signs `Seconded` statements for conflicting candidates that share
+//! the descriptor of the original one but carry fabricated commitments.
+//!
+//! The conflicting statements are sent right after the statement on the original candidate, to
+//! the same peers. They bypass statement distribution, which would refuse to issue more local
+//! `Seconded` statements than the seconding limit allows. Honest validators accept such
+//! statements only up to the seconding limit and punish the excess. The conflicting candidates
+//! can never be backed, because the malus node is not able to serve them, so parachain progress
+//! must not be affected.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_network_protocol::{
+	v2 as protocol_v2, v3 as protocol_v3, Versioned, VersionedValidationProtocol,
+};
+use polkadot_node_primitives::{SignedFullStatementWithPVD, StatementWithPVD};
+use polkadot_node_subsystem::SpawnGlue;
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use polkadot_node_subsystem_util::Validator;
+use polkadot_primitives::{
+	vstaging::CommittedCandidateReceiptV2 as CommittedCandidateReceipt, CandidateHash,
+	CompactStatement, Hash, UncheckedSignedStatement,
+};
+use sp_core::traits::SpawnNamed;
+use sp_keystore::KeystorePtr;
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS, variants::create_fake_candidate_commitments};
+
+use rand::distributions::{Bernoulli, Distribution};
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+/// Maximum number of candidates with conflicting statements waiting to be sent.
+///
+/// All of them are forgotten once the limit is reached, e.g. because the original candidates
+/// turned out to be invalid and their statements were never sent.
+const MAX_PENDING_EQUIVOCATIONS: usize = 64;
+
+/// Conflicting statements waiting to be sent, by the hash of the original candidate.
+type PendingEquivocations = Arc<Mutex<HashMap<CandidateHash, Vec<UncheckedSignedStatement>>>>;
+
+/// Wraps around candidate backing and replaces it.
+#[derive(Clone)]
+struct StatementEquivocator<Spawner> {
+	spawner: Spawner,
+	keystore: KeystorePtr,
+	/// Probability of equivocating on a seconded candidate.
+	distribution: Bernoulli,
+	/// Number of conflicting `Seconded` statements issued per seconded candidate.
+	equivocations: u8,
+	/// Where the conflicting statements are left for [`EquivocationSender`].
+	pending: PendingEquivocations,
+}
+
+impl<Sender, Spawner> MessageInterceptor<Sender> for StatementEquivocator<Spawner>
+where
+	Sender: overseer::CandidateBackingSenderTrait + Clone + Send + 'static,
+	Spawner: overseer::gen::Spawner + Clone + 'static,
+{
+	type Message = CandidateBackingMessage;
+
+	/// Pass `CandidateBackingMessage::Second` through and issue conflicting statements for the
+	/// same descriptor on the side.
+	fn intercept_incoming(
+		&self,
+		subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: CandidateBackingMessage::Second(relay_parent, candidate, pvd, pov),
+			} => {
+				if !self.distribution.sample(&mut rand::thread_rng()) {
+					return Some(FromOrchestra::Communication {
+						msg: CandidateBackingMessage::Second(relay_parent, candidate, pvd, pov),
+					})
+				}
+
+				let mut sender = subsystem_sender.clone();
+				let keystore = self.keystore.clone();
+				let equivocations = self.equivocations;
+				let pending = self.pending.clone();
+				let original_hash = candidate.hash();
+				let descriptor = candidate.descriptor.clone();
+				let commitments_hash = candidate.commitments_hash;
+				let validation_data = pvd.clone();
+
+				self.spawner.spawn_blocking(
+					"malus-equivocate-statements",
+					Some("malus"),
+					Box::pin(async move {
+						let validator =
+							match Validator::new(relay_parent, keystore.clone(), &mut sender).await
+							{
+								Ok(validator) => validator,
+								Err(e) => {
+									gum::error!(
+										target: MALUS,
+										?relay_parent,
+										"😈 Not a validator at relay parent: {:?}", e,
+									);
+									return
+								},
+							};
+
+						let mut commitments = create_fake_candidate_commitments(&validation_data);
+						for i in 0..equivocations {
+							// Every extra byte of head data yields another distinct candidate.
+							commitments.head_data.0.push(i);
+							if commitments.hash() == commitments_hash {
+								continue
+							}
+
+							let conflicting = CommittedCandidateReceipt {
+								descriptor: descriptor.clone(),
+								commitments: commitments.clone(),
+							};
+							let conflicting_hash = conflicting.hash();
+							let statement =
+								StatementWithPVD::Seconded(conflicting, validation_data.clone());
+
+							let signed: SignedFullStatementWithPVD =
+								match validator.sign(keystore.clone(), statement) {
+									Ok(Some(signed)) => signed,
+									_ => {
+										gum::error!(
+											target: MALUS,
+											?relay_parent,
+											"😈 Failed to sign conflicting statement",
+										);
+										return
+									},
+								};
+
+							gum::info!(
+								target: MALUS,
+								?relay_parent,
+								candidate_hash = ?conflicting_hash,
+								"😈 Equivocating: seconding a conflicting candidate",
+							);
+
+							let mut pending = pending.lock().expect("poisoned lock");
+							if pending.len() >= MAX_PENDING_EQUIVOCATIONS &&
+								!pending.contains_key(&original_hash)
+							{
+								pending.clear();
+							}
+							pending
+								.entry(original_hash)
+								.or_default()
+								.push(StatementWithPVD::signed_to_compact(signed).into_unchecked());
+						}
+					}),
+				);
+
+				Some(FromOrchestra::Communication {
+					msg: CandidateBackingMessage::Second(relay_parent, candidate, pvd, pov),
+				})
+			},
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Wraps around statement distribution and sends the conflicting statements along with the
+/// `Seconded` statement on the original candidate.
+#[derive(Clone)]
+struct EquivocationSender {
+	pending: PendingEquivocations,
+}
+
+/// The relay parent and the candidate of a network message carrying a `Seconded` statement.
+fn seconded_statement(message: &VersionedValidationProtocol) -> Option<(Hash, CandidateHash)> {
+	let (relay_parent, statement) = match message {
+		Versioned::V2(protocol_v2::ValidationProtocol::StatementDistribution(
+			protocol_v2::StatementDistributionMessage::Statement(relay_parent, statement),
+		)) |
+		Versioned::V3(protocol_v3::ValidationProtocol::StatementDistribution(
+			protocol_v3::StatementDistributionMessage::Statement(relay_parent, statement),
+		)) => (relay_parent, statement),
+		_ => return None,
+	};
+
+	match statement.unchecked_payload() {
+		CompactStatement::Seconded(candidate_hash) => Some((*relay_parent, *candidate_hash)),
+		CompactStatement::Valid(_) => None,
+	}
+}
+
+impl<Sender> MessageInterceptor<Sender> for EquivocationSender
+where
+	Sender: overseer::StatementDistributionSenderTrait + Clone + Send + 'static,
+{
+	type Message = StatementDistributionMessage;
+
+	fn need_intercept_outgoing(
+		&self,
+		msg: &overseer::StatementDistributionOutgoingMessages,
+	) -> bool {
+		match msg {
+			overseer::StatementDistributionOutgoingMessages::NetworkBridgeTxMessage(
+				NetworkBridgeTxMessage::SendValidationMessage(_, message),
+			) => seconded_statement(message).map_or(false, |(_, candidate_hash)| {
+				self.pending.lock().expect("poisoned lock").contains_key(&candidate_hash)
+			}),
+			_ => false,
+		}
+	}
+
+	/// Send the pending conflicting statements to the peers the original statement is sent to.
+	fn intercept_outgoing(
+		&self,
+		msg: &overseer::StatementDistributionOutgoingMessages,
+	) -> Option<overseer::StatementDistributionOutgoingMessages> {
+		let (peers, message) = match msg {
+			overseer::StatementDistributionOutgoingMessages::NetworkBridgeTxMessage(
+				NetworkBridgeTxMessage::SendValidationMessage(peers, message),
+			) => (peers, message),
+			_ => return None,
+		};
+		let (relay_parent, candidate_hash) = seconded_statement(message)?;
+		let conflicting = self
+			.pending
+			.lock()
+			.expect("poisoned lock")
+			.remove(&candidate_hash)
+			.unwrap_or_default();
+
+		gum::info!(
+			target: MALUS,
+			?relay_parent,
+			?candidate_hash,
+			n_peers = peers.len(),
+			"😈 Sending {} conflicting `Seconded` statements",
+			conflicting.len(),
+		);
+
+		let mut messages = vec![(peers.clone(), message.clone())];
+		for statement in conflicting {
+			let message = match message {
+				Versioned::V2(_) => Versioned::V2(
+					protocol_v2::StatementDistributionMessage::Statement(relay_parent, statement),
+				)
+				.into(),
+				_ => Versioned::V3(protocol_v3::StatementDistributionMessage::Statement(
+					relay_parent,
+					statement,
+				))
+				.into(),
+			};
+			messages.push((peers.clone(), message));
+		}
+
+		Some(NetworkBridgeTxMessage::SendValidationMessages(messages).into())
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct EquivocateStatementsOptions {
+	/// Determines the percentage of seconded candidates malus equivocates on.
+	/// Value should be in the range [0..=100].
+	#[clap(long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	/// Number of conflicting `Seconded` statements issued for each seconded candidate.
+	#[clap(long, ignore_case = true, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=16))]
+	pub equivocations: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// EquivocateStatements implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct EquivocateStatements {
+	/// Percentage of seconded candidates malus equivocates on.
+	pub percentage: u8,
+	/// Number of conflicting statements per seconded candidate.
+	pub equivocations: u8,
+}
+
+impl OverseerGen for EquivocateStatements {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that issues {} conflicting `Seconded` statements for {}% of the candidates it seconds.",
+			self.equivocations,
+			self.percentage,
+		);
+
+		let ext_args =
+			ext_args.expect("Extended arguments required to build validator overseer are provided");
+
+		let pending = PendingEquivocations::default();
+		let equivocator = StatementEquivocator {
+			spawner: SpawnGlue(args.spawner.clone()),
+			keystore: ext_args.keystore.clone(),
+			distribution: Bernoulli::new(f64::from(self.percentage) / 100.0)
+				.expect("Invalid probability! Percentage must be in range [0..=100]."),
+			equivocations: self.equivocations,
+			pending: pending.clone(),
+		};
+		let equivocation_sender = EquivocationSender { pending };
+
+		validator_overseer_builder(args, ext_args)?
+			.replace_candidate_backing(move |cb| InterceptedSubsystem::new(cb, equivocator))
+			.replace_statement_distribution(move |sd| {
+				InterceptedSubsystem::new(sd, equivocation_sender)
+			})
+			.build_with_connector(connector)
+			.map_err(|e| e.into())
+	}
+}
@@ -20,16 +20,22 @@ mod back_garbage_candidate;
 mod common;
 mod dispute_finalized_candidates;
 mod dispute_valid_candidates;
+mod equivocate_statements;
 mod spam_statement_requests;
 mod suggest_garbage_candidate;
 mod support_disabled;
+mod withhold_approvals;
+mod withhold_availability;
 
 pub(crate) use self::{
 	back_garbage_candidate::{BackGarbageCandidateOptions, BackGarbageCandidates},
 	dispute_finalized_candidates::{DisputeFinalizedCandidates, DisputeFinalizedCandidatesOptions},
 	dispute_valid_candidates::{DisputeAncestorOptions, DisputeValidCandidates},
+	equivocate_statements::{EquivocateStatements, EquivocateStatementsOptions},
 	spam_statement_requests::{SpamStatementRequests, SpamStatementRequestsOptions},
 	suggest_garbage_candidate::{SuggestGarbageCandidateOptions, SuggestGarbageCandidates},
 	support_disabled::{SupportDisabled, SupportDisabledOptions},
+	withhold_approvals::{WithholdApprovals, WithholdApprovalsOptions},
+	withhold_availability::{WithholdAvailability, WithholdAvailabilityOptions},
 };
 pub(crate) use common::*;
@@ -0,0 +1,186 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that turns into a no-show in approval checking.
+//!
+//! This malus variant announces its assignments and checks candidates like an honest node, but
+//! silently drops a configurable percentage of its own approval votes before they reach the
+//! network. Honest validators see the assignment without a matching approval, count the malus
+//! node as a no-show and cover it with assignments from later tranches.
+//!
+//! Both approval distribution and the parallel approval subsystem are wrapped, so the variant
+//! works regardless of whether approval-voting-parallel is enabled.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_primitives::approval::v2::IndirectSignedApprovalVoteV2;
+use polkadot_node_subsystem::SpawnGlue;
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use sp_core::traits::SpawnNamed;
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use rand::distributions::{Bernoulli, Distribution};
+use std::sync::Arc;
+
+/// Drops our own approval votes with a fixed probability.
+#[derive(Clone)]
+struct ApprovalWithholder {
+	distribution: Bernoulli,
+}
+
+impl ApprovalWithholder {
+	/// Returns `true` if the vote must not be distributed.
+	fn withhold(&self, vote: &IndirectSignedApprovalVoteV2) -> bool {
+		if !self.distribution.sample(&mut rand::thread_rng()) {
+			return false
+		}
+
+		gum::info!(
+			target: MALUS,
+			block_hash = ?vote.block_hash,
+			candidate_indices = ?vote.candidate_indices,
+			validator = ?vote.validator,
+			"😈 Withholding approval",
+		);
+		true
+	}
+}
+
+impl<Sender> MessageInterceptor<Sender> for ApprovalWithholder
+where
+	Sender: overseer::ApprovalDistributionSenderTrait + Clone + Send + 'static,
+{
+	type Message = ApprovalDistributionMessage;
+
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: ApprovalDistributionMessage::DistributeApproval(vote),
+			} => {
+				if self.withhold(&vote) {
+					return None
+				}
+				Some(FromOrchestra::Communication {
+					msg: ApprovalDistributionMessage::DistributeApproval(vote),
+				})
+			},
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Same as [`ApprovalWithholder`], used when approval-voting-parallel is enabled.
+#[derive(Clone)]
+struct ParallelApprovalWithholder(ApprovalWithholder);
+
+impl<Sender> MessageInterceptor<Sender> for ParallelApprovalWithholder
+where
+	Sender: overseer::ApprovalVotingParallelSenderTrait + Clone + Send + 'static,
+{
+	type Message = ApprovalVotingParallelMessage;
+
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: ApprovalVotingParallelMessage::DistributeApproval(vote),
+			} => {
+				if self.0.withhold(&vote) {
+					return None
+				}
+				Some(FromOrchestra::Communication {
+					msg: ApprovalVotingParallelMessage::DistributeApproval(vote),
+				})
+			},
+			msg => Some(msg),
+		}
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct WithholdApprovalsOptions {
+	/// Determines the percentage of our own approval votes that are never distributed.
+	/// Value should be in the range [0..=100].
+	#[clap(long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// WithholdApprovals implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct WithholdApprovals {
+	/// Percentage of approval votes that are withheld.
+	pub percentage: u8,
+}
+
+impl OverseerGen for WithholdApprovals {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that withholds {}% of its approval votes.",
+			self.percentage,
+		);
+
+		let withholder = ApprovalWithholder {
+			distribution: Bernoulli::new(f64::from(self.percentage) / 100.0)
+				.expect("Invalid probability! Percentage must be in range [0..=100]."),
+		};
+		let parallel_withholder = ParallelApprovalWithholder(withholder.clone());
+
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_approval_distribution(move |cb| InterceptedSubsystem::new(cb, withholder))
+		.replace_approval_voting_parallel(move |cb| {
+			InterceptedSubsystem::new(cb, parallel_withholder)
+		})
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
@@ -0,0 +1,182 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that backs candidates but refuses to serve their erasure chunks.
+//!
+//! This malus variant behaves honestly in backing, approval voting and disputes. For a
+//! configurable percentage of the candidates it backs, it answers every chunk request with
+//! "not found" instead of the chunk stored in its availability store. The full available data is
+//! still served, so that other members of the backing group can fetch the PoV and back the
+//! candidate as usual. Honest validators are expected to fetch their chunks from the remaining
+//! backers, so availability and parachain progress should not be affected.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_subsystem::SpawnGlue;
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use polkadot_primitives::CandidateHash;
+use sp_core::traits::SpawnNamed;
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use rand::distributions::{Bernoulli, Distribution};
+use std::{
+	collections::HashSet,
+	sync::{Arc, Mutex},
+};
+
+/// Wraps around the availability store and replaces it.
+#[derive(Clone)]
+struct ChunkWithholder {
+	/// Probability of withholding the chunks of a backed candidate.
+	distribution: Bernoulli,
+	/// Candidates for which we refuse to serve chunks.
+	withheld: Arc<Mutex<HashSet<CandidateHash>>>,
+}
+
+impl<Sender> MessageInterceptor<Sender> for ChunkWithholder
+where
+	Sender: overseer::AvailabilityStoreSenderTrait + Clone + Send + 'static,
+{
+	type Message = AvailabilityStoreMessage;
+
+	/// Pick candidates to withhold when their available data is stored after backing and answer
+	/// chunk queries for those candidates with `None`.
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg:
+					AvailabilityStoreMessage::StoreAvailableData {
+						candidate_hash,
+						n_validators,
+						available_data,
+						expected_erasure_root,
+						core_index,
+						node_features,
+						tx,
+					},
+			} => {
+				if self.distribution.sample(&mut rand::thread_rng()) {
+					gum::debug!(
+						target: MALUS,
+						?candidate_hash,
+						"😈 Marking candidate for chunk withholding",
+					);
+					self.withheld.lock().expect("poisoned lock").insert(candidate_hash);
+				}
+
+				Some(FromOrchestra::Communication {
+					msg: AvailabilityStoreMessage::StoreAvailableData {
+						candidate_hash,
+						n_validators,
+						available_data,
+						expected_erasure_root,
+						core_index,
+						node_features,
+						tx,
+					},
+				})
+			},
+			FromOrchestra::Communication {
+				msg: AvailabilityStoreMessage::QueryChunk(candidate_hash, validator_index, tx),
+			} => {
+				if self.withheld.lock().expect("poisoned lock").contains(&candidate_hash) {
+					gum::info!(
+						target: MALUS,
+						?candidate_hash,
+						?validator_index,
+						"😈 Withholding chunk",
+					);
+					let _ = tx.send(None);
+					return None
+				}
+
+				Some(FromOrchestra::Communication {
+					msg: AvailabilityStoreMessage::QueryChunk(candidate_hash, validator_index, tx),
+				})
+			},
+			msg => Some(msg),
+		}
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct WithholdAvailabilityOptions {
+	/// Determines the percentage of backed candidates for which chunks are withheld.
+	/// Value should be in the range [0..=100].
+	#[clap(long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// WithholdAvailability implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct WithholdAvailability {
+	/// Percentage of backed candidates for which chunks are withheld.
+	pub percentage: u8,
+}
+
+impl OverseerGen for WithholdAvailability {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that withholds chunks of {}% of the candidates it backs.",
+			self.percentage,
+		);
+
+		let withholder = ChunkWithholder {
+			distribution: Bernoulli::new(f64::from(self.percentage) / 100.0)
+				.expect("Invalid probability! Percentage must be in range [0..=100]."),
+			withheld: Arc::new(Mutex::new(HashSet::new())),
+		};
+
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_availability_store(move |cb| InterceptedSubsystem::new(cb, withholder))
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
@@ -36,11 +36,14 @@ use polkadot_node_network_protocol::{
 	v1 as protocol_v1, v2 as protocol_v2, OurView, PeerId, UnifiedReputationChange as Rep,
 	Versioned, View,
 };
-use polkadot_node_primitives::{CollationSecondedSignal, PoV, Statement};
+use polkadot_node_primitives::{
+	CollationDropReason, CollationEvent, CollationEventSubscribers, CollationSecondedSignal, PoV,
+	Statement,
+};
 use polkadot_node_subsystem::{
 	messages::{
-		CollatorProtocolMessage, NetworkBridgeEvent, NetworkBridgeTxMessage, ParentHeadData,
-		RuntimeApiMessage,
+		ChainApiMessage, CollatorProtocolMessage, NetworkBridgeEvent, NetworkBridgeTxMessage,
+		ParentHeadData, RuntimeApiMessage,
 	},
 	overseer, FromOrchestra, OverseerSignal,
 };
@@ -48,15 +51,15 @@ use polkadot_node_subsystem_util::{
 	backing_implicit_view::View as ImplicitView,
 	reputation::{ReputationAggregator, REPUTATION_CHANGE_INTERVAL},
 	runtime::{
-		fetch_claim_queue, get_availability_cores, get_group_rotation_info,
+		fetch_claim_queue, get_availability_cores, get_candidate_events, get_group_rotation_info,
 		prospective_parachains_mode, ProspectiveParachainsMode, RuntimeInfo,
 	},
 	TimeoutExt,
 };
 use polkadot_primitives::{
-	vstaging::{CandidateReceiptV2 as CandidateReceipt, CoreState},
-	AuthorityDiscoveryId, CandidateHash, CollatorPair, CoreIndex, GroupIndex, Hash, HeadData,
-	Id as ParaId, SessionIndex,
+	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt, CoreState},
+	AuthorityDiscoveryId, BlockNumber, CandidateHash, CollatorPair, CoreIndex, GroupIndex, Hash,
+	HeadData, Id as ParaId, SessionIndex,
 };
 
 use super::LOG_TARGET;
@@ -130,6 +133,9 @@ struct ValidatorGroup {
 	/// distributing a collation.
 	validators: Vec<AuthorityDiscoveryId>,
 
+	/// The index of the group.
+	group_index: GroupIndex,
+
 	/// Bits indicating which validators have already seen the announcement
 	/// per candidate.
 	advertised_to: HashMap<CandidateHash, BitVec>,
@@ -228,6 +234,14 @@ impl CollationWithCoreIndex {
 	}
 }
 
+/// A distributed candidate we report the on-chain progress of.
+struct TrackedCandidate {
+	/// The relay parent of the candidate.
+	relay_parent: Hash,
+	/// The relay chain blocks we have seen the candidate backed in.
+	backed_in: Vec<(Hash, BlockNumber)>,
+}
+
 struct PerRelayParent {
 	prospective_parachains_mode: ProspectiveParachainsMode,
 	/// Per core index validators group responsible for backing candidates built
@@ -287,6 +301,14 @@ struct State {
 	/// The result senders per collation.
 	collation_result_senders: HashMap<CandidateHash, oneshot::Sender<CollationSecondedSignal>>,
 
+	/// The subscribers to collation events.
+	collation_events: CollationEventSubscribers,
+
+	/// Distributed candidates, which are not yet included or dropped.
+	///
+	/// Only tracked while there are subscribers to collation events.
+	tracked_candidates: HashMap<CandidateHash, TrackedCandidate>,
+
 	/// The mapping from [`PeerId`] to [`HashSet<AuthorityDiscoveryId>`]. This is filled over time
 	/// as we learn the [`PeerId`]'s by `PeerConnected` events.
 	peer_ids: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
@@ -343,6 +365,8 @@ impl State {
 			active_leaves: Default::default(),
 			per_relay_parent: Default::default(),
 			collation_result_senders: Default::default(),
+			collation_events: Default::default(),
+			tracked_candidates: Default::default(),
 			peer_ids: Default::default(),
 			validator_groups_buf: ValidatorGroupsBuffer::with_capacity(VALIDATORS_BUFFER_CAPACITY),
 			reconnect_timeout: Fuse::terminated(),
@@ -388,6 +412,11 @@ async fn distribute_collation<Context>(
 				candidate_hash = ?candidate_hash,
 				"Candidate relay parent is out of our view",
 			);
+			state.collation_events.notify(collation_dropped(
+				candidate_relay_parent,
+				candidate_hash,
+				CollationDropReason::RelayParentOutOfView,
+			));
 			return Ok(())
 		},
 	};
@@ -406,6 +435,11 @@ async fn distribute_collation<Context>(
 			"The limit of {} collations per relay parent is already reached",
 			collations_limit,
 		);
+		state.collation_events.notify(collation_dropped(
+			candidate_relay_parent,
+			candidate_hash,
+			CollationDropReason::TooManyCollations,
+		));
 		return Ok(())
 	}
 
@@ -430,6 +464,11 @@ async fn distribute_collation<Context>(
 					para_id = %id,
 					"looks like no core is assigned to {} at {}", id, candidate_relay_parent,
 				);
+				state.collation_events.notify(collation_dropped(
+					candidate_relay_parent,
+					candidate_hash,
+					CollationDropReason::NotAssignedToCore,
+				));
 
 				return Ok(())
 			},
@@ -456,6 +495,11 @@ async fn distribute_collation<Context>(
 			?core_index,
 			"Attempting to distribute collation for a core we are not assigned to ",
 		);
+		state.collation_events.notify(collation_dropped(
+			candidate_relay_parent,
+			candidate_hash,
+			CollationDropReason::NotAssignedToCore,
+		));
 
 		return Ok(())
 	}
@@ -475,6 +519,11 @@ async fn distribute_collation<Context>(
 			core = ?our_core,
 			"there are no validators assigned to core",
 		);
+		state.collation_events.notify(collation_dropped(
+			candidate_relay_parent,
+			candidate_hash,
+			CollationDropReason::NoBackingGroup,
+		));
 
 		return Ok(())
 	}
@@ -507,6 +556,7 @@ async fn distribute_collation<Context>(
 	per_relay_parent.validator_group.entry(core_index).or_insert_with(|| {
 		let mut group = ValidatorGroup::default();
 		group.validators = validators;
+		group.group_index = group_index;
 		group
 	});
 
@@ -517,6 +567,13 @@ async fn distribute_collation<Context>(
 		state.collation_result_senders.insert(candidate_hash, result_sender);
 	}
 
+	if !state.collation_events.is_empty() {
+		state.tracked_candidates.insert(
+			candidate_hash,
+			TrackedCandidate { relay_parent: candidate_relay_parent, backed_in: Vec::new() },
+		);
+	}
+
 	let parent_head_data = if elastic_scaling {
 		ParentHeadData::WithData { hash: parent_head_data_hash, head_data: parent_head_data }
 	} else {
@@ -562,6 +619,7 @@ async fn distribute_collation<Context>(
 			peer_data.version,
 			&state.peer_ids,
 			&mut state.advertisement_timeouts,
+			&mut state.collation_events,
 			&state.metrics,
 		)
 		.await;
@@ -738,6 +796,7 @@ async fn advertise_collation<Context>(
 	protocol_version: CollationVersion,
 	peer_ids: &HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
 	advertisement_timeouts: &mut FuturesUnordered<ResetInterestTimeout>,
+	collation_events: &mut CollationEventSubscribers,
 	metrics: &Metrics,
 ) {
 	for (candidate_hash, collation_and_core) in per_relay_parent.collations.iter_mut() {
@@ -791,6 +850,12 @@ async fn advertise_collation<Context>(
 			"Advertising collation.",
 		);
 
+		if matches!(collation.status, CollationStatus::Created) {
+			collation_events.notify(CollationEvent::Advertised {
+				relay_parent,
+				candidate_hash: *candidate_hash,
+			});
+		}
 		collation.status.advance_to_advertised();
 
 		let collation_message = match protocol_version {
@@ -899,6 +964,7 @@ async fn process_msg<Context>(
 				);
 			}
 		},
+		SubscribeCollationEvents(sender) => state.collation_events.subscribe(sender),
 		msg @ (ReportCollator(..) | Invalid(..) | Seconded(..)) => {
 			gum::warn!(
 				target: LOG_TARGET,
@@ -1014,6 +1080,17 @@ async fn handle_incoming_peer_message<Context>(
 					.await?
 					.map_err(Error::InvalidStatementSignature)?;
 
+				let candidate_hash = statement.payload().candidate_hash();
+				if state.per_relay_parent.get(&relay_parent).map_or(false, |per_relay_parent| {
+					per_relay_parent.collations.contains_key(&candidate_hash)
+				}) {
+					state.collation_events.notify(CollationEvent::Seconded {
+						relay_parent,
+						candidate_hash,
+						validator_index: statement.validator_index(),
+					});
+				}
+
 				let removed =
 					state.collation_result_senders.remove(&statement.payload().candidate_hash());
 
@@ -1111,14 +1188,19 @@ async fn handle_incoming_request<Context>(
 					return Ok(())
 				},
 			};
-			let (receipt, pov, parent_head_data) =
+			let (receipt, pov, parent_head_data, group_index) =
 				if let Some(collation_with_core) = collation_with_core {
+					let group_index = per_relay_parent
+						.validator_group
+						.get(collation_with_core.core_index())
+						.map(|group| group.group_index);
 					let collation = collation_with_core.collation_mut();
 					collation.status.advance_to_requested();
 					(
 						collation.receipt.clone(),
 						collation.pov.clone(),
 						collation.parent_head_data.clone(),
+						group_index,
 					)
 				} else {
 					gum::warn!(
@@ -1150,6 +1232,14 @@ async fn handle_incoming_request<Context>(
 				return Ok(())
 			}
 
+			if let Some(group_index) = group_index {
+				state.collation_events.notify(CollationEvent::Fetched {
+					relay_parent,
+					candidate_hash,
+					group_index,
+				});
+			}
+
 			if waiting.collation_fetch_active {
 				waiting.req_queue.push_back(req);
 			} else {
@@ -1238,6 +1328,7 @@ async fn handle_peer_view_change<Context>(
 				*version,
 				&state.peer_ids,
 				&mut state.advertisement_timeouts,
+				&mut state.collation_events,
 				&state.metrics,
 			)
 			.await;
@@ -1327,6 +1418,151 @@ async fn handle_network_msg<Context>(
 	Ok(())
 }
 
+/// Report the on-chain progress of tracked candidates in a new leaf.
+#[overseer::contextbounds(CollatorProtocol, prefix = crate::overseer)]
+async fn note_candidate_events<Context>(ctx: &mut Context, state: &mut State, leaf: Hash) {
+	let events = match get_candidate_events(ctx.sender(), leaf).await {
+		Ok(events) => events,
+		Err(err) => {
+			gum::debug!(target: LOG_TARGET, ?leaf, ?err, "Failed to fetch candidate events");
+			return
+		},
+	};
+
+	// The number of the leaf is only needed to find out later whether it was finalized.
+	let backs_tracked_candidate = events.iter().any(|event| {
+		matches!(
+			event,
+			CandidateEvent::CandidateBacked(receipt, ..)
+				if state.tracked_candidates.contains_key(&receipt.hash())
+		)
+	});
+	let leaf_number = if backs_tracked_candidate { block_number(ctx, leaf).await } else { None };
+
+	for event in events {
+		match event {
+			CandidateEvent::CandidateBacked(receipt, ..) => {
+				let candidate_hash = receipt.hash();
+				if let Some(tracked) = state.tracked_candidates.get_mut(&candidate_hash) {
+					if let Some(number) = leaf_number {
+						tracked.backed_in.push((leaf, number));
+					}
+					state.collation_events.notify(CollationEvent::Backed {
+						relay_parent: tracked.relay_parent,
+						candidate_hash,
+						relay_block: leaf,
+					});
+				}
+			},
+			CandidateEvent::CandidateIncluded(receipt, ..) => {
+				let candidate_hash = receipt.hash();
+				if let Some(tracked) = state.tracked_candidates.remove(&candidate_hash) {
+					state.collation_events.notify(CollationEvent::Included {
+						relay_parent: tracked.relay_parent,
+						candidate_hash,
+						relay_block: leaf,
+					});
+				}
+			},
+			CandidateEvent::CandidateTimedOut(receipt, ..) => {
+				let candidate_hash = receipt.hash();
+				if let Some(tracked) = state.tracked_candidates.remove(&candidate_hash) {
+					state.collation_events.notify(collation_dropped(
+						tracked.relay_parent,
+						candidate_hash,
+						CollationDropReason::AvailabilityTimedOut,
+					));
+				}
+			},
+		}
+	}
+}
+
+/// Drop the tracked candidates which were only backed in relay chain blocks that were not
+/// finalized.
+///
+/// Candidates are only dropped once their relay parent left our view, as they may still be backed
+/// on the finalized chain before that.
+#[overseer::contextbounds(CollatorProtocol, prefix = crate::overseer)]
+async fn note_block_finalized<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	finalized_number: BlockNumber,
+) {
+	let candidates: Vec<_> = state
+		.tracked_candidates
+		.iter()
+		.filter(|(_, tracked)| {
+			!tracked.backed_in.is_empty() &&
+				!state.per_relay_parent.contains_key(&tracked.relay_parent) &&
+				tracked.backed_in.iter().all(|(_, number)| *number <= finalized_number)
+		})
+		.map(|(candidate_hash, tracked)| (*candidate_hash, tracked.backed_in.clone()))
+		.collect();
+
+	let mut finalized_hashes = HashMap::new();
+	for (candidate_hash, backed_in) in candidates {
+		let mut abandoned = true;
+		for (block, number) in backed_in {
+			if !finalized_hashes.contains_key(&number) {
+				let hash = finalized_block_hash(ctx, number).await;
+				finalized_hashes.insert(number, hash);
+			}
+			// Keep the candidate if we can't tell whether the block was finalized.
+			if finalized_hashes[&number].map_or(true, |hash| hash == block) {
+				abandoned = false;
+				break
+			}
+		}
+
+		if abandoned {
+			if let Some(tracked) = state.tracked_candidates.remove(&candidate_hash) {
+				state.collation_events.notify(collation_dropped(
+					tracked.relay_parent,
+					candidate_hash,
+					CollationDropReason::BackedOnAbandonedFork,
+				));
+			}
+		}
+	}
+}
+
+#[overseer::contextbounds(CollatorProtocol, prefix = crate::overseer)]
+async fn block_number<Context>(ctx: &mut Context, hash: Hash) -> Option<BlockNumber> {
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(ChainApiMessage::BlockNumber(hash, tx)).await;
+	match rx.await {
+		Ok(Ok(number)) => number,
+		Ok(Err(err)) => {
+			gum::debug!(target: LOG_TARGET, ?hash, ?err, "Failed to fetch block number");
+			None
+		},
+		Err(_) => None,
+	}
+}
+
+#[overseer::contextbounds(CollatorProtocol, prefix = crate::overseer)]
+async fn finalized_block_hash<Context>(ctx: &mut Context, number: BlockNumber) -> Option<Hash> {
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(ChainApiMessage::FinalizedBlockHash(number, tx)).await;
+	match rx.await {
+		Ok(Ok(hash)) => hash,
+		Ok(Err(err)) => {
+			gum::debug!(target: LOG_TARGET, ?number, ?err, "Failed to fetch finalized block hash");
+			None
+		},
+		Err(_) => None,
+	}
+}
+
+fn collation_dropped(
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	reason: CollationDropReason,
+) -> CollationEvent {
+	CollationEvent::Dropped { relay_parent, candidate_hash: Some(candidate_hash), reason }
+}
+
 /// Handles our view changes.
 #[overseer::contextbounds(CollatorProtocol, prefix = crate::overseer)]
 async fn handle_our_view_change<Context>(
@@ -1345,6 +1581,10 @@ async fn handle_our_view_change<Context>(
 		state.active_leaves.insert(*leaf, mode);
 		state.per_relay_parent.insert(*leaf, PerRelayParent::new(mode));
 
+		if !state.tracked_candidates.is_empty() {
+			note_candidate_events(ctx, state, *leaf).await;
+		}
+
 		if mode.is_enabled() {
 			if let Some(ref mut implicit_view) = state.implicit_view {
 				implicit_view
@@ -1382,6 +1622,7 @@ async fn handle_our_view_change<Context>(
 							*peer_version,
 							&state.peer_ids,
 							&mut state.advertisement_timeouts,
+							&mut state.collation_events,
 							&state.metrics,
 						)
 						.await;
@@ -1421,6 +1662,21 @@ async fn handle_our_view_change<Context>(
 				state.collation_result_senders.remove(&candidate_hash);
 				state.validator_groups_buf.remove_candidate(&candidate_hash);
 
+				// Backed candidates stay tracked until they are included, time out or their fork
+				// is abandoned.
+				if state
+					.tracked_candidates
+					.get(&candidate_hash)
+					.map_or(false, |tracked| tracked.backed_in.is_empty())
+				{
+					state.tracked_candidates.remove(&candidate_hash);
+					state.collation_events.notify(collation_dropped(
+						*removed,
+						candidate_hash,
+						CollationDropReason::RelayParentOutOfView,
+					));
+				}
+
 				match collation.status {
 					CollationStatus::Created => gum::warn!(
 						target: LOG_TARGET,
@@ -1515,7 +1771,11 @@ async fn run_inner<Context>(
 						*reconnect_timeout = futures_timer::Delay::new(RECONNECT_AFTER_LEAF_TIMEOUT).fuse();
 					}
 				}
-				FromOrchestra::Signal(BlockFinalized(..)) => {}
+				FromOrchestra::Signal(BlockFinalized(_, number)) => {
+					if !state.tracked_candidates.is_empty() {
+						note_block_finalized(&mut ctx, &mut state, number).await;
+					}
+				}
 				FromOrchestra::Signal(Conclude) => return Ok(()),
 			},
 			CollationSendResult { relay_parent, candidate_hash, peer_id, timed_out } =
@@ -18,11 +18,12 @@ use super::*;
 
 use std::{
 	collections::{BTreeMap, HashSet, VecDeque},
+	sync::Arc,
 	time::Duration,
 };
 
 use assert_matches::assert_matches;
-use futures::{executor, future, Future};
+use futures::{channel::mpsc, executor, future, Future};
 use futures_timer::Delay;
 
 use codec::{Decode, Encode};
@@ -30,6 +31,7 @@ use codec::{Decode, Encode};
 use sc_network::config::IncomingRequest as RawIncomingRequest;
 use sp_core::crypto::Pair;
 use sp_keyring::Sr25519Keyring;
+use sp_keystore::{Keystore, KeystorePtr};
 use sp_runtime::traits::AppVerify;
 
 use polkadot_node_network_protocol::{
@@ -38,7 +40,7 @@ use polkadot_node_network_protocol::{
 	request_response::{IncomingRequest, ReqProtocolNames},
 	view,
 };
-use polkadot_node_primitives::BlockData;
+use polkadot_node_primitives::{BlockData, SignedFullStatement};
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
 	messages::{AllMessages, ReportPeerMessage, RuntimeApiMessage, RuntimeApiRequest},
@@ -47,9 +49,10 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::{reputation::add_reputation, TimeoutExt};
 use polkadot_primitives::{
-	AuthorityDiscoveryId, Block, CollatorPair, ExecutorParams, GroupIndex, GroupRotationInfo,
-	IndexedVec, NodeFeatures, ScheduledCore, SessionIndex, SessionInfo, ValidatorId,
-	ValidatorIndex,
+	vstaging::CommittedCandidateReceiptV2 as CommittedCandidateReceipt, AuthorityDiscoveryId,
+	Block, BlockNumber, CandidateCommitments, CollatorPair, ExecutorParams, GroupIndex,
+	GroupRotationInfo, IndexedVec, NodeFeatures, ScheduledCore, SessionIndex, SessionInfo,
+	SigningContext, ValidatorId, ValidatorIndex,
 };
 use polkadot_primitives_test_helpers::TestCandidateBuilder;
 use test_helpers::mock::new_leaf;
@@ -1600,3 +1603,292 @@ fn connect_to_buffered_groups() {
 		},
 	);
 }
+
+/// Subscribe to the collation events of the subsystem.
+async fn subscribe_collation_events(
+	virtual_overseer: &mut VirtualOverseer,
+) -> mpsc::Receiver<CollationEvent> {
+	let (tx, rx) = mpsc::channel(16);
+	overseer_send(virtual_overseer, CollatorProtocolMessage::SubscribeCollationEvents(tx)).await;
+	rx
+}
+
+async fn expect_collation_event(events: &mut mpsc::Receiver<CollationEvent>) -> CollationEvent {
+	events
+		.next()
+		.timeout(TIMEOUT)
+		.await
+		.flatten()
+		.expect(&format!("{:?} is more than enough to receive collation events", TIMEOUT))
+}
+
+/// Answer the request for the candidate events of a new leaf and for its number, if needed.
+async fn answer_candidate_events(
+	virtual_overseer: &mut VirtualOverseer,
+	leaf: Hash,
+	leaf_number: Option<BlockNumber>,
+	events: Vec<CandidateEvent>,
+) {
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::CandidateEvents(tx)
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			tx.send(Ok(events)).unwrap();
+		}
+	);
+
+	if let Some(number) = leaf_number {
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::BlockNumber(hash, tx)) => {
+				assert_eq!(hash, leaf);
+				tx.send(Ok(Some(number))).unwrap();
+			}
+		);
+	}
+}
+
+#[test]
+fn collation_lifecycle_events() {
+	let mut test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id;
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(
+		local_peer_id,
+		collator_pair,
+		ReputationAggregator::new(|_| true),
+		|test_harness| async move {
+			let mut virtual_overseer = test_harness.virtual_overseer;
+			let mut req_v1_cfg = test_harness.req_v1_cfg;
+			let req_v2_cfg = test_harness.req_v2_cfg;
+
+			setup_system(&mut virtual_overseer, &test_state).await;
+			let mut events = subscribe_collation_events(&mut virtual_overseer).await;
+
+			let relay_parent = test_state.relay_parent;
+			let pov = PoV { block_data: BlockData(vec![42, 43, 44]) };
+			let commitments = CandidateCommitments::default();
+			let receipt = TestCandidateBuilder {
+				para_id: test_state.para_id,
+				relay_parent,
+				pov_hash: pov.hash(),
+				commitments_hash: commitments.hash(),
+				..Default::default()
+			}
+			.build();
+			let candidate =
+				CommittedCandidateReceipt { descriptor: receipt.descriptor.clone(), commitments };
+			let candidate_hash = receipt.hash();
+			assert_eq!(candidate.hash(), candidate_hash);
+
+			distribute_collation_with_receipt(
+				&mut virtual_overseer,
+				&test_state,
+				relay_parent,
+				true,
+				receipt.clone(),
+				pov,
+				Hash::zero(),
+			)
+			.await;
+
+			// Alice is the validator 0, in the backing group 0.
+			let peer = test_state.validator_peer_id[0];
+			let authority_id = test_state.session_info.discovery_keys[0].clone();
+			connect_peer(&mut virtual_overseer, peer, CollationVersion::V1, Some(authority_id))
+				.await;
+			expect_declare_msg(&mut virtual_overseer, &test_state, &peer).await;
+
+			send_peer_view_change(&mut virtual_overseer, &peer, vec![relay_parent]).await;
+			expect_advertise_collation_msg(&mut virtual_overseer, &[peer], relay_parent, None)
+				.await;
+			assert_eq!(
+				expect_collation_event(&mut events).await,
+				CollationEvent::Advertised { relay_parent, candidate_hash },
+			);
+
+			let (pending_response, rx) = oneshot::channel();
+			req_v1_cfg
+				.inbound_queue
+				.as_mut()
+				.unwrap()
+				.send(RawIncomingRequest {
+					peer,
+					payload: request_v1::CollationFetchingRequest {
+						relay_parent,
+						para_id: test_state.para_id,
+					}
+					.encode(),
+					pending_response,
+				})
+				.await
+				.unwrap();
+			assert_matches!(rx.await, Ok(_));
+			assert_eq!(
+				expect_collation_event(&mut events).await,
+				CollationEvent::Fetched {
+					relay_parent,
+					candidate_hash,
+					group_index: GroupIndex(0)
+				},
+			);
+
+			let keystore: KeystorePtr = Arc::new(sc_keystore::LocalKeystore::in_memory());
+			Keystore::sr25519_generate_new(
+				&*keystore,
+				polkadot_primitives::PARACHAIN_KEY_TYPE_ID,
+				Some(&Sr25519Keyring::Alice.to_seed()),
+			)
+			.expect("Insert key into keystore");
+			let statement = SignedFullStatement::sign(
+				&keystore,
+				Statement::Seconded(candidate),
+				&SigningContext {
+					session_index: test_state.session_index,
+					parent_hash: relay_parent,
+				},
+				ValidatorIndex(0),
+				&ValidatorId::from(Sr25519Keyring::Alice.public()),
+			)
+			.ok()
+			.flatten()
+			.expect("should be signed");
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
+					peer,
+					Versioned::V1(protocol_v1::CollatorProtocolMessage::CollationSeconded(
+						relay_parent,
+						statement.into_unchecked(),
+					)),
+				)),
+			)
+			.await;
+			assert_eq!(
+				expect_collation_event(&mut events).await,
+				CollationEvent::Seconded {
+					relay_parent,
+					candidate_hash,
+					validator_index: ValidatorIndex(0),
+				},
+			);
+
+			// The candidate is backed in the next relay chain block.
+			test_state.advance_to_new_round(&mut virtual_overseer, true).await;
+			let backed_in = test_state.relay_parent;
+			answer_candidate_events(
+				&mut virtual_overseer,
+				backed_in,
+				Some(2),
+				vec![CandidateEvent::CandidateBacked(
+					receipt.clone(),
+					HeadData::default(),
+					CoreIndex(0),
+					GroupIndex(0),
+				)],
+			)
+			.await;
+			assert_eq!(
+				expect_collation_event(&mut events).await,
+				CollationEvent::Backed { relay_parent, candidate_hash, relay_block: backed_in },
+			);
+
+			// The relay parent leaves our view, but the backed candidate is still tracked.
+			test_state.advance_to_new_round(&mut virtual_overseer, true).await;
+			let included_in = test_state.relay_parent;
+			answer_candidate_events(
+				&mut virtual_overseer,
+				included_in,
+				None,
+				vec![CandidateEvent::CandidateIncluded(
+					receipt,
+					HeadData::default(),
+					CoreIndex(0),
+					GroupIndex(0),
+				)],
+			)
+			.await;
+			assert_eq!(
+				expect_collation_event(&mut events).await,
+				CollationEvent::Included { relay_parent, candidate_hash, relay_block: included_in },
+			);
+
+			assert!(events.next().timeout(TIMEOUT).await.is_none());
+			TestHarness { virtual_overseer, req_v1_cfg, req_v2_cfg }
+		},
+	);
+}
+
+#[test]
+fn collation_backed_on_abandoned_fork_is_dropped() {
+	let mut test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id;
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(
+		local_peer_id,
+		collator_pair,
+		ReputationAggregator::new(|_| true),
+		|mut test_harness| async move {
+			let virtual_overseer = &mut test_harness.virtual_overseer;
+
+			setup_system(virtual_overseer, &test_state).await;
+			let mut events = subscribe_collation_events(virtual_overseer).await;
+
+			let relay_parent = test_state.relay_parent;
+			let DistributeCollation { candidate, .. } =
+				distribute_collation(virtual_overseer, &test_state, relay_parent, true).await;
+			let candidate_hash = candidate.hash();
+
+			test_state.advance_to_new_round(virtual_overseer, true).await;
+			let backed_in = test_state.relay_parent;
+			answer_candidate_events(
+				virtual_overseer,
+				backed_in,
+				Some(2),
+				vec![CandidateEvent::CandidateBacked(
+					candidate,
+					HeadData::default(),
+					CoreIndex(0),
+					GroupIndex(0),
+				)],
+			)
+			.await;
+			assert_eq!(
+				expect_collation_event(&mut events).await,
+				CollationEvent::Backed { relay_parent, candidate_hash, relay_block: backed_in },
+			);
+
+			// Switch to another fork, the candidate is neither included nor timed out on it.
+			test_state.advance_to_new_round(virtual_overseer, false).await;
+			answer_candidate_events(virtual_overseer, test_state.relay_parent, None, Vec::new())
+				.await;
+
+			overseer_signal(
+				virtual_overseer,
+				OverseerSignal::BlockFinalized(test_state.relay_parent, 3),
+			)
+			.await;
+			assert_matches!(
+				overseer_recv(virtual_overseer).await,
+				AllMessages::ChainApi(ChainApiMessage::FinalizedBlockHash(2, tx)) => {
+					tx.send(Ok(Some(Hash::repeat_byte(0xaa)))).unwrap();
+				}
+			);
+			assert_eq!(
+				expect_collation_event(&mut events).await,
+				CollationEvent::Dropped {
+					relay_parent,
+					candidate_hash: Some(candidate_hash),
+					reason: CollationDropReason::BackedOnAbandonedFork,
+				},
+			);
+
+			test_harness
+		},
+	);
+}
@@ -1462,6 +1462,12 @@ async fn process_msg<Context>(
 				"DistributeCollation message is not expected on the validator side of the protocol",
 			);
 		},
+		SubscribeCollationEvents(_) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				"SubscribeCollationEvents message is not expected on the validator side of the protocol",
+			);
+		},
 		ReportCollator(id) => {
 			report_collator(&mut state.reputation, ctx.sender(), &state.peer_data, id).await;
 		},
@@ -99,6 +99,9 @@ pub use self::metrics::Metrics as OverseerMetrics;
 pub mod dummy;
 pub use self::dummy::DummySubsystem;
 
+pub mod message_trace;
+pub use self::message_trace::{MessageTracer, TracedSubsystem};
+
 pub use polkadot_node_metrics::{
 	metrics::{prometheus, Metrics as MetricsTrait},
 	Metronome,
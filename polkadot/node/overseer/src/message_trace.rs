@@ -0,0 +1,560 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracing of the messages exchanged between subsystems.
+//!
+//! Wrapping subsystems into a [`TracedSubsystem`] records every message they send and receive in
+//! a shared [`MessageTracer`], which logs origin, destination, type and queueing latency of each
+//! message. The [`MessageTracer::run_watchdog`] task uses the same records to find subsystems
+//! which have messages queued but haven't received any of them for a while, which is what a
+//! stalled or deadlocked subsystem looks like from the outside.
+//!
+//! Where a stalled subsystem is awaiting can't be observed from outside of its task. The closest
+//! the watchdog gets is to report the message the subsystem received last, the message it sent
+//! last, which is usually the request it is waiting on, and whether it is still blocked sending
+//! that message because the channel of the destination is full.
+//!
+//! Origins and destinations are identified by the message types the subsystems consume, as that is
+//! all a sender knows about the receiving side. Their names are filled in once the subsystems are
+//! started.
+//!
+//! This is a debugging aid and too expensive to be enabled by default.
+
+use std::{
+	collections::{BTreeMap, HashMap, VecDeque},
+	fmt::{self, Debug, Write as _},
+	future::Future,
+	pin::Pin,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use futures_timer::Delay;
+use parking_lot::Mutex;
+
+use crate::{
+	gen::SpawnedSubsystem, FromOrchestra, Priority, Subsystem, SubsystemContext, SubsystemError,
+	SubsystemSender, TrySendError,
+};
+
+const LOG_TARGET: &str = "parachain::overseer-trace";
+
+/// The suffix of the enums orchestra generates to wrap all messages a subsystem may send.
+const OUTGOING_MESSAGES_SUFFIX: &str = "OutgoingMessages";
+
+/// Messages of a single destination the tracer keeps track of, to bound memory usage when
+/// receives can't be matched to sends.
+const MAX_PENDING_PER_DESTINATION: usize = 16 * 1024;
+
+/// The type name of `T`, without its path.
+fn short_type_name<T>() -> &'static str {
+	let name = std::any::type_name::<T>();
+	name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Collects the leading identifiers of a `Debug` representation.
+///
+/// Formatting is aborted as soon as enough identifiers are collected, so that messages carrying
+/// large payloads are never formatted in full.
+struct LeadingIdents {
+	wanted: usize,
+	current: String,
+	idents: Vec<String>,
+}
+
+impl LeadingIdents {
+	fn of(value: &impl Debug, wanted: usize) -> Vec<String> {
+		let mut collector = Self { wanted, current: String::new(), idents: Vec::new() };
+		// An error just means we have seen enough.
+		let _ = write!(collector, "{:?}", value);
+		if collector.idents.len() < wanted && !collector.current.is_empty() {
+			collector.idents.push(collector.current);
+		}
+		collector.idents
+	}
+}
+
+impl fmt::Write for LeadingIdents {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		for c in s.chars() {
+			if c.is_alphanumeric() || c == '_' {
+				self.current.push(c);
+			} else if !self.current.is_empty() {
+				self.idents.push(std::mem::take(&mut self.current));
+				if self.idents.len() >= self.wanted {
+					return Err(fmt::Error)
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Destination and kind of an outgoing message of type `M`.
+///
+/// Subsystems usually send the orchestra generated wrapper of all their outgoing messages, whose
+/// variants are named after the wrapped message types.
+pub(crate) fn describe_outgoing<M: Debug>(msg: &M) -> (String, String) {
+	let type_name = short_type_name::<M>();
+	if type_name.ends_with(OUTGOING_MESSAGES_SUFFIX) {
+		let mut idents = LeadingIdents::of(msg, 2).into_iter();
+		let destination = idents.next().unwrap_or_default();
+		(destination, idents.next().unwrap_or_default())
+	} else {
+		(type_name.to_owned(), LeadingIdents::of(msg, 1).pop().unwrap_or_default())
+	}
+}
+
+struct PendingMessage {
+	origin: &'static str,
+	kind: String,
+	sent_at: Instant,
+}
+
+struct Inbox {
+	pending: VecDeque<PendingMessage>,
+	last_received_at: Instant,
+	/// The kind of the last message received, i.e. what the subsystem is busy with.
+	handling: Option<String>,
+	/// Destination and kind of the last message the subsystem sent.
+	last_sent: Option<(String, String)>,
+	/// Whether the subsystem is waiting for its last message to be accepted by the destination.
+	blocked_sending: bool,
+	stalled: bool,
+}
+
+impl Inbox {
+	fn new(now: Instant) -> Self {
+		Self {
+			pending: VecDeque::new(),
+			last_received_at: now,
+			handling: None,
+			last_sent: None,
+			blocked_sending: false,
+			stalled: false,
+		}
+	}
+}
+
+#[derive(Default)]
+struct TracerState {
+	/// Subsystem names by the type of message they consume.
+	names: HashMap<String, &'static str>,
+	/// Inboxes by the type of message consumed.
+	inboxes: HashMap<String, Inbox>,
+}
+
+impl TracerState {
+	fn name<'a>(&'a self, key: &'a str) -> &'a str {
+		self.names.get(key).copied().unwrap_or(key)
+	}
+}
+
+/// A subsystem which has had messages queued without receiving any of them for too long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stall {
+	/// The name of the subsystem.
+	pub subsystem: String,
+	/// How long ago the subsystem last received a message or signal.
+	pub since_last_receive: Duration,
+	/// The kind of message the subsystem received last, which it is presumably stuck on.
+	pub handling: Option<String>,
+	/// The destination and kind of the message the subsystem sent last, often a request it is
+	/// waiting for the response to.
+	pub last_sent: Option<(String, String)>,
+	/// Whether the subsystem is blocked sending `last_sent`, because the channel of the
+	/// destination is full.
+	pub blocked_sending: bool,
+	/// The number of queued messages by kind.
+	pub pending: BTreeMap<String, usize>,
+	/// The number of queued messages by sending subsystem.
+	pub senders: BTreeMap<String, usize>,
+	/// How long the oldest queued message has been waiting.
+	pub oldest_pending: Duration,
+}
+
+/// Records the messages exchanged between [`TracedSubsystem`]s.
+#[derive(Clone, Default)]
+pub struct MessageTracer {
+	state: Arc<Mutex<TracerState>>,
+}
+
+impl MessageTracer {
+	/// Create a new tracer.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub(crate) fn register(&self, key: &'static str, name: &'static str) {
+		let mut state = self.state.lock();
+		state.names.insert(key.to_owned(), name);
+		state
+			.inboxes
+			.entry(key.to_owned())
+			.or_insert_with(|| Inbox::new(Instant::now()));
+	}
+
+	pub(crate) fn on_send(&self, origin: &'static str, destination: String, kind: String) {
+		let now = Instant::now();
+		let mut state = self.state.lock();
+
+		let sender = state.inboxes.entry(origin.to_owned()).or_insert_with(|| Inbox::new(now));
+		sender.last_sent = Some((destination.clone(), kind.clone()));
+
+		let inbox = state.inboxes.entry(destination).or_insert_with(|| Inbox::new(now));
+		if inbox.pending.len() >= MAX_PENDING_PER_DESTINATION {
+			inbox.pending.pop_front();
+		}
+		inbox.pending.push_back(PendingMessage { origin, kind, sent_at: now });
+	}
+
+	/// Note that `origin` started or finished waiting for its last message to be accepted.
+	pub(crate) fn set_blocked_sending(&self, origin: &'static str, blocked: bool) {
+		let mut state = self.state.lock();
+		if let Some(inbox) = state.inboxes.get_mut(origin) {
+			inbox.blocked_sending = blocked;
+		}
+	}
+
+	fn on_send_failed(&self, destination: &str, kind: &str) {
+		let mut state = self.state.lock();
+		if let Some(inbox) = state.inboxes.get_mut(destination) {
+			if let Some(i) = inbox.pending.iter().rposition(|pending| pending.kind == kind) {
+				inbox.pending.remove(i);
+			}
+		}
+	}
+
+	fn on_receive<M: Debug, S: Debug>(&self, destination: &'static str, msg: &FromOrchestra<M, S>) {
+		match msg {
+			FromOrchestra::Signal(signal) => self.on_signal(destination, signal),
+			FromOrchestra::Communication { msg } => self.on_message(destination, msg),
+		}
+	}
+
+	pub(crate) fn on_signal<S: Debug>(&self, destination: &'static str, signal: &S) {
+		let kind = LeadingIdents::of(signal, 1).pop().unwrap_or_default();
+		let mut state = self.state.lock();
+		Self::note_progress(&mut state, destination, kind, Instant::now());
+	}
+
+	pub(crate) fn on_message<M: Debug>(&self, destination: &'static str, msg: &M) {
+		let kind = LeadingIdents::of(msg, 1).pop().unwrap_or_default();
+		let now = Instant::now();
+		let mut state = self.state.lock();
+
+		// Channels are not strictly FIFO across senders and priorities, so match by kind.
+		let sent = state.inboxes.get_mut(destination).and_then(|inbox| {
+			let i = inbox.pending.iter().position(|pending| pending.kind == kind)?;
+			inbox.pending.remove(i)
+		});
+
+		match sent {
+			Some(sent) => gum::trace!(
+				target: LOG_TARGET,
+				origin = state.name(sent.origin),
+				destination = state.name(destination),
+				kind = %kind,
+				latency = ?now.duration_since(sent.sent_at),
+				"Message received",
+			),
+			None => gum::trace!(
+				target: LOG_TARGET,
+				destination = state.name(destination),
+				kind = %kind,
+				"Message from outside of the traced subsystems received",
+			),
+		}
+
+		Self::note_progress(&mut state, destination, kind, now);
+	}
+
+	fn note_progress(
+		state: &mut TracerState,
+		destination: &'static str,
+		kind: String,
+		now: Instant,
+	) {
+		let name = state.names.get(destination).copied().unwrap_or(destination);
+		let inbox = state.inboxes.entry(destination.to_owned()).or_insert_with(|| Inbox::new(now));
+
+		if inbox.stalled {
+			gum::info!(
+				target: LOG_TARGET,
+				subsystem = name,
+				stalled_for = ?now.duration_since(inbox.last_received_at),
+				"Subsystem is receiving messages again",
+			);
+			inbox.stalled = false;
+		}
+
+		inbox.last_received_at = now;
+		inbox.handling = Some(kind);
+	}
+
+	/// Find subsystems which have had messages queued for at least `stall_timeout`, without
+	/// receiving anything in that time.
+	///
+	/// Subsystems are only returned once per stall.
+	pub(crate) fn detect_stalls(&self, stall_timeout: Duration, now: Instant) -> Vec<Stall> {
+		let mut state = self.state.lock();
+		let state = &mut *state;
+		let mut stalls = Vec::new();
+
+		for (key, inbox) in state.inboxes.iter_mut() {
+			if inbox.stalled {
+				continue
+			}
+
+			let since_last_receive = now.saturating_duration_since(inbox.last_received_at);
+			let oldest_pending = match inbox.pending.front() {
+				Some(oldest) => now.saturating_duration_since(oldest.sent_at),
+				None => continue,
+			};
+			if since_last_receive < stall_timeout || oldest_pending < stall_timeout {
+				continue
+			}
+
+			let mut pending = BTreeMap::new();
+			let mut senders = BTreeMap::new();
+			for message in &inbox.pending {
+				*pending.entry(message.kind.clone()).or_default() += 1;
+				let sender = state.names.get(message.origin).copied().unwrap_or(message.origin);
+				*senders.entry(sender.to_owned()).or_default() += 1;
+			}
+
+			inbox.stalled = true;
+			stalls.push(Stall {
+				subsystem: state
+					.names
+					.get(key)
+					.map_or_else(|| key.clone(), |name| name.to_string()),
+				since_last_receive,
+				handling: inbox.handling.clone(),
+				last_sent: inbox.last_sent.as_ref().map(|(destination, kind)| {
+					(
+						state
+							.names
+							.get(destination)
+							.map_or(destination.as_str(), |name| *name)
+							.to_owned(),
+						kind.clone(),
+					)
+				}),
+				blocked_sending: inbox.blocked_sending,
+				pending,
+				senders,
+				oldest_pending,
+			});
+		}
+
+		stalls.sort_by(|a, b| a.subsystem.cmp(&b.subsystem));
+		stalls
+	}
+
+	/// Periodically check for stalled subsystems and log the messages they received and sent
+	/// last, and what is queued for them.
+	///
+	/// Each stall is reported once, and again if the subsystem stalls after having recovered.
+	pub async fn run_watchdog(self, stall_timeout: Duration) {
+		let check_interval = (stall_timeout / 2).max(Duration::from_millis(100));
+
+		loop {
+			Delay::new(check_interval).await;
+
+			for stall in self.detect_stalls(stall_timeout, Instant::now()) {
+				gum::warn!(
+					target: LOG_TARGET,
+					subsystem = %stall.subsystem,
+					since_last_receive = ?stall.since_last_receive,
+					handling = ?stall.handling,
+					last_sent = ?stall.last_sent,
+					blocked_sending = stall.blocked_sending,
+					oldest_pending = ?stall.oldest_pending,
+					pending = ?stall.pending,
+					senders = ?stall.senders,
+					"Subsystem is not receiving its queued messages, it might be stalled",
+				);
+			}
+		}
+	}
+}
+
+/// A sender recording all outgoing messages in a [`MessageTracer`].
+#[derive(Clone)]
+pub struct TracedSender<Sender> {
+	inner: Sender,
+	tracer: MessageTracer,
+	origin: &'static str,
+}
+
+#[async_trait::async_trait]
+impl<OutgoingMessage, Sender> SubsystemSender<OutgoingMessage> for TracedSender<Sender>
+where
+	OutgoingMessage: Debug + Send + 'static,
+	Sender: SubsystemSender<OutgoingMessage>,
+{
+	async fn send_message(&mut self, msg: OutgoingMessage) {
+		let (destination, kind) = describe_outgoing(&msg);
+		self.tracer.on_send(self.origin, destination, kind);
+		self.tracer.set_blocked_sending(self.origin, true);
+		self.inner.send_message(msg).await;
+		self.tracer.set_blocked_sending(self.origin, false);
+	}
+
+	async fn send_message_with_priority<P: Priority>(&mut self, msg: OutgoingMessage) {
+		let (destination, kind) = describe_outgoing(&msg);
+		self.tracer.on_send(self.origin, destination, kind);
+		self.tracer.set_blocked_sending(self.origin, true);
+		self.inner.send_message_with_priority::<P>(msg).await;
+		self.tracer.set_blocked_sending(self.origin, false);
+	}
+
+	fn try_send_message(
+		&mut self,
+		msg: OutgoingMessage,
+	) -> Result<(), TrySendError<OutgoingMessage>> {
+		self.try_send_message_with_priority::<crate::NormalPriority>(msg)
+	}
+
+	fn try_send_message_with_priority<P: Priority>(
+		&mut self,
+		msg: OutgoingMessage,
+	) -> Result<(), TrySendError<OutgoingMessage>> {
+		let (destination, kind) = describe_outgoing(&msg);
+		// Recorded before sending, the receiver might be faster than us otherwise.
+		self.tracer.on_send(self.origin, destination.clone(), kind.clone());
+		self.inner.try_send_message_with_priority::<P>(msg).map_err(|err| {
+			self.tracer.on_send_failed(&destination, &kind);
+			err
+		})
+	}
+
+	async fn send_messages<T>(&mut self, msgs: T)
+	where
+		T: IntoIterator<Item = OutgoingMessage> + Send,
+		T::IntoIter: Send,
+	{
+		for msg in msgs {
+			self.send_message(msg).await;
+		}
+	}
+
+	fn send_unbounded_message(&mut self, msg: OutgoingMessage) {
+		let (destination, kind) = describe_outgoing(&msg);
+		self.tracer.on_send(self.origin, destination, kind);
+		self.inner.send_unbounded_message(msg)
+	}
+}
+
+/// A subsystem context recording all messages in a [`MessageTracer`].
+pub struct TracedContext<Context: SubsystemContext> {
+	inner: Context,
+	sender: TracedSender<Context::Sender>,
+	tracer: MessageTracer,
+	key: &'static str,
+}
+
+impl<Context: SubsystemContext> TracedContext<Context> {
+	fn new(mut inner: Context, tracer: MessageTracer) -> Self {
+		let key = short_type_name::<Context::Message>();
+		let sender =
+			TracedSender { inner: inner.sender().clone(), tracer: tracer.clone(), origin: key };
+		Self { inner, sender, tracer, key }
+	}
+}
+
+#[async_trait::async_trait]
+impl<Context> SubsystemContext for TracedContext<Context>
+where
+	Context: SubsystemContext,
+	Context::Message: Debug,
+	Context::Signal: Debug,
+	TracedSender<Context::Sender>: SubsystemSender<Context::OutgoingMessages>,
+{
+	type Message = Context::Message;
+	type Signal = Context::Signal;
+	type OutgoingMessages = Context::OutgoingMessages;
+	type Sender = TracedSender<Context::Sender>;
+	type Error = Context::Error;
+
+	async fn try_recv(&mut self) -> Result<Option<FromOrchestra<Self::Message, Self::Signal>>, ()> {
+		let msg = self.inner.try_recv().await?;
+		if let Some(msg) = &msg {
+			self.tracer.on_receive(self.key, msg);
+		}
+		Ok(msg)
+	}
+
+	async fn recv(&mut self) -> Result<FromOrchestra<Self::Message, Self::Signal>, Self::Error> {
+		let msg = self.inner.recv().await?;
+		self.tracer.on_receive(self.key, &msg);
+		Ok(msg)
+	}
+
+	async fn recv_signal(&mut self) -> Result<Self::Signal, Self::Error> {
+		let signal = self.inner.recv_signal().await?;
+		self.tracer.on_signal(self.key, &signal);
+		Ok(signal)
+	}
+
+	fn spawn(
+		&mut self,
+		name: &'static str,
+		s: Pin<Box<dyn Future<Output = ()> + Send>>,
+	) -> Result<(), Self::Error> {
+		self.inner.spawn(name, s)
+	}
+
+	fn spawn_blocking(
+		&mut self,
+		name: &'static str,
+		s: Pin<Box<dyn Future<Output = ()> + Send>>,
+	) -> Result<(), Self::Error> {
+		self.inner.spawn_blocking(name, s)
+	}
+
+	fn sender(&mut self) -> &mut Self::Sender {
+		&mut self.sender
+	}
+}
+
+/// A subsystem whose messages are recorded in a [`MessageTracer`].
+pub struct TracedSubsystem<Sub> {
+	subsystem: Sub,
+	tracer: MessageTracer,
+}
+
+impl<Sub> TracedSubsystem<Sub> {
+	/// Trace the messages of `subsystem` in `tracer`.
+	pub fn new(subsystem: Sub, tracer: MessageTracer) -> Self {
+		Self { subsystem, tracer }
+	}
+}
+
+impl<Context, Sub> Subsystem<Context, SubsystemError> for TracedSubsystem<Sub>
+where
+	Context: SubsystemContext<Error = SubsystemError>,
+	TracedContext<Context>: SubsystemContext<Error = SubsystemError>,
+	Sub: Subsystem<TracedContext<Context>, SubsystemError>,
+{
+	fn start(self, ctx: Context) -> SpawnedSubsystem<SubsystemError> {
+		let key = short_type_name::<Context::Message>();
+		let spawned = self.subsystem.start(TracedContext::new(ctx, self.tracer.clone()));
+		self.tracer.register(key, spawned.name);
+		spawned
+	}
+}
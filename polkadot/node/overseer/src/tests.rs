@@ -16,7 +16,7 @@
 
 use async_trait::async_trait;
 use futures::{executor, pending, pin_mut, poll, select, stream, FutureExt};
-use std::{collections::HashMap, sync::atomic, task::Poll};
+use std::{collections::HashMap, sync::atomic, task::Poll, time::Instant};
 
 use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange};
 use polkadot_node_primitives::{
@@ -1238,3 +1238,117 @@ fn context_holds_onto_message_until_enough_signals_received() {
 
 	futures::executor::block_on(test_fut);
 }
+
+#[test]
+fn outgoing_messages_are_described_by_destination_and_variant() {
+	use crate::message_trace::describe_outgoing;
+
+	assert_eq!(
+		describe_outgoing(&test_candidate_validation_msg()),
+		("CandidateValidationMessage".to_owned(), "ValidateFromExhaustive".to_owned()),
+	);
+	assert_eq!(
+		describe_outgoing(&<CandidateBackingMessage as AssociateOutgoing>::OutgoingMessages::from(
+			test_candidate_validation_msg()
+		)),
+		("CandidateValidationMessage".to_owned(), "ValidateFromExhaustive".to_owned()),
+	);
+}
+
+#[test]
+fn traced_subsystems_keep_exchanging_messages() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let tracer = MessageTracer::new();
+
+	executor::block_on(async {
+		let (s1_tx, s1_rx) = metered::channel::<usize>(64);
+		let (s2_tx, _s2_rx) = metered::channel::<usize>(64);
+
+		let mut s1_rx = s1_rx.fuse();
+		let (overseer, handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_candidate_validation(|_| {
+				TracedSubsystem::new(TestSubsystem1(s1_tx), tracer.clone())
+			})
+			.replace_candidate_backing(|_| {
+				TracedSubsystem::new(TestSubsystem2(s2_tx), tracer.clone())
+			})
+			.build()
+			.unwrap();
+		let mut handle = Handle::new(handle);
+		let overseer_fut = overseer.run().fuse();
+
+		pin_mut!(overseer_fut);
+
+		let mut s1_results = Vec::new();
+		loop {
+			select! {
+				_ = overseer_fut => break,
+				s1_next = s1_rx.next() => {
+					match s1_next {
+						Some(msg) => {
+							s1_results.push(msg);
+							if s1_results.len() == 10 {
+								handle.stop().await;
+							}
+						}
+						None => break,
+					}
+				},
+				complete => break,
+			}
+		}
+
+		assert_eq!(s1_results, (0..10).collect::<Vec<_>>());
+	});
+
+	// Every message sent was received, so nothing can be stalled.
+	assert!(tracer.detect_stalls(Duration::ZERO, Instant::now()).is_empty());
+}
+
+#[test]
+fn stalled_subsystems_are_reported_once() {
+	let tracer = MessageTracer::new();
+	let stall_timeout = Duration::from_secs(10);
+
+	tracer.register("CandidateValidationMessage", "candidate-validation");
+	tracer.register("CandidateBackingMessage", "candidate-backing");
+	tracer.on_signal("CandidateValidationMessage", &OverseerSignal::Conclude);
+	for _ in 0..3 {
+		tracer.on_send(
+			"CandidateBackingMessage",
+			"CandidateValidationMessage".to_owned(),
+			"ValidateFromExhaustive".to_owned(),
+		);
+	}
+	// Candidate validation is waiting for room in the runtime API channel.
+	tracer.on_send(
+		"CandidateValidationMessage",
+		"RuntimeApiMessage".to_owned(),
+		"Request".to_owned(),
+	);
+	tracer.set_blocked_sending("CandidateValidationMessage", true);
+
+	let start = Instant::now();
+	assert!(tracer.detect_stalls(stall_timeout, start).is_empty());
+
+	let stalls = tracer.detect_stalls(stall_timeout, start + stall_timeout * 2);
+	assert_eq!(stalls.len(), 1);
+	assert_eq!(stalls[0].subsystem, "candidate-validation");
+	assert_eq!(stalls[0].handling.as_deref(), Some("Conclude"));
+	assert_eq!(stalls[0].last_sent, Some(("RuntimeApiMessage".to_owned(), "Request".to_owned())));
+	assert!(stalls[0].blocked_sending);
+	assert_eq!(stalls[0].pending, [("ValidateFromExhaustive".to_owned(), 3)].into_iter().collect(),);
+	assert_eq!(stalls[0].senders, [("candidate-backing".to_owned(), 3)].into_iter().collect());
+
+	// Already reported.
+	assert!(tracer.detect_stalls(stall_timeout, start + stall_timeout * 3).is_empty());
+
+	// Receiving a message ends the stall, the remaining ones stall again later.
+	tracer.on_message("CandidateValidationMessage", &test_candidate_validation_msg());
+	let now = Instant::now();
+	assert!(tracer.detect_stalls(stall_timeout, now).is_empty());
+	let stalls = tracer.detect_stalls(stall_timeout, now + stall_timeout * 2);
+	assert_eq!(stalls.len(), 1);
+	assert_eq!(stalls[0].pending.get("ValidateFromExhaustive"), Some(&2));
+}
@@ -24,7 +24,7 @@ use codec::{Decode, Encode};
 use polkadot_primitives::{
 	AssignmentId, CandidateHash, CoreIndex, GroupIndex, IndexedVec, SessionInfo, ValidatorIndex,
 };
-use sc_keystore::LocalKeystore;
+use sp_keystore::Keystore;
 
 use std::collections::HashMap;
 
@@ -114,10 +114,11 @@ impl<'a> From<&'a SessionInfo> for Config {
 /// Approval voting subsystem implements a a real implemention
 /// for it and tests use a mock implementation.
 pub trait AssignmentCriteria {
-	/// Compute the assignments for the given relay VRF story.
+	/// Compute the assignments for the given relay VRF story, signing them with the assignment
+	/// key held by `keystore`.
 	fn compute_assignments(
 		&self,
-		keystore: &LocalKeystore,
+		keystore: &dyn Keystore,
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
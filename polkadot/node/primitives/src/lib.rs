@@ -32,8 +32,9 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use polkadot_primitives::{
 	vstaging::CommittedCandidateReceiptV2 as CommittedCandidateReceipt, BlakeTwo256, BlockNumber,
 	CandidateCommitments, CandidateHash, ChunkIndex, CollatorPair, CompactStatement, CoreIndex,
-	EncodeAs, Hash, HashT, HeadData, Id as ParaId, PersistedValidationData, SessionIndex, Signed,
-	UncheckedSigned, ValidationCode, ValidationCodeHash, MAX_CODE_SIZE, MAX_POV_SIZE,
+	EncodeAs, GroupIndex, Hash, HashT, HeadData, Id as ParaId, PersistedValidationData,
+	SessionIndex, Signed, UncheckedSigned, ValidationCode, ValidationCodeHash, ValidatorIndex,
+	MAX_CODE_SIZE, MAX_POV_SIZE,
 };
 pub use sp_consensus_babe::{
 	AllowedSlots as BabeAllowedSlots, BabeEpochConfiguration, Epoch as BabeEpoch,
@@ -451,6 +452,128 @@ pub struct CollationSecondedSignal {
 	pub statement: SignedFullStatement,
 }
 
+/// An event in the lifecycle of a collation, as seen from the collator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(not(target_os = "unknown"))]
+pub enum CollationEvent {
+	/// The collation was advertised to a validator for the first time.
+	Advertised {
+		/// The relay parent the collation was built on.
+		relay_parent: Hash,
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+	},
+	/// A validator of the backing group fetched the collation.
+	///
+	/// Emitted for every validator that fetches it.
+	Fetched {
+		/// The relay parent the collation was built on.
+		relay_parent: Hash,
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The backing group the validator belongs to.
+		group_index: GroupIndex,
+	},
+	/// A validator seconded the collation.
+	Seconded {
+		/// The relay parent the collation was built on.
+		relay_parent: Hash,
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The validator that seconded the collation.
+		validator_index: ValidatorIndex,
+	},
+	/// The candidate was backed on chain.
+	Backed {
+		/// The relay parent the collation was built on.
+		relay_parent: Hash,
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The relay chain block the candidate was backed in.
+		relay_block: Hash,
+	},
+	/// The candidate was included on chain.
+	Included {
+		/// The relay parent the collation was built on.
+		relay_parent: Hash,
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The relay chain block the candidate was included in.
+		relay_block: Hash,
+	},
+	/// The collation won't make it on chain.
+	Dropped {
+		/// The relay parent the collation was built on.
+		relay_parent: Hash,
+		/// The hash of the candidate, `None` if the collation was dropped before a candidate was
+		/// built from it.
+		candidate_hash: Option<CandidateHash>,
+		/// Why the collation was dropped.
+		reason: CollationDropReason,
+	},
+}
+
+/// Why a collation was dropped, see [`CollationEvent::Dropped`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(not(target_os = "unknown"))]
+pub enum CollationDropReason {
+	/// There is no validation data for the para at the relay parent.
+	NoValidationData,
+	/// The compressed PoV is larger than the relay chain allows.
+	PoVTooLarge {
+		/// The size of the compressed PoV.
+		size: usize,
+		/// The maximum PoV size.
+		max_size: usize,
+	},
+	/// Building the candidate failed for another reason.
+	CandidateConstructionFailed(String),
+	/// The relay parent was not, or no longer, in our view before the candidate was backed.
+	RelayParentOutOfView,
+	/// We already distribute the maximum number of collations for the relay parent.
+	TooManyCollations,
+	/// The para is not assigned to the core the collation was built for.
+	NotAssignedToCore,
+	/// There are no validators assigned to the core.
+	NoBackingGroup,
+	/// The candidate was backed, but not made available in time.
+	AvailabilityTimedOut,
+	/// The candidate was only backed in relay chain blocks which were not finalized.
+	BackedOnAbandonedFork,
+}
+
+/// The sending half of a subscription to [`CollationEvent`]s.
+#[cfg(not(target_os = "unknown"))]
+pub type CollationEventSender = futures::channel::mpsc::Sender<CollationEvent>;
+
+/// The subscribers to collation events of a subsystem.
+#[derive(Default, Clone)]
+#[cfg(not(target_os = "unknown"))]
+pub struct CollationEventSubscribers(Vec<CollationEventSender>);
+
+#[cfg(not(target_os = "unknown"))]
+impl CollationEventSubscribers {
+	/// Add a subscriber.
+	pub fn subscribe(&mut self, sender: CollationEventSender) {
+		self.0.push(sender);
+	}
+
+	/// Whether there is nobody to notify.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Send an event to all subscribers and forget about those that went away.
+	///
+	/// Subscribers that don't keep up miss events, so that they can't stall the subsystem.
+	pub fn notify(&mut self, event: CollationEvent) {
+		self.0.retain_mut(|sender| match sender.try_send(event.clone()) {
+			Ok(()) => true,
+			Err(err) => !err.is_disconnected(),
+		});
+	}
+}
+
 /// Result of the [`CollatorFn`] invocation.
 #[cfg(not(target_os = "unknown"))]
 pub struct CollationResult {
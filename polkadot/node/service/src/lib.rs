@@ -66,6 +66,9 @@ use polkadot_overseer::SpawnGlue;
 
 #[cfg(feature = "full-node")]
 pub use {
+	polkadot_node_core_candidate_validation::{
+		DryRunKind, DryRunOutcome, DryRunReport, DryRunRequest,
+	},
 	polkadot_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
 	polkadot_primitives::runtime_api::ParachainHost,
 	relay_chain_selection::SelectRelayChain,
@@ -89,7 +92,10 @@ pub use chain_spec::{GenericChainSpec, RococoChainSpec, WestendChainSpec};
 use frame_benchmarking_cli::SUBSTRATE_REFERENCE_HARDWARE;
 use mmr_gadget::MmrGadget;
 use polkadot_node_subsystem_types::DefaultSubsystemClient;
-pub use polkadot_primitives::{Block, BlockId, BlockNumber, CollatorPair, Hash, Id as ParaId};
+pub use polkadot_primitives::{
+	Block, BlockId, BlockNumber, CandidateHash, CollatorPair, DisputeState, ExecutorParams, Hash,
+	Id as ParaId, PersistedValidationData, SessionIndex, ValidationCode,
+};
 pub use sc_client_api::{Backend, CallExecutor};
 pub use sc_consensus::{BlockImport, LongestChain};
 pub use sc_executor::NativeExecutionDispatch;
@@ -218,6 +224,9 @@ pub enum Error {
 	#[error(transparent)]
 	Telemetry(#[from] sc_telemetry::Error),
 
+	#[error(transparent)]
+	SlashingProtection(#[from] sc_consensus::slashing_protection::Error),
+
 	#[cfg(feature = "full-node")]
 	#[error(transparent)]
 	Availability(#[from] AvailabilityError),
@@ -233,6 +242,14 @@ pub enum Error {
 	#[error("Expected at least one of polkadot, kusama, westend or rococo runtime feature")]
 	NoRuntime,
 
+	#[cfg(feature = "full-node")]
+	#[error("Failed to validate the candidate")]
+	DryRun(#[source] polkadot_overseer::SubsystemError),
+
+	#[cfg(feature = "full-node")]
+	#[error("Failed to fetch the disputes known on chain")]
+	OnChainDisputes(#[source] sp_api::ApiError),
+
 	#[cfg(feature = "full-node")]
 	#[error("Worker binaries not executable, prepare binary: {prep_worker_path:?}, execute binary: {exec_worker_path:?}")]
 	InvalidWorkerBinaries { prep_worker_path: PathBuf, exec_worker_path: PathBuf },
@@ -630,11 +647,17 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub prepare_workers_hard_max_num: Option<usize>,
 	pub overseer_gen: OverseerGenerator,
 	pub overseer_message_channel_capacity_override: Option<usize>,
+	/// Trace the messages exchanged between subsystems and warn about subsystems which haven't
+	/// received their queued messages for the given duration.
+	pub overseer_message_tracing: Option<Duration>,
 	#[allow(dead_code)]
 	pub malus_finality_delay: Option<u32>,
 	pub hwbench: Option<sc_sysinfo::HwBench>,
 	/// Enable approval voting processing in parallel.
 	pub enable_approval_voting_parallel: bool,
+	/// How long to keep the availability data of specific parachains after finality, instead of
+	/// the default.
+	pub availability_retention: HashMap<ParaId, Duration>,
 }
 
 #[cfg(feature = "full-node")]
@@ -696,6 +719,86 @@ pub const AVAILABILITY_CONFIG: AvailabilityConfig = AvailabilityConfig {
 	col_meta: parachains_db::REAL_COLUMNS.col_availability_meta,
 };
 
+/// Open the availability store of a node that is not running, e.g. to export or pin candidates.
+#[cfg(feature = "full-node")]
+pub fn open_availability_store(
+	db_source: &DatabaseSource,
+) -> Result<polkadot_node_core_av_store::OfflineStore, Error> {
+	Ok(polkadot_node_core_av_store::OfflineStore::new(
+		open_database(db_source)?,
+		AVAILABILITY_CONFIG,
+	))
+}
+
+/// Open the dispute coordinator database of a node that is not running, e.g. to inspect or repair
+/// it.
+#[cfg(feature = "full-node")]
+pub fn open_dispute_store(
+	db_source: &DatabaseSource,
+) -> Result<polkadot_node_core_dispute_coordinator::OfflineStore, Error> {
+	Ok(polkadot_node_core_dispute_coordinator::OfflineStore::new(
+		open_database(db_source)?,
+		DisputeCoordinatorConfig {
+			col_dispute_data: parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
+		},
+	))
+}
+
+/// The disputes the runtime knows about at the best block.
+#[cfg(feature = "full-node")]
+pub fn on_chain_disputes(
+	client: &FullClient,
+) -> Result<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>, Error> {
+	client
+		.runtime_api()
+		.disputes(client.info().best_hash)
+		.map_err(Error::OnChainDisputes)
+}
+
+/// Validate a single candidate with the PVF worker binaries a node would use, without starting
+/// the node.
+///
+/// `workers_path` is resolved like for [`new_full`]. Prepared artifacts are written to
+/// `artifacts_cache_path`, which should be empty for the preparation to be measured, or to a
+/// temporary directory removed afterwards if not given. The workers run in Secure Validator Mode
+/// if `secure_validator_mode` is set, like on a validator.
+#[cfg(feature = "full-node")]
+pub async fn dry_run_candidate_validation(
+	request: DryRunRequest,
+	workers_path: Option<PathBuf>,
+	node_version: Option<String>,
+	secure_validator_mode: bool,
+	artifacts_cache_path: Option<PathBuf>,
+) -> Result<DryRunReport, Error> {
+	let (prep_worker_path, exec_worker_path) =
+		workers::determine_workers_paths(workers_path, None, node_version.clone())?;
+	log::info!("🚀 Using prepare-worker binary at: {:?}", prep_worker_path);
+	log::info!("🚀 Using execute-worker binary at: {:?}", exec_worker_path);
+
+	// The temporary directory is removed when dropped, once the validation is done.
+	let (artifacts_cache_path, _temp_dir) = match artifacts_cache_path {
+		Some(path) => (path, None),
+		None => {
+			let dir = tempfile::tempdir()?;
+			(dir.path().to_path_buf(), Some(dir))
+		},
+	};
+	let config = CandidateValidationConfig {
+		artifacts_cache_path,
+		node_version,
+		secure_validator_mode,
+		prep_worker_path,
+		exec_worker_path,
+		pvf_execute_workers_max_num: 1,
+		pvf_prepare_workers_soft_max_num: 1,
+		pvf_prepare_workers_hard_max_num: 1,
+	};
+
+	polkadot_node_core_candidate_validation::dry_run(config, request)
+		.await
+		.map_err(Error::DryRun)
+}
+
 /// Create a new full node of arbitrary runtime and executor.
 ///
 /// This is an advanced feature and not recommended for general use. Generally, `build_full` is
@@ -722,12 +825,14 @@ pub fn new_full<
 		workers_names,
 		overseer_gen,
 		overseer_message_channel_capacity_override,
+		overseer_message_tracing,
 		malus_finality_delay: _malus_finality_delay,
 		hwbench,
 		execute_workers_max_num,
 		prepare_workers_soft_max_num,
 		prepare_workers_hard_max_num,
 		enable_approval_voting_parallel,
+		availability_retention,
 	}: NewFullParams<OverseerGenerator>,
 ) -> Result<NewFull, Error> {
 	use polkadot_availability_recovery::FETCH_CHUNKS_THRESHOLD;
@@ -772,11 +877,16 @@ pub fn new_full<
 	let basics = new_partial_basics(&mut config, telemetry_worker_handle)?;
 
 	let prometheus_registry = config.prometheus_registry().cloned();
+	let slashing_protection = config
+		.slashing_protection
+		.as_ref()
+		.map(|path| sc_consensus::SlashingProtection::open(path, prometheus_registry.as_ref()))
+		.transpose()?;
 
 	let overseer_connector = OverseerConnector::default();
 	let overseer_handle = Handle::new(overseer_connector.handle());
 
-	let keystore = basics.keystore_container.local_keystore();
+	let keystore = basics.keystore_container.keystore();
 	let auth_or_collator = role.is_authority() || is_parachain_node.is_collator();
 
 	let select_chain = if auth_or_collator {
@@ -1000,6 +1110,7 @@ pub fn new_full<
 			chain_selection_config,
 			fetch_chunks_threshold,
 			enable_approval_voting_parallel,
+			availability_retention,
 		})
 	};
 
@@ -1168,6 +1279,7 @@ pub fn new_full<
 					spawner,
 					is_parachain_node,
 					overseer_message_channel_capacity_override,
+					overseer_message_tracing,
 					req_protocol_names,
 					peerset_protocol_names,
 					notification_services,
@@ -1263,6 +1375,7 @@ pub fn new_full<
 			block_proposal_slot_portion: sc_consensus_babe::SlotProportion::new(2f32 / 3f32),
 			max_block_proposal_slot_portion: None,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
+			slashing_protection: slashing_protection.clone(),
 		};
 
 		let babe = sc_consensus_babe::start_babe(babe_config)?;
@@ -1341,6 +1454,7 @@ pub fn new_full<
 		local_role: role,
 		telemetry: telemetry.as_ref().map(|x| x.handle()),
 		protocol_name: grandpa_protocol_name,
+		slashing_protection,
 	};
 
 	let enable_grandpa = !disable_grandpa;
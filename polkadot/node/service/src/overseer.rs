@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use super::{Error, IsParachainNode, Registry};
+use super::{Error, IsParachainNode, ParaId, Registry};
 use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
 use polkadot_overseer::{DummySubsystem, InitializedOverseerBuilder, SubsystemError};
 use sp_core::traits::SpawnNamed;
@@ -34,16 +34,16 @@ use polkadot_node_network_protocol::{
 #[cfg(any(feature = "malus", test))]
 pub use polkadot_overseer::{dummy::dummy_overseer_builder, HeadSupportsParachains};
 use polkadot_overseer::{
-	metrics::Metrics as OverseerMetrics, MetricsTrait, Overseer, OverseerConnector, OverseerHandle,
-	SpawnGlue,
+	metrics::Metrics as OverseerMetrics, MessageTracer, MetricsTrait, Overseer, OverseerConnector,
+	OverseerHandle, SpawnGlue, TracedSubsystem,
 };
 
 use parking_lot::Mutex;
 use sc_authority_discovery::Service as AuthorityDiscoveryService;
 use sc_client_api::AuxStore;
-use sc_keystore::LocalKeystore;
+use sc_keystore::Keystore;
 use sc_network::{NetworkStateInfo, NotificationService};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 pub use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
 pub use polkadot_availability_bitfield_distribution::BitfieldDistribution as BitfieldDistributionSubsystem;
@@ -103,6 +103,9 @@ where
 	pub is_parachain_node: IsParachainNode,
 	/// Overseer channel capacity override.
 	pub overseer_message_channel_capacity_override: Option<usize>,
+	/// Trace the messages exchanged between subsystems, warning about subsystems which haven't
+	/// received their queued messages for the given duration.
+	pub overseer_message_tracing: Option<Duration>,
 	/// Request-response protocol names source.
 	pub req_protocol_names: ReqProtocolNames,
 	/// `PeerSet` protocol names to protocols mapping.
@@ -113,7 +116,7 @@ where
 
 pub struct ExtendedOverseerGenArgs {
 	/// The keystore to use for i.e. validator keys.
-	pub keystore: Arc<LocalKeystore>,
+	pub keystore: Arc<dyn Keystore>,
 	/// The underlying key value store for the parachains.
 	pub parachains_db: Arc<dyn polkadot_node_subsystem_util::database::Database>,
 	/// Configuration for the candidate validation subsystem.
@@ -145,6 +148,8 @@ pub struct ExtendedOverseerGenArgs {
 	/// Enable approval-voting-parallel subsystem and disable the standalone approval-voting and
 	/// approval-distribution subsystems.
 	pub enable_approval_voting_parallel: bool,
+	/// Per-parachain overrides of how long the availability store keeps data after finality.
+	pub availability_retention: HashMap<ParaId, Duration>,
 }
 
 /// Obtain a prepared validator `Overseer`, that is initialized with all default values.
@@ -165,6 +170,7 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		spawner,
 		is_parachain_node,
 		overseer_message_channel_capacity_override,
+		overseer_message_tracing: _,
 		req_protocol_names,
 		peerset_protocol_names,
 		notification_services,
@@ -185,6 +191,7 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		chain_selection_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
+		availability_retention,
 	}: ExtendedOverseerGenArgs,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -272,12 +279,15 @@ where
 			&req_protocol_names,
 			Metrics::register(registry)?,
 		))
-		.availability_store(AvailabilityStoreSubsystem::new(
-			parachains_db.clone(),
-			availability_config,
-			Box::new(sync_service.clone()),
-			Metrics::register(registry)?,
-		))
+		.availability_store(
+			AvailabilityStoreSubsystem::new(
+				parachains_db.clone(),
+				availability_config,
+				Box::new(sync_service.clone()),
+				Metrics::register(registry)?,
+			)
+			.with_para_retention(availability_retention),
+		)
 		.bitfield_distribution(BitfieldDistributionSubsystem::new(Metrics::register(registry)?))
 		.bitfield_signing(BitfieldSigningSubsystem::new(
 			keystore.clone(),
@@ -389,6 +399,7 @@ pub fn validator_with_parallel_overseer_builder<Spawner, RuntimeClient>(
 		spawner,
 		is_parachain_node,
 		overseer_message_channel_capacity_override,
+		overseer_message_tracing: _,
 		req_protocol_names,
 		peerset_protocol_names,
 		notification_services,
@@ -409,6 +420,7 @@ pub fn validator_with_parallel_overseer_builder<Spawner, RuntimeClient>(
 		chain_selection_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
+		availability_retention,
 	}: ExtendedOverseerGenArgs,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -496,12 +508,15 @@ where
 			&req_protocol_names,
 			Metrics::register(registry)?,
 		))
-		.availability_store(AvailabilityStoreSubsystem::new(
-			parachains_db.clone(),
-			availability_config,
-			Box::new(sync_service.clone()),
-			Metrics::register(registry)?,
-		))
+		.availability_store(
+			AvailabilityStoreSubsystem::new(
+				parachains_db.clone(),
+				availability_config,
+				Box::new(sync_service.clone()),
+				Metrics::register(registry)?,
+			)
+			.with_para_retention(availability_retention),
+		)
 		.bitfield_distribution(BitfieldDistributionSubsystem::new(Metrics::register(registry)?))
 		.bitfield_signing(BitfieldSigningSubsystem::new(
 			keystore.clone(),
@@ -606,6 +621,7 @@ pub fn collator_overseer_builder<Spawner, RuntimeClient>(
 		spawner,
 		is_parachain_node,
 		overseer_message_channel_capacity_override,
+		overseer_message_tracing: _,
 		req_protocol_names,
 		peerset_protocol_names,
 		notification_services,
@@ -739,6 +755,53 @@ where
 	Ok(builder)
 }
 
+/// Build the overseer out of a prepared builder.
+///
+/// When message tracing is requested, every subsystem is wrapped into a [`TracedSubsystem`] and
+/// the stall watchdog is spawned.
+macro_rules! build_overseer {
+	($builder:expr, $connector:expr, $spawner:expr, $message_tracing:expr $(,)?) => {{
+		let overseer = match $message_tracing {
+			Some(stall_timeout) => {
+				let tracer = MessageTracer::new();
+				$spawner.spawn(
+					"overseer-message-watchdog",
+					Some("overseer"),
+					Box::pin(tracer.clone().run_watchdog(stall_timeout)),
+				);
+				$builder
+					.replace_candidate_validation(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_pvf_checker(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_candidate_backing(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_statement_distribution(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_availability_distribution(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_availability_recovery(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_bitfield_signing(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_bitfield_distribution(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_provisioner(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_runtime_api(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_availability_store(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_network_bridge_rx(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_network_bridge_tx(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_chain_api(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_collation_generation(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_collator_protocol(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_approval_distribution(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_approval_voting(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_approval_voting_parallel(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_gossip_support(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_dispute_coordinator(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_dispute_distribution(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_chain_selection(|s| TracedSubsystem::new(s, tracer.clone()))
+					.replace_prospective_parachains(|s| TracedSubsystem::new(s, tracer.clone()))
+					.build_with_connector($connector)
+			},
+			None => $builder.build_with_connector($connector),
+		};
+		overseer.map_err(|e| e.into())
+	}};
+}
+
 /// Trait for the `fn` generating the overseer.
 pub trait OverseerGen {
 	/// Overwrite the full generation of the overseer, including the subsystems.
@@ -775,14 +838,22 @@ impl OverseerGen for ValidatorOverseerGen {
 			"create validator overseer as mandatory extended arguments were not provided"
 				.to_owned(),
 		)))?;
+		let spawner = args.spawner.clone();
+		let message_tracing = args.overseer_message_tracing;
 		if ext_args.enable_approval_voting_parallel {
-			validator_with_parallel_overseer_builder(args, ext_args)?
-				.build_with_connector(connector)
-				.map_err(|e| e.into())
+			build_overseer!(
+				validator_with_parallel_overseer_builder(args, ext_args)?,
+				connector,
+				spawner,
+				message_tracing,
+			)
 		} else {
-			validator_overseer_builder(args, ext_args)?
-				.build_with_connector(connector)
-				.map_err(|e| e.into())
+			build_overseer!(
+				validator_overseer_builder(args, ext_args)?,
+				connector,
+				spawner,
+				message_tracing,
+			)
 		}
 	}
 }
@@ -801,8 +872,8 @@ impl OverseerGen for CollatorOverseerGen {
 		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
-		collator_overseer_builder(args)?
-			.build_with_connector(connector)
-			.map_err(|e| e.into())
+		let spawner = args.spawner.clone();
+		let message_tracing = args.overseer_message_tracing;
+		build_overseer!(collator_overseer_builder(args)?, connector, spawner, message_tracing)
 	}
 }
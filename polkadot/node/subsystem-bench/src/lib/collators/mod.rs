@@ -0,0 +1,282 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collator protocol (validator side) benchmark.
+//!
+//! Emulated peers act as collators: they declare themselves for one of the paras and advertise a
+//! collation at every relay chain block. The node under test fetches the collations of the para
+//! assigned to its backing group and seconds them until the seconding limit is reached.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies},
+	mock::{
+		candidate_backing::MockCandidateBacking,
+		chain_api::{ChainApiState, MockChainApi},
+		network_bridge::{MockNetworkBridgeRx, MockNetworkBridgeTx},
+		prospective_parachains::{MockProspectiveParachains, ProspectiveParachainsState},
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::{new_network, NetworkEmulatorHandle, NetworkInterface, NetworkInterfaceReceiver},
+	statement::make_keystore,
+	usage::BenchmarkUsage,
+	NODE_UNDER_TEST,
+};
+use colored::Colorize;
+use itertools::Itertools;
+use polkadot_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_network_protocol::{
+	peer_set::CollationVersion, v1 as protocol_v1, v2 as protocol_v2, ObservedRole, OurView,
+	Versioned,
+};
+use polkadot_node_subsystem::messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeEvent};
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{Hash, Id};
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_core::Pair;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+pub use test_state::TestState;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::collators";
+
+/// Parameters specific to the collator protocol benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct CollatorsOptions {
+	#[clap(short, long, default_value_t = 2)]
+	/// Number of collators advertising a collation for each para at every block.
+	pub n_collators_per_para: usize,
+}
+
+fn build_overseer(
+	state: &TestState,
+	network: NetworkEmulatorHandle,
+	network_interface: NetworkInterface,
+	network_receiver: NetworkInterfaceReceiver,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		Default::default(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Scheduled,
+	);
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	// Only the leaf itself is an allowed relay parent for the collations.
+	let paras = (1..=state.config.n_cores as u32).map(Id::new).collect_vec();
+	let mock_prospective_parachains =
+		MockProspectiveParachains::with_state(ProspectiveParachainsState {
+			min_relay_parents: state
+				.block_infos
+				.iter()
+				.map(|info| (info.hash, paras.iter().map(|para| (*para, info.number)).collect()))
+				.collect(),
+			validation_data: Some(state.pvd.clone()),
+		});
+	let mock_candidate_backing = MockCandidateBacking::new(
+		state.config.clone(),
+		state
+			.test_authorities
+			.validator_pairs
+			.get(NODE_UNDER_TEST as usize)
+			.unwrap()
+			.clone(),
+		state.pvd.clone(),
+		Default::default(),
+	)
+	.with_committed_receipts(state.committed_receipts());
+	let subsystem = CollatorProtocolSubsystem::new(ProtocolSide::Validator {
+		keystore: make_keystore(),
+		eviction_policy: Default::default(),
+		metrics: Metrics::try_register(&dependencies.registry).unwrap(),
+	});
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network,
+		network_interface.subsystem_sender(),
+		state.test_authorities.clone(),
+	);
+	let network_bridge_rx = MockNetworkBridgeRx::new(network_receiver, None, false);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_prospective_parachains(|_| mock_prospective_parachains)
+		.replace_candidate_backing(|_| mock_candidate_backing)
+		.replace_collator_protocol(|_| subsystem)
+		.replace_network_bridge_tx(|_| network_bridge_tx)
+		.replace_network_bridge_rx(|_| network_bridge_rx);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	let (network, network_interface, network_receiver) = new_network(
+		&state.config,
+		&dependencies,
+		&state.test_authorities,
+		vec![Arc::new(state.clone())],
+	);
+	let (overseer, overseer_handle) =
+		build_overseer(state, network.clone(), network_interface, network_receiver, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+fn our_view_change(block_hash: Hash) -> AllMessages {
+	AllMessages::CollatorProtocol(CollatorProtocolMessage::NetworkBridgeUpdate(
+		NetworkBridgeEvent::OurViewChange(OurView::new([block_hash], 0)),
+	))
+}
+
+pub async fn benchmark_collator_protocol(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	state.reset_trackers();
+
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	// Collators are hosted by the emulated peers connected to the node under test.
+	let hosts = state
+		.test_authorities
+		.validator_authority_id
+		.iter()
+		.enumerate()
+		.filter(|(index, id)| {
+			*index != NODE_UNDER_TEST as usize && env.network().is_peer_connected(id)
+		})
+		.map(|(index, _)| index)
+		.collect_vec();
+	let n_collators = state.collators.len().min(hosts.len());
+	if n_collators < state.collators.len() {
+		gum::warn!(target: LOG_TARGET, "Only {}/{} collators can be hosted by the connected peers", n_collators, state.collators.len());
+	}
+
+	// The node under test is assigned to the first para, the collators of the other paras are
+	// rejected once they declare.
+	let own_para = Id::new(1);
+	let own_collators = (0..n_collators)
+		.filter(|index| state.collators[*index].para_id == own_para)
+		.collect_vec();
+	let expected_seconded = own_collators.len().min(config.max_candidate_depth + 1);
+
+	for index in 0..n_collators {
+		let peer_id = state.test_authorities.peer_ids[hosts[index]];
+		env.send_message(AllMessages::CollatorProtocol(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
+				peer_id,
+				ObservedRole::Collator,
+				CollationVersion::V2.into(),
+				None,
+			)),
+		))
+		.await;
+	}
+
+	let test_start = Instant::now();
+	for (block_index, block_info) in state.block_infos.iter().enumerate() {
+		let block_num = block_info.number as usize;
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?}", block_num, config.num_blocks, block_info.hash);
+		env.metrics().set_current_block(block_num);
+		env.import_block(block_info.clone()).await;
+		env.send_message(our_view_change(block_info.hash)).await;
+
+		// Collators can only declare once the node knows its assignment.
+		if block_index == 0 {
+			for index in 0..n_collators {
+				let collator = &state.collators[index];
+				let peer_id = state.test_authorities.peer_ids[hosts[index]];
+				let declare = protocol_v2::CollatorProtocolMessage::Declare(
+					collator.pair.public(),
+					collator.para_id,
+					collator.pair.sign(&protocol_v1::declare_signature_payload(&peer_id)),
+				);
+				let _ = env.network().send_collation_message_from_peer(
+					&state.test_authorities.validator_authority_id[hosts[index]],
+					Versioned::V2(protocol_v2::CollationProtocol::CollatorProtocol(declare)),
+				);
+			}
+		}
+
+		let block_start_ts = Instant::now();
+		let collations = state.collations.get(&block_info.hash).expect("pregenerated");
+		for index in 0..n_collators {
+			let advertisement = protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+				relay_parent: block_info.hash,
+				candidate_hash: collations[index],
+				parent_head_data_hash: state.pvd.parent_head.hash(),
+			};
+			let _ = env.network().send_collation_message_from_peer(
+				&state.test_authorities.validator_authority_id[hosts[index]],
+				Versioned::V2(protocol_v2::CollationProtocol::CollatorProtocol(advertisement)),
+			);
+		}
+
+		let own_collations = own_collators.iter().map(|index| collations[*index]).collect_vec();
+		loop {
+			let seconded = state.seconded_count(&own_collations);
+			gum::debug!(target: LOG_TARGET, "{}/{} collations seconded", seconded, expected_seconded);
+
+			if seconded >= expected_seconded {
+				break
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+
+		gum::info!(target: LOG_TARGET, "{} collations seconded in {}", expected_seconded, format!("{:?}ms", block_start_ts.elapsed().as_millis()).cyan());
+	}
+
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "All blocks processed in {}", format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+
+	env.stop().await;
+	env.collect_resource_usage(&["collator-protocol"], false)
+}
@@ -0,0 +1,242 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	collators::CollatorsOptions,
+	configuration::{TestAuthorities, TestConfiguration},
+	network::{HandleNetworkMessage, NetworkMessage},
+};
+use codec::Encode;
+use itertools::Itertools;
+use polkadot_node_network_protocol::{
+	request_response::{v2::CollationFetchingResponse, Requests},
+	v2::{CollationProtocol, CollatorProtocolMessage},
+	Versioned,
+};
+use polkadot_node_primitives::{BlockData, PoV};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{
+		CandidateReceiptV2 as CandidateReceipt,
+		CommittedCandidateReceiptV2 as CommittedCandidateReceipt, MutateDescriptorV2,
+	},
+	BlockNumber, CandidateHash, CollatorPair, CoreIndex, Hash, HeadData, Header, Id,
+	PersistedValidationData, SessionIndex,
+};
+use polkadot_primitives_test_helpers::{
+	dummy_committed_candidate_receipt_v2, dummy_head_data, dummy_pvd,
+};
+use sc_network::ProtocolName;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: SessionIndex = 0;
+
+/// An emulated collator.
+#[derive(Clone)]
+pub struct Collator {
+	// The key the collator declares itself with
+	pub pair: CollatorPair,
+	// The para the collator builds blocks for
+	pub para_id: Id,
+}
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// One candidate per core at each block, needed by the runtime api mock
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// PersistedValidationData, we use one for all candidates
+	pub pvd: PersistedValidationData,
+	// All collators, one para gets `n_collators_per_para` of them
+	pub collators: Vec<Collator>,
+	// The collation of each collator at each relay chain block
+	pub collations: HashMap<H256, Vec<CandidateHash>>,
+	// Collation data served when the node fetches a collation
+	pub collation_data: HashMap<CandidateHash, (CommittedCandidateReceipt, PoV)>,
+	// Tracks which collations the node reported as seconded to their collators
+	pub seconded_tracker: HashMap<CandidateHash, Arc<AtomicBool>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &CollatorsOptions) -> Self {
+		let mut state = Self {
+			config: config.clone(),
+			test_authorities: config.generate_authorities(),
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			candidate_receipts: Default::default(),
+			pvd: dummy_pvd(dummy_head_data(), 0),
+			collators: (0..config.n_cores * options.n_collators_per_para)
+				.map(|index| Collator {
+					pair: CollatorPair::generate().0,
+					para_id: Id::new((index / options.n_collators_per_para) as u32 + 1),
+				})
+				.collect(),
+			collations: Default::default(),
+			collation_data: Default::default(),
+			seconded_tracker: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let pov_sizes = Vec::from(config.pov_sizes());
+		for block_info in state.block_infos.iter() {
+			let receipts = (0..config.n_cores)
+				.map(|core_idx| {
+					make_receipt(block_info.hash, Id::new(core_idx as u32 + 1), &[], &state.pvd)
+						.0
+						.to_plain()
+				})
+				.collect();
+			state.candidate_receipts.insert(block_info.hash, receipts);
+
+			let collations = state
+				.collators
+				.iter()
+				.enumerate()
+				.map(|(index, collator)| {
+					let pov_size = pov_sizes.get(index % pov_sizes.len()).expect("not empty; qed");
+					let (receipt, pov) = make_receipt(
+						block_info.hash,
+						collator.para_id,
+						&vec![index as u8; *pov_size],
+						&state.pvd,
+					);
+					(receipt.hash(), (receipt, pov))
+				})
+				.collect_vec();
+
+			state
+				.collations
+				.insert(block_info.hash, collations.iter().map(|(hash, _)| *hash).collect());
+			for (candidate_hash, data) in collations {
+				state.seconded_tracker.insert(candidate_hash, Arc::new(AtomicBool::new(false)));
+				state.collation_data.insert(candidate_hash, data);
+			}
+		}
+
+		state
+	}
+
+	pub fn reset_trackers(&self) {
+		self.seconded_tracker
+			.values()
+			.for_each(|v| v.as_ref().store(false, Ordering::SeqCst));
+	}
+
+	/// Returns how many of the given collations the node has seconded.
+	pub fn seconded_count(&self, collations: &[CandidateHash]) -> usize {
+		collations
+			.iter()
+			.filter(|hash| {
+				self.seconded_tracker.get(hash).map_or(false, |v| v.load(Ordering::SeqCst))
+			})
+			.count()
+	}
+
+	/// All committed receipts the candidate backing mock should be able to second.
+	pub fn committed_receipts(&self) -> HashMap<CandidateHash, CommittedCandidateReceipt> {
+		self.collation_data
+			.iter()
+			.map(|(hash, (receipt, _))| (*hash, receipt.clone()))
+			.collect()
+	}
+}
+
+fn make_receipt(
+	relay_parent: H256,
+	para_id: Id,
+	block_data: &[u8],
+	pvd: &PersistedValidationData,
+) -> (CommittedCandidateReceipt, PoV) {
+	let pov = PoV { block_data: BlockData(block_data.to_vec()) };
+	let mut receipt = dummy_committed_candidate_receipt_v2(relay_parent);
+	receipt.descriptor.set_para_id(para_id);
+	receipt.descriptor.set_core_index(CoreIndex(u32::from(para_id) - 1));
+	receipt.descriptor.set_session_index(SESSION_INDEX);
+	receipt.descriptor.set_persisted_validation_data_hash(pvd.hash());
+	receipt.descriptor.set_pov_hash(pov.hash());
+	// Collations of the same para at the same relay parent must have different hashes.
+	receipt.commitments.head_data = HeadData(pov.hash().as_ref().to_vec());
+
+	(receipt, pov)
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::CollationFetchingV2(req)) => {
+				let (receipt, pov) = self
+					.collation_data
+					.get(&req.payload.candidate_hash)
+					.expect("Only advertised collations are fetched");
+				let res = CollationFetchingResponse::Collation(receipt.to_plain(), pov.clone());
+				let _ = req.pending_response.send(Ok((res.encode(), ProtocolName::from(""))));
+				None
+			},
+			NetworkMessage::CollationMessageFromNode(
+				_authority_id,
+				Versioned::V2(CollationProtocol::CollatorProtocol(
+					CollatorProtocolMessage::CollationSeconded(_relay_parent, statement),
+				)),
+			) => {
+				let candidate_hash = statement.unchecked_payload().candidate_hash();
+				if let Some(tracker) = self.seconded_tracker.get(&candidate_hash) {
+					tracker.as_ref().store(true, Ordering::SeqCst);
+				}
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
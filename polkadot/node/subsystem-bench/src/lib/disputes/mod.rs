@@ -0,0 +1,304 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dispute coordinator and dispute distribution benchmark.
+//!
+//! Emulated peers raise disputes against candidates included in the imported blocks and against
+//! candidates the node under test never saw, which end up in the spam slots. The benchmark waits
+//! for the node under test to participate in each of the non-spam disputes and to distribute its
+//! own vote back to the peers.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies, GENESIS_HASH},
+	mock::{
+		authority_discovery::MockAuthorityDiscovery,
+		availability_recovery::MockAvailabilityRecovery,
+		candidate_validation::MockCandidateValidation,
+		chain_api::{ChainApiState, MockChainApi},
+		network_bridge::{MockNetworkBridgeRx, MockNetworkBridgeTx},
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::{new_network, NetworkEmulatorHandle, NetworkInterface, NetworkInterfaceReceiver},
+	usage::BenchmarkUsage,
+	NODE_UNDER_TEST,
+};
+use colored::Colorize;
+use futures::channel::oneshot;
+use itertools::Itertools;
+use polkadot_dispute_distribution::DisputeDistributionSubsystem;
+use polkadot_node_core_dispute_coordinator::{Config, DisputeCoordinatorSubsystem};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_network_protocol::request_response::{IncomingRequest, ReqProtocolNames};
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{AuthorityDiscoveryId, Block, Hash, ValidatorId};
+use sc_keystore::LocalKeystore;
+use sc_network::request_responses::IncomingRequest as RawIncomingRequest;
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_keystore::Keystore;
+use sp_runtime::RuntimeAppPublic;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+pub use test_state::TestState;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::disputes";
+
+/// How long to wait for the node under test to participate in the disputes raised at a block.
+const PARTICIPATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Parameters specific to the disputes benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct DisputesOptions {
+	#[clap(short, long, default_value_t = 1)]
+	/// Number of included candidates disputed at each block, capped at the number of cores.
+	pub n_disputes: usize,
+	#[clap(short = 's', long, default_value_t = 0)]
+	/// Number of disputes raised at each block for candidates the node has never seen.
+	pub n_spam_disputes: usize,
+}
+
+fn make_keystore(state: &TestState) -> Arc<LocalKeystore> {
+	let seed = state.test_authorities.key_seeds.get(NODE_UNDER_TEST as usize).unwrap().as_str();
+	let keystore = Arc::new(LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(&*keystore, ValidatorId::ID, Some(seed))
+		.expect("Insert key into keystore");
+	Keystore::sr25519_generate_new(&*keystore, AuthorityDiscoveryId::ID, Some(seed))
+		.expect("Insert key into keystore");
+	keystore
+}
+
+fn build_overseer(
+	state: &TestState,
+	network: NetworkEmulatorHandle,
+	network_interface: NetworkInterface,
+	network_receiver: NetworkInterfaceReceiver,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		state.candidate_events.clone(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Occupied,
+	);
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	let keystore = make_keystore(state);
+
+	let db = kvdb_memorydb::create(1);
+	let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[]);
+	let dispute_coordinator = DisputeCoordinatorSubsystem::new(
+		Arc::new(db),
+		Config { col_dispute_data: 0 },
+		keystore.clone(),
+		Metrics::try_register(&dependencies.registry).unwrap(),
+		false,
+	);
+
+	let (dispute_req_receiver, dispute_req_cfg) = IncomingRequest::get_config_receiver::<
+		Block,
+		sc_network::NetworkWorker<Block, Hash>,
+	>(&ReqProtocolNames::new(GENESIS_HASH, None));
+	let dispute_distribution = DisputeDistributionSubsystem::new(
+		keystore,
+		dispute_req_receiver,
+		MockAuthorityDiscovery::new(&state.test_authorities),
+		Metrics::try_register(&dependencies.registry).unwrap(),
+	);
+
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network,
+		network_interface.subsystem_sender(),
+		state.test_authorities.clone(),
+	);
+	let network_bridge_rx =
+		MockNetworkBridgeRx::new(network_receiver, Some(dispute_req_cfg), false);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_availability_recovery(|_| MockAvailabilityRecovery::new())
+		.replace_candidate_validation(|_| MockCandidateValidation::new())
+		.replace_dispute_coordinator(|_| dispute_coordinator)
+		.replace_dispute_distribution(|_| dispute_distribution)
+		.replace_network_bridge_tx(|_| network_bridge_tx)
+		.replace_network_bridge_rx(|_| network_bridge_rx);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	let (network, network_interface, network_receiver) = new_network(
+		&state.config,
+		&dependencies,
+		&state.test_authorities,
+		vec![Arc::new(state.clone())],
+	);
+	let (overseer, overseer_handle) =
+		build_overseer(state, network.clone(), network_interface, network_receiver, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+pub async fn benchmark_dispute_coordinator(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	state.reset_trackers();
+
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	// Disputes are raised by the emulated peers that are connected to the node under test.
+	let connected_validators = state
+		.test_authorities
+		.validator_authority_id
+		.iter()
+		.enumerate()
+		.filter(|(index, id)| {
+			*index != NODE_UNDER_TEST as usize && env.network().is_peer_connected(id)
+		})
+		.map(|(index, _)| index)
+		.collect_vec();
+	assert!(!connected_validators.is_empty(), "At least one emulated peer must be connected");
+
+	let test_start = Instant::now();
+	let mut total_rejected = 0;
+	let mut total_timed_out = 0;
+	for block_info in state.block_infos.iter() {
+		let block_num = block_info.number as usize;
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?}", block_num, config.num_blocks, block_info.hash);
+		env.metrics().set_current_block(block_num);
+		env.import_block(block_info.clone()).await;
+
+		let block_start_ts = Instant::now();
+		let payloads = state.dispute_requests.get(&block_info.hash).expect("pregenerated");
+		let receivers = payloads
+			.iter()
+			.zip(connected_validators.iter().cycle())
+			.map(|(payload, index)| {
+				let (pending_response, pending_response_receiver) = oneshot::channel();
+				let peer_id =
+					*state.test_authorities.peer_ids.get(*index).expect("all validators have ids");
+				let request = RawIncomingRequest {
+					peer: peer_id,
+					payload: payload.clone(),
+					pending_response,
+				};
+				let peer = state
+					.test_authorities
+					.validator_authority_id
+					.get(*index)
+					.expect("all validators have keys");
+
+				env.network()
+					.send_request_from_peer(peer, request)
+					.ok()
+					.map(|_| pending_response_receiver)
+			})
+			.collect_vec();
+
+		// Requests that hit the rate limit or were dropped by the network emulation are not
+		// answered, we count them as rejected.
+		let accepted =
+			futures::future::join_all(receivers.into_iter().map(|receiver| async move {
+				match receiver {
+					Some(receiver) => matches!(receiver.await, Ok(r) if r.result.is_ok()),
+					None => false,
+				}
+			}))
+			.await;
+		let rejected = accepted.iter().filter(|accepted| !**accepted).count();
+		total_rejected += rejected;
+		gum::info!(target: LOG_TARGET, "{} disputes raised, {} rejected", payloads.len(), rejected);
+
+		// The node under test only participates in the disputes it accepted. The first requests
+		// are for the disputed candidates, the rest is spam.
+		let candidates = state
+			.disputed_candidates
+			.get(&block_info.hash)
+			.into_iter()
+			.flatten()
+			.zip(accepted.iter())
+			.filter(|(_, accepted)| **accepted)
+			.map(|(candidate_hash, _)| *candidate_hash)
+			.collect_vec();
+		let expected = candidates.len();
+		let deadline = block_start_ts + PARTICIPATION_TIMEOUT;
+		loop {
+			let participated = state.participated_count(&candidates);
+			gum::debug!(target: LOG_TARGET, "{}/{} participations", participated, expected);
+
+			if participated == expected {
+				gum::info!(target: LOG_TARGET, "All disputes concluded in {}", format!("{:?}ms", block_start_ts.elapsed().as_millis()).cyan());
+				break
+			}
+			if Instant::now() >= deadline {
+				gum::warn!(
+					target: LOG_TARGET,
+					"Timed out after {:?} with {}/{} participations",
+					PARTICIPATION_TIMEOUT,
+					participated,
+					expected,
+				);
+				total_timed_out += expected - participated;
+				break
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+	}
+
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "All blocks processed in {}", format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+	gum::info!(target: LOG_TARGET, "Rejected dispute requests: {}", total_rejected);
+	if total_timed_out > 0 {
+		gum::warn!(target: LOG_TARGET, "Participations that timed out: {}", total_timed_out);
+	}
+
+	env.stop().await;
+	env.collect_resource_usage(&["dispute-coordinator", "dispute-distribution"], false)
+}
@@ -0,0 +1,275 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	configuration::{TestAuthorities, TestConfiguration},
+	disputes::DisputesOptions,
+	mock::runtime_api::session_info_for_peers,
+	network::{HandleNetworkMessage, NetworkMessage},
+	NODE_UNDER_TEST,
+};
+use codec::Encode;
+use itertools::Itertools;
+use polkadot_node_network_protocol::request_response::{
+	v1::{DisputeRequest, DisputeResponse},
+	Requests,
+};
+use polkadot_node_primitives::{DisputeMessage, SignedDisputeStatement};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{
+		CandidateEvent, CandidateReceiptV2 as CandidateReceipt, CommittedCandidateReceiptV2,
+		MutateDescriptorV2,
+	},
+	BlockNumber, CandidateHash, CoreIndex, DisputeStatement, GroupIndex, Hash, Header, Id,
+	InvalidDisputeStatementKind, SessionIndex, SessionInfo, ValidDisputeStatementKind,
+	ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::dummy_committed_candidate_receipt_v2;
+use sc_network::ProtocolName;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: SessionIndex = 0;
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Disputes specific options
+	pub options: DisputesOptions,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// Session info
+	pub session_info: SessionInfo,
+	// Candidates included in each relay chain block
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// `CandidateIncluded` events for each relay chain block
+	pub candidate_events: HashMap<H256, Vec<CandidateEvent>>,
+	// Encoded `DisputeRequest`s sent by the emulated peers at each relay chain block.
+	// Disputes against included candidates come first, followed by the spam ones.
+	pub dispute_requests: HashMap<H256, Vec<Vec<u8>>>,
+	// Included candidates disputed at each relay chain block
+	pub disputed_candidates: HashMap<H256, Vec<CandidateHash>>,
+	// Tracks if the vote of the node under test reached the emulated peers
+	pub participation_tracker: HashMap<CandidateHash, Arc<AtomicBool>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &DisputesOptions) -> Self {
+		let test_authorities = config.generate_authorities();
+		let session_info = session_info_for_peers(config, &test_authorities);
+		let mut state = Self {
+			config: config.clone(),
+			options: options.clone(),
+			test_authorities,
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			session_info,
+			candidate_receipts: Default::default(),
+			candidate_events: Default::default(),
+			dispute_requests: Default::default(),
+			disputed_candidates: Default::default(),
+			participation_tracker: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let n_disputes = options.n_disputes.min(config.n_cores);
+		// Any validator except the node under test can vote, we spread the votes evenly.
+		let mut voters = (0..config.n_validators as u32)
+			.filter(|index| *index != NODE_UNDER_TEST)
+			.map(ValidatorIndex)
+			.cycle();
+
+		for block_info in state.block_infos.iter() {
+			let included = (0..config.n_cores)
+				.map(|core_idx| make_candidate(block_info.hash, core_idx as u32 + 1, core_idx))
+				.collect_vec();
+			let spam = (0..options.n_spam_disputes)
+				.map(|index| {
+					make_candidate(block_info.hash, (config.n_cores + index) as u32 + 1, 0)
+				})
+				.collect_vec();
+
+			state.candidate_events.insert(
+				block_info.hash,
+				included
+					.iter()
+					.enumerate()
+					.map(|(core_idx, receipt)| {
+						CandidateEvent::CandidateIncluded(
+							receipt.clone(),
+							Vec::new().into(),
+							CoreIndex(core_idx as u32),
+							GroupIndex(core_idx as u32),
+						)
+					})
+					.collect(),
+			);
+
+			let requests = included
+				.iter()
+				.take(n_disputes)
+				.chain(spam.iter())
+				.map(|receipt| {
+					let valid_voter = voters.next().expect("cycle is infinite; qed");
+					let invalid_voter = voters.next().expect("cycle is infinite; qed");
+					state.sign_dispute(receipt.clone(), valid_voter, invalid_voter).encode()
+				})
+				.collect();
+			state.dispute_requests.insert(block_info.hash, requests);
+
+			let disputed =
+				included.iter().take(n_disputes).map(|receipt| receipt.hash()).collect_vec();
+			for candidate_hash in disputed.iter() {
+				state
+					.participation_tracker
+					.insert(*candidate_hash, Arc::new(AtomicBool::new(false)));
+			}
+			state.disputed_candidates.insert(block_info.hash, disputed);
+			state.candidate_receipts.insert(block_info.hash, included);
+		}
+
+		state
+	}
+
+	pub fn reset_trackers(&self) {
+		self.participation_tracker
+			.values()
+			.for_each(|v| v.as_ref().store(false, Ordering::SeqCst));
+	}
+
+	/// Returns how many of the disputes on `candidates` the node under test voted on.
+	pub fn participated_count(&self, candidates: &[CandidateHash]) -> usize {
+		candidates
+			.iter()
+			.filter(|hash| {
+				self.participation_tracker.get(hash).map_or(false, |v| v.load(Ordering::SeqCst))
+			})
+			.count()
+	}
+
+	fn sign_dispute(
+		&self,
+		candidate_receipt: CandidateReceipt,
+		valid_voter: ValidatorIndex,
+		invalid_voter: ValidatorIndex,
+	) -> DisputeRequest {
+		let candidate_hash = candidate_receipt.hash();
+		let sign = |statement: DisputeStatement, voter: ValidatorIndex| {
+			let pair = self
+				.test_authorities
+				.validator_pairs
+				.get(voter.0 as usize)
+				.expect("all validators have keys");
+			let payload = statement
+				.payload_data(candidate_hash, SESSION_INDEX)
+				.expect("explicit statements always have a payload; qed");
+			SignedDisputeStatement::new_checked(
+				statement,
+				candidate_hash,
+				SESSION_INDEX,
+				pair.public(),
+				pair.sign(&payload[..]),
+			)
+			.expect("signature is valid; qed")
+		};
+
+		let valid = sign(DisputeStatement::Valid(ValidDisputeStatementKind::Explicit), valid_voter);
+		let invalid =
+			sign(DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit), invalid_voter);
+
+		DisputeMessage::from_signed_statements(
+			valid,
+			valid_voter,
+			invalid,
+			invalid_voter,
+			candidate_receipt,
+			&self.session_info,
+		)
+		.expect("votes are generated for the same candidate and session; qed")
+		.into()
+	}
+}
+
+fn make_candidate(relay_parent: H256, para_id: u32, core_idx: usize) -> CandidateReceipt {
+	let mut receipt: CommittedCandidateReceiptV2 =
+		dummy_committed_candidate_receipt_v2(relay_parent);
+	receipt.descriptor.set_para_id(Id::new(para_id));
+	receipt.descriptor.set_core_index(CoreIndex(core_idx as u32));
+	receipt.descriptor.set_session_index(SESSION_INDEX);
+
+	receipt.to_plain()
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::DisputeSendingV1(req)) => {
+				let dispute = &req.payload.0;
+				if dispute.valid_vote.validator_index.0 == NODE_UNDER_TEST ||
+					dispute.invalid_vote.validator_index.0 == NODE_UNDER_TEST
+				{
+					if let Some(tracker) =
+						self.participation_tracker.get(&dispute.candidate_receipt.hash())
+					{
+						tracker.as_ref().store(true, Ordering::SeqCst);
+					}
+				}
+
+				let _ = req
+					.pending_response
+					.send(Ok((DisputeResponse::Confirmed.encode(), ProtocolName::from(""))));
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
@@ -0,0 +1,56 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A mocked authority discovery service that resolves the peers of the emulated network.
+
+use crate::configuration::TestAuthorities;
+use polkadot_node_network_protocol::authority_discovery::AuthorityDiscovery;
+use polkadot_primitives::AuthorityDiscoveryId;
+use sc_network::Multiaddr;
+use sc_network_types::PeerId;
+use std::collections::{HashMap, HashSet};
+
+/// Maps the `PeerId`s of the emulated peers to their `AuthorityDiscoveryId`s.
+#[derive(Debug, Clone)]
+pub struct MockAuthorityDiscovery {
+	peer_id_to_authority: HashMap<PeerId, AuthorityDiscoveryId>,
+}
+
+impl MockAuthorityDiscovery {
+	pub fn new(test_authorities: &TestAuthorities) -> Self {
+		Self { peer_id_to_authority: test_authorities.peer_id_to_authority.clone() }
+	}
+}
+
+#[async_trait::async_trait]
+impl AuthorityDiscovery for MockAuthorityDiscovery {
+	async fn get_addresses_by_authority_id(
+		&mut self,
+		_authority: AuthorityDiscoveryId,
+	) -> Option<HashSet<Multiaddr>> {
+		// The emulated network doesn't use addresses.
+		None
+	}
+
+	async fn get_authority_ids_by_peer_id(
+		&mut self,
+		peer_id: PeerId,
+	) -> Option<HashSet<AuthorityDiscoveryId>> {
+		self.peer_id_to_authority
+			.get(&peer_id)
+			.map(|authority_id| HashSet::from([authority_id.clone()]))
+	}
+}
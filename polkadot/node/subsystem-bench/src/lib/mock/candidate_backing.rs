@@ -18,13 +18,17 @@
 
 use crate::{configuration::TestConfiguration, NODE_UNDER_TEST};
 use futures::FutureExt;
-use polkadot_node_primitives::{SignedFullStatementWithPVD, Statement, StatementWithPVD};
+use polkadot_node_primitives::{
+	SignedFullStatement, SignedFullStatementWithPVD, Statement, StatementWithPVD,
+};
 use polkadot_node_subsystem::{
-	messages::CandidateBackingMessage, overseer, SpawnedSubsystem, SubsystemError,
+	messages::{CandidateBackingMessage, CollatorProtocolMessage},
+	overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
 use polkadot_primitives::{
-	CandidateHash, Hash, PersistedValidationData, SigningContext, ValidatorIndex, ValidatorPair,
+	vstaging::CommittedCandidateReceiptV2 as CommittedCandidateReceipt, CandidateHash, Hash,
+	PersistedValidationData, SigningContext, ValidatorIndex, ValidatorPair,
 };
 use sp_core::Pair;
 use std::collections::HashMap;
@@ -35,6 +39,8 @@ struct MockCandidateBackingState {
 	pair: ValidatorPair,
 	pvd: PersistedValidationData,
 	own_backing_group: Vec<ValidatorIndex>,
+	// Candidates that can be seconded, the receipts are required to sign the statement.
+	committed_receipts: HashMap<CandidateHash, CommittedCandidateReceipt>,
 }
 
 pub struct MockCandidateBacking {
@@ -49,7 +55,44 @@ impl MockCandidateBacking {
 		pvd: PersistedValidationData,
 		own_backing_group: Vec<ValidatorIndex>,
 	) -> Self {
-		Self { config, state: MockCandidateBackingState { pair, pvd, own_backing_group } }
+		Self {
+			config,
+			state: MockCandidateBackingState {
+				pair,
+				pvd,
+				own_backing_group,
+				committed_receipts: Default::default(),
+			},
+		}
+	}
+
+	/// Allows seconding of the given candidates. Any of them passed in
+	/// `CandidateBackingMessage::Second` is considered valid and reported back as seconded.
+	pub fn with_committed_receipts(
+		mut self,
+		committed_receipts: HashMap<CandidateHash, CommittedCandidateReceipt>,
+	) -> Self {
+		self.state.committed_receipts = committed_receipts;
+		self
+	}
+
+	fn handle_second(
+		&self,
+		relay_parent: Hash,
+		candidate_hash: CandidateHash,
+	) -> Option<SignedFullStatement> {
+		let receipt = self.state.committed_receipts.get(&candidate_hash)?.clone();
+		let statement = Statement::Seconded(receipt);
+		let context = SigningContext { parent_hash: relay_parent, session_index: 0 };
+		let payload = statement.to_compact().signing_payload(&context);
+
+		SignedFullStatement::new(
+			statement,
+			ValidatorIndex(NODE_UNDER_TEST),
+			self.state.pair.sign(&payload[..]),
+			&context,
+			&self.state.pair.public(),
+		)
 	}
 
 	fn handle_statement(
@@ -160,6 +203,26 @@ impl MockCandidateBacking {
 								ctx.send_message(message).await;
 							}
 						},
+						CandidateBackingMessage::CanSecond(_request, tx) => {
+							let _ = tx.send(true);
+						},
+						CandidateBackingMessage::Second(relay_parent, receipt, _pvd, _pov) =>
+							match self.handle_second(relay_parent, receipt.hash()) {
+								Some(statement) => {
+									ctx.send_message(CollatorProtocolMessage::Seconded(
+										relay_parent,
+										statement,
+									))
+									.await;
+								},
+								None => {
+									ctx.send_message(CollatorProtocolMessage::Invalid(
+										relay_parent,
+										receipt,
+									))
+									.await;
+								},
+							},
 						_ => {
 							unimplemented!("Unexpected candidate-backing message")
 						},
@@ -22,10 +22,11 @@ use crate::{
 	network::{NetworkEmulatorHandle, NetworkInterfaceReceiver, NetworkMessage, RequestExt},
 };
 use futures::{channel::mpsc::UnboundedSender, FutureExt, StreamExt};
-use polkadot_node_network_protocol::Versioned;
+use polkadot_node_network_protocol::{v1 as protocol_v1, v2 as protocol_v2, Versioned};
 use polkadot_node_subsystem::{
 	messages::{
-		ApprovalDistributionMessage, ApprovalVotingParallelMessage, NetworkBridgeTxMessage,
+		ApprovalDistributionMessage, ApprovalVotingParallelMessage, CollatorProtocolMessage,
+		NetworkBridgeTxMessage,
 	},
 	overseer, SpawnedSubsystem, SubsystemError,
 };
@@ -39,6 +40,7 @@ const LOG_TARGET: &str = "subsystem-bench::network-bridge";
 const ALLOWED_PROTOCOLS: &[&str] = &[
 	"/ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff/req_chunk/2",
 	"/ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff/req_attested_candidate/2",
+	"/ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff/send_dispute/1",
 ];
 
 /// A mock of the network bridge tx subsystem.
@@ -142,6 +144,9 @@ impl MockNetworkBridgeTx {
 					NetworkBridgeTxMessage::ReportPeer(_) => {
 						// ignore rep changes
 					},
+					NetworkBridgeTxMessage::DisconnectPeer(_, _) => {
+						// emulated peers stay connected for the whole test
+					},
 					NetworkBridgeTxMessage::SendValidationMessage(peers, message) => {
 						for peer in peers {
 							self.to_network_interface
@@ -172,6 +177,36 @@ impl MockNetworkBridgeTx {
 							}
 						}
 					},
+					NetworkBridgeTxMessage::SendCollationMessage(peers, message) => {
+						for peer in peers {
+							self.to_network_interface
+								.unbounded_send(NetworkMessage::CollationMessageFromNode(
+									self.test_authorities
+										.peer_id_to_authority
+										.get(&peer)
+										.unwrap()
+										.clone(),
+									message.clone(),
+								))
+								.expect("Should not fail");
+						}
+					},
+					NetworkBridgeTxMessage::SendCollationMessages(messages) => {
+						for (peers, message) in messages {
+							for peer in peers {
+								self.to_network_interface
+									.unbounded_send(NetworkMessage::CollationMessageFromNode(
+										self.test_authorities
+											.peer_id_to_authority
+											.get(&peer)
+											.unwrap()
+											.clone(),
+										message.clone(),
+									))
+									.expect("Should not fail");
+							}
+						}
+					},
 					message => unimplemented!("Unexpected network bridge message {:?}", message),
 				},
 			}
@@ -223,6 +258,19 @@ impl MockNetworkBridgeRx {
 									unimplemented!("We only talk v2 network protocol")
 								},
 							},
+							NetworkMessage::CollationMessageFromPeer(peer_id, message) => {
+								let message = match message {
+									Versioned::V1(protocol_v1::CollationProtocol::CollatorProtocol(msg)) =>
+										Versioned::V1(msg),
+									Versioned::V2(protocol_v2::CollationProtocol::CollatorProtocol(msg)) =>
+										Versioned::V2(msg),
+									Versioned::V3(protocol_v2::CollationProtocol::CollatorProtocol(msg)) =>
+										Versioned::V3(msg),
+								};
+								ctx.send_message(
+									CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(peer_id, message))
+								).await;
+							},
 							NetworkMessage::RequestFromPeer(request) => {
 								if let Some(protocol) = self.chunk_request_sender.as_mut() {
 									assert!(ALLOWED_PROTOCOLS.contains(&&*protocol.name));
@@ -21,13 +21,29 @@ use polkadot_node_subsystem::{
 	messages::ProspectiveParachainsMessage, overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
-use polkadot_primitives::Hash;
+use polkadot_primitives::{BlockNumber, Hash, Id as ParaId, PersistedValidationData};
+use std::collections::HashMap;
 
-pub struct MockProspectiveParachains {}
+/// Minimal state to answer requests.
+#[derive(Clone, Default)]
+pub struct ProspectiveParachainsState {
+	/// Minimum relay parents per para for each known leaf.
+	pub min_relay_parents: HashMap<Hash, Vec<(ParaId, BlockNumber)>>,
+	/// PersistedValidationData returned for any candidate, `None` if unknown.
+	pub validation_data: Option<PersistedValidationData>,
+}
+
+pub struct MockProspectiveParachains {
+	state: ProspectiveParachainsState,
+}
 
 impl MockProspectiveParachains {
 	pub fn new() -> Self {
-		Self {}
+		Self::with_state(Default::default())
+	}
+
+	pub fn with_state(state: ProspectiveParachainsState) -> Self {
+		Self { state }
 	}
 }
 
@@ -51,8 +67,18 @@ impl MockProspectiveParachains {
 						return
 					},
 				orchestra::FromOrchestra::Communication { msg } => match msg {
-					ProspectiveParachainsMessage::GetMinimumRelayParents(_relay_parent, tx) => {
-						tx.send(vec![]).unwrap();
+					ProspectiveParachainsMessage::GetMinimumRelayParents(relay_parent, tx) => {
+						tx.send(
+							self.state
+								.min_relay_parents
+								.get(&relay_parent)
+								.cloned()
+								.unwrap_or_default(),
+						)
+						.unwrap();
+					},
+					ProspectiveParachainsMessage::GetProspectiveValidationData(_req, tx) => {
+						tx.send(self.state.validation_data.clone()).unwrap();
 					},
 					ProspectiveParachainsMessage::GetHypotheticalMembership(req, tx) => {
 						tx.send(
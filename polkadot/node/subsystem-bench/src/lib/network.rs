@@ -54,7 +54,7 @@ use itertools::Itertools;
 use net_protocol::{
 	peer_set::ValidationVersion,
 	request_response::{Recipient, Requests, ResponseSender},
-	ObservedRole, VersionedValidationProtocol, View,
+	ObservedRole, VersionedCollationProtocol, VersionedValidationProtocol, View,
 };
 use polkadot_node_network_protocol::{self as net_protocol, Versioned};
 use polkadot_node_subsystem::messages::StatementDistributionMessage;
@@ -158,6 +158,10 @@ pub enum NetworkMessage {
 	RequestFromNode(AuthorityDiscoveryId, Requests),
 	/// A request originating from an emulated peer
 	RequestFromPeer(IncomingRequest),
+	/// A collation protocol message from peer to node.
+	CollationMessageFromPeer(PeerId, VersionedCollationProtocol),
+	/// A collation protocol message from node to a peer.
+	CollationMessageFromNode(AuthorityDiscoveryId, VersionedCollationProtocol),
 }
 
 impl NetworkMessage {
@@ -175,6 +179,15 @@ impl NetworkMessage {
 				message.encoded_size(),
 			NetworkMessage::RequestFromNode(_peer_id, incoming) => incoming.size(),
 			NetworkMessage::RequestFromPeer(request) => request.payload.encoded_size(),
+			NetworkMessage::CollationMessageFromPeer(_, Versioned::V1(message)) =>
+				message.encoded_size(),
+			NetworkMessage::CollationMessageFromPeer(_, Versioned::V2(message)) |
+			NetworkMessage::CollationMessageFromPeer(_, Versioned::V3(message)) => message.encoded_size(),
+			NetworkMessage::CollationMessageFromNode(_peer_id, Versioned::V1(message)) =>
+				message.encoded_size(),
+			NetworkMessage::CollationMessageFromNode(_peer_id, Versioned::V2(message)) |
+			NetworkMessage::CollationMessageFromNode(_peer_id, Versioned::V3(message)) =>
+				message.encoded_size(),
 		}
 	}
 
@@ -182,7 +195,8 @@ impl NetworkMessage {
 	pub fn peer(&self) -> Option<&AuthorityDiscoveryId> {
 		match &self {
 			NetworkMessage::MessageFromNode(peer_id, _) |
-			NetworkMessage::RequestFromNode(peer_id, _) => Some(peer_id),
+			NetworkMessage::RequestFromNode(peer_id, _) |
+			NetworkMessage::CollationMessageFromNode(peer_id, _) => Some(peer_id),
 			_ => None,
 		}
 	}
@@ -351,6 +365,8 @@ impl NetworkInterface {
 					match peer_message {
 						NetworkMessage::MessageFromNode(peer, message) =>
 							tx_network.send_message_to_peer(&peer, message),
+						NetworkMessage::CollationMessageFromNode(peer, message) =>
+							tx_network.send_collation_message_to_peer(&peer, message),
 						NetworkMessage::RequestFromNode(peer, request) => {
 							// Send request through a proxy so we can account and limit bandwidth
 							// usage for the node.
@@ -454,6 +470,13 @@ impl EmulatedPeerHandle {
 			.expect("Peer action channel hangup");
 	}
 
+	/// Send a collation protocol message to the node.
+	pub fn send_collation_message(&self, message: VersionedCollationProtocol) {
+		self.actions_tx
+			.unbounded_send(NetworkMessage::CollationMessageFromPeer(self.peer_id, message))
+			.expect("Peer action channel hangup");
+	}
+
 	/// Send a `request` to the node.
 	pub fn send_request(&self, request: IncomingRequest) {
 		self.actions_tx
@@ -876,6 +899,19 @@ impl NetworkEmulatorHandle {
 		peer.handle().receive(NetworkMessage::MessageFromNode(peer_id.clone(), message));
 	}
 
+	/// Forward collation protocol `message` to an emulated `peer`.
+	/// Panics if peer is not connected.
+	pub fn send_collation_message_to_peer(
+		&self,
+		peer_id: &AuthorityDiscoveryId,
+		message: VersionedCollationProtocol,
+	) {
+		let peer = self.peer(peer_id);
+		assert!(peer.is_connected(), "forward message only for connected peers.");
+		peer.handle()
+			.receive(NetworkMessage::CollationMessageFromNode(peer_id.clone(), message));
+	}
+
 	/// Forward a `request`` to an emulated `peer`.
 	/// Panics if peer is not connected.
 	pub fn send_request_to_peer(&self, peer_id: &AuthorityDiscoveryId, request: Requests) {
@@ -901,6 +937,23 @@ impl NetworkEmulatorHandle {
 		Ok(())
 	}
 
+	/// Send a collation protocol message from a peer to the node.
+	pub fn send_collation_message_from_peer(
+		&self,
+		from_peer: &AuthorityDiscoveryId,
+		message: VersionedCollationProtocol,
+	) -> Result<(), EmulatedPeerError> {
+		let dst_peer = self.peer(from_peer);
+
+		if !dst_peer.is_connected() {
+			gum::warn!(target: LOG_TARGET, "Attempted to send message from a peer not connected to our node, operation ignored");
+			return Err(EmulatedPeerError::NotConnected)
+		}
+
+		dst_peer.handle().send_collation_message(message);
+		Ok(())
+	}
+
 	/// Send a request from a peer to the node.
 	pub fn send_request_from_peer(
 		&self,
@@ -1030,8 +1083,15 @@ impl RequestExt for Requests {
 					None
 				}
 			},
+			Requests::DisputeSendingV1(request) => {
+				if let Recipient::Authority(authority_id) = &request.peer {
+					Some(authority_id)
+				} else {
+					None
+				}
+			},
 			// Requested by PeerId
-			Requests::AttestedCandidateV2(_) => None,
+			Requests::AttestedCandidateV2(_) | Requests::CollationFetchingV2(_) => None,
 			request => {
 				unimplemented!("RequestAuthority not implemented for {:?}", request)
 			},
@@ -1044,6 +1104,10 @@ impl RequestExt for Requests {
 				Recipient::Authority(_) => None,
 				Recipient::Peer(peer_id) => Some(peer_id),
 			},
+			Requests::CollationFetchingV2(request) => match &request.peer {
+				Recipient::Authority(_) => None,
+				Recipient::Peer(peer_id) => Some(peer_id),
+			},
 			request => {
 				unimplemented!("peer_id() is not implemented for {:?}", request)
 			},
@@ -1055,6 +1119,8 @@ impl RequestExt for Requests {
 			Requests::ChunkFetching(outgoing_request) => outgoing_request.pending_response,
 			Requests::AvailableDataFetchingV1(outgoing_request) =>
 				outgoing_request.pending_response,
+			Requests::DisputeSendingV1(outgoing_request) => outgoing_request.pending_response,
+			Requests::CollationFetchingV2(outgoing_request) => outgoing_request.pending_response,
 			_ => unimplemented!("unsupported request type"),
 		}
 	}
@@ -1068,6 +1134,10 @@ impl RequestExt for Requests {
 				std::mem::replace(&mut outgoing_request.pending_response, new_sender),
 			Requests::AttestedCandidateV2(outgoing_request) =>
 				std::mem::replace(&mut outgoing_request.pending_response, new_sender),
+			Requests::DisputeSendingV1(outgoing_request) =>
+				std::mem::replace(&mut outgoing_request.pending_response, new_sender),
+			Requests::CollationFetchingV2(outgoing_request) =>
+				std::mem::replace(&mut outgoing_request.pending_response, new_sender),
 			_ => unimplemented!("unsupported request type"),
 		}
 	}
@@ -1080,6 +1150,9 @@ impl RequestExt for Requests {
 				outgoing_request.payload.encoded_size(),
 			Requests::AttestedCandidateV2(outgoing_request) =>
 				outgoing_request.payload.encoded_size(),
+			Requests::DisputeSendingV1(outgoing_request) => outgoing_request.payload.encoded_size(),
+			Requests::CollationFetchingV2(outgoing_request) =>
+				outgoing_request.payload.encoded_size(),
 			_ => unimplemented!("received an unexpected request"),
 		}
 	}
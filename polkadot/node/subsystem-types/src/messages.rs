@@ -36,10 +36,10 @@ use polkadot_node_primitives::{
 		v1::{BlockApprovalMeta, DelayTranche},
 		v2::{CandidateBitfield, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2},
 	},
-	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
-	CollationSecondedSignal, DisputeMessage, DisputeStatus, ErasureChunk, PoV,
-	SignedDisputeStatement, SignedFullStatement, SignedFullStatementWithPVD, SubmitCollationParams,
-	ValidationResult,
+	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationEventSender,
+	CollationGenerationConfig, CollationSecondedSignal, DisputeMessage, DisputeStatus,
+	ErasureChunk, PoV, SignedDisputeStatement, SignedFullStatement, SignedFullStatementWithPVD,
+	SubmitCollationParams, ValidationResult,
 };
 use polkadot_primitives::{
 	async_backing, slashing, vstaging,
@@ -265,6 +265,8 @@ pub enum CollatorProtocolMessage {
 	///
 	/// The hash is the relay parent.
 	Seconded(Hash, SignedFullStatement),
+	/// Receive events about the lifecycle of every collation distributed from now on.
+	SubscribeCollationEvents(CollationEventSender),
 }
 
 impl Default for CollatorProtocolMessage {
@@ -896,6 +898,10 @@ pub enum CollationGenerationMessage {
 	///
 	/// If sent before `Initialize`, this will be ignored.
 	SubmitCollation(SubmitCollationParams),
+	/// Receive events about the lifecycle of every collation built from now on.
+	///
+	/// The subscription is forwarded to the collator side of the collator protocol.
+	SubscribeCollationEvents(CollationEventSender),
 }
 
 /// The result type of [`ApprovalVotingMessage::ImportAssignment`] request.
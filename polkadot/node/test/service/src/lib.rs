@@ -95,12 +95,14 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					workers_names: None,
 					overseer_gen,
 					overseer_message_channel_capacity_override: None,
+					overseer_message_tracing: None,
 					malus_finality_delay: None,
 					hwbench: None,
 					execute_workers_max_num: None,
 					prepare_workers_hard_max_num: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
+					availability_retention: Default::default(),
 				},
 			),
 		sc_network::config::NetworkBackendType::Litep2p =>
@@ -117,12 +119,14 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					workers_names: None,
 					overseer_gen,
 					overseer_message_channel_capacity_override: None,
+					overseer_message_tracing: None,
 					malus_finality_delay: None,
 					hwbench: None,
 					execute_workers_max_num: None,
 					prepare_workers_hard_max_num: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
+					availability_retention: Default::default(),
 				},
 			),
 	}
@@ -223,12 +227,16 @@ pub fn node_config(
 			rate_limit: None,
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			archive_fallback: None,
+			ip_quota: None,
+			method_costs: Default::default(),
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
 		offchain_worker: Default::default(),
 		force_authoring: false,
 		disable_grandpa: false,
+		slashing_protection: None,
 		dev_key_seed: Some(key_seed),
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
@@ -94,12 +94,14 @@ fn main() -> Result<()> {
 
 						overseer_gen: polkadot_service::CollatorOverseerGen,
 						overseer_message_channel_capacity_override: None,
+						overseer_message_tracing: None,
 						malus_finality_delay: None,
 						hwbench: None,
 						execute_workers_max_num: None,
 						prepare_workers_hard_max_num: None,
 						prepare_workers_soft_max_num: None,
 						enable_approval_voting_parallel: false,
+						availability_retention: Default::default(),
 					},
 				)
 				.map_err(|e| e.to_string())?;
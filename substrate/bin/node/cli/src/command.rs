@@ -166,6 +166,7 @@ pub fn run() -> Result<()> {
 					},
 					BenchmarkCmd::Machine(cmd) =>
 						cmd.run(&config, SUBSTRATE_REFERENCE_HARDWARE.clone()),
+					BenchmarkCmd::Compare(cmd) => cmd.run(),
 				}
 			})
 		},
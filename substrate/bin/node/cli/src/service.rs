@@ -26,7 +26,7 @@ use polkadot_sdk::{
 };
 
 use crate::Cli;
-use codec::Encode;
+use codec::{Decode, Encode};
 use frame_benchmarking_cli::SUBSTRATE_REFERENCE_HARDWARE;
 use frame_system_rpc_runtime_api::AccountNonceApi;
 use futures::prelude::*;
@@ -166,6 +166,17 @@ pub fn create_extrinsic(
 	.into()
 }
 
+/// Returns the encoded address of the account that signed the given opaque extrinsic.
+///
+/// Used by the transaction pool to enforce the per-sender limit.
+fn transaction_sender(xt: &<Block as BlockT>::Extrinsic) -> Option<Vec<u8>> {
+	let xt = kitchensink_runtime::UncheckedExtrinsic::decode(&mut &xt.encode()[..]).ok()?;
+	match xt.preamble {
+		generic::Preamble::Signed(address, ..) => Some(address.encode()),
+		_ => None,
+	}
+}
+
 /// Creates a new partial node.
 pub fn new_partial(
 	config: &Configuration,
@@ -211,6 +222,7 @@ pub fn new_partial(
 		.transpose()?;
 
 	let executor = sc_service::new_wasm_executor(&config.executor);
+	let rpc_executor = executor.clone();
 
 	let (client, backend, keystore_container, task_manager) =
 		sc_service::new_full_parts::<Block, RuntimeApi, _>(
@@ -235,6 +247,7 @@ pub fn new_partial(
 		)
 		.with_options(config.transaction_pool.clone())
 		.with_prometheus(config.prometheus_registry())
+		.with_sender_extractor(transaction_sender)
 		.build(),
 	);
 
@@ -288,11 +301,16 @@ pub fn new_partial(
 
 	let import_setup = (block_import, grandpa_link, babe_link, beefy_voter_links);
 
+	// Statements are decrypted with the secret keys, which a remote signer doesn't hand out.
+	let statement_keystore = keystore_container.local_keystore().unwrap_or_else(|_| {
+		log::info!("Statements can't be decrypted with the keys of the remote signer");
+		Arc::new(sc_keystore::LocalKeystore::in_memory())
+	});
 	let statement_store = sc_statement_store::Store::new_shared(
 		&config.data_path,
 		Default::default(),
 		client.clone(),
-		keystore_container.local_keystore(),
+		statement_keystore,
 		config.prometheus_registry(),
 		&task_manager.spawn_handle(),
 	)
@@ -351,6 +369,7 @@ pub fn new_partial(
 					statement_store: rpc_statement_store.clone(),
 					backend: rpc_backend.clone(),
 					mixnet_api: mixnet_api.as_ref().cloned(),
+					runtime_executor: rpc_executor.clone(),
 				};
 
 				node_rpc::create_full(deps).map_err(Into::into)
@@ -416,6 +435,12 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
+	let slashing_protection = config
+		.slashing_protection
+		.as_ref()
+		.map(|path| sc_consensus::SlashingProtection::open(path, prometheus_registry.as_ref()))
+		.transpose()
+		.map_err(|e| ServiceError::Other(format!("Slashing protection error: {e}")))?;
 	let enable_offchain_worker = config.offchain_worker.enabled;
 
 	let hwbench = (!disable_hardware_benchmarks)
@@ -629,6 +654,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 			block_proposal_slot_portion: SlotProportion::new(0.5),
 			max_block_proposal_slot_portion: None,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
+			slashing_protection: slashing_protection.clone(),
 		};
 
 		let babe = sc_consensus_babe::start_babe(babe_config)?;
@@ -727,6 +753,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		local_role: role,
 		telemetry: telemetry.as_ref().map(|x| x.handle()),
 		protocol_name: grandpa_protocol_name,
+		slashing_protection,
 	};
 
 	if enable_grandpa {
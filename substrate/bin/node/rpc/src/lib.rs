@@ -87,7 +87,7 @@ pub struct BeefyDeps<AuthorityId: AuthorityIdBound> {
 }
 
 /// Full client dependencies.
-pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
+pub struct FullDeps<C, P, SC, B, E, AuthorityId: AuthorityIdBound> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
@@ -108,10 +108,12 @@ pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	pub backend: Arc<B>,
 	/// Mixnet API.
 	pub mixnet_api: Option<sc_mixnet::Api>,
+	/// Executor used to dry-run runtime upgrades, only used with the `try-runtime` feature.
+	pub runtime_executor: E,
 }
 
 /// Instantiate all Full RPC extensions.
-pub fn create_full<C, P, SC, B, AuthorityId>(
+pub fn create_full<C, P, SC, B, E, AuthorityId>(
 	FullDeps {
 		client,
 		pool,
@@ -123,7 +125,8 @@ pub fn create_full<C, P, SC, B, AuthorityId>(
 		statement_store,
 		backend,
 		mixnet_api,
-	}: FullDeps<C, P, SC, B, AuthorityId>,
+		runtime_executor,
+	}: FullDeps<C, P, SC, B, E, AuthorityId>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>
@@ -143,6 +146,7 @@ where
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashingFor<Block>>,
+	E: sp_core::traits::CodeExecutor + Clone + Send + Sync + 'static,
 	AuthorityId: AuthorityIdBound,
 	<AuthorityId as RuntimeAppPublic>::Signature: Send + Sync,
 {
@@ -159,6 +163,8 @@ where
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
+	#[cfg(feature = "try-runtime")]
+	use substrate_try_runtime_rpc::{TryRuntimeApiServer, TryRuntimeRpc};
 
 	let mut io = RpcModule::new(());
 
@@ -204,7 +210,11 @@ where
 			.into_rpc(),
 	)?;
 
-	io.merge(StateMigration::new(client.clone(), backend).into_rpc())?;
+	io.merge(StateMigration::new(client.clone(), backend.clone()).into_rpc())?;
+	#[cfg(feature = "try-runtime")]
+	io.merge(TryRuntimeRpc::new(client.clone(), backend, runtime_executor).into_rpc())?;
+	#[cfg(not(feature = "try-runtime"))]
+	let _ = runtime_executor;
 	io.merge(Dev::new(client).into_rpc())?;
 	let statement_store = sc_rpc::statement::StatementStore::new(statement_store).into_rpc();
 	io.merge(statement_store)?;
@@ -0,0 +1,327 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of raw storage keys and values using the runtime metadata.
+
+use codec::{Compact, Decode};
+use frame_metadata::{
+	v14::{StorageEntryType, StorageHasher},
+	RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use sc_chain_spec::GenesisConfigBuilderRuntimeCaller;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{json, Map, Value};
+use sp_crypto_hashing::twox_128;
+use std::collections::HashMap;
+
+/// A storage item as described by the runtime metadata.
+struct StorageItem {
+	pallet: String,
+	name: String,
+	/// The hashers and the type of the key, `None` for plain storage values.
+	key: Option<(Vec<StorageHasher>, u32)>,
+	value: u32,
+}
+
+/// The location and the decoded key of a storage entry.
+pub struct DecodedKey {
+	/// The pallet (or the well-known key group) the entry belongs to.
+	pub pallet: String,
+	/// The storage item name.
+	pub item: String,
+	/// The decoded map key, `None` for plain storage values.
+	pub key: Option<Value>,
+}
+
+/// Decodes the storage of a runtime based on its metadata.
+pub struct StorageDecoder {
+	types: PortableRegistry,
+	/// Items indexed by `twox128(pallet prefix) ++ twox128(item name)`.
+	items: HashMap<[u8; 32], StorageItem>,
+}
+
+impl StorageDecoder {
+	/// Creates the decoder using the metadata exposed by the given runtime code.
+	pub fn new(code: &[u8]) -> Result<Self, String> {
+		let caller: GenesisConfigBuilderRuntimeCaller =
+			GenesisConfigBuilderRuntimeCaller::new(code);
+		let metadata = caller.get_metadata()?;
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+			.map_err(|e| format!("metadata decoding failed: {e}"))?;
+
+		let (types, pallets) = match metadata.1 {
+			RuntimeMetadata::V14(metadata) => (
+				metadata.types,
+				metadata.pallets.into_iter().filter_map(|p| p.storage).collect::<Vec<_>>(),
+			),
+			RuntimeMetadata::V15(metadata) =>
+				(metadata.types, metadata.pallets.into_iter().filter_map(|p| p.storage).collect()),
+			_ => return Err("Only metadata V14 and V15 are supported".into()),
+		};
+
+		let mut items = HashMap::new();
+		for storage in pallets {
+			let pallet_hash = twox_128(storage.prefix.as_bytes());
+			for entry in storage.entries {
+				let mut prefix = [0u8; 32];
+				prefix[..16].copy_from_slice(&pallet_hash);
+				prefix[16..].copy_from_slice(&twox_128(entry.name.as_bytes()));
+
+				let (key, value) = match entry.ty {
+					StorageEntryType::Plain(value) => (None, value.id),
+					StorageEntryType::Map { hashers, key, value } =>
+						(Some((hashers, key.id)), value.id),
+				};
+				items.insert(
+					prefix,
+					StorageItem { pallet: storage.prefix.clone(), name: entry.name, key, value },
+				);
+			}
+		}
+
+		Ok(Self { types, items })
+	}
+
+	/// Decodes the given storage key.
+	///
+	/// Well-known keys (e.g. `:code`) are grouped under `WellKnown`, keys not described by the
+	/// metadata under `Unknown`.
+	pub fn decode_key(&self, key: &[u8]) -> DecodedKey {
+		if key.starts_with(b":") {
+			return DecodedKey {
+				pallet: "WellKnown".into(),
+				item: String::from_utf8_lossy(key).into_owned(),
+				key: None,
+			}
+		}
+
+		let Some(item) = self.item(key) else {
+			return DecodedKey { pallet: "Unknown".into(), item: hex(key), key: None }
+		};
+
+		let key = match item.key {
+			None => None,
+			Some((ref hashers, ty)) => Some(
+				self.decode_map_key(hashers, ty, &key[32..])
+					.unwrap_or_else(|_| Value::String(hex(&key[32..]))),
+			),
+		};
+
+		DecodedKey { pallet: item.pallet.clone(), item: item.name.clone(), key }
+	}
+
+	/// Decodes the value stored under the given key.
+	///
+	/// The hex representation is returned if the value can't be decoded.
+	pub fn decode_value(&self, key: &[u8], value: &[u8]) -> Value {
+		if key == sp_core::storage::well_known_keys::CODE {
+			return json!({
+				"hash": hex(&sp_crypto_hashing::blake2_256(value)),
+				"size": value.len(),
+			})
+		}
+
+		self.item(key)
+			.and_then(|item| decode_all(&self.types, item.value, value).ok())
+			.unwrap_or_else(|| Value::String(hex(value)))
+	}
+
+	fn item(&self, key: &[u8]) -> Option<&StorageItem> {
+		key.get(..32)
+			.and_then(|prefix| <[u8; 32]>::try_from(prefix).ok())
+			.and_then(|prefix| self.items.get(&prefix))
+	}
+
+	fn decode_map_key(
+		&self,
+		hashers: &[StorageHasher],
+		ty: u32,
+		mut input: &[u8],
+	) -> Result<Value, String> {
+		// A map with several hashers is keyed by a tuple, one hasher per tuple field.
+		let key_types = if hashers.len() > 1 {
+			match self.resolve(ty)? {
+				TypeDef::Tuple(tuple) => tuple.fields.iter().map(|f| f.id).collect(),
+				_ => return Err("map key of a multi-hasher map must be a tuple".into()),
+			}
+		} else {
+			vec![ty]
+		};
+
+		let mut keys = hashers
+			.iter()
+			.zip(key_types)
+			.map(|(hasher, ty)| {
+				let (hash_len, concat) = match hasher {
+					StorageHasher::Blake2_128 | StorageHasher::Twox128 => (16, false),
+					StorageHasher::Blake2_256 | StorageHasher::Twox256 => (32, false),
+					StorageHasher::Blake2_128Concat => (16, true),
+					StorageHasher::Twox64Concat => (8, true),
+					StorageHasher::Identity => (0, true),
+				};
+				let hash = input.get(..hash_len).ok_or("key is too short")?;
+				input = &input[hash_len..];
+				if concat {
+					decode(&self.types, ty, &mut input)
+				} else {
+					Ok(Value::String(hex(hash)))
+				}
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+
+		if !input.is_empty() {
+			return Err("key was not fully decoded".into())
+		}
+
+		Ok(if keys.len() == 1 { keys.remove(0) } else { Value::Array(keys) })
+	}
+
+	fn resolve(&self, ty: u32) -> Result<&TypeDef<PortableForm>, String> {
+		resolve(&self.types, ty)
+	}
+}
+
+/// Hex representation of the given bytes.
+pub fn hex(bytes: &[u8]) -> String {
+	array_bytes::bytes2hex("0x", bytes)
+}
+
+fn resolve(types: &PortableRegistry, ty: u32) -> Result<&TypeDef<PortableForm>, String> {
+	types
+		.resolve(ty)
+		.map(|ty| &ty.type_def)
+		.ok_or_else(|| format!("type {ty} not found in metadata"))
+}
+
+/// Decodes a value of the given type, failing if the input is not fully consumed.
+fn decode_all(types: &PortableRegistry, ty: u32, mut input: &[u8]) -> Result<Value, String> {
+	let value = decode(types, ty, &mut input)?;
+	if !input.is_empty() {
+		return Err("value was not fully decoded".into())
+	}
+	Ok(value)
+}
+
+fn decode(types: &PortableRegistry, ty: u32, input: &mut &[u8]) -> Result<Value, String> {
+	let err = |e: codec::Error| e.to_string();
+
+	match resolve(types, ty)? {
+		TypeDef::Composite(composite) => decode_fields(types, &composite.fields, input),
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input).map_err(err)?;
+			let variant = variant
+				.variants
+				.iter()
+				.find(|v| v.index == index)
+				.ok_or_else(|| format!("variant {index} not found for type {ty}"))?;
+			if variant.fields.is_empty() {
+				Ok(Value::String(variant.name.clone()))
+			} else {
+				let mut map = Map::new();
+				map.insert(variant.name.clone(), decode_fields(types, &variant.fields, input)?);
+				Ok(Value::Object(map))
+			}
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = Compact::<u32>::decode(input).map_err(err)?.0;
+			decode_items(types, sequence.type_param.id, len as usize, input)
+		},
+		TypeDef::Array(array) =>
+			decode_items(types, array.type_param.id, array.len as usize, input),
+		TypeDef::Tuple(tuple) => tuple
+			.fields
+			.iter()
+			.map(|f| decode(types, f.id, input))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Value::Array),
+		TypeDef::Primitive(primitive) => decode_primitive(primitive, input),
+		TypeDef::Compact(_) => {
+			let value = Compact::<u128>::decode(input).map_err(err)?.0;
+			Ok(u64::try_from(value).map_or_else(|_| value.to_string().into(), Into::into))
+		},
+		TypeDef::BitSequence(_) => Err("bit sequences are not supported".into()),
+	}
+}
+
+fn decode_fields(
+	types: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	input: &mut &[u8],
+) -> Result<Value, String> {
+	match fields {
+		[field] if field.name.is_none() => decode(types, field.ty.id, input),
+		_ if fields.iter().all(|f| f.name.is_some()) => {
+			let mut map = Map::new();
+			for field in fields {
+				let name = field.name.clone().expect("all fields are named; qed");
+				map.insert(name, decode(types, field.ty.id, input)?);
+			}
+			Ok(Value::Object(map))
+		},
+		_ => fields
+			.iter()
+			.map(|f| decode(types, f.ty.id, input))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Value::Array),
+	}
+}
+
+fn decode_items(
+	types: &PortableRegistry,
+	ty: u32,
+	len: usize,
+	input: &mut &[u8],
+) -> Result<Value, String> {
+	// Byte sequences are way more readable as hex.
+	if let TypeDef::Primitive(TypeDefPrimitive::U8) = resolve(types, ty)? {
+		let bytes = input.get(..len).ok_or("input is too short")?;
+		*input = &input[len..];
+		return Ok(Value::String(hex(bytes)))
+	}
+
+	(0..len)
+		.map(|_| decode(types, ty, input))
+		.collect::<Result<Vec<_>, _>>()
+		.map(Value::Array)
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value, String> {
+	let err = |e: codec::Error| e.to_string();
+
+	Ok(match primitive {
+		TypeDefPrimitive::Bool => bool::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::Char => char::from_u32(u32::decode(input).map_err(err)?)
+			.ok_or("invalid char")?
+			.to_string()
+			.into(),
+		TypeDefPrimitive::Str => String::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::U8 => u8::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::U16 => u16::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::U32 => u32::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::U64 => u64::decode(input).map_err(err)?.into(),
+		// Large integers are represented as strings to not lose precision.
+		TypeDefPrimitive::U128 => u128::decode(input).map_err(err)?.to_string().into(),
+		TypeDefPrimitive::I8 => i8::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::I16 => i16::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::I32 => i32::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::I64 => i64::decode(input).map_err(err)?.into(),
+		TypeDefPrimitive::I128 => i128::decode(input).map_err(err)?.to_string().into(),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 =>
+			hex(&<[u8; 32]>::decode(input).map_err(err)?).into(),
+	})
+}
@@ -0,0 +1,190 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Comparison of two raw chain specs.
+
+use crate::decode::{hex, StorageDecoder};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Storage differences grouped by pallet and storage item.
+type StorageDiff = BTreeMap<String, BTreeMap<String, Vec<Value>>>;
+
+/// Compares two raw chain specs.
+///
+/// Returns a JSON object with the chain spec fields that differ (`fields`) and the storage
+/// differences (`storage`), grouped by pallet and storage item. Every storage difference holds the
+/// decoded map key (if any) and both values, `null` meaning the entry is absent from that side.
+pub fn diff(left: &Value, right: &Value) -> Result<Value, String> {
+	let fields = diff_fields(left, right);
+
+	let left_top = top_storage(left)?;
+	let right_top = top_storage(right)?;
+
+	let code = |top: &BTreeMap<Vec<u8>, Vec<u8>>| {
+		top.get(sp_core::storage::well_known_keys::CODE)
+			.cloned()
+			.ok_or_else(|| "chain spec does not contain the runtime code".to_string())
+	};
+	let (left_code, right_code) = (code(&left_top)?, code(&right_top)?);
+	let left_decoder = StorageDecoder::new(&left_code)?;
+	let right_decoder =
+		if left_code == right_code { None } else { Some(StorageDecoder::new(&right_code)?) };
+	let right_decoder = right_decoder.as_ref().unwrap_or(&left_decoder);
+
+	let mut storage = StorageDiff::new();
+	let keys = left_top.keys().chain(right_top.keys()).collect::<BTreeSet<_>>();
+	for key in keys {
+		let (left_value, right_value) = (left_top.get(key), right_top.get(key));
+		if left_value == right_value {
+			continue
+		}
+
+		// Prefer the new runtime to locate the entry, the storage item may have been added.
+		let decoded = match right_value {
+			Some(_) => right_decoder.decode_key(key),
+			None => left_decoder.decode_key(key),
+		};
+
+		let mut entry = Map::new();
+		if let Some(key) = decoded.key {
+			entry.insert("key".into(), key);
+		}
+		entry.insert(
+			"left".into(),
+			left_value.map_or(Value::Null, |v| left_decoder.decode_value(key, v)),
+		);
+		entry.insert(
+			"right".into(),
+			right_value.map_or(Value::Null, |v| right_decoder.decode_value(key, v)),
+		);
+
+		storage
+			.entry(decoded.pallet)
+			.or_default()
+			.entry(decoded.item)
+			.or_default()
+			.push(Value::Object(entry));
+	}
+
+	diff_child_storage(left, right, &mut storage)?;
+
+	Ok(json!({ "fields": fields, "storage": storage }))
+}
+
+/// Chain spec fields other than the genesis that differ.
+fn diff_fields(left: &Value, right: &Value) -> Map<String, Value> {
+	let (empty_left, empty_right) = (Map::new(), Map::new());
+	let left = left.as_object().unwrap_or(&empty_left);
+	let right = right.as_object().unwrap_or(&empty_right);
+
+	left.keys()
+		.chain(right.keys())
+		.filter(|key| *key != "genesis")
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.filter(|key| left.get(*key) != right.get(*key))
+		.map(|key| {
+			let side = |map: &Map<String, Value>| map.get(key).cloned().unwrap_or(Value::Null);
+			(key.clone(), json!({ "left": side(left), "right": side(right) }))
+		})
+		.collect()
+}
+
+/// Child storages can't be decoded using the metadata, their differences are reported in hex
+/// under `ChildStorage`.
+fn diff_child_storage(
+	left: &Value,
+	right: &Value,
+	storage: &mut StorageDiff,
+) -> Result<(), String> {
+	let left = child_storages(left)?;
+	let right = child_storages(right)?;
+	let empty = BTreeMap::new();
+
+	for child in left.keys().chain(right.keys()).collect::<BTreeSet<_>>() {
+		let left = left.get(child).unwrap_or(&empty);
+		let right = right.get(child).unwrap_or(&empty);
+
+		for key in left.keys().chain(right.keys()).collect::<BTreeSet<_>>() {
+			let (left_value, right_value) = (left.get(key), right.get(key));
+			if left_value == right_value {
+				continue
+			}
+
+			let side = |value: Option<&Vec<u8>>| value.map_or(Value::Null, |v| hex(v).into());
+			storage
+				.entry("ChildStorage".into())
+				.or_default()
+				.entry(hex(child))
+				.or_default()
+				.push(json!({
+					"key": hex(key),
+					"left": side(left_value),
+					"right": side(right_value),
+				}));
+		}
+	}
+
+	Ok(())
+}
+
+fn raw_genesis(chain_spec: &Value) -> Result<&Value, String> {
+	chain_spec
+		.get("genesis")
+		.and_then(|genesis| genesis.get("raw"))
+		.ok_or_else(|| "chain spec does not contain the raw genesis".to_string())
+}
+
+fn top_storage(chain_spec: &Value) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, String> {
+	raw_genesis(chain_spec)?
+		.get("top")
+		.map_or(Ok(Default::default()), parse_storage)
+}
+
+fn child_storages(
+	chain_spec: &Value,
+) -> Result<BTreeMap<Vec<u8>, BTreeMap<Vec<u8>, Vec<u8>>>, String> {
+	let Some(children) = raw_genesis(chain_spec)?.get("childrenDefault") else {
+		return Ok(Default::default())
+	};
+
+	children
+		.as_object()
+		.ok_or("`childrenDefault` shall be an object")?
+		.iter()
+		.map(|(child, storage)| Ok::<_, String>((parse_hex(child)?, parse_storage(storage)?)))
+		.collect()
+}
+
+fn parse_storage(storage: &Value) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, String> {
+	storage
+		.as_object()
+		.ok_or("raw storage shall be an object")?
+		.iter()
+		.map(|(key, value)| {
+			let value =
+				value.as_str().ok_or_else(|| format!("value of {key} shall be a string"))?;
+			Ok::<_, String>((parse_hex(key)?, parse_hex(value)?))
+		})
+		.collect()
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(value).map_err(|e| format!("invalid hex `{value}`: {e:?}"))
+}
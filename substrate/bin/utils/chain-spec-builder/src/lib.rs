@@ -19,6 +19,10 @@
 #[cfg(feature = "generate-readme")]
 docify::compile_markdown!("README.docify.md", "README.md");
 
+mod decode;
+mod diff;
+mod lint;
+
 use clap::{Parser, Subcommand};
 use sc_chain_spec::{
 	json_patch, set_code_substitute_in_json_chain_spec, update_code_in_json_chain_spec, ChainType,
@@ -53,6 +57,8 @@ pub enum ChainSpecBuilderCmd {
 	ListPresets(ListPresetsCmd),
 	DisplayPreset(DisplayPresetCmd),
 	AddCodeSubstitute(AddCodeSubstituteCmd),
+	Diff(DiffCmd),
+	Lint(LintCmd),
 }
 
 /// Create a new chain spec by interacting with the provided runtime wasm blob.
@@ -200,6 +206,33 @@ pub struct VerifyCmd {
 	pub input_chain_spec: PathBuf,
 }
 
+/// Compares two chain specs.
+///
+/// Both plain and raw formats are supported, plain chain specs are converted to raw first. The
+/// storage keys and values are decoded using the metadata of the runtime contained in each chain
+/// spec, so the differences are reported per pallet and storage item. Entries that can't be decoded
+/// are reported in hex.
+///
+/// The result is printed as JSON.
+#[derive(Parser, Debug, Clone)]
+pub struct DiffCmd {
+	/// The chain spec to compare from, e.g. the one of the previous release.
+	pub left: PathBuf,
+	/// The chain spec to compare to.
+	pub right: PathBuf,
+}
+
+/// Checks the provided chain spec for common mistakes.
+///
+/// Reports bootnodes with invalid multiaddrs, and for plain chain specs, invulnerables or
+/// validators without session keys or with a zero or unparseable balance. Every issue is printed
+/// on its own line, the command fails if any is found.
+#[derive(Parser, Debug, Clone)]
+pub struct LintCmd {
+	/// Chain spec to be checked.
+	pub input_chain_spec: PathBuf,
+}
+
 #[derive(Deserialize, Serialize, Clone)]
 pub struct ParachainExtension {
 	/// The relay chain of the Parachain.
@@ -254,29 +287,9 @@ impl ChainSpecBuilder {
 				fs::write(chain_spec_path, chain_spec_json).map_err(|err| err.to_string())?;
 			},
 			ChainSpecBuilderCmd::ConvertToRaw(ConvertToRawCmd { ref input_chain_spec }) => {
-				let chain_spec = ChainSpec::from_json_file(input_chain_spec.clone())?;
+				let chain_spec_json = convert_to_raw_chain_spec_json(input_chain_spec.as_path())?;
 
-				let mut genesis_json =
-					serde_json::from_str::<serde_json::Value>(&chain_spec.as_json(true)?)
-						.map_err(|e| format!("Conversion to json failed: {e}"))?;
-
-				// We want to extract only raw genesis ("genesis::raw" key), and apply it as a patch
-				// for the original json file.
-				genesis_json.as_object_mut().map(|map| {
-					map.retain(|key, _| key == "genesis");
-				});
-
-				let mut org_chain_spec_json = extract_chain_spec_json(input_chain_spec.as_path())?;
-
-				// The original plain genesis ("genesis::runtimeGenesis") is no longer needed, so
-				// just remove it:
-				org_chain_spec_json
-					.get_mut("genesis")
-					.and_then(|genesis| genesis.as_object_mut())
-					.and_then(|genesis| genesis.remove("runtimeGenesis"));
-				json_patch::merge(&mut org_chain_spec_json, genesis_json);
-
-				let chain_spec_json = serde_json::to_string_pretty(&org_chain_spec_json)
+				let chain_spec_json = serde_json::to_string_pretty(&chain_spec_json)
 					.map_err(|e| format!("Conversion to pretty failed: {e}"))?;
 				fs::write(chain_spec_path, chain_spec_json).map_err(|err| err.to_string())?;
 			},
@@ -305,6 +318,24 @@ impl ChainSpecBuilder {
 					.map_err(|e| format!("getting default config from runtime should work: {e}"))?;
 				println!("{preset}");
 			},
+			ChainSpecBuilderCmd::Diff(DiffCmd { ref left, ref right }) => {
+				let diff = diff::diff(
+					&convert_to_raw_chain_spec_json(left.as_path())?,
+					&convert_to_raw_chain_spec_json(right.as_path())?,
+				)?;
+				let diff = serde_json::to_string_pretty(&diff)
+					.map_err(|e| format!("Conversion to pretty failed: {e}"))?;
+				println!("{diff}");
+			},
+			ChainSpecBuilderCmd::Lint(LintCmd { ref input_chain_spec }) => {
+				let issues = lint::lint(&extract_chain_spec_json(input_chain_spec.as_path())?)?;
+				for issue in &issues {
+					println!("{issue}");
+				}
+				if !issues.is_empty() {
+					return Err(format!("Chain spec lint failed: {} issue(s) found", issues.len()))
+				}
+			},
 		}
 		Ok(())
 	}
@@ -425,3 +456,32 @@ fn extract_chain_spec_json(input_chain_spec: &Path) -> Result<serde_json::Value,
 
 	serde_json::from_slice(&chain_spec).map_err(|e| format!("Conversion to json failed: {e}"))
 }
+
+/// Convert any chain spec to the raw format and return it as JSON.
+///
+/// Only the genesis is taken from the converted chain spec, so that fields unknown to
+/// [`ChainSpec`] (e.g. the extensions) are preserved.
+fn convert_to_raw_chain_spec_json(input_chain_spec: &Path) -> Result<serde_json::Value, String> {
+	let chain_spec = ChainSpec::from_json_file(input_chain_spec.to_path_buf())?;
+
+	let mut genesis_json = serde_json::from_str::<serde_json::Value>(&chain_spec.as_json(true)?)
+		.map_err(|e| format!("Conversion to json failed: {e}"))?;
+
+	// We want to extract only raw genesis ("genesis::raw" key), and apply it as a patch
+	// for the original json file.
+	genesis_json.as_object_mut().map(|map| {
+		map.retain(|key, _| key == "genesis");
+	});
+
+	let mut org_chain_spec_json = extract_chain_spec_json(input_chain_spec)?;
+
+	// The original plain genesis ("genesis::runtimeGenesis") is no longer needed, so
+	// just remove it:
+	org_chain_spec_json
+		.get_mut("genesis")
+		.and_then(|genesis| genesis.as_object_mut())
+		.and_then(|genesis| genesis.remove("runtimeGenesis"));
+	json_patch::merge(&mut org_chain_spec_json, genesis_json);
+
+	Ok(org_chain_spec_json)
+}
@@ -0,0 +1,218 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Detection of common mistakes in chain specs.
+
+use sc_chain_spec::{json_patch, GenesisConfigBuilderRuntimeCaller};
+use sc_network::config::MultiaddrWithPeerId;
+use serde_json::Value;
+use sp_core::crypto::{AccountId32, Ss58Codec};
+use std::collections::HashSet;
+
+/// Checks the given chain spec, returning the issues found.
+///
+/// The genesis checks are only performed for plain chain specs, the raw storage can't be
+/// interpreted reliably.
+pub fn lint(chain_spec: &Value) -> Result<Vec<String>, String> {
+	let mut issues = lint_boot_nodes(chain_spec);
+
+	if let Some(genesis) = runtime_genesis_config(chain_spec)? {
+		let invulnerables = invulnerables(&genesis);
+		lint_balances(&genesis, &invulnerables, &mut issues);
+		lint_session_keys(&genesis, &invulnerables, &mut issues);
+	}
+
+	Ok(issues)
+}
+
+fn lint_boot_nodes(chain_spec: &Value) -> Vec<String> {
+	let mut issues = Vec::new();
+	let mut seen = HashSet::new();
+
+	for boot_node in chain_spec.get("bootNodes").and_then(Value::as_array).into_iter().flatten() {
+		let Some(boot_node) = boot_node.as_str() else {
+			issues.push(format!("bootnode {boot_node} is not a string"));
+			continue
+		};
+		if let Err(e) = boot_node.parse::<MultiaddrWithPeerId>() {
+			issues.push(format!("bootnode `{boot_node}` is invalid: {e}"));
+		}
+		if !seen.insert(boot_node) {
+			issues.push(format!("bootnode `{boot_node}` is duplicated"));
+		}
+	}
+
+	issues
+}
+
+/// Returns the full `RuntimeGenesisConfig` of a plain chain spec.
+///
+/// A genesis config patch is applied on top of the default config provided by the runtime.
+fn runtime_genesis_config(chain_spec: &Value) -> Result<Option<Value>, String> {
+	let Some(genesis) = chain_spec.get("genesis").and_then(|g| g.get("runtimeGenesis")) else {
+		return Ok(None)
+	};
+
+	if let Some(config) = genesis.get("config") {
+		return Ok(Some(config.clone()))
+	}
+
+	let Some(patch) = genesis.get("patch") else { return Ok(None) };
+	let code = genesis
+		.get("code")
+		.and_then(Value::as_str)
+		.ok_or("plain genesis shall contain the runtime code")?;
+	let code = array_bytes::hex2bytes(code).map_err(|e| format!("invalid runtime code: {e:?}"))?;
+
+	let caller: GenesisConfigBuilderRuntimeCaller = GenesisConfigBuilderRuntimeCaller::new(&code);
+	let mut config = caller
+		.get_default_config()
+		.map_err(|e| format!("getting default config from runtime should work: {e}"))?;
+	json_patch::merge(&mut config, patch.clone());
+
+	Ok(Some(config))
+}
+
+/// An account that is expected to author blocks from genesis on.
+struct Invulnerable {
+	account: String,
+	/// The genesis config section it comes from, e.g. `collatorSelection.invulnerables`.
+	origin: &'static str,
+}
+
+fn invulnerables(genesis: &Value) -> Vec<Invulnerable> {
+	let accounts = |pallet: &str, field: &str| {
+		genesis
+			.get(pallet)
+			.and_then(|p| p.get(field))
+			.and_then(Value::as_array)
+			.cloned()
+			.unwrap_or_default()
+	};
+
+	let mut invulnerables = Vec::new();
+	for account in accounts("collatorSelection", "invulnerables") {
+		if let Some(account) = account.as_str() {
+			invulnerables.push(Invulnerable {
+				account: account.into(),
+				origin: "collatorSelection.invulnerables",
+			});
+		}
+	}
+	for account in accounts("staking", "invulnerables") {
+		if let Some(account) = account.as_str() {
+			invulnerables
+				.push(Invulnerable { account: account.into(), origin: "staking.invulnerables" });
+		}
+	}
+	// The first field of every staker is the stash, whatever the version of the genesis config.
+	for staker in accounts("staking", "stakers") {
+		let is_validator = staker
+			.as_array()
+			.map_or(false, |s| s.iter().any(|f| f.as_str() == Some("Validator")));
+		if let Some(stash) = staker.get(0).and_then(Value::as_str).filter(|_| is_validator) {
+			invulnerables.push(Invulnerable { account: stash.into(), origin: "staking.stakers" });
+		}
+	}
+
+	invulnerables
+}
+
+fn lint_balances(genesis: &Value, invulnerables: &[Invulnerable], issues: &mut Vec<String>) {
+	let Some(balances) = genesis.get("balances").and_then(|b| b.get("balances")) else { return };
+	let balances = balances
+		.as_array()
+		.into_iter()
+		.flatten()
+		.filter_map(|entry| {
+			let account = entry.get(0).and_then(Value::as_str)?;
+			Some((normalize_account(account), entry.get(1).unwrap_or(&Value::Null)))
+		})
+		.collect::<Vec<_>>();
+
+	for Invulnerable { account, origin } in invulnerables {
+		let normalized = normalize_account(account);
+		let Some((_, balance)) = balances.iter().find(|(a, _)| *a == normalized) else {
+			issues.push(format!("`{account}` from `{origin}` has no balance"));
+			continue
+		};
+		match parse_balance(balance) {
+			Some(0) => issues.push(format!("`{account}` from `{origin}` has zero balance")),
+			Some(_) => {},
+			None => issues
+				.push(format!("`{account}` from `{origin}` has an unparseable balance {balance}")),
+		}
+	}
+}
+
+fn lint_session_keys(genesis: &Value, invulnerables: &[Invulnerable], issues: &mut Vec<String>) {
+	let Some(session) = genesis.get("session") else { return };
+	let keys = session.get("keys").and_then(Value::as_array).cloned().unwrap_or_default();
+
+	if keys.is_empty() {
+		issues.push("`session.keys` is empty, no authority will be able to author blocks".into());
+	}
+
+	// Every entry is `(account, validator id, keys)`.
+	let mut accounts = HashSet::new();
+	for entry in &keys {
+		for account in [entry.get(0), entry.get(1)].into_iter().flatten() {
+			if let Some(account) = account.as_str() {
+				accounts.insert(normalize_account(account));
+			}
+		}
+
+		let Some(session_keys) = entry.get(2).and_then(Value::as_object) else {
+			issues.push(format!("session keys entry {entry} is malformed"));
+			continue
+		};
+		for (name, key) in session_keys {
+			if key.is_null() || key.as_str() == Some("") {
+				issues.push(format!("session keys entry {entry} misses the `{name}` key"));
+			}
+		}
+	}
+
+	for Invulnerable { account, origin } in invulnerables {
+		if !accounts.contains(&normalize_account(account)) {
+			issues.push(format!("`{account}` from `{origin}` has no session keys"));
+		}
+	}
+}
+
+/// Accounts may be given in any SS58 format, compare their public keys.
+fn normalize_account(account: &str) -> String {
+	AccountId32::from_ss58check(account)
+		.map(|a| array_bytes::bytes2hex("0x", a))
+		.unwrap_or_else(|_| account.to_lowercase())
+}
+
+/// Balances are `u128`, given either as a number or as a decimal string.
+///
+/// Numbers that don't fit into a `u64` are only known as floats, which is enough to tell whether
+/// they are zero.
+fn parse_balance(balance: &Value) -> Option<u128> {
+	match balance {
+		Value::Number(n) => n
+			.as_u64()
+			.map(u128::from)
+			.or_else(|| n.as_f64().filter(|b| b.is_finite() && *b >= 0.0).map(|b| b as u128)),
+		Value::String(s) => s.trim().parse().ok(),
+		_ => None,
+	}
+}
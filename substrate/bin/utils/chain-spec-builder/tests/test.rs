@@ -234,6 +234,56 @@ fn test_add_code_substitute() {
 	assert_output_eq_expected(true, SUFFIX, "tests/expected/add_code_substitute.json");
 }
 
+#[docify::export_content]
+fn cmd_diff(left: &str, right: &str) -> String {
+	bash!(
+		chain-spec-builder diff $left $right
+	)
+}
+
+#[test]
+fn diff() {
+	let left = OUTPUT_FILE.to_string() + "11";
+	let right = OUTPUT_FILE.to_string() + "12";
+	for (path, action) in
+		[(&left, vec!["default"]), (&right, vec!["patch", "tests/input/patch.json"])]
+	{
+		let mut args = vec!["dummy", "-c", path.as_str(), "create", "-r", DUMMY_PATH];
+		args.extend(action);
+		let mut builder = ChainSpecBuilder::parse_from(args);
+		builder.set_create_cmd_runtime_code(substrate_test_runtime::WASM_BINARY.unwrap().into());
+		builder.run().unwrap();
+	}
+
+	let output: Value = from_str(&cmd_diff(&left, &right)).expect("a valid JSON. qed.");
+	assert_eq!(output["fields"], serde_json::json!({}));
+
+	// Bob is endowed by the patch, the account is decoded from the `System::Account` key.
+	let bob = "0x8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48";
+	let accounts = output["storage"]["System"]["Account"].as_array().unwrap();
+	let bob = accounts.iter().find(|entry| entry["key"] == bob).unwrap();
+	assert_eq!(bob["left"], Value::Null);
+	assert_eq!(bob["right"]["data"]["free"], 1000000000000000u64);
+
+	let output: Value = from_str(&cmd_diff(&left, &left)).expect("a valid JSON. qed.");
+	assert_eq!(output, serde_json::json!({ "fields": {}, "storage": {} }));
+
+	std::fs::remove_file(left).expect("Failed to delete file");
+	std::fs::remove_file(right).expect("Failed to delete file");
+}
+
+#[docify::export_content]
+fn cmd_lint() -> String {
+	bash!(
+		chain-spec-builder lint "tests/input/chain_spec_lint.json"
+	)
+}
+
+#[test]
+fn lint() {
+	assert_eq!(cmd_lint().trim_end(), include_str!("expected/lint.txt").trim_end(),);
+}
+
 #[docify::export_content]
 fn cmd_create_default(runtime_path: &str) -> String {
 	bash!(
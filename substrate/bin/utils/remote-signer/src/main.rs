@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reference signer process, serving the keys of a local keystore to the nodes started with
+//! `--keystore-remote-signer`.
+
+use clap::Parser;
+use sc_keystore::{
+	remote::{serve, AuthKey, Endpoint, Listener, SlashingProtection},
+	LocalKeystore,
+};
+use sp_core::crypto::{KeyTypeId, SecretString};
+use std::{path::PathBuf, sync::Arc};
+
+/// Serve the keys of a keystore to remote nodes.
+#[derive(Debug, Parser)]
+#[command(author, version, about)]
+struct Cli {
+	/// Endpoint to listen on, `unix:<PATH>` or `tcp:<HOST:PORT>`.
+	///
+	/// The directory of a Unix socket must only be accessible by the user running the signer, it
+	/// is created with mode 0700 if missing.
+	#[arg(long, value_name = "ENDPOINT")]
+	listen: Endpoint,
+
+	/// File that contains the hex encoded key authenticating the requests of the nodes.
+	#[arg(long, value_name = "PATH")]
+	auth_key_file: PathBuf,
+
+	/// Path of the keystore holding the keys.
+	#[arg(long, value_name = "PATH")]
+	keystore_path: PathBuf,
+
+	/// File that contains the password used by the keystore.
+	#[arg(long, value_name = "PATH")]
+	password_filename: Option<PathBuf>,
+
+	/// Refuse to sign two different GRANDPA votes in the same round with the keys of the given
+	/// type, e.g. `gran`.
+	#[arg(long, value_name = "KEY_TYPE", value_parser = parse_key_type)]
+	protect_grandpa: Option<KeyTypeId>,
+
+	/// File persisting the slashing protection state across restarts.
+	#[arg(long, value_name = "PATH", requires = "protect_grandpa")]
+	slashing_protection_path: Option<PathBuf>,
+}
+
+fn parse_key_type(s: &str) -> Result<KeyTypeId, String> {
+	KeyTypeId::try_from(s).map_err(|_| format!("Invalid key type `{s}`"))
+}
+
+fn main() -> Result<(), String> {
+	sp_tracing::try_init_simple();
+	let cli = Cli::parse();
+
+	let password = cli
+		.password_filename
+		.map(|path| std::fs::read_to_string(path).map(SecretString::new))
+		.transpose()
+		.map_err(|e| format!("Failed to read the password: {e}"))?;
+	let keystore = LocalKeystore::open(cli.keystore_path, password)
+		.map_err(|e| format!("Failed to open the keystore: {e}"))?;
+
+	let auth_key = AuthKey::from_file(&cli.auth_key_file)
+		.map_err(|e| format!("Failed to read the authentication key: {e}"))?;
+
+	let protection = cli
+		.protect_grandpa
+		.map(|key_type| SlashingProtection::new(key_type, cli.slashing_protection_path))
+		.transpose()
+		.map_err(|e| format!("Failed to load the slashing protection state: {e}"))?
+		.map(Arc::new);
+
+	let listener = Listener::bind(&cli.listen)
+		.map_err(|e| format!("Failed to listen on {}: {e}", cli.listen))?;
+	log::info!("🔑 Serving keys on {}", cli.listen);
+	serve(listener, Arc::new(keystore), auth_key, protection).map_err(|e| e.to_string())
+}
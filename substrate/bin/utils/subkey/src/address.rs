@@ -0,0 +1,538 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Derivation of the addresses of accounts that are controlled by other accounts.
+
+use clap::{Args, Parser, Subcommand};
+use codec::Decode;
+use polkadot_parachain_primitives::primitives::{Id as ParaId, Sibling};
+use sc_cli::{Error, NetworkSchemeFlag, OutputType, OutputTypeFlag};
+use serde_json::{json, Map, Value};
+use sp_core::{
+	crypto::{unwrap_or_default_ss58_version, AccountId32, Ss58Codec},
+	Get,
+};
+use std::cell::Cell;
+use xcm::{latest::prelude::*, VersionedLocation};
+use xcm_builder::{
+	AccountId32Aliases, ChildParachainConvertsVia, DescribeAllTerminal, DescribeFamily,
+	HashedDescription, ParentIsPreset, SiblingParachainConvertsVia,
+};
+use xcm_executor::traits::ConvertLocation;
+
+/// Derive the address of an account that is controlled by other accounts
+#[derive(Debug, Subcommand)]
+pub enum AddressCmd {
+	/// The account of a multisig of `pallet-multisig`
+	Multisig(MultisigCmd),
+
+	/// The account created by `create_pure` of `pallet-proxy`
+	PureProxy(PureProxyCmd),
+
+	/// A derivative account of `pallet-utility`, as used by `as_derivative`
+	Derivative(DerivativeCmd),
+
+	/// The sovereign account of a parachain on the relay chain or on a sibling parachain
+	Sovereign(SovereignCmd),
+
+	/// The accounts that the common XCM location converters derive from a location
+	Location(LocationCmd),
+}
+
+impl AddressCmd {
+	/// Run the command
+	pub fn run(&self) -> Result<(), Error> {
+		match self {
+			Self::Multisig(cmd) => cmd.run(),
+			Self::PureProxy(cmd) => cmd.run(),
+			Self::Derivative(cmd) => cmd.run(),
+			Self::Sovereign(cmd) => cmd.run(),
+			Self::Location(cmd) => cmd.run(),
+		}
+	}
+}
+
+/// The `address multisig` command
+#[derive(Debug, Parser)]
+pub struct MultisigCmd {
+	/// The signatories, as SS58 addresses or hex encoded account ids. Their order does not matter.
+	#[arg(required = true, value_parser = parse_account)]
+	signatories: Vec<AccountId32>,
+
+	/// The number of signatories that need to approve a call.
+	#[arg(long)]
+	threshold: u16,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	output: AddressOutputParams,
+}
+
+impl MultisigCmd {
+	/// Run the command
+	pub fn run(&self) -> Result<(), Error> {
+		self.output.print_account(&self.account()?);
+		Ok(())
+	}
+
+	fn account(&self) -> Result<AccountId32, Error> {
+		let mut signatories = self.signatories.clone();
+		signatories.sort();
+		if signatories.windows(2).any(|w| w[0] == w[1]) {
+			return Err("The signatories must not contain duplicates".into())
+		}
+		if self.threshold == 0 || self.threshold as usize > signatories.len() {
+			return Err(format!(
+				"The threshold must be between 1 and the number of signatories ({})",
+				signatories.len()
+			)
+			.into())
+		}
+
+		Ok(pallet_multisig::multi_account_id(&signatories, self.threshold))
+	}
+}
+
+/// The `address pure-proxy` command
+#[derive(Debug, Parser)]
+pub struct PureProxyCmd {
+	/// The account that called `create_pure`, as SS58 address or hex encoded account id.
+	#[arg(long, value_parser = parse_account)]
+	spawner: AccountId32,
+
+	/// The index of the proxy type in the `ProxyType` enum of the runtime.
+	///
+	/// This is `0` (`Any`) for the Polkadot and Kusama relay chains and most parachains.
+	#[arg(long, default_value_t = 0)]
+	proxy_type: u8,
+
+	/// The disambiguation index that was passed to `create_pure`.
+	#[arg(long, default_value_t = 0)]
+	index: u16,
+
+	/// The number of the block that contains the `create_pure` extrinsic.
+	///
+	/// Block numbers are assumed to be 32 bits, as on Polkadot and most parachains.
+	#[arg(long)]
+	block_number: u32,
+
+	/// The index of the `create_pure` extrinsic in its block.
+	#[arg(long)]
+	extrinsic_index: u32,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	output: AddressOutputParams,
+}
+
+impl PureProxyCmd {
+	/// Run the command
+	pub fn run(&self) -> Result<(), Error> {
+		self.output.print_account(&self.account());
+		Ok(())
+	}
+
+	fn account(&self) -> AccountId32 {
+		pallet_proxy::pure_account(
+			&self.spawner,
+			&self.proxy_type,
+			self.index,
+			self.block_number,
+			self.extrinsic_index,
+		)
+	}
+}
+
+/// The `address derivative` command
+#[derive(Debug, Parser)]
+pub struct DerivativeCmd {
+	/// The owner of the derivative account, as SS58 address or hex encoded account id.
+	#[arg(value_parser = parse_account)]
+	account: AccountId32,
+
+	/// The index of the derivative account.
+	///
+	/// Can be given several times to derive nested derivative accounts, starting with the index
+	/// of the outermost one.
+	#[arg(long, required = true)]
+	index: Vec<u16>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	output: AddressOutputParams,
+}
+
+impl DerivativeCmd {
+	/// Run the command
+	pub fn run(&self) -> Result<(), Error> {
+		self.output.print_account(&self.derive());
+		Ok(())
+	}
+
+	fn derive(&self) -> AccountId32 {
+		self.index.iter().fold(self.account.clone(), |who, index| {
+			pallet_utility::derivative_account_id(&who, *index)
+		})
+	}
+}
+
+/// The `address sovereign` command
+#[derive(Debug, Parser)]
+pub struct SovereignCmd {
+	/// The id of the parachain.
+	#[arg(long)]
+	para_id: u32,
+
+	/// Derive the sovereign account on a sibling parachain instead of the relay chain.
+	#[arg(long)]
+	sibling: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	output: AddressOutputParams,
+}
+
+impl SovereignCmd {
+	/// Run the command
+	pub fn run(&self) -> Result<(), Error> {
+		let account = if self.sibling {
+			SiblingParachainConvertsVia::<Sibling, AccountId32>::convert_location(&Location::new(
+				1,
+				[Parachain(self.para_id)],
+			))
+		} else {
+			ChildParachainConvertsVia::<ParaId, AccountId32>::convert_location(&Location::new(
+				0,
+				[Parachain(self.para_id)],
+			))
+		}
+		.expect("The location is a parachain; qed");
+
+		self.output.print_account(&account);
+		Ok(())
+	}
+}
+
+/// The `address location` command
+#[derive(Debug, Parser)]
+pub struct LocationCmd {
+	/// The location, relative to the chain of the account.
+	///
+	/// Either the JSON representation of an XCM `Location`, e.g.
+	/// `{"parents":1,"interior":{"X1":[{"Parachain":1000}]}}`, or a hex encoded
+	/// `VersionedLocation`.
+	#[arg(value_parser = parse_location)]
+	location: Location,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	output: AddressOutputParams,
+}
+
+impl LocationCmd {
+	/// Run the command
+	pub fn run(&self) -> Result<(), Error> {
+		let accounts = convert_location(&self.location);
+		if accounts.is_empty() {
+			return Err("None of the location converters supports this location".into())
+		}
+		self.output.print_accounts(&accounts);
+		Ok(())
+	}
+}
+
+/// A chain without a network of its own, whose `AccountId32Aliases` only alias `AccountId32`
+/// junctions without a network.
+struct NoNetwork;
+
+impl Get<Option<NetworkId>> for NoNetwork {
+	fn get() -> Option<NetworkId> {
+		None
+	}
+}
+
+thread_local! {
+	static LOCATION_NETWORK: Cell<Option<NetworkId>> = const { Cell::new(None) };
+}
+
+/// A chain of the network of the location that is being converted, see
+/// [`convert_with_location_network`].
+struct LocationNetwork;
+
+impl Get<Option<NetworkId>> for LocationNetwork {
+	fn get() -> Option<NetworkId> {
+		LOCATION_NETWORK.with(Cell::get)
+	}
+}
+
+/// Converts an `AccountId32` junction with a network as `AccountId32Aliases` do on the chains
+/// configured with that network.
+fn convert_with_location_network(location: &Location) -> Option<AccountId32> {
+	let (0, [Junction::AccountId32 { network: Some(network), .. }]) = location.unpack() else {
+		return None
+	};
+	LOCATION_NETWORK.with(|n| n.set(Some(*network)));
+	AccountId32Aliases::<LocationNetwork, AccountId32>::convert_location(location)
+}
+
+/// Converts the location with the converters that runtimes commonly use in their
+/// `LocationToAccountId`, returns the name and the result of those supporting the location.
+fn convert_location(location: &Location) -> Vec<(&'static str, AccountId32)> {
+	[
+		("ParentIsPreset", ParentIsPreset::<AccountId32>::convert_location(location)),
+		(
+			"ChildParachainConvertsVia",
+			ChildParachainConvertsVia::<ParaId, AccountId32>::convert_location(location),
+		),
+		(
+			"SiblingParachainConvertsVia",
+			SiblingParachainConvertsVia::<Sibling, AccountId32>::convert_location(location),
+		),
+		(
+			"AccountId32Aliases",
+			AccountId32Aliases::<NoNetwork, AccountId32>::convert_location(location),
+		),
+		// A junction with a network is only an alias on chains configured with that network.
+		(
+			"AccountId32Aliases, on chains of the same network",
+			convert_with_location_network(location),
+		),
+		(
+			"HashedDescription<DescribeFamily<DescribeAllTerminal>>",
+			HashedDescription::<AccountId32, DescribeFamily<DescribeAllTerminal>>::convert_location(
+				location,
+			),
+		),
+	]
+	.into_iter()
+	.filter_map(|(converter, account)| Some((converter, account?)))
+	.collect()
+}
+
+/// Output options shared by all `address` commands.
+#[derive(Debug, Clone, Args)]
+struct AddressOutputParams {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	network_scheme: NetworkSchemeFlag,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	output_scheme: OutputTypeFlag,
+}
+
+impl AddressOutputParams {
+	fn print_account(&self, account: &AccountId32) {
+		let network = unwrap_or_default_ss58_version(self.network_scheme.network);
+		match self.output_scheme.output_type {
+			OutputType::Json => {
+				let json = json!({
+					"networkId": String::from(network),
+					"accountId": format!("0x{}", hex(account)),
+					"ss58Address": account.to_ss58check_with_version(network),
+				});
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&json).expect("Json pretty print failed")
+				);
+			},
+			OutputType::Text => {
+				println!(
+					"Network ID/Version: {}\n  \
+					 Account ID:         0x{}\n  \
+					 SS58 Address:       {}",
+					String::from(network),
+					hex(account),
+					account.to_ss58check_with_version(network),
+				);
+			},
+		}
+	}
+
+	fn print_accounts(&self, accounts: &[(&str, AccountId32)]) {
+		let network = unwrap_or_default_ss58_version(self.network_scheme.network);
+		match self.output_scheme.output_type {
+			OutputType::Json => {
+				let accounts = accounts
+					.iter()
+					.map(|(converter, account)| {
+						let account = json!({
+							"accountId": format!("0x{}", hex(account)),
+							"ss58Address": account.to_ss58check_with_version(network),
+						});
+						(converter.to_string(), account)
+					})
+					.collect::<Map<_, _>>();
+				let json = json!({
+					"networkId": String::from(network),
+					"accounts": Value::Object(accounts),
+				});
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&json).expect("Json pretty print failed")
+				);
+			},
+			OutputType::Text => {
+				println!("Network ID/Version: {}", String::from(network));
+				for (converter, account) in accounts {
+					println!(
+						"{converter}:\n  \
+						 Account ID:         0x{}\n  \
+						 SS58 Address:       {}",
+						hex(account),
+						account.to_ss58check_with_version(network),
+					);
+				}
+			},
+		}
+	}
+}
+
+fn hex(account: &AccountId32) -> String {
+	sp_core::hexdisplay::HexDisplay::from(account.as_ref()).to_string()
+}
+
+/// Parses an SS58 address or a hex encoded account id.
+fn parse_account(account: &str) -> Result<AccountId32, String> {
+	if let Some(hex) = account.strip_prefix("0x") {
+		let bytes = <[u8; 32]>::try_from(
+			array_bytes::hex2bytes(hex).map_err(|e| format!("Invalid hex: {e:?}"))?,
+		)
+		.map_err(|_| "An account id must be 32 bytes long".to_string())?;
+		return Ok(bytes.into())
+	}
+
+	AccountId32::from_ss58check(account).map_err(|e| format!("Invalid SS58 address: {e}"))
+}
+
+/// Parses the JSON representation of a `Location` or a hex encoded `VersionedLocation`.
+fn parse_location(location: &str) -> Result<Location, String> {
+	if let Some(hex) = location.strip_prefix("0x") {
+		let bytes = array_bytes::hex2bytes(hex).map_err(|e| format!("Invalid hex: {e:?}"))?;
+		let versioned = VersionedLocation::decode(&mut &bytes[..])
+			.map_err(|e| format!("Invalid `VersionedLocation`: {e}"))?;
+		return Location::try_from(versioned)
+			.map_err(|_| "The location can't be converted to the latest version".to_string())
+	}
+
+	serde_json::from_str(location).map_err(|e| format!("Invalid location: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use sp_core::crypto::Ss58AddressFormat;
+
+	fn account(prefix: &[u8]) -> AccountId32 {
+		let mut bytes = [0u8; 32];
+		bytes[..prefix.len()].copy_from_slice(prefix);
+		bytes.into()
+	}
+
+	#[test]
+	fn convert_location_works() {
+		let sibling =
+			parse_location(r#"{"parents":1,"interior":{"X1":[{"Parachain":1000}]}}"#).unwrap();
+		let accounts = convert_location(&sibling);
+		assert_eq!(accounts[0], ("SiblingParachainConvertsVia", account(b"sibl\xe8\x03")));
+		assert_eq!(accounts[1].0, "HashedDescription<DescribeFamily<DescribeAllTerminal>>");
+		assert_eq!(accounts.len(), 2);
+
+		let child = Location::new(0, [Parachain(1000)]);
+		let encoded = array_bytes::bytes2hex("0x", VersionedLocation::from(child.clone()).encode());
+		assert_eq!(parse_location(&encoded).unwrap(), child);
+		assert_eq!(
+			convert_location(&child)[0],
+			("ChildParachainConvertsVia", account(b"para\xe8\x03"))
+		);
+
+		assert_eq!(
+			convert_location(&Location::parent())[0],
+			("ParentIsPreset", account(b"Parent"))
+		);
+
+		let no_network = Location::new(0, [Junction::AccountId32 { network: None, id: [1; 32] }]);
+		assert_eq!(
+			convert_location(&no_network)[0],
+			("AccountId32Aliases", AccountId32::new([1; 32]))
+		);
+		let polkadot = Location::new(
+			0,
+			[Junction::AccountId32 { network: Some(NetworkId::Polkadot), id: [1; 32] }],
+		);
+		assert_eq!(
+			convert_location(&polkadot)[0],
+			("AccountId32Aliases, on chains of the same network", AccountId32::new([1; 32]))
+		);
+	}
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+	const CHARLIE: &str = "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y";
+
+	fn ss58(account: AccountId32) -> String {
+		account.to_ss58check_with_version(Ss58AddressFormat::custom(42))
+	}
+
+	#[test]
+	fn multisig_works() {
+		let cmd = MultisigCmd::parse_from(["multisig", "--threshold", "2", CHARLIE, ALICE, BOB]);
+		assert_eq!(
+			ss58(cmd.account().unwrap()),
+			"5DjYJStmdZ2rcqXbXGX7TW85JsrW6uG4y9MUcLq2BoPMpRA7"
+		);
+
+		let cmd = MultisigCmd::parse_from(["multisig", "--threshold", "3", ALICE, BOB]);
+		assert!(cmd.account().is_err());
+		let cmd = MultisigCmd::parse_from(["multisig", "--threshold", "1", ALICE, ALICE]);
+		assert!(cmd.account().is_err());
+	}
+
+	#[test]
+	fn pure_proxy_works() {
+		let cmd = PureProxyCmd::parse_from([
+			"pure-proxy",
+			"--spawner",
+			ALICE,
+			"--block-number",
+			"10",
+			"--extrinsic-index",
+			"1",
+		]);
+		assert_eq!(ss58(cmd.account()), "5GkYTee1U2oJpxCRJNCGZ8NcmsJcVqFZPtVZACyySiNc7t8a");
+	}
+
+	#[test]
+	fn derivative_works() {
+		let cmd = DerivativeCmd::parse_from(["derivative", ALICE, "--index", "0"]);
+		assert_eq!(ss58(cmd.derive()), "5Ep769A4Ka6QrHYoPfzA1fTWRSXpf28vhdbWHWmkWmi4SNHi");
+
+		let cmd = DerivativeCmd::parse_from(["derivative", ALICE, "--index", "0", "--index", "1"]);
+		assert_eq!(ss58(cmd.derive()), "5CTYNd82jLn5dkedUfz5AXNFEZnuruDF4Nyjj1ThGzknMt3o");
+	}
+
+	#[test]
+	fn parse_account_works() {
+		let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+		let hex = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+		assert_eq!(parse_account(alice).unwrap(), parse_account(hex).unwrap());
+		assert!(parse_account("0x1234").is_err());
+		assert!(parse_account("invalid").is_err());
+	}
+}
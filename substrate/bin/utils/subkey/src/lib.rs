@@ -307,6 +307,39 @@
 //! **Note**: While `Bob`, having a short name (3 chars), got a result rather quickly, it will take
 //! much longer for `Alice` who has a much longer name, thus the chances to generate a random
 //! address that contains the chain `alice` will be much smaller.
+//!
+//! ### Derive the address of a multisig, pure proxy or sovereign account
+//!
+//! Some accounts are not controlled by a key but by other accounts. `subkey address` derives their
+//! addresses the same way as the runtime does:
+//!
+//! - `subkey address multisig --threshold 2 <SIGNATORIES>...` for `pallet-multisig`
+//! - `subkey address pure-proxy --spawner <ACCOUNT> --block-number <N> --extrinsic-index <N>` for
+//!   the pure proxies of `pallet-proxy`
+//! - `subkey address derivative <ACCOUNT> --index <N>` for the derivative accounts of
+//!   `pallet-utility`
+//! - `subkey address sovereign --para-id <ID>` for the sovereign account of a parachain
+//! - `subkey address location <LOCATION>` for the accounts that the XCM location converters of
+//!   `xcm-builder` derive from a location
+//!
+//! The command is part of the default `address` feature.
+//!
+//! For instance, the sovereign account of the parachain `1000` on Polkadot is:
+//!
+//! ```bash
+//! subkey address sovereign --para-id 1000 --network polkadot
+//! ```
+//!
+//! output:
+//!
+//! ```text
+//! Network ID/Version: polkadot
+//!   Account ID:         0x70617261e8030000000000000000000000000000000000000000000000000000
+//!   SS58 Address:       13YMK2edbuhwMBxeUWm9c643A2wyYHwSVh1bCM7tShtg7Dtk
+//! ```
+
+#[cfg(feature = "address")]
+mod address;
 
 use clap::Parser;
 use sc_cli::{
@@ -314,6 +347,9 @@ use sc_cli::{
 	VerifyCmd,
 };
 
+#[cfg(feature = "address")]
+pub use address::AddressCmd;
+
 #[derive(Debug, Parser)]
 #[command(
 	name = "subkey",
@@ -343,6 +379,11 @@ pub enum Subkey {
 
 	/// Verify a signature for a message, provided on STDIN, with a given (public or secret) key.
 	Verify(VerifyCmd),
+
+	/// Derive the address of a multisig, pure proxy, derivative or sovereign account
+	#[cfg(feature = "address")]
+	#[command(subcommand)]
+	Address(AddressCmd),
 }
 
 /// Run the subkey command, given the appropriate runtime.
@@ -355,5 +396,7 @@ pub fn run() -> Result<(), Error> {
 		Subkey::Vanity(cmd) => cmd.run(),
 		Subkey::Verify(cmd) => cmd.run(),
 		Subkey::Sign(cmd) => cmd.run(),
+		#[cfg(feature = "address")]
+		Subkey::Address(cmd) => cmd.run(),
 	}
 }
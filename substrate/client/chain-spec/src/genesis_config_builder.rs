@@ -171,6 +171,29 @@ where
 
 		Ok(preset_names)
 	}
+
+	/// Returns the SCALE encoded `RuntimeMetadataPrefixed` provided by the `runtime`.
+	///
+	/// Metadata V15 is requested first, the default metadata version is returned if the `runtime`
+	/// does not support it.
+	pub fn get_metadata(&self) -> core::result::Result<Vec<u8>, String> {
+		let mut t = BasicExternalities::new_empty();
+		// Older runtimes don't expose `metadata_at_version` at all.
+		if let Ok(call_result) = self.call(&mut t, "Metadata_metadata_at_version", &15u32.encode())
+		{
+			if let Some(metadata) = Option::<Vec<u8>>::decode(&mut &call_result[..])
+				.map_err(|e| format!("scale codec error: {e}"))?
+			{
+				return Ok(metadata)
+			}
+		}
+
+		let call_result = self
+			.call(&mut t, "Metadata_metadata", &[])
+			.map_err(|e| format!("wasm call error {e}"))?;
+
+		Vec::<u8>::decode(&mut &call_result[..]).map_err(|e| format!("scale codec error: {e}"))
+	}
 }
 
 #[cfg(test)]
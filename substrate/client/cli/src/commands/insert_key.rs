@@ -71,7 +71,10 @@ impl InsertKeyCmd {
 				let keystore: KeystorePtr = LocalKeystore::open(path, password)?.into();
 				(keystore, public)
 			},
-			_ => unreachable!("keystore_config always returns path and password; qed"),
+			KeystoreConfig::Remote { .. } =>
+				return Err(Error::Input("Keys must be inserted into the remote signer".into())),
+			KeystoreConfig::InMemory =>
+				unreachable!("keystore_config never returns an in-memory keystore; qed"),
 		};
 
 		let key_type =
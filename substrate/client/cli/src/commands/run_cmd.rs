@@ -28,12 +28,13 @@ use clap::Parser;
 use regex::Regex;
 use sc_service::{
 	config::{
-		BasePath, IpNetwork, PrometheusConfig, RpcBatchRequestConfig, TransactionPoolOptions,
+		ArchiveFallbackConfig, BasePath, IpNetwork, PrometheusConfig, RpcBatchRequestConfig,
+		RpcMethodCosts, TransactionPoolOptions,
 	},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, path::PathBuf};
 
 /// The `run` command used to run a node.
 #[derive(Debug, Clone, Parser)]
@@ -151,6 +152,14 @@ pub struct RunCmd {
 	#[arg(long)]
 	pub force_authoring: bool,
 
+	/// Path of the slashing protection database.
+	///
+	/// Before sealing a block or casting a GRANDPA vote, the node checks that it doesn't conflict
+	/// with anything signed earlier with the same key and records it in this file. Nodes running
+	/// the same keys may share the file.
+	#[arg(long, value_name = "PATH")]
+	pub slashing_protection: Option<PathBuf>,
+
 	/// Run a temporary node.
 	///
 	/// A temporary directory will be created to store the configuration and will be deleted
@@ -282,6 +291,10 @@ impl CliConfiguration for RunCmd {
 		Ok(self.no_grandpa)
 	}
 
+	fn slashing_protection(&self) -> Result<Option<PathBuf>> {
+		Ok(self.slashing_protection.clone())
+	}
+
 	fn rpc_max_connections(&self) -> Result<u32> {
 		Ok(self.rpc_params.rpc_max_connections)
 	}
@@ -330,6 +343,21 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_params.rpc_rate_limit_trust_proxy_headers)
 	}
 
+	fn rpc_ip_quota(&self) -> Result<Option<NonZeroU32>> {
+		Ok(self.rpc_params.rpc_ip_quota)
+	}
+
+	fn rpc_method_costs(&self) -> Result<RpcMethodCosts> {
+		Ok(RpcMethodCosts::new(self.rpc_params.rpc_method_cost.clone()))
+	}
+
+	fn rpc_archive_fallback(&self) -> Result<Option<ArchiveFallbackConfig>> {
+		Ok(self.rpc_params.rpc_archive_fallback.clone().map(|url| ArchiveFallbackConfig {
+			url,
+			unverified_calls: self.rpc_params.rpc_archive_fallback_unverified_calls,
+		}))
+	}
+
 	fn transaction_pool(&self, is_dev: bool) -> Result<TransactionPoolOptions> {
 		Ok(self.pool_config.transaction_pool(is_dev))
 	}
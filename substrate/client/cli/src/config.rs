@@ -27,10 +27,11 @@ use log::warn;
 use names::{Generator, Name};
 use sc_service::{
 	config::{
-		BasePath, Configuration, DatabaseSource, ExecutorConfiguration, IpNetwork, KeystoreConfig,
-		NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode,
-		Role, RpcBatchRequestConfig, RpcConfiguration, RpcMethods, TelemetryEndpoints,
-		TransactionPoolOptions, WasmExecutionMethod,
+		ArchiveFallbackConfig, BasePath, Configuration, DatabaseSource, ExecutorConfiguration,
+		IpNetwork, KeystoreConfig, NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig,
+		PrometheusConfig, PruningMode, Role, RpcBatchRequestConfig, RpcConfiguration,
+		RpcMethodCosts, RpcMethods, TelemetryEndpoints, TransactionPoolOptions,
+		WasmExecutionMethod,
 	},
 	BlocksPruning, ChainSpec, TracingReceiver,
 };
@@ -366,6 +367,23 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(false)
 	}
 
+	/// RPC quota per minute, shared by all the connections from the same IP address.
+	fn rpc_ip_quota(&self) -> Result<Option<NonZeroU32>> {
+		Ok(None)
+	}
+
+	/// Costs of the RPC methods accounted against the quota.
+	fn rpc_method_costs(&self) -> Result<RpcMethodCosts> {
+		Ok(Default::default())
+	}
+
+	/// Remote archive node used to serve state queries for blocks pruned locally.
+	///
+	/// By default this is `None`.
+	fn rpc_archive_fallback(&self) -> Result<Option<ArchiveFallbackConfig>> {
+		Ok(None)
+	}
+
 	/// Get the prometheus configuration (`None` if disabled)
 	///
 	/// By default this is `None`.
@@ -417,6 +435,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Default::default())
 	}
 
+	/// Get the path of the slashing protection database.
+	///
+	/// By default this is `None`.
+	fn slashing_protection(&self) -> Result<Option<PathBuf>> {
+		Ok(Default::default())
+	}
+
 	/// Get the development key seed from the current object
 	///
 	/// By default this is `None`.
@@ -552,6 +577,9 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 				rate_limit: self.rpc_rate_limit()?,
 				rate_limit_whitelisted_ips: self.rpc_rate_limit_whitelisted_ips()?,
 				rate_limit_trust_proxy_headers: self.rpc_rate_limit_trust_proxy_headers()?,
+				archive_fallback: self.rpc_archive_fallback()?,
+				ip_quota: self.rpc_ip_quota()?,
+				method_costs: self.rpc_method_costs()?,
 			},
 			prometheus_config: self
 				.prometheus_config(DCV::prometheus_listen_port(), &chain_spec)?,
@@ -559,6 +587,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			offchain_worker: self.offchain_worker(&role)?,
 			force_authoring: self.force_authoring()?,
 			disable_grandpa: self.disable_grandpa()?,
+			slashing_protection: self.slashing_protection()?,
 			dev_key_seed: self.dev_key_seed(is_dev)?,
 			tracing_targets: self.tracing_targets()?,
 			tracing_receiver: self.tracing_receiver()?,
@@ -18,7 +18,7 @@
 
 use crate::{error, error::Result};
 use clap::Args;
-use sc_service::config::KeystoreConfig;
+use sc_service::config::{KeystoreConfig, RemoteSignerAuthKey, RemoteSignerEndpoint};
 use sp_core::crypto::SecretString;
 use std::{
 	fs,
@@ -56,6 +56,22 @@ pub struct KeystoreParams {
 		conflicts_with_all = &["password_interactive", "password"]
 	)]
 	pub password_filename: Option<PathBuf>,
+
+	/// Use the keys held by a separate signer process, instead of the local keystore.
+	///
+	/// The signer is reached at `unix:<PATH>` or `tcp:<HOST:PORT>`. Requires
+	/// `--keystore-remote-signer-key`.
+	#[arg(
+		long,
+		value_name = "ENDPOINT",
+		requires = "keystore_remote_signer_key",
+		conflicts_with_all = &["keystore_path", "password_interactive", "password", "password_filename"]
+	)]
+	pub keystore_remote_signer: Option<RemoteSignerEndpoint>,
+
+	/// File that contains the hex encoded key authenticating the requests to the remote signer.
+	#[arg(long, value_name = "PATH", requires = "keystore_remote_signer")]
+	pub keystore_remote_signer_key: Option<PathBuf>,
 }
 
 /// Parse a secret string, returning a displayable error.
@@ -66,6 +82,16 @@ pub fn secret_string_from_str(s: &str) -> std::result::Result<SecretString, Stri
 impl KeystoreParams {
 	/// Get the keystore configuration for the parameters
 	pub fn keystore_config(&self, config_dir: &Path) -> Result<KeystoreConfig> {
+		if let Some(endpoint) = &self.keystore_remote_signer {
+			let path = self
+				.keystore_remote_signer_key
+				.as_deref()
+				.ok_or("`--keystore-remote-signer` requires `--keystore-remote-signer-key`")?;
+			let auth_key = RemoteSignerAuthKey::from_file(path)
+				.map_err(|e| format!("Failed to read the remote signer key: {e}"))?;
+			return Ok(KeystoreConfig::Remote { endpoint: endpoint.clone(), auth_key })
+		}
+
 		let password = if self.password_interactive {
 			Some(SecretString::new(input_keystore_password()?))
 		} else if let Some(ref file) = self.password_filename {
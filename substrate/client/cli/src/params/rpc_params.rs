@@ -18,7 +18,7 @@
 
 use crate::{
 	arg_enums::{Cors, RpcMethods},
-	params::{IpNetwork, RpcBatchRequestConfig},
+	params::{IpNetwork, RpcBatchRequestConfig, RpcMethodCost},
 	RPC_DEFAULT_MAX_CONNECTIONS, RPC_DEFAULT_MAX_REQUEST_SIZE_MB, RPC_DEFAULT_MAX_RESPONSE_SIZE_MB,
 	RPC_DEFAULT_MAX_SUBS_PER_CONN, RPC_DEFAULT_MESSAGE_CAPACITY_PER_CONN,
 };
@@ -100,6 +100,37 @@ pub struct RpcParams {
 	#[arg(long)]
 	pub rpc_rate_limit_trust_proxy_headers: bool,
 
+	/// RPC quota per minute, shared by all the connections from the same IP address.
+	///
+	/// Each call consumes the cost of the method, see `--rpc-method-cost`. Calls over the quota
+	/// are rejected with an error indicating when to retry. The IP addresses in
+	/// `--rpc-rate-limit-whitelisted-ips` are exempt from the quota.
+	#[arg(long)]
+	pub rpc_ip_quota: Option<NonZeroU32>,
+
+	/// Override the cost of an RPC method accounted against `--rpc-ip-quota`.
+	///
+	/// The format is `<method>=<cost>`, e.g. `state_call=100`. The methods not listed
+	/// have a cost of 1, except for a set of methods known to be heavy.
+	#[arg(long, value_name = "METHOD=COST", num_args = 1..)]
+	pub rpc_method_cost: Vec<RpcMethodCost>,
+
+	/// URL of a remote archive node used to serve state queries for blocks pruned locally.
+	///
+	/// Storage values are fetched with `state_getReadProof` and verified against the locally
+	/// known block headers before being served. Only the (child) storage values, their hashes and
+	/// sizes are served this way; key listings, sizes of key prefixes and other state queries
+	/// still fail for pruned blocks.
+	#[arg(long, value_name = "URL")]
+	pub rpc_archive_fallback: Option<String>,
+
+	/// Forward `state_call` requests for pruned blocks to the remote archive node.
+	///
+	/// The results of runtime calls can not be verified with storage proofs, thus the remote
+	/// archive node must be trusted when this flag is set.
+	#[arg(long, requires = "rpc_archive_fallback")]
+	pub rpc_archive_fallback_unverified_calls: bool,
+
 	/// Set the maximum RPC request payload size for both HTTP and WS in megabytes.
 	#[arg(long, default_value_t = RPC_DEFAULT_MAX_REQUEST_SIZE_MB)]
 	pub rpc_max_request_size: u32,
@@ -18,6 +18,7 @@
 
 use clap::{Args, ValueEnum};
 use sc_transaction_pool::TransactionPoolOptions;
+use sp_runtime::Percent;
 
 /// Type of transaction pool to be used
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -59,6 +60,19 @@ pub struct TransactionPoolParams {
 	/// The type of transaction pool to be instantiated.
 	#[arg(long, value_enum, default_value_t = TransactionPoolType::SingleState)]
 	pub pool_type: TransactionPoolType,
+
+	/// Maximum number of transactions from a single sender kept in the transaction pool.
+	///
+	/// Only used by the fork-aware transaction pool, and only if the node is able to determine
+	/// the sender of the transaction. A warning is logged at startup if the limit can't be
+	/// enforced. No limit by default.
+	#[arg(long, value_name = "COUNT")]
+	pub pool_max_txs_per_sender: Option<usize>,
+
+	/// The minimal priority increase (in percent) required to replace a transaction already in
+	/// the pool with a new one providing the same tags.
+	#[arg(long, value_name = "PERCENT", default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub pool_min_priority_bump: u8,
 }
 
 impl TransactionPoolParams {
@@ -71,5 +85,7 @@ impl TransactionPoolParams {
 			self.pool_type.into(),
 			is_dev,
 		)
+		.with_min_priority_bump(Percent::from_percent(self.pool_min_priority_bump))
+		.with_max_transactions_per_sender(self.pool_max_txs_per_sender)
 	}
 }
@@ -282,12 +282,16 @@ mod tests {
 					rate_limit: None,
 					rate_limit_whitelisted_ips: Default::default(),
 					rate_limit_trust_proxy_headers: Default::default(),
+					archive_fallback: None,
+					ip_quota: None,
+					method_costs: Default::default(),
 				},
 				prometheus_config: None,
 				telemetry_endpoints: None,
 				offchain_worker: Default::default(),
 				force_authoring: false,
 				disable_grandpa: false,
+				slashing_protection: None,
 				dev_key_seed: None,
 				tracing_targets: None,
 				tracing_receiver: Default::default(),
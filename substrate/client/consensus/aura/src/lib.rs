@@ -36,14 +36,16 @@ use codec::Codec;
 use futures::prelude::*;
 
 use sc_client_api::{backend::AuxStore, BlockOf};
-use sc_consensus::{BlockImport, BlockImportParams, ForkChoiceStrategy, StateAction};
+use sc_consensus::{
+	BlockImport, BlockImportParams, ForkChoiceStrategy, SlashingProtection, StateAction,
+};
 use sc_consensus_slots::{
 	BackoffAuthoringBlocksStrategy, InherentDataProviderExt, SimpleSlotWorkerToSlotWorker,
 	SlotInfo, StorageChanges,
 };
 use sc_telemetry::TelemetryHandle;
 use sp_api::{Core, ProvideRuntimeApi};
-use sp_application_crypto::AppPublic;
+use sp_application_crypto::{AppCrypto, AppPublic};
 use sp_blockchain::HeaderBackend;
 use sp_consensus::{BlockOrigin, Environment, Error as ConsensusError, Proposer, SelectChain};
 use sp_consensus_slots::Slot;
@@ -148,6 +150,8 @@ pub struct StartAuraParams<C, SC, I, PF, SO, L, CIDP, BS, N> {
 	///
 	/// If in doubt, use `Default::default()`.
 	pub compatibility_mode: CompatibilityMode<N>,
+	/// The slashing protection database consulted before sealing a block, if any.
+	pub slashing_protection: Option<SlashingProtection>,
 }
 
 /// Start the aura worker. The returned future should be run in a futures executor.
@@ -168,6 +172,7 @@ pub fn start_aura<P, B, C, SC, I, PF, SO, L, CIDP, BS, Error>(
 		max_block_proposal_slot_portion,
 		telemetry,
 		compatibility_mode,
+		slashing_protection,
 	}: StartAuraParams<C, SC, I, PF, SO, L, CIDP, BS, NumberFor<B>>,
 ) -> Result<impl Future<Output = ()>, ConsensusError>
 where
@@ -201,6 +206,7 @@ where
 		block_proposal_slot_portion,
 		max_block_proposal_slot_portion,
 		compatibility_mode,
+		slashing_protection,
 	});
 
 	Ok(sc_consensus_slots::start_slot_worker(
@@ -245,6 +251,8 @@ pub struct BuildAuraWorkerParams<C, I, PF, SO, L, BS, N> {
 	///
 	/// If in doubt, use `Default::default()`.
 	pub compatibility_mode: CompatibilityMode<N>,
+	/// The slashing protection database consulted before sealing a block, if any.
+	pub slashing_protection: Option<SlashingProtection>,
 }
 
 /// Build the aura worker.
@@ -264,6 +272,7 @@ pub fn build_aura_worker<P, B, C, PF, I, SO, L, BS, Error>(
 		telemetry,
 		force_authoring,
 		compatibility_mode,
+		slashing_protection,
 	}: BuildAuraWorkerParams<C, I, PF, SO, L, BS, NumberFor<B>>,
 ) -> impl sc_consensus_slots::SimpleSlotWorker<
 	B,
@@ -302,6 +311,7 @@ where
 		block_proposal_slot_portion,
 		max_block_proposal_slot_portion,
 		compatibility_mode,
+		slashing_protection,
 		_phantom: PhantomData::<fn() -> P>,
 	}
 }
@@ -319,6 +329,7 @@ struct AuraWorker<C, E, I, P, SO, L, BS, N> {
 	max_block_proposal_slot_portion: Option<SlotProportion>,
 	telemetry: Option<TelemetryHandle>,
 	compatibility_mode: CompatibilityMode<N>,
+	slashing_protection: Option<SlashingProtection>,
 	_phantom: PhantomData<fn() -> P>,
 }
 
@@ -392,6 +403,19 @@ where
 		public: Self::Claim,
 		_authorities: Self::AuxData,
 	) -> Result<sc_consensus::BlockImportParams<B>, ConsensusError> {
+		if let Some(slashing_protection) = &self.slashing_protection {
+			let slot = find_pre_digest::<B, P::Signature>(&header)
+				.map_err(|e| ConsensusError::CannotSign(e.to_string()))?;
+			slashing_protection
+				.check_and_record_slot(
+					<AuthorityId<P> as AppCrypto>::ID,
+					public.as_ref(),
+					*slot,
+					header_hash.as_ref(),
+				)
+				.map_err(|e| ConsensusError::CannotSign(e.to_string()))?;
+		}
+
 		let signature_digest_item =
 			crate::standalone::seal::<_, P>(header_hash, &public, &self.keystore)?;
 
@@ -758,6 +782,7 @@ mod tests {
 					max_block_proposal_slot_portion: None,
 					telemetry: None,
 					compatibility_mode: CompatibilityMode::None,
+					slashing_protection: None,
 				})
 				.expect("Starts aura"),
 			);
@@ -810,6 +835,7 @@ mod tests {
 			block_proposal_slot_portion: SlotProportion::new(0.5),
 			max_block_proposal_slot_portion: None,
 			compatibility_mode: Default::default(),
+			slashing_protection: None,
 			_phantom: PhantomData::<fn() -> AuthorityPair>,
 		};
 
@@ -860,6 +886,7 @@ mod tests {
 			block_proposal_slot_portion: SlotProportion::new(0.5),
 			max_block_proposal_slot_portion: None,
 			compatibility_mode: Default::default(),
+			slashing_protection: None,
 			_phantom: PhantomData::<fn() -> AuthorityPair>,
 		};
 
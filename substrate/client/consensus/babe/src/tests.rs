@@ -402,6 +402,7 @@ async fn run_one_test(mutator: impl Fn(&mut TestHeader, Stage) + Send + Sync + '
 				block_proposal_slot_portion: SlotProportion::new(0.5),
 				max_block_proposal_slot_portion: None,
 				telemetry: None,
+				slashing_protection: None,
 			})
 			.expect("Starts babe"),
 		);
@@ -49,6 +49,8 @@ const REBROADCAST_AFTER: Duration = Duration::from_secs(5);
 pub(super) enum Action<H> {
 	// repropagate under given topic, to the given peers, applying cost/benefit to originator.
 	Keep(H, ReputationChange),
+	// process locally without repropagating, applying cost/benefit to originator.
+	ProcessAndDiscard(H, ReputationChange),
 	// discard, applying cost/benefit to originator.
 	Discard(ReputationChange),
 	// ignore, no cost/benefit applied to originator.
@@ -283,17 +285,22 @@ where
 
 		// Verify general usefulness of the message.
 		// We are going to discard old votes right away (without verification).
-		{
+		let is_future_round = {
 			let filter = self.gossip_filter.read();
 
-			match filter.consider_vote(round, set_id) {
+			let is_future_round = match filter.consider_vote(round, set_id) {
 				Consider::RejectPast => return Action::Discard(cost::OUTDATED_MESSAGE),
+				// Votes of the current set for rounds above our finality might be votes on
+				// future blocks, let the worker check them for equivocations.
+				Consider::RejectFuture
+					if filter.validator_set().map(|set| set.id()) == Some(set_id) =>
+					true,
 				Consider::RejectFuture => return Action::Discard(cost::FUTURE_MESSAGE),
 				// When we can't evaluate, it's our fault (e.g. filter not initialized yet), we
 				// discard the vote without punishing or rewarding the sending peer.
 				Consider::CannotEvaluate => return Action::DiscardNoReport,
-				Consider::Accept => {},
-			}
+				Consider::Accept => false,
+			};
 
 			// ensure authority is part of the set.
 			if !filter
@@ -304,10 +311,15 @@ where
 				debug!(target: LOG_TARGET, "Message from voter not in validator set: {}", vote.id);
 				return Action::Discard(cost::UNKNOWN_VOTER);
 			}
-		}
+			is_future_round
+		};
 
 		if BeefyKeystore::verify(&vote.id, &vote.signature, &vote.commitment.encode()) {
-			Action::Keep(self.votes_topic, benefit::VOTE_MESSAGE)
+			if is_future_round {
+				Action::ProcessAndDiscard(self.votes_topic, cost::FUTURE_MESSAGE)
+			} else {
+				Action::Keep(self.votes_topic, benefit::VOTE_MESSAGE)
+			}
 		} else {
 			debug!(
 				target: LOG_TARGET,
@@ -408,6 +420,10 @@ where
 				context.broadcast_message(topic, data.to_vec(), false);
 				ValidationResult::ProcessAndKeep(topic)
 			},
+			Action::ProcessAndDiscard(topic, cb) => {
+				self.report(*sender, cb);
+				ValidationResult::ProcessAndDiscard(topic)
+			},
 			Action::Discard(cb) => {
 				self.report(*sender, cb);
 				ValidationResult::Discard
@@ -715,12 +731,22 @@ pub(crate) mod tests {
 		expected_report.cost_benefit = cost::UNKNOWN_VOTER;
 		assert_eq!(report_stream.try_next().unwrap().unwrap(), expected_report);
 
-		// reject if the round is not GRANDPA finalized
+		// don't repropagate if the round is not GRANDPA finalized, but check it for equivocations
 		gv.update_filter(GossipFilterCfg { start: 1, end: 2, validator_set: &validator_set });
 		let number = vote.commitment.block_number;
 		let set_id = vote.commitment.validator_set_id;
 		assert_eq!(gv.gossip_filter.read().consider_vote(number, set_id), Consider::RejectFuture);
 		let res = gv.validate(&mut context, &sender, &encoded);
+		assert!(matches!(res, ValidationResult::ProcessAndDiscard(_)));
+		expected_report.cost_benefit = cost::FUTURE_MESSAGE;
+		assert_eq!(report_stream.try_next().unwrap().unwrap(), expected_report);
+
+		// reject vote, future set_id
+		let mut future_vote = vote.clone();
+		future_vote.commitment.validator_set_id = 1;
+		let future_vote =
+			GossipMessage::<Block, ecdsa_crypto::AuthorityId>::Vote(future_vote).encode();
+		let res = gv.validate(&mut context, &sender, &future_vote);
 		assert!(matches!(res, ValidationResult::Discard));
 		expected_report.cost_benefit = cost::FUTURE_MESSAGE;
 		assert_eq!(report_stream.try_next().unwrap().unwrap(), expected_report);
@@ -23,12 +23,13 @@ use sp_api::ProvideRuntimeApi;
 use sp_application_crypto::RuntimeAppPublic;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_beefy::{
-	check_double_voting_proof, AuthorityIdBound, BeefyApi, BeefySignatureHasher, DoubleVotingProof,
-	OpaqueKeyOwnershipProof, ValidatorSetId,
+	check_commitment_signature, check_double_voting_proof, AuthorityIdBound, BeefyApi,
+	BeefySignatureHasher, DoubleVotingProof, ForkVotingProof, FutureBlockVotingProof,
+	OpaqueKeyOwnershipProof, ValidatorSetId, VoteMessage,
 };
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block, NumberFor},
+	traits::{Block, Header, NumberFor},
 };
 use std::{marker::PhantomData, sync::Arc};
 
@@ -113,6 +114,35 @@ where
 		Ok(proved_offenders)
 	}
 
+	/// Check that the single `vote` was signed by a member of the active validator set, other
+	/// than ourselves.
+	fn is_reportable_vote(
+		&self,
+		vote: &VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+		active_rounds: &Rounds<B, AuthorityId>,
+	) -> bool {
+		let validators = active_rounds.validators();
+		if vote.commitment.validator_set_id != active_rounds.validator_set_id() ||
+			!validators.contains(&vote.id) ||
+			!check_commitment_signature::<_, _, BeefySignatureHasher>(
+				&vote.commitment,
+				&vote.id,
+				&vote.signature,
+			) {
+			debug!(target: LOG_TARGET, "🥩 Skipping report for bad equivocation {:?}", vote);
+			return false;
+		}
+
+		if let Some(local_id) = self.key_store.authority_id(validators) {
+			if vote.id == local_id {
+				warn!(target: LOG_TARGET, "🥩 Skipping report for own equivocation");
+				return false;
+			}
+		}
+
+		true
+	}
+
 	/// Report the given equivocation to the BEEFY runtime module. This method
 	/// generates a session membership proof of the offender and then submits an
 	/// extrinsic to report the equivocation. In particular, the session membership
@@ -164,4 +194,100 @@ where
 
 		Ok(())
 	}
+
+	/// Report the given fork voting equivocation to the BEEFY runtime module.
+	///
+	/// The `vote` is expected to be for a block of our finalized chain, but with a payload that
+	/// doesn't match the canonical one. The ancestry proof of the voted block is generated at our
+	/// **best** block, which is also where the equivocation report is submitted.
+	pub fn report_fork_voting(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+		active_rounds: &Rounds<B, AuthorityId>,
+	) -> Result<(), Error> {
+		if !self.is_reportable_vote(&vote, active_rounds) {
+			return Ok(());
+		}
+
+		let best_block_hash = self.backend.blockchain().info().best_hash;
+		let best_header =
+			self.backend.blockchain().expect_header(best_block_hash).map_err(|err| {
+				Error::Backend(format!(
+					"Couldn't get header for best block {:?} (error: {:?}). \
+				Skipping report for equivocation",
+					best_block_hash, err
+				))
+			})?;
+		let runtime_api = self.runtime.runtime_api();
+		let ancestry_proof = match runtime_api
+			.generate_ancestry_proof(best_block_hash, vote.commitment.block_number, None)
+			.map_err(Error::RuntimeApi)?
+		{
+			Some(ancestry_proof) => ancestry_proof,
+			None => {
+				debug!(
+					target: LOG_TARGET,
+					"🥩 Couldn't generate ancestry proof for block #{:?} at best block {:?}. \
+					Skipping report for equivocation",
+					vote.commitment.block_number,
+					best_header.number()
+				);
+				return Ok(());
+			},
+		};
+
+		let key_owner_proofs = self.prove_offenders(
+			BlockId::Number(vote.commitment.block_number),
+			vec![&vote.id].into_iter(),
+			active_rounds.validator_set_id(),
+		)?;
+
+		let proof = ForkVotingProof { vote, ancestry_proof, header: best_header };
+		for ProvedValidator { key_owner_proof, .. } in key_owner_proofs {
+			runtime_api
+				.submit_report_fork_voting_unsigned_extrinsic(
+					best_block_hash,
+					proof.clone(),
+					key_owner_proof,
+				)
+				.map_err(Error::RuntimeApi)?;
+		}
+
+		Ok(())
+	}
+
+	/// Report the given future block voting equivocation to the BEEFY runtime module.
+	///
+	/// The `vote` is expected to be for a block above our best block. Since the voted block
+	/// doesn't exist, the session membership proof is generated at the best block.
+	pub fn report_future_block_voting(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+		active_rounds: &Rounds<B, AuthorityId>,
+	) -> Result<(), Error> {
+		if !self.is_reportable_vote(&vote, active_rounds) {
+			return Ok(());
+		}
+
+		let best_block_hash = self.backend.blockchain().info().best_hash;
+		let key_owner_proofs = self.prove_offenders(
+			BlockId::Hash(best_block_hash),
+			vec![&vote.id].into_iter(),
+			active_rounds.validator_set_id(),
+		)?;
+
+		let proof = FutureBlockVotingProof { vote };
+		for ProvedValidator { key_owner_proof, .. } in key_owner_proofs {
+			self.runtime
+				.runtime_api()
+				.submit_report_future_block_voting_unsigned_extrinsic(
+					best_block_hash,
+					proof.clone(),
+					key_owner_proof,
+				)
+				.map_err(Error::RuntimeApi)?;
+		}
+
+		Ok(())
+	}
 }
@@ -329,6 +329,7 @@ where
 			comms,
 			links,
 			pending_justifications,
+			canonical_payloads: BTreeMap::new(),
 			is_authority,
 		}
 	}
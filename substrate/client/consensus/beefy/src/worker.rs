@@ -42,8 +42,8 @@ use sp_api::ProvideRuntimeApi;
 use sp_arithmetic::traits::{AtLeast32Bit, Saturating};
 use sp_consensus::SyncOracle;
 use sp_consensus_beefy::{
-	AuthorityIdBound, BeefyApi, Commitment, DoubleVotingProof, PayloadProvider, ValidatorSet,
-	VersionedFinalityProof, VoteMessage, BEEFY_ENGINE_ID,
+	AuthorityIdBound, BeefyApi, Commitment, DoubleVotingProof, Payload, PayloadProvider,
+	ValidatorSet, VersionedFinalityProof, VoteMessage, BEEFY_ENGINE_ID,
 };
 use sp_runtime::{
 	generic::BlockId,
@@ -61,6 +61,10 @@ use std::{
 /// of justifications possible in a single session.
 const MAX_BUFFERED_JUSTIFICATIONS: usize = 2400;
 
+/// Votes on blocks more than this many blocks above our best block are reported as future block
+/// voting. The margin covers for our block import lagging behind the voters.
+const FUTURE_BLOCK_VOTING_MARGIN: u32 = 32;
+
 pub(crate) enum RoundAction {
 	Drop,
 	Process,
@@ -399,6 +403,8 @@ pub(crate) struct BeefyWorker<B: Block, BE, P, RuntimeApi, S, N, AuthorityId: Au
 	// voter state
 	/// Buffer holding justifications for future processing.
 	pub pending_justifications: BTreeMap<NumberFor<B>, BeefyVersionedFinalityProof<B, AuthorityId>>,
+	/// Payloads of the finalized blocks votes are accepted for, to check votes for fork voting.
+	pub canonical_payloads: BTreeMap<NumberFor<B>, Option<Payload>>,
 	/// Persisted voter state.
 	pub persisted_state: PersistedState<B, AuthorityId>,
 	/// BEEFY voter metrics
@@ -524,7 +530,20 @@ where
 	{
 		let block_num = vote.commitment.block_number;
 		match self.voting_oracle().triage_round(block_num)? {
-			RoundAction::Process =>
+			RoundAction::Process => {
+				// Votes on forks are still imported, so they are also checked for double voting.
+				match self.is_fork_vote(&vote) {
+					Ok(true) => {
+						metric_inc!(self.metrics, beefy_equivocation_votes);
+						if let Err(err) = self.report_fork_voting(vote.clone()) {
+							debug!(target: LOG_TARGET, "🥩 Failed to report fork voting: {}", err);
+						}
+					},
+					Ok(false) => (),
+					Err(err) => {
+						debug!(target: LOG_TARGET, "🥩 Failed to check for fork voting: {}", err)
+					},
+				}
 				if let Some(finality_proof) = self.handle_vote(vote)? {
 					let gossip_proof =
 						GossipMessage::<B, AuthorityId>::FinalityProof(finality_proof);
@@ -534,13 +553,79 @@ where
 						encoded_proof,
 						true,
 					);
-				},
+				}
+			},
 			RoundAction::Drop => metric_inc!(self.metrics, beefy_stale_votes),
-			RoundAction::Enqueue => error!(target: LOG_TARGET, "🥩 unexpected vote: {:?}.", vote),
+			// While syncing, votes above our best block are expected.
+			RoundAction::Enqueue
+				if !self.sync.is_major_syncing() &&
+					block_num >
+						self.backend
+							.blockchain()
+							.info()
+							.best_number
+							.saturating_add(FUTURE_BLOCK_VOTING_MARGIN.into()) =>
+			{
+				metric_inc!(self.metrics, beefy_equivocation_votes);
+				if let Err(err) = self.report_future_block_voting(vote) {
+					debug!(target: LOG_TARGET, "🥩 Failed to report future block voting: {}", err);
+				}
+			},
+			RoundAction::Enqueue => {
+				debug!(target: LOG_TARGET, "🥩 Drop vote ahead of our finality: {:?}.", vote);
+				metric_inc!(self.metrics, beefy_stale_votes);
+			},
 		};
 		Ok(())
 	}
 
+	/// Return `true` if `vote` is for a block of our finalized chain, but its payload doesn't
+	/// match the payload of the canonical block.
+	fn is_fork_vote(
+		&mut self,
+		vote: &VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) -> Result<bool, Error> {
+		let block_num = vote.commitment.block_number;
+		if block_num > self.best_grandpa_block() {
+			return Ok(false);
+		}
+		let payload = match self.canonical_payloads.get(&block_num) {
+			Some(payload) => payload.clone(),
+			None => {
+				let payload = self.canonical_payload(block_num)?;
+				// Votes below the accepted interval are dropped, so are their payloads.
+				let (start, _) = self.voting_oracle().accepted_interval()?;
+				self.canonical_payloads.retain(|number, _| *number >= start);
+				self.canonical_payloads.insert(block_num, payload.clone());
+				payload
+			},
+		};
+
+		// Without a canonical payload we can't tell, let the vote be processed as usual.
+		Ok(payload.map_or(false, |payload| payload != vote.commitment.payload))
+	}
+
+	/// The payload of the finalized block `block_num`.
+	fn canonical_payload(&self, block_num: NumberFor<B>) -> Result<Option<Payload>, Error> {
+		let hash = self
+			.backend
+			.blockchain()
+			.expect_block_hash_from_id(&BlockId::Number(block_num))
+			.map_err(|err| {
+				Error::Backend(format!(
+					"Couldn't get hash for block #{:?} (error: {:?}), skipping vote..",
+					block_num, err
+				))
+			})?;
+		let header = self.backend.blockchain().expect_header(hash).map_err(|err| {
+			Error::Backend(format!(
+				"Couldn't get header for block #{:?} ({:?}) (error: {:?}), skipping vote..",
+				block_num, hash, err
+			))
+		})?;
+		Ok(self.payload_provider.payload(&header))
+	}
+
 	/// Based on [VoterOracle] this justification is either processed here or enqueued for later.
 	///
 	/// Expects `justification` to be valid.
@@ -980,6 +1065,25 @@ where
 		let rounds = self.persisted_state.voting_oracle.active_rounds()?;
 		self.fisherman.report_double_voting(proof, rounds)
 	}
+
+	/// Report the given vote on a block not part of our finalized chain to the BEEFY runtime
+	/// module.
+	fn report_fork_voting(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) -> Result<(), Error> {
+		let rounds = self.persisted_state.voting_oracle.active_rounds()?;
+		self.fisherman.report_fork_voting(vote, rounds)
+	}
+
+	/// Report the given vote on a block above our best block to the BEEFY runtime module.
+	fn report_future_block_voting(
+		&self,
+		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
+	) -> Result<(), Error> {
+		let rounds = self.persisted_state.voting_oracle.active_rounds()?;
+		self.fisherman.report_future_block_voting(vote, rounds)
+	}
 }
 
 /// Calculate next block number to vote on.
@@ -1044,7 +1148,7 @@ pub(crate) mod tests {
 		ecdsa_crypto, known_payloads,
 		known_payloads::MMR_ROOT_ID,
 		mmr::MmrRootProvider,
-		test_utils::{generate_double_voting_proof, Keyring},
+		test_utils::{generate_double_voting_proof, signed_vote, Keyring},
 		ConsensusLog, Payload, SignedCommitment,
 	};
 	use sp_runtime::traits::{Header as HeaderT, One};
@@ -1159,6 +1263,7 @@ pub(crate) mod tests {
 			links,
 			comms,
 			pending_justifications: BTreeMap::new(),
+			canonical_payloads: BTreeMap::new(),
 			persisted_state,
 			is_authority: true,
 		}
@@ -1632,4 +1737,151 @@ pub(crate) mod tests {
 		// verify nothing reported to runtime
 		assert!(api_alice.reported_equivocations.as_ref().unwrap().lock().is_empty());
 	}
+
+	#[tokio::test]
+	async fn should_report_fork_and_future_block_voting() {
+		let set_id = 1;
+		let keys = [Keyring::Alice, Keyring::Bob];
+		let validator_set = ValidatorSet::new(make_beefy_ids(&keys), set_id).unwrap();
+		// Alice votes on good MMR roots, equivocations are allowed/expected
+		let mut api_alice = TestApi::with_validator_set(&validator_set);
+		api_alice.allow_equivocations();
+		let api_alice = Arc::new(api_alice);
+
+		let mut net = BeefyTestNet::new(1);
+		let mut worker = create_beefy_worker(net.peer(0), &keys[0], 1, validator_set.clone());
+		worker.runtime = api_alice.clone();
+		worker.fisherman = Arc::new(Fisherman::new(
+			worker.backend.clone(),
+			worker.runtime.clone(),
+			worker.key_store.clone(),
+		));
+
+		// let there be a block with num = 1:
+		let _ = net.peer(0).push_blocks(1, false);
+		let best_hash = worker.backend.blockchain().info().best_hash;
+		let best_header = worker.backend.blockchain().expect_header(best_hash).unwrap();
+		let payload = Payload::from_single_entry(MMR_ROOT_ID, vec![42]);
+
+		// Bob votes on a fork of block #1
+		let fork_vote = signed_vote(1, payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.report_fork_voting(fork_vote.clone()), Ok(()));
+		{
+			let reported = api_alice.reported_fork_voting_equivocations.as_ref().unwrap().lock();
+			assert_eq!(reported.len(), 1);
+			assert_eq!(reported[0].vote, fork_vote);
+			assert_eq!(reported[0].header, best_header);
+		}
+
+		// Bob votes on block #10, which doesn't exist yet
+		let future_vote = signed_vote(10, payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.report_future_block_voting(future_vote.clone()), Ok(()));
+		{
+			let reported =
+				api_alice.reported_future_block_voting_equivocations.as_ref().unwrap().lock();
+			assert_eq!(reported.len(), 1);
+			assert_eq!(reported[0].vote, future_vote);
+		}
+		api_alice.reported_fork_voting_equivocations.as_ref().unwrap().lock().clear();
+		api_alice
+			.reported_future_block_voting_equivocations
+			.as_ref()
+			.unwrap()
+			.lock()
+			.clear();
+
+		// votes of voters outside of the validator set are simply ignored
+		let charlie_vote = signed_vote(10, payload.clone(), set_id, &Keyring::Charlie);
+		assert_eq!(worker.report_future_block_voting(charlie_vote), Ok(()));
+		// votes of the old set are simply ignored
+		let old_vote = signed_vote(1, payload.clone(), 0, &Keyring::Bob);
+		assert_eq!(worker.report_fork_voting(old_vote), Ok(()));
+		// votes with bad signatures are simply ignored
+		let mut bad_vote = signed_vote(10, payload.clone(), set_id, &Keyring::Bob);
+		bad_vote.commitment.block_number = 11;
+		assert_eq!(worker.report_future_block_voting(bad_vote), Ok(()));
+		// equivocations done by 'self' are simply ignored (not reported)
+		let self_vote = signed_vote(1, payload, set_id, &Keyring::Alice);
+		assert_eq!(worker.report_fork_voting(self_vote), Ok(()));
+		// verify nothing reported to runtime
+		assert!(api_alice.reported_fork_voting_equivocations.as_ref().unwrap().lock().is_empty());
+		assert!(api_alice
+			.reported_future_block_voting_equivocations
+			.as_ref()
+			.unwrap()
+			.lock()
+			.is_empty());
+	}
+
+	#[tokio::test]
+	async fn should_detect_fork_and_future_block_voting() {
+		let set_id = 1;
+		let keys = [Keyring::Alice, Keyring::Bob];
+		let validator_set = ValidatorSet::new(make_beefy_ids(&keys), set_id).unwrap();
+		let mut api_alice = TestApi::with_validator_set(&validator_set);
+		api_alice.allow_equivocations();
+		let api_alice = Arc::new(api_alice);
+
+		let mut net = BeefyTestNet::new(1);
+		let mut worker = create_beefy_worker(net.peer(0), &keys[0], 1, validator_set.clone());
+		worker.runtime = api_alice.clone();
+		worker.fisherman = Arc::new(Fisherman::new(
+			worker.backend.clone(),
+			worker.runtime.clone(),
+			worker.key_store.clone(),
+		));
+		let fork_voting_reports =
+			|| api_alice.reported_fork_voting_equivocations.as_ref().unwrap().lock().len();
+		let future_block_voting_reports = || {
+			api_alice
+				.reported_future_block_voting_equivocations
+				.as_ref()
+				.unwrap()
+				.lock()
+				.len()
+		};
+
+		// Block #1 is the mandatory block of the session, and finalized by GRANDPA.
+		let best_number = worker.backend.blockchain().info().best_number;
+		assert_eq!(best_number, 1);
+		let header = worker
+			.backend
+			.blockchain()
+			.expect_header(worker.backend.blockchain().info().best_hash)
+			.unwrap();
+		let canonical_payload = worker.payload_provider.payload(&header).unwrap();
+
+		// Bob votes on the canonical block #1
+		let vote = signed_vote(1, canonical_payload, set_id, &Keyring::Bob);
+		worker.triage_incoming_vote(vote).unwrap();
+		assert_eq!(fork_voting_reports(), 0);
+		// the canonical payload was fetched once, and is reused for the next votes
+		assert_eq!(worker.canonical_payloads.len(), 1);
+
+		// Bob votes on a fork of block #1
+		let payload = Payload::from_single_entry(MMR_ROOT_ID, vec![42]);
+		let fork_vote = signed_vote(1, payload.clone(), set_id, &Keyring::Bob);
+		worker.triage_incoming_vote(fork_vote.clone()).unwrap();
+		assert_eq!(fork_voting_reports(), 1);
+		assert_eq!(
+			api_alice.reported_fork_voting_equivocations.as_ref().unwrap().lock()[0].vote,
+			fork_vote
+		);
+		assert_eq!(worker.canonical_payloads.len(), 1);
+
+		// Bob votes on a block we may just not have imported yet
+		let margin = best_number + FUTURE_BLOCK_VOTING_MARGIN as u64;
+		let vote = signed_vote(margin, payload.clone(), set_id, &Keyring::Bob);
+		worker.triage_incoming_vote(vote).unwrap();
+		assert_eq!(future_block_voting_reports(), 0);
+
+		// Bob votes on a block way ahead of our best block
+		let future_vote = signed_vote(margin + 1, payload, set_id, &Keyring::Bob);
+		worker.triage_incoming_vote(future_vote.clone()).unwrap();
+		assert_eq!(future_block_voting_reports(), 1);
+		assert_eq!(
+			api_alice.reported_future_block_voting_equivocations.as_ref().unwrap().lock()[0].vote,
+			future_vote
+		);
+	}
 }
@@ -21,6 +21,7 @@
 pub mod block_import;
 pub mod import_queue;
 pub mod metrics;
+pub mod slashing_protection;
 
 pub use block_import::{
 	BlockCheckParams, BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult,
@@ -32,6 +33,8 @@ pub use import_queue::{
 	BoxJustificationImport, DefaultImportQueue, ImportQueue, IncomingBlock, Link, Verifier,
 };
 
+pub use slashing_protection::SlashingProtection;
+
 mod longest_chain;
 
 pub mod shared_data;
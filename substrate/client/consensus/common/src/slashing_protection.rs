@@ -0,0 +1,619 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Slashing protection for block authors and finality voters.
+//!
+//! Running the same authority keys on two nodes makes them equivocate, e.g. by sealing two headers
+//! in the same slot or by casting conflicting votes in the same GRANDPA round. The
+//! [`SlashingProtection`] database keeps the last slot and the recent votes signed with every
+//! authority key and refuses to sign anything conflicting with them.
+//!
+//! The database is a JSON file in the [`Interchange`] format. It is re-read before every check, so
+//! it may be shared by several nodes, or exported from one node and imported into another one.
+//! Every read-check-write cycle holds an exclusive lock on a `.lock` file next to the database, so
+//! nodes sharing it can't both sign conflicting messages.
+//!
+//! Nodes open the database given with the `--slashing-protection <PATH>` flag, see
+//! `sc_service::Configuration::slashing_protection`.
+
+use fs4::FileExt;
+use parking_lot::Mutex;
+use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+use serde::{Deserialize, Serialize};
+use sp_core::{crypto::KeyTypeId, hexdisplay::HexDisplay, Bytes};
+use std::{
+	collections::BTreeMap,
+	fs,
+	io::Write,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+const LOG_TARGET: &str = "slashing-protection";
+
+/// The version of the [`Interchange`] format written by this implementation.
+pub const INTERCHANGE_VERSION: u32 = 1;
+
+/// Number of rounds of the latest set for which the signed votes of every kind are remembered.
+const KEPT_ROUNDS: u64 = 64;
+
+/// Errors of the slashing protection database.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Reading or writing the database failed.
+	#[error("slashing protection database I/O error: {0}")]
+	Io(#[from] std::io::Error),
+
+	/// The database isn't valid JSON in the interchange format.
+	#[error("malformed slashing protection database: {0}")]
+	Malformed(#[from] serde_json::Error),
+
+	/// The database was written in an unknown version of the interchange format.
+	#[error("unsupported slashing protection interchange version {0}")]
+	UnsupportedVersion(u32),
+
+	/// A record of the database has an invalid key type.
+	#[error("invalid key type in slashing protection database: {0}")]
+	InvalidKeyType(String),
+
+	/// Signing was refused, since it would conflict with a previously signed message.
+	#[error("refusing to sign {0}")]
+	Refused(String),
+}
+
+/// The kind of a GRANDPA vote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteKind {
+	/// A prevote.
+	Prevote,
+	/// A precommit.
+	Precommit,
+}
+
+impl VoteKind {
+	/// The name of the vote kind, as used in logs and metrics.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Prevote => "prevote",
+			Self::Precommit => "precommit",
+		}
+	}
+}
+
+/// The last slot signed with a key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedSlot {
+	/// The slot.
+	pub slot: u64,
+	/// The signed payload, i.e. the hash of the header sealed in the slot.
+	pub payload: Bytes,
+}
+
+/// A vote of a kind signed with a key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedVote {
+	/// The authority set id of the vote.
+	pub set_id: u64,
+	/// The round of the vote.
+	pub round: u64,
+	/// The hash of the block voted for.
+	pub target: Bytes,
+}
+
+/// The signing history of a single key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorityRecord {
+	/// The key type, e.g. `babe` or `gran`.
+	pub key_type: String,
+	/// The public key.
+	pub public: Bytes,
+	/// The last slot signed with the key.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub last_slot: Option<SignedSlot>,
+	/// The prevotes signed with the key in the recent rounds.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub prevotes: Vec<SignedVote>,
+	/// The precommits signed with the key in the recent rounds.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub precommits: Vec<SignedVote>,
+}
+
+/// The format of the slashing protection database, used to share it between nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interchange {
+	/// The version of the format, see [`INTERCHANGE_VERSION`].
+	pub version: u32,
+	/// The records of all keys.
+	pub records: Vec<AuthorityRecord>,
+}
+
+/// Map over `(set id, round)` -> `Hash of the block voted for`.
+type Votes = BTreeMap<(u64, u64), Bytes>;
+
+#[derive(Debug, Clone, Default)]
+struct Record {
+	slot: Option<SignedSlot>,
+	prevotes: Votes,
+	precommits: Votes,
+}
+
+impl Record {
+	fn votes_mut(&mut self, kind: VoteKind) -> &mut Votes {
+		match kind {
+			VoteKind::Prevote => &mut self.prevotes,
+			VoteKind::Precommit => &mut self.precommits,
+		}
+	}
+
+	/// Merge another record into this one, keeping the latest slot and the recent votes.
+	///
+	/// Of two votes in the same round, the known one is kept.
+	fn merge(&mut self, other: Record) {
+		if let Some(slot) = other.slot {
+			if self.slot.as_ref().map_or(true, |s| s.slot < slot.slot) {
+				self.slot = Some(slot);
+			}
+		}
+		for (kind, votes) in
+			[(VoteKind::Prevote, other.prevotes), (VoteKind::Precommit, other.precommits)]
+		{
+			let current = self.votes_mut(kind);
+			for (round, target) in votes {
+				current.entry(round).or_insert(target);
+			}
+			prune(current);
+		}
+	}
+}
+
+/// Only remember the votes of the last [`KEPT_ROUNDS`] rounds of the latest set.
+fn prune(votes: &mut Votes) {
+	let Some(&(latest_set, latest_round)) = votes.keys().next_back() else { return };
+	votes.retain(|&(set_id, round), _| {
+		set_id == latest_set && round.saturating_add(KEPT_ROUNDS) > latest_round
+	});
+}
+
+type Records = BTreeMap<(KeyTypeId, Vec<u8>), Record>;
+
+#[derive(Clone)]
+struct Metrics {
+	refused: CounterVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			refused: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_slashing_protection_refused_total",
+						"Number of signatures refused by the slashing protection",
+					),
+					&["kind"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+struct Inner {
+	path: Option<PathBuf>,
+	records: Mutex<Records>,
+	metrics: Option<Metrics>,
+}
+
+/// Persistent record of the last slot and the recent votes signed with every authority key.
+///
+/// It is consulted before signing: [`SlashingProtection::check_and_record_slot`] and
+/// [`SlashingProtection::check_and_record_vote`] either record the message about to be signed, or
+/// refuse it if it conflicts with an earlier one. Signing the very same message again is allowed.
+///
+/// Cloning returns a handle to the same database.
+#[derive(Clone)]
+pub struct SlashingProtection {
+	inner: Arc<Inner>,
+}
+
+impl SlashingProtection {
+	/// Open the database at `path`, creating it on the first signature if it doesn't exist yet.
+	pub fn open(path: impl Into<PathBuf>, registry: Option<&Registry>) -> Result<Self, Error> {
+		let this = Self::new(Some(path.into()), registry);
+		let _lock = this.lock_file()?;
+		this.reload(&mut this.inner.records.lock())?;
+		Ok(this)
+	}
+
+	/// Create a database only kept in memory.
+	pub fn in_memory(registry: Option<&Registry>) -> Self {
+		Self::new(None, registry)
+	}
+
+	fn new(path: Option<PathBuf>, registry: Option<&Registry>) -> Self {
+		let metrics = registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|err| {
+					log::warn!(target: LOG_TARGET, "Failed to register metrics: {err}");
+				})
+				.ok()
+		});
+		Self { inner: Arc::new(Inner { path, records: Default::default(), metrics }) }
+	}
+
+	/// Check that a header for `slot` with the hash `payload` may be sealed with the given key and
+	/// record it as signed.
+	///
+	/// Refuses if the key signed a later slot, or another payload in the same slot.
+	pub fn check_and_record_slot(
+		&self,
+		key_type: KeyTypeId,
+		public: &[u8],
+		slot: u64,
+		payload: &[u8],
+	) -> Result<(), Error> {
+		self.check_and_record(key_type, public, "slot", |record| {
+			match &record.slot {
+				Some(last) if last.slot > slot =>
+					return Err(format!("slot {slot}, the later slot {} was signed", last.slot)),
+				Some(last) if last.slot == slot && &last.payload[..] != payload =>
+					return Err(format!("slot {slot}, another header was signed in this slot")),
+				_ => {},
+			}
+			record.slot = Some(SignedSlot { slot, payload: payload.to_vec().into() });
+			Ok(())
+		})
+	}
+
+	/// Check that a vote of `kind` for `target` in the given round may be signed with the given
+	/// key and record it as signed.
+	///
+	/// Refuses if the key signed a vote of the same kind for another target in the same round of
+	/// the same set. Votes in older rounds are fine, since GRANDPA may still vote in them, unless
+	/// they are older than the remembered rounds and can't be checked anymore.
+	pub fn check_and_record_vote(
+		&self,
+		key_type: KeyTypeId,
+		public: &[u8],
+		kind: VoteKind,
+		set_id: u64,
+		round: u64,
+		target: &[u8],
+	) -> Result<(), Error> {
+		self.check_and_record(key_type, public, kind.as_str(), |record| {
+			let votes = record.votes_mut(kind);
+			match votes.get(&(set_id, round)) {
+				Some(signed) if &signed[..] == target => return Ok(()),
+				Some(_) =>
+					return Err(format!(
+						"{} in round {round} of set {set_id}, a {} for another block was signed \
+						 in this round",
+						kind.as_str(),
+						kind.as_str(),
+					)),
+				None => {},
+			}
+			if let Some(&(oldest_set, oldest_round)) = votes.keys().next() {
+				if (set_id, round) < (oldest_set, oldest_round) {
+					return Err(format!(
+						"{} in round {round} of set {set_id}, which is older than the remembered \
+						 round {oldest_round} of set {oldest_set}",
+						kind.as_str(),
+					))
+				}
+			}
+			votes.insert((set_id, round), target.to_vec().into());
+			prune(votes);
+			Ok(())
+		})
+	}
+
+	/// Export the database in the [`Interchange`] format.
+	pub fn export(&self) -> Result<Interchange, Error> {
+		let mut records = self.inner.records.lock();
+		let _lock = self.lock_file()?;
+		self.reload(&mut records)?;
+		Ok(to_interchange(&records))
+	}
+
+	/// Import the records of another database, e.g. exported from another node.
+	///
+	/// For every key, the latest of the imported and known slots is kept, along with the votes of
+	/// both in the recent rounds.
+	pub fn import(&self, interchange: Interchange) -> Result<(), Error> {
+		let imported = from_interchange(interchange)?;
+		let mut records = self.inner.records.lock();
+		let _lock = self.lock_file()?;
+		self.reload(&mut records)?;
+		merge(&mut records, imported);
+		self.persist(&records)
+	}
+
+	fn check_and_record(
+		&self,
+		key_type: KeyTypeId,
+		public: &[u8],
+		kind: &'static str,
+		check: impl FnOnce(&mut Record) -> Result<(), String>,
+	) -> Result<(), Error> {
+		let mut records = self.inner.records.lock();
+		let _lock = self.lock_file()?;
+		self.reload(&mut records)?;
+
+		let record = records.entry((key_type, public.to_vec())).or_default();
+		if let Err(reason) = check(record) {
+			let reason = format!(
+				"{reason} with {} key 0x{}",
+				String::from_utf8_lossy(&key_type.0),
+				HexDisplay::from(&public),
+			);
+			log::error!(
+				target: LOG_TARGET,
+				"🚨 Refusing to sign {reason}. Are the keys used by another node?",
+			);
+			if let Some(metrics) = &self.inner.metrics {
+				metrics.refused.with_label_values(&[kind]).inc();
+			}
+			return Err(Error::Refused(reason))
+		}
+
+		self.persist(&records)
+	}
+
+	/// Take an exclusive lock on the database, held until the returned file is dropped.
+	///
+	/// The in-process mutex only serializes the handles of this node, the file lock serializes
+	/// every process sharing the database.
+	fn lock_file(&self) -> Result<Option<fs::File>, Error> {
+		let Some(path) = &self.inner.path else { return Ok(None) };
+		let file = fs::OpenOptions::new()
+			.create(true)
+			.truncate(false)
+			.write(true)
+			.open(path.with_extension("lock"))?;
+		file.lock_exclusive()?;
+		Ok(Some(file))
+	}
+
+	/// Merge the records written to the database by other nodes.
+	fn reload(&self, records: &mut Records) -> Result<(), Error> {
+		let Some(path) = &self.inner.path else { return Ok(()) };
+		if !path.exists() {
+			return Ok(())
+		}
+		let interchange = serde_json::from_slice(&fs::read(path)?)?;
+		merge(records, from_interchange(interchange)?);
+		Ok(())
+	}
+
+	fn persist(&self, records: &Records) -> Result<(), Error> {
+		let Some(path) = &self.inner.path else { return Ok(()) };
+		let dir = match path.parent() {
+			Some(dir) if !dir.as_os_str().is_empty() => dir,
+			_ => Path::new("."),
+		};
+
+		// Write a uniquely named file next to the database and atomically replace the database with
+		// it, so that a crash never leaves a truncated database behind.
+		let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+		tmp.write_all(&serde_json::to_vec_pretty(&to_interchange(records))?)?;
+		tmp.as_file().sync_all()?;
+		tmp.persist(path).map_err(|err| err.error)?;
+
+		// Make the rename itself durable.
+		#[cfg(unix)]
+		fs::File::open(dir)?.sync_all()?;
+
+		Ok(())
+	}
+}
+
+fn merge(records: &mut Records, other: Records) {
+	for (key, record) in other {
+		records.entry(key).or_default().merge(record);
+	}
+}
+
+fn to_interchange(records: &Records) -> Interchange {
+	Interchange {
+		version: INTERCHANGE_VERSION,
+		records: records
+			.iter()
+			.map(|((key_type, public), record)| AuthorityRecord {
+				key_type: String::from_utf8_lossy(&key_type.0).into_owned(),
+				public: public.clone().into(),
+				last_slot: record.slot.clone(),
+				prevotes: to_signed_votes(&record.prevotes),
+				precommits: to_signed_votes(&record.precommits),
+			})
+			.collect(),
+	}
+}
+
+fn from_interchange(interchange: Interchange) -> Result<Records, Error> {
+	if interchange.version != INTERCHANGE_VERSION {
+		return Err(Error::UnsupportedVersion(interchange.version))
+	}
+
+	let mut records = Records::new();
+	for record in interchange.records {
+		let key_type = KeyTypeId::try_from(record.key_type.as_str())
+			.map_err(|_| Error::InvalidKeyType(record.key_type.clone()))?;
+		records.entry((key_type, record.public.0)).or_default().merge(Record {
+			slot: record.last_slot,
+			prevotes: from_signed_votes(record.prevotes),
+			precommits: from_signed_votes(record.precommits),
+		});
+	}
+	Ok(records)
+}
+
+fn to_signed_votes(votes: &Votes) -> Vec<SignedVote> {
+	votes
+		.iter()
+		.map(|(&(set_id, round), target)| SignedVote { set_id, round, target: target.clone() })
+		.collect()
+}
+
+fn from_signed_votes(votes: Vec<SignedVote>) -> Votes {
+	votes.into_iter().map(|vote| ((vote.set_id, vote.round), vote.target)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	const BABE: KeyTypeId = KeyTypeId(*b"babe");
+	const GRANDPA: KeyTypeId = KeyTypeId(*b"gran");
+
+	#[test]
+	fn refuses_conflicting_slots() {
+		let protection = SlashingProtection::in_memory(None);
+
+		assert!(protection.check_and_record_slot(BABE, b"alice", 10, b"header 1").is_ok());
+		// signing the same header again is fine.
+		assert!(protection.check_and_record_slot(BABE, b"alice", 10, b"header 1").is_ok());
+		assert!(matches!(
+			protection.check_and_record_slot(BABE, b"alice", 10, b"header 2"),
+			Err(Error::Refused(_)),
+		));
+		assert!(matches!(
+			protection.check_and_record_slot(BABE, b"alice", 9, b"header 3"),
+			Err(Error::Refused(_)),
+		));
+		assert!(protection.check_and_record_slot(BABE, b"bob", 10, b"header 2").is_ok());
+		assert!(protection.check_and_record_slot(BABE, b"alice", 11, b"header 3").is_ok());
+	}
+
+	#[test]
+	fn refuses_conflicting_votes() {
+		let protection = SlashingProtection::in_memory(None);
+		let vote = |kind, set_id, round, target: &[u8]| {
+			protection.check_and_record_vote(GRANDPA, b"alice", kind, set_id, round, target)
+		};
+
+		assert!(vote(VoteKind::Prevote, 1, 5, b"a").is_ok());
+		assert!(vote(VoteKind::Prevote, 1, 5, b"a").is_ok());
+		assert!(vote(VoteKind::Precommit, 1, 5, b"b").is_ok());
+		assert!(matches!(vote(VoteKind::Prevote, 1, 5, b"b"), Err(Error::Refused(_))));
+		assert!(matches!(vote(VoteKind::Precommit, 1, 5, b"a"), Err(Error::Refused(_))));
+		assert!(vote(VoteKind::Prevote, 2, 1, b"c").is_ok());
+		// the votes of the previous set are forgotten and can't be signed anymore.
+		assert!(matches!(vote(VoteKind::Prevote, 1, 6, b"c"), Err(Error::Refused(_))));
+	}
+
+	#[test]
+	fn votes_in_earlier_rounds_are_allowed() {
+		let protection = SlashingProtection::in_memory(None);
+		let vote = |kind, round, target: &[u8]| {
+			protection.check_and_record_vote(GRANDPA, b"alice", kind, 1, round, target)
+		};
+
+		assert!(vote(VoteKind::Prevote, 10, b"a").is_ok());
+		assert!(vote(VoteKind::Precommit, 10, b"a").is_ok());
+		// GRANDPA may still vote in a round that it left, e.g. to complete it.
+		assert!(vote(VoteKind::Prevote, 9, b"b").is_ok());
+		assert!(vote(VoteKind::Precommit, 9, b"b").is_ok());
+		assert!(vote(VoteKind::Prevote, 9, b"b").is_ok());
+		assert!(matches!(vote(VoteKind::Prevote, 9, b"c"), Err(Error::Refused(_))));
+
+		// only the last `KEPT_ROUNDS` rounds are remembered, older ones can't be checked anymore.
+		assert!(vote(VoteKind::Prevote, 9 + KEPT_ROUNDS, b"d").is_ok());
+		assert!(matches!(vote(VoteKind::Prevote, 9, b"b"), Err(Error::Refused(_))));
+		assert!(vote(VoteKind::Prevote, 10, b"a").is_ok());
+		assert!(vote(VoteKind::Prevote, 11, b"e").is_ok());
+		assert_eq!(protection.inner.records.lock().values().next().unwrap().prevotes.len(), 3);
+	}
+
+	#[test]
+	fn database_is_shared_through_file() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("slashing-protection.json");
+
+		let first = SlashingProtection::open(&path, None).unwrap();
+		let second = SlashingProtection::open(&path, None).unwrap();
+
+		assert!(first.check_and_record_slot(BABE, b"alice", 10, b"header 1").is_ok());
+		assert!(second.check_and_record_slot(BABE, b"alice", 10, b"header 2").is_err());
+		drop((first, second));
+
+		let reopened = SlashingProtection::open(&path, None).unwrap();
+		assert!(reopened.check_and_record_slot(BABE, b"alice", 10, b"header 2").is_err());
+		assert!(reopened.check_and_record_slot(BABE, b"alice", 10, b"header 1").is_ok());
+	}
+
+	#[test]
+	fn concurrent_nodes_never_both_sign() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("slashing-protection.json");
+
+		for slot in 0..20u64 {
+			let signed = std::thread::scope(|scope| {
+				let handles = (0..4u8)
+					.map(|node| {
+						// every thread opens its own handle, like separate processes would.
+						let protection = SlashingProtection::open(&path, None).unwrap();
+						scope.spawn(move || {
+							protection.check_and_record_slot(BABE, b"alice", slot, &[node]).is_ok()
+						})
+					})
+					.collect::<Vec<_>>();
+				handles
+					.into_iter()
+					.filter_map(|handle| handle.join().unwrap().then_some(()))
+					.count()
+			});
+			assert_eq!(signed, 1, "slot {slot} was signed {signed} times");
+		}
+
+		// no temporary files are left behind.
+		let leftovers = fs::read_dir(temp_dir.path())
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+			.filter(|name| !name.starts_with("slashing-protection."))
+			.collect::<Vec<_>>();
+		assert!(leftovers.is_empty(), "{leftovers:?}");
+	}
+
+	#[test]
+	fn export_and_import() {
+		let exporter = SlashingProtection::in_memory(None);
+		exporter.check_and_record_slot(BABE, b"alice", 10, b"header 1").unwrap();
+		exporter
+			.check_and_record_vote(GRANDPA, b"alice", VoteKind::Precommit, 1, 5, b"a")
+			.unwrap();
+
+		let interchange = exporter.export().unwrap();
+		let json = serde_json::to_string(&interchange).unwrap();
+
+		let importer = SlashingProtection::in_memory(None);
+		importer.check_and_record_slot(BABE, b"alice", 12, b"header 2").unwrap();
+		importer.import(serde_json::from_str(&json).unwrap()).unwrap();
+
+		// the later slot known locally is kept.
+		assert!(importer.check_and_record_slot(BABE, b"alice", 11, b"header 3").is_err());
+		assert!(importer
+			.check_and_record_vote(GRANDPA, b"alice", VoteKind::Precommit, 1, 5, b"b")
+			.is_err());
+	}
+}
@@ -1681,6 +1681,7 @@ mod tests {
 			observer_enabled: true,
 			telemetry: None,
 			protocol_name: communication::grandpa_protocol_name::NAME.into(),
+			slashing_protection: None,
 		}
 	}
 
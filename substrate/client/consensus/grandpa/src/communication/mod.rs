@@ -30,7 +30,7 @@
 //! under certain conditions that are used to un-stick the protocol.
 
 use futures::{channel::mpsc, prelude::*};
-use log::{debug, trace};
+use log::{debug, error, trace, warn};
 use parking_lot::Mutex;
 use prometheus_endpoint::Registry;
 use std::{
@@ -46,9 +46,12 @@ use finality_grandpa::{
 	voter_set::VoterSet,
 	Message::{Precommit, Prevote, PrimaryPropose},
 };
+use sc_consensus::slashing_protection::{
+	Error as SlashingProtectionError, SlashingProtection, VoteKind,
+};
 use sc_network::{NetworkBlock, NetworkSyncForkRequest, NotificationService, ReputationChange};
 use sc_network_gossip::{GossipEngine, Network as GossipNetwork};
-use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_INFO};
+use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_INFO, CONSENSUS_WARN};
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, Hash as HashT, Header as HeaderT, NumberFor};
 
@@ -61,7 +64,9 @@ use gossip::{
 };
 use sc_network_sync::SyncEventStream;
 use sc_utils::mpsc::TracingUnboundedReceiver;
-use sp_consensus_grandpa::{AuthorityId, AuthoritySignature, RoundNumber, SetId as SetIdNumber};
+use sp_consensus_grandpa::{
+	AuthorityId, AuthoritySignature, RoundNumber, SetId as SetIdNumber, KEY_TYPE,
+};
 
 pub mod gossip;
 mod periodic;
@@ -338,6 +343,7 @@ impl<B: BlockT, N: Network<B>, S: Syncing<B>> NetworkBridge<B, N, S> {
 		set_id: SetId,
 		voters: Arc<VoterSet<AuthorityId>>,
 		has_voted: HasVoted<B::Header>,
+		slashing_protection: Option<SlashingProtection>,
 	) -> (impl Stream<Item = SignedMessage<B::Header>> + Unpin, OutgoingMessages<B>) {
 		self.note_round(round, set_id, &voters);
 
@@ -426,6 +432,7 @@ impl<B: BlockT, N: Network<B>, S: Syncing<B>> NetworkBridge<B, N, S> {
 			network: self.gossip_engine.clone(),
 			sender: tx,
 			has_voted,
+			slashing_protection,
 			telemetry: self.telemetry.clone(),
 		};
 
@@ -715,6 +722,7 @@ pub(crate) struct OutgoingMessages<Block: BlockT> {
 	sender: mpsc::Sender<SignedMessage<Block::Header>>,
 	network: Arc<Mutex<GossipEngine<Block>>>,
 	has_voted: HasVoted<Block::Header>,
+	slashing_protection: Option<SlashingProtection>,
 	telemetry: Option<TelemetryHandle>,
 }
 
@@ -757,6 +765,55 @@ impl<Block: BlockT> Sink<Message<Block::Header>> for OutgoingMessages<Block> {
 		// when locals exist, sign messages on import
 		if let Some(ref keystore) = self.keystore {
 			let target_hash = *(msg.target().0);
+
+			let vote_kind = match msg {
+				finality_grandpa::Message::PrimaryPropose(_) => None,
+				finality_grandpa::Message::Prevote(_) => Some(VoteKind::Prevote),
+				finality_grandpa::Message::Precommit(_) => Some(VoteKind::Precommit),
+			};
+			if let (Some(slashing_protection), Some(kind)) = (&self.slashing_protection, vote_kind)
+			{
+				if let Err(e) = slashing_protection.check_and_record_vote(
+					KEY_TYPE,
+					keystore.local_id().as_ref(),
+					kind,
+					self.set_id,
+					self.round,
+					target_hash.as_ref(),
+				) {
+					// Not voting is safe, the round will conclude without our vote. The vote is
+					// dropped instead of failing the sink, since an error would tear down the
+					// voter and the refusal would repeat once it restarts.
+					match e {
+						SlashingProtectionError::Refused(_) => warn!(
+							target: LOG_TARGET,
+							"🚨 Not casting {} in round {} of set {}, slashing protection \
+							 refused it: {}",
+							kind.as_str(),
+							self.round,
+							self.set_id,
+							e,
+						),
+						_ => error!(
+							target: LOG_TARGET,
+							"Not casting {} in round {} of set {}, slashing protection \
+							 database is unavailable: {}",
+							kind.as_str(),
+							self.round,
+							self.set_id,
+							e,
+						),
+					}
+					telemetry!(
+						self.telemetry;
+						CONSENSUS_WARN;
+						"afg.vote_refused_by_slashing_protection";
+						"kind" => kind.as_str(), "round" => ?self.round, "set_id" => ?self.set_id,
+					);
+					return Ok(())
+				}
+			}
+
 			let signed = sp_consensus_grandpa::sign_message(
 				keystore.keystore(),
 				msg,
@@ -304,6 +304,7 @@ fn config() -> crate::Config {
 		observer_enabled: true,
 		telemetry: None,
 		protocol_name: grandpa_protocol_name::NAME.into(),
+		slashing_protection: None,
 	}
 }
 
@@ -734,3 +735,59 @@ fn grandpa_protocol_name() {
 	let proto_name = grandpa_protocol_name::standard_name(&genesis_hash, &chain_spec);
 	assert_eq!(proto_name.to_string(), expected);
 }
+
+#[test]
+fn votes_refused_by_slashing_protection_are_not_sent() {
+	use crate::environment::HasVoted;
+	use sc_consensus::slashing_protection::{SlashingProtection, VoteKind};
+	use sp_consensus_grandpa::{AuthorityId, KEY_TYPE};
+	use sp_keystore::{testing::MemoryKeystore, Keystore, KeystorePtr};
+
+	let keystore = MemoryKeystore::new();
+	let alice: AuthorityId = keystore
+		.ed25519_generate_new(KEY_TYPE, Some(&Ed25519Keyring::Alice.to_seed()))
+		.unwrap()
+		.into();
+	let voters = Arc::new(VoterSet::new(vec![(alice.clone(), 1)]).unwrap());
+
+	// the key already prevoted for another block in this round, e.g. on another node sharing the
+	// database.
+	let protection = SlashingProtection::in_memory(None);
+	protection
+		.check_and_record_vote(KEY_TYPE, alice.as_ref(), VoteKind::Prevote, 0, 1, &[2; 32])
+		.unwrap();
+
+	let (tester, _net) = make_test_network();
+	let test = tester.then(move |tester| async move {
+		let keystore: KeystorePtr = Arc::new(keystore);
+		let (mut incoming, mut outgoing) = tester.net_handle.round_communication(
+			Some((alice, keystore).into()),
+			Round(1),
+			SetId(0),
+			voters,
+			HasVoted::No,
+			Some(protection),
+		);
+
+		let target_hash = Hash::repeat_byte(1);
+		let prevote = finality_grandpa::Message::Prevote(finality_grandpa::Prevote {
+			target_hash,
+			target_number: 1,
+		});
+		let precommit = finality_grandpa::Message::Precommit(finality_grandpa::Precommit {
+			target_hash,
+			target_number: 1,
+		});
+
+		// the refused prevote doesn't fail the sink, but it is neither signed nor forwarded.
+		outgoing.send(prevote).await.unwrap();
+		assert!(futures::poll!(incoming.next()).is_pending());
+
+		// the precommit doesn't conflict with anything signed before.
+		outgoing.send(precommit).await.unwrap();
+		let signed = incoming.next().await.unwrap();
+		assert!(matches!(signed.message, finality_grandpa::Message::Precommit(_)));
+	});
+
+	futures::executor::block_on(test);
+}
@@ -763,6 +763,7 @@ where
 			crate::communication::SetId(self.set_id),
 			self.voters.clone(),
 			has_voted,
+			self.config.slashing_protection.clone(),
 		);
 
 		// schedule incoming messages from the network to be held until
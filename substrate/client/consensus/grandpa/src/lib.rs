@@ -233,6 +233,9 @@ pub struct Config {
 	pub telemetry: Option<TelemetryHandle>,
 	/// Chain specific GRANDPA protocol name. See [`crate::protocol_standard_name`].
 	pub protocol_name: ProtocolName,
+	/// The slashing protection database consulted before signing prevotes and precommits, if
+	/// any.
+	pub slashing_protection: Option<sc_consensus::SlashingProtection>,
 }
 
 impl Config {
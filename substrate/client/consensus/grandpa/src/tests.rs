@@ -331,6 +331,7 @@ fn initialize_grandpa(
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -490,6 +491,7 @@ async fn finalize_3_voters_1_full_observer() {
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -589,6 +591,7 @@ async fn transition_3_voters_twice_1_full_observer() {
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -1024,6 +1027,7 @@ async fn voter_persists_its_votes() {
 			observer_enabled: true,
 			telemetry: None,
 			protocol_name: grandpa_protocol_name::NAME.into(),
+			slashing_protection: None,
 		};
 
 		let set_state = {
@@ -1072,6 +1076,7 @@ async fn voter_persists_its_votes() {
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -1122,6 +1127,7 @@ async fn voter_persists_its_votes() {
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -1165,6 +1171,7 @@ async fn voter_persists_its_votes() {
 			communication::SetId(0),
 			Arc::new(VoterSet::new(voters).unwrap()),
 			HasVoted::No,
+			None,
 		);
 
 		tokio::spawn(bob_network);
@@ -1291,6 +1298,7 @@ async fn finalize_3_voters_1_light_observer() {
 			observer_enabled: true,
 			telemetry: None,
 			protocol_name: grandpa_protocol_name::NAME.into(),
+			slashing_protection: None,
 		},
 		net.peers[3].data.lock().take().expect("link initialized at startup; qed"),
 		net.peers[3].network_service().clone(),
@@ -1340,6 +1348,7 @@ async fn voter_catches_up_to_latest_round_when_behind() {
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net.peer(peer_id).network_service().clone(),
@@ -1460,6 +1469,7 @@ where
 		observer_enabled: true,
 		telemetry: None,
 		protocol_name: grandpa_protocol_name::NAME.into(),
+		slashing_protection: None,
 	};
 
 	let network = NetworkBridge::new(
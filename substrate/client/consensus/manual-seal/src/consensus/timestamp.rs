@@ -37,6 +37,71 @@ use std::{
 	time::SystemTime,
 };
 
+/// Shared control over the time seen by the runtime, used by the `engine_setTimestamp` and
+/// `engine_increaseTime` commands.
+///
+/// By default the time is the system time shifted by an offset, hence it keeps advancing between
+/// the blocks. A time control created with [`TimeControl::starting_at`] only moves when told to.
+/// The node should provide the timestamp inherent using [`TimeControl::inherent_data_provider`]
+/// and pass a clone of the same instance in [`crate::ManualSealParams::time_control`].
+#[derive(Clone, Default)]
+pub struct TimeControl {
+	// time in milliseconds the offset is applied to, the system time if `None`
+	start: Option<u64>,
+	// offset in milliseconds applied to the start time
+	offset_millis: Arc<atomic::AtomicI64>,
+}
+
+impl TimeControl {
+	/// Create a new time control, following the system time.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a new time control starting at the given unix `timestamp` in milliseconds, which
+	/// doesn't follow the system time.
+	pub fn starting_at(timestamp: u64) -> Self {
+		Self { start: Some(timestamp), ..Default::default() }
+	}
+
+	fn now(&self) -> i64 {
+		self.start.unwrap_or_else(|| sp_timestamp::Timestamp::current().as_millis()) as i64
+	}
+
+	/// Gets the current controlled time stamp.
+	pub fn timestamp(&self) -> sp_timestamp::Timestamp {
+		let offset = self.offset_millis.load(atomic::Ordering::SeqCst);
+		sp_timestamp::Timestamp::new(self.now().saturating_add(offset).max(0) as u64)
+	}
+
+	/// Set the current time to the given unix `timestamp` in milliseconds.
+	pub fn set_timestamp(&self, timestamp: u64) {
+		self.offset_millis
+			.store((timestamp as i64).saturating_sub(self.now()), atomic::Ordering::SeqCst);
+	}
+
+	/// Move the current time forward by `millis` milliseconds, returning the new time stamp.
+	pub fn increase_time(&self, millis: u64) -> sp_timestamp::Timestamp {
+		self.offset_millis.fetch_add(millis as i64, atomic::Ordering::SeqCst);
+		self.timestamp()
+	}
+
+	/// Returns the current offset applied to the system time, in milliseconds.
+	pub fn offset(&self) -> i64 {
+		self.offset_millis.load(atomic::Ordering::SeqCst)
+	}
+
+	/// Restore an offset previously returned by [`TimeControl::offset`].
+	pub fn set_offset(&self, offset_millis: i64) {
+		self.offset_millis.store(offset_millis, atomic::Ordering::SeqCst);
+	}
+
+	/// Create the timestamp inherent data provider for the current controlled time.
+	pub fn inherent_data_provider(&self) -> sp_timestamp::InherentDataProvider {
+		sp_timestamp::InherentDataProvider::new(self.timestamp())
+	}
+}
+
 /// Provide duration since unix epoch in millisecond for timestamp inherent.
 /// Mocks the timestamp inherent to always produce a valid timestamp for the next slot.
 ///
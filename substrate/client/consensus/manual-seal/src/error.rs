@@ -35,6 +35,8 @@ mod codes {
 	pub const CONSENSUS_ERROR: i32 = 14_000;
 	pub const INHERENTS_ERROR: i32 = 15_000;
 	pub const BLOCKCHAIN_ERROR: i32 = 16_000;
+	pub const UNSUPPORTED_COMMAND: i32 = 17_000;
+	pub const SNAPSHOT_NOT_FOUND: i32 = 18_000;
 	pub const UNKNOWN_ERROR: i32 = 20_000;
 }
 
@@ -61,6 +63,18 @@ pub enum Error {
 	/// Supplied parent_hash doesn't exist in chain
 	#[error("Supplied parent_hash: {0} doesn't exist in chain")]
 	BlockNotFound(String),
+	/// The block to revert to isn't in the best chain
+	#[error("Block {0} is not in the best chain")]
+	NotInBestChain(String),
+	/// The command requires a component which wasn't provided to the authorship task
+	#[error("{0} is not enabled for this node")]
+	Unsupported(&'static str),
+	/// The supplied snapshot id doesn't exist
+	#[error("Snapshot {0} doesn't exist")]
+	SnapshotNotFound(u64),
+	/// More blocks were requested than can be mined by a single command
+	#[error("Can't mine {0} blocks at once, the limit is {}", crate::MAX_MINE_BLOCKS)]
+	TooManyBlocks(u32),
 	/// Some string error
 	#[error("{0}")]
 	StringError(String),
@@ -92,11 +106,13 @@ impl Error {
 		use Error::*;
 		match self {
 			BlockImportError(_) => codes::BLOCK_IMPORT_FAILED,
-			BlockNotFound(_) => codes::BLOCK_NOT_FOUND,
+			BlockNotFound(_) | NotInBestChain(_) => codes::BLOCK_NOT_FOUND,
 			EmptyTransactionPool => codes::EMPTY_TRANSACTION_POOL,
 			ConsensusError(_) => codes::CONSENSUS_ERROR,
 			InherentError(_) => codes::INHERENTS_ERROR,
 			BlockchainError(_) => codes::BLOCKCHAIN_ERROR,
+			Unsupported(_) => codes::UNSUPPORTED_COMMAND,
+			SnapshotNotFound(_) => codes::SNAPSHOT_NOT_FOUND,
 			SendError(_) | Canceled(_) => codes::SERVER_SHUTTING_DOWN,
 			_ => codes::UNKNOWN_ERROR,
 		}
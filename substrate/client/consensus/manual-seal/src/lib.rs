@@ -34,7 +34,10 @@ use sp_blockchain::HeaderBackend;
 use sp_consensus::{Environment, Proposer, SelectChain};
 use sp_core::traits::SpawnNamed;
 use sp_inherents::CreateInherentDataProviders;
-use sp_runtime::{traits::Block as BlockT, ConsensusEngineId};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, Saturating},
+	ConsensusEngineId,
+};
 use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 mod error;
@@ -45,13 +48,13 @@ pub mod consensus;
 pub mod rpc;
 
 pub use self::{
-	consensus::ConsensusDataProvider,
+	consensus::{timestamp::TimeControl, ConsensusDataProvider},
 	error::Error,
 	finalize_block::{finalize_block, FinalizeBlockParams},
 	rpc::{CreatedBlock, EngineCommand},
 	seal_block::{seal_block, SealBlockParams, MAX_PROPOSAL_DURATION},
 };
-use sc_transaction_pool_api::TransactionPool;
+use sc_transaction_pool_api::{ChainEvent, MaintainedTransactionPool, TransactionPool};
 use sp_api::ProvideRuntimeApi;
 
 const LOG_TARGET: &str = "manual-seal";
@@ -59,6 +62,9 @@ const LOG_TARGET: &str = "manual-seal";
 /// The `ConsensusEngineId` of Manual Seal.
 pub const MANUAL_SEAL_ENGINE_ID: ConsensusEngineId = [b'm', b'a', b'n', b'l'];
 
+/// The maximum number of blocks sealed by a single [`EngineCommand::MineBlocks`].
+pub const MAX_MINE_BLOCKS: u32 = 1_000;
+
 /// The verifier for the manual seal engine; instantly finalizes.
 struct ManualSealVerifier;
 
@@ -74,6 +80,74 @@ impl<B: BlockT> Verifier<B> for ManualSealVerifier {
 	}
 }
 
+/// Something that can revert the best chain, used by the [`EngineCommand::RevertTo`] and
+/// [`EngineCommand::RevertToSnapshot`] commands.
+pub trait RevertChain<B: BlockT>: Send + Sync {
+	/// Revert the best chain by `n` blocks, including finalized blocks.
+	///
+	/// Returns the number of blocks that were reverted.
+	fn revert_chain(&self, n: NumberFor<B>) -> sp_blockchain::Result<NumberFor<B>>;
+}
+
+impl<B: BlockT, BA: ClientBackend<B>> RevertChain<B> for BA {
+	fn revert_chain(&self, n: NumberFor<B>) -> sp_blockchain::Result<NumberFor<B>> {
+		self.revert(n, true).map(|(reverted, _)| reverted)
+	}
+}
+
+/// Object safe part of the [`MaintainedTransactionPool`] used when reverting the chain.
+#[async_trait::async_trait]
+trait MaintainPool<B: BlockT>: Send + Sync {
+	/// Move the best block of the pool to `hash`.
+	async fn set_best_block(&self, hash: B::Hash);
+}
+
+#[async_trait::async_trait]
+impl<B: BlockT, TP: MaintainedTransactionPool<Block = B>> MaintainPool<B> for TP {
+	async fn set_best_block(&self, hash: B::Hash) {
+		self.maintain(ChainEvent::NewBestBlock { hash, tree_route: None }).await
+	}
+}
+
+/// Development controls of the manual sealing authorship task, disabled by default.
+pub struct ManualSealControls<B: BlockT> {
+	time_control: Option<TimeControl>,
+	revert: Option<(Arc<dyn RevertChain<B>>, Arc<dyn MaintainPool<B>>)>,
+}
+
+impl<B: BlockT> Default for ManualSealControls<B> {
+	fn default() -> Self {
+		Self { time_control: None, revert: None }
+	}
+}
+
+impl<B: BlockT> ManualSealControls<B> {
+	/// Create controls with everything disabled.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Enable the [`EngineCommand::SetTimestamp`] and [`EngineCommand::IncreaseTime`] commands.
+	///
+	/// The time must be shared with the `create_inherent_data_providers` of the task.
+	pub fn with_time_control(self, time_control: TimeControl) -> Self {
+		Self { time_control: Some(time_control), ..self }
+	}
+
+	/// Enable the [`EngineCommand::RevertTo`] and [`EngineCommand::RevertToSnapshot`] commands.
+	///
+	/// The `pool` is moved to the block reverted to before reverting, so that it resubmits the
+	/// transactions of the reverted blocks. The pool doesn't retract finalized blocks, the
+	/// transactions of the reverted finalized blocks are not resubmitted.
+	pub fn with_revert<TP>(self, revert: Arc<dyn RevertChain<B>>, pool: Arc<TP>) -> Self
+	where
+		TP: MaintainedTransactionPool<Block = B> + 'static,
+	{
+		let pool: Arc<dyn MaintainPool<B>> = pool;
+		Self { revert: Some((revert, pool)), ..self }
+	}
+}
+
 /// Instantiate the import queue for the manual seal consensus engine.
 pub fn import_queue<Block>(
 	block_import: BoxBlockImport<Block>,
@@ -152,6 +226,26 @@ pub struct DelayedFinalizeParams<C, S> {
 
 /// Creates the background authorship task for the manually seal engine.
 pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
+	params: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P>,
+) where
+	B: BlockT + 'static,
+	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	CB: ClientBackend<B> + 'static,
+	E: Environment<B> + 'static,
+	E::Proposer: Proposer<B, Proof = P>,
+	CS: Stream<Item = EngineCommand<<B as BlockT>::Hash>> + Unpin + 'static,
+	SC: SelectChain<B> + 'static,
+	TP: TransactionPool<Block = B>,
+	CIDP: CreateInherentDataProviders<B, ()>,
+	P: codec::Encode + Send + Sync + 'static,
+{
+	run_manual_seal_with_controls(params, ManualSealControls::new()).await
+}
+
+/// Creates the background authorship task for the manually seal engine, with the given
+/// development `controls` enabled.
+pub async fn run_manual_seal_with_controls<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 	ManualSealParams {
 		mut block_import,
 		mut env,
@@ -162,6 +256,7 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 		consensus_data_provider,
 		create_inherent_data_providers,
 	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P>,
+	ManualSealControls { time_control, revert }: ManualSealControls<B>,
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
@@ -175,6 +270,10 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: codec::Encode + Send + Sync + 'static,
 {
+	// snapshots taken by `EngineCommand::Snapshot`, as `(id, best hash, time offset)`.
+	let mut snapshots = Vec::<(u64, B::Hash, Option<i64>)>::new();
+	let mut next_snapshot_id = 0u64;
+
 	while let Some(command) = commands_stream.next().await {
 		match command {
 			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender } => {
@@ -204,10 +303,136 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 				})
 				.await
 			},
+			EngineCommand::SetTimestamp { timestamp, mut sender } => {
+				let result = time_control
+					.as_ref()
+					.map(|time| time.set_timestamp(timestamp))
+					.ok_or(Error::Unsupported("Time control"));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::IncreaseTime { millis, mut sender } => {
+				let result = time_control
+					.as_ref()
+					.map(|time| time.increase_time(millis).as_millis())
+					.ok_or(Error::Unsupported("Time control"));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::MineBlocks { count, interval, finalize, mut sender } => {
+				let result = async {
+					if count > MAX_MINE_BLOCKS {
+						return Err(Error::TooManyBlocks(count))
+					}
+
+					let mut blocks = Vec::new();
+					for _ in 0..count {
+						if let Some(interval) = interval {
+							time_control
+								.as_ref()
+								.ok_or(Error::Unsupported("Time control"))?
+								.increase_time(interval);
+						}
+
+						let (block_sender, block_receiver) = futures::channel::oneshot::channel();
+						seal_block(SealBlockParams {
+							sender: Some(block_sender),
+							parent_hash: None,
+							finalize,
+							create_empty: true,
+							env: &mut env,
+							select_chain: &select_chain,
+							block_import: &mut block_import,
+							consensus_data_provider: consensus_data_provider.as_deref(),
+							pool: pool.clone(),
+							client: client.clone(),
+							create_inherent_data_providers: &create_inherent_data_providers,
+						})
+						.await;
+						blocks.push(block_receiver.await??);
+					}
+					Ok::<_, Error>(blocks)
+				}
+				.await;
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::RevertTo { hash, mut sender } => {
+				let result = match &revert {
+					Some((revert, pool)) =>
+						revert_to(&*client, &**revert, &**pool, hash).await.map(|number| {
+							// snapshots of the reverted blocks can't be restored anymore
+							snapshots.retain(|(_, snapshot, _)| {
+								client
+									.number(*snapshot)
+									.ok()
+									.flatten()
+									.map_or(false, |n| n <= number)
+							});
+						}),
+					None => Err(Error::Unsupported("Reverting the chain")),
+				};
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::Snapshot { mut sender } => {
+				let result = select_chain.best_chain().await.map_err(Error::from).map(|best| {
+					let id = next_snapshot_id;
+					next_snapshot_id += 1;
+					snapshots.push((
+						id,
+						best.hash(),
+						time_control.as_ref().map(TimeControl::offset),
+					));
+					id
+				});
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::RevertToSnapshot { id, mut sender } => {
+				let result = match (&revert, snapshots.iter().position(|(i, ..)| *i == id)) {
+					(None, _) => Err(Error::Unsupported("Reverting the chain")),
+					(_, None) => Err(Error::SnapshotNotFound(id)),
+					(Some((revert, pool)), Some(index)) => {
+						let (_, hash, offset) = snapshots[index];
+						snapshots.truncate(index);
+						revert_to(&*client, &**revert, &**pool, hash).await.map(|_| {
+							if let (Some(time), Some(offset)) = (&time_control, offset) {
+								time.set_offset(offset);
+							}
+						})
+					},
+				};
+				rpc::send_result(&mut sender, result)
+			},
 		}
 	}
 }
 
+/// Revert the best chain to the block with the given `hash`, returning its number.
+///
+/// The transaction pool is moved to the block first, while the reverted blocks are still around
+/// to resubmit their transactions.
+async fn revert_to<B: BlockT, C: HeaderBackend<B>>(
+	client: &C,
+	revert: &dyn RevertChain<B>,
+	pool: &dyn MaintainPool<B>,
+	hash: B::Hash,
+) -> Result<NumberFor<B>, Error> {
+	let number = client.number(hash)?.ok_or_else(|| Error::BlockNotFound(hash.to_string()))?;
+	if client.hash(number)? != Some(hash) {
+		return Err(Error::NotInBestChain(hash.to_string()))
+	}
+
+	pool.set_best_block(hash).await;
+
+	let to_revert = client.info().best_number.saturating_sub(number);
+	let reverted = revert.revert_chain(to_revert)?;
+	if reverted < to_revert {
+		return Err(Error::StringError(format!(
+			"Reverted only {} of {} blocks on top of {}",
+			reverted, to_revert, hash
+		)))
+	}
+	log::info!(target: LOG_TARGET, "⏪ Reverted {} blocks to #{} ({})", reverted, number, hash);
+	Ok(number)
+}
+
 /// runs the background authorship task for the instant seal engine.
 /// instant-seal creates a new block for every transaction imported into
 /// the transaction pool.
@@ -346,6 +571,7 @@ pub async fn run_delayed_finalize<B, CB, C, S>(
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use rpc::ManualSealApiServer;
 	use sc_basic_authorship::ProposerFactory;
 	use sc_consensus::ImportedAux;
 	use sc_transaction_pool::{BasicPool, FullChainApi, Options, RevalidationType};
@@ -800,4 +1026,214 @@ mod tests {
 		let header = client.header(created_block.hash).unwrap().unwrap();
 		assert_eq!(header.number, 1);
 	}
+
+	#[tokio::test]
+	async fn manual_seal_mine_revert_and_snapshot() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			api(),
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let time = TimeControl::new();
+
+		let (sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let rpc = rpc::ManualSeal::new(sink);
+
+		// spawn the background authorship task
+		tokio::spawn(run_manual_seal_with_controls(
+			ManualSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider: None,
+				create_inherent_data_providers: |_, _| async { Ok(()) },
+			},
+			ManualSealControls::new()
+				.with_time_control(time.clone())
+				.with_revert(backend, pool.clone()),
+		));
+
+		let before = time.timestamp().as_millis();
+		let blocks = rpc.mine_blocks(3, Some(60_000), false).await.unwrap();
+		assert_eq!(blocks.len(), 3);
+		assert_eq!(client.info().best_hash, blocks[2].hash);
+		assert!(time.timestamp().as_millis() >= before + 180_000);
+
+		let snapshot = rpc.snapshot().await.unwrap();
+		let offset = time.offset();
+		rpc.increase_time(1_000).await.unwrap();
+		rpc.mine_blocks(2, None, false).await.unwrap();
+		assert_eq!(client.info().best_number, 5);
+
+		// reverting to the snapshot restores the best block and the time
+		assert!(rpc.revert_to_snapshot(snapshot).await.unwrap());
+		assert_eq!(client.info().best_hash, blocks[2].hash);
+		assert_eq!(time.offset(), offset);
+		// the snapshot can only be restored once
+		assert_matches::assert_matches!(
+			rpc.revert_to_snapshot(snapshot).await,
+			Err(Error::SnapshotNotFound(_))
+		);
+
+		assert!(rpc.revert_to(blocks[0].hash).await.unwrap());
+		assert_eq!(client.info().best_hash, blocks[0].hash);
+		assert_eq!(client.info().best_number, 1);
+		// the reverted blocks are gone
+		assert_matches::assert_matches!(
+			rpc.revert_to(blocks[2].hash).await,
+			Err(Error::BlockNotFound(_) | Error::NotInBestChain(_))
+		);
+	}
+
+	#[tokio::test]
+	async fn revert_resubmits_the_reverted_transactions() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), None, &spawner.clone()));
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+
+		let (sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let rpc = rpc::ManualSeal::new(sink);
+
+		// spawn the background authorship task
+		tokio::spawn(run_manual_seal_with_controls(
+			ManualSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider: None,
+				create_inherent_data_providers: |_, _| async { Ok(()) },
+			},
+			ManualSealControls::new().with_revert(backend, pool.clone()),
+		));
+
+		let first = rpc.create_block(true, false, None).await.unwrap();
+		assert!(pool.submit_one(first.hash, SOURCE, uxt(Alice, 0)).await.is_ok());
+		let second = rpc.create_block(false, false, None).await.unwrap();
+		for hash in [first.hash, second.hash] {
+			pool.maintain(sc_transaction_pool_api::ChainEvent::NewBestBlock {
+				hash,
+				tree_route: None,
+			})
+			.await;
+		}
+		// the transaction was included and pruned from the pool
+		assert_eq!(pool.status().ready, 0);
+
+		assert!(rpc.revert_to(first.hash).await.unwrap());
+		assert_eq!(client.info().best_hash, first.hash);
+		// the transaction of the reverted block is back in the pool
+		assert_eq!(pool.status().ready, 1);
+	}
+
+	#[tokio::test]
+	async fn mine_blocks_moves_the_timestamp_inherent() {
+		use sp_inherents::InherentDataProvider;
+
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			api(),
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let time = TimeControl::starting_at(1_000_000);
+
+		// the timestamps put into the inherent data of every sealed block.
+		let timestamps = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let create_inherent_data_providers = {
+			let (time, timestamps) = (time.clone(), timestamps.clone());
+			move |_, _| {
+				let (time, timestamps) = (time.clone(), timestamps.clone());
+				async move {
+					let provider = time.inherent_data_provider();
+					let mut inherent_data = InherentData::new();
+					provider.provide_inherent_data(&mut inherent_data).await?;
+					let timestamp = inherent_data
+						.get_data::<sp_timestamp::InherentType>(&sp_timestamp::INHERENT_IDENTIFIER)?
+						.expect("the timestamp was just provided");
+					timestamps.lock().unwrap().push(timestamp.as_millis());
+					Ok::<_, Box<dyn std::error::Error + Send + Sync>>(provider)
+				}
+			}
+		};
+
+		let (sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let rpc = rpc::ManualSeal::new(sink);
+
+		// spawn the background authorship task
+		tokio::spawn(run_manual_seal_with_controls(
+			ManualSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider: None,
+				create_inherent_data_providers,
+			},
+			ManualSealControls::new().with_time_control(time.clone()),
+		));
+
+		rpc.mine_blocks(3, Some(6_000), false).await.unwrap();
+		assert_eq!(rpc.increase_time(1_000).await.unwrap(), 1_019_000);
+		rpc.mine_blocks(1, None, false).await.unwrap();
+		assert!(rpc.set_timestamp(2_000_000).await.unwrap());
+		rpc.mine_blocks(1, None, false).await.unwrap();
+		assert_eq!(
+			*timestamps.lock().unwrap(),
+			vec![1_006_000, 1_012_000, 1_018_000, 1_019_000, 2_000_000],
+		);
+
+		assert_matches::assert_matches!(
+			rpc.mine_blocks(MAX_MINE_BLOCKS + 1, None, false).await,
+			Err(Error::TooManyBlocks(_))
+		);
+		assert_eq!(client.info().best_number, 5);
+	}
 }
@@ -59,6 +59,55 @@ pub enum EngineCommand<Hash> {
 		/// finalization justification
 		justification: Option<EncodedJustification>,
 	},
+	/// Tells the engine to set the time seen by the next blocks
+	SetTimestamp {
+		/// unix timestamp in milliseconds
+		timestamp: u64,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
+	/// Tells the engine to move the time seen by the next blocks forward
+	IncreaseTime {
+		/// duration to add, in milliseconds
+		millis: u64,
+		/// sender to report the new unix timestamp in milliseconds to the rpc.
+		sender: Sender<u64>,
+	},
+	/// Tells the engine to seal `count` empty blocks on top of the best block
+	MineBlocks {
+		/// number of blocks to seal
+		count: u32,
+		/// if set, the time is moved forward by this many milliseconds before every block.
+		interval: Option<u64>,
+		/// instantly finalize the blocks?
+		finalize: bool,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<Vec<CreatedBlock<Hash>>>,
+	},
+	/// Tells the engine to revert the best chain to the block with the supplied hash
+	///
+	/// Finalized blocks are reverted as well. The transactions of the reverted blocks that are
+	/// not finalized are resubmitted to the transaction pool.
+	RevertTo {
+		/// hash of the block, which must be in the best chain
+		hash: Hash,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
+	/// Tells the engine to record the current best block and time
+	Snapshot {
+		/// sender to report the id of the snapshot to the rpc.
+		sender: Sender<u64>,
+	},
+	/// Tells the engine to revert the chain and the time to the snapshot with the supplied id
+	///
+	/// The snapshot and all the snapshots taken after it are discarded.
+	RevertToSnapshot {
+		/// id of the snapshot
+		id: u64,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -80,6 +129,36 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<EncodedJustification>,
 	) -> Result<bool, Error>;
+
+	/// Sets the time seen by the next blocks to the given unix timestamp in milliseconds
+	#[method(name = "engine_setTimestamp")]
+	async fn set_timestamp(&self, timestamp: u64) -> Result<bool, Error>;
+
+	/// Moves the time seen by the next blocks forward, returns the new unix timestamp
+	#[method(name = "engine_increaseTime")]
+	async fn increase_time(&self, millis: u64) -> Result<u64, Error>;
+
+	/// Instructs the manual-seal authorship task to create `count` empty blocks, at most
+	/// [`crate::MAX_MINE_BLOCKS`]
+	#[method(name = "engine_mineBlocks")]
+	async fn mine_blocks(
+		&self,
+		count: u32,
+		interval: Option<u64>,
+		finalize: bool,
+	) -> Result<Vec<CreatedBlock<Hash>>, Error>;
+
+	/// Reverts the best chain to the given block
+	#[method(name = "engine_revertTo")]
+	async fn revert_to(&self, hash: Hash) -> Result<bool, Error>;
+
+	/// Records the current best block and time, returns the id of the snapshot
+	#[method(name = "engine_snapshot")]
+	async fn snapshot(&self) -> Result<u64, Error>;
+
+	/// Reverts the chain and the time to the given snapshot
+	#[method(name = "engine_revertToSnapshot")]
+	async fn revert_to_snapshot(&self, id: u64) -> Result<bool, Error>;
 }
 
 /// A struct that implements the [`ManualSealApiServer`].
@@ -105,6 +184,18 @@ impl<Hash> ManualSeal<Hash> {
 	}
 }
 
+impl<Hash> ManualSeal<Hash> {
+	async fn send_command<T>(
+		&self,
+		command: impl FnOnce(Sender<T>) -> EngineCommand<Hash>,
+	) -> Result<T, Error> {
+		let mut sink = self.import_block_channel.clone();
+		let (sender, receiver) = oneshot::channel();
+		sink.send(command(Some(sender))).await?;
+		receiver.await?
+	}
+}
+
 #[async_trait]
 impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 	async fn create_block(
@@ -143,6 +234,42 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		sink.send(command).await?;
 		receiver.await.map(|_| true).map_err(Into::into)
 	}
+
+	async fn set_timestamp(&self, timestamp: u64) -> Result<bool, Error> {
+		self.send_command(|sender| EngineCommand::SetTimestamp { timestamp, sender })
+			.await
+			.map(|_| true)
+	}
+
+	async fn increase_time(&self, millis: u64) -> Result<u64, Error> {
+		self.send_command(|sender| EngineCommand::IncreaseTime { millis, sender }).await
+	}
+
+	async fn mine_blocks(
+		&self,
+		count: u32,
+		interval: Option<u64>,
+		finalize: bool,
+	) -> Result<Vec<CreatedBlock<Hash>>, Error> {
+		self.send_command(|sender| EngineCommand::MineBlocks { count, interval, finalize, sender })
+			.await
+	}
+
+	async fn revert_to(&self, hash: Hash) -> Result<bool, Error> {
+		self.send_command(|sender| EngineCommand::RevertTo { hash, sender })
+			.await
+			.map(|_| true)
+	}
+
+	async fn snapshot(&self) -> Result<u64, Error> {
+		self.send_command(|sender| EngineCommand::Snapshot { sender }).await
+	}
+
+	async fn revert_to_snapshot(&self, id: u64) -> Result<bool, Error> {
+		self.send_command(|sender| EngineCommand::RevertToSnapshot { id, sender })
+			.await
+			.map(|_| true)
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
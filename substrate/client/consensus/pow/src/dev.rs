@@ -0,0 +1,161 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A trivial proof of work algorithm and CPU miner, to run a PoW chain locally.
+//!
+//! The work is the Blake2-256 hash of the pre-hash and a nonce, which is the seal. It is not
+//! meant to be used on a live chain.
+
+use crate::{Error, MiningHandle, PowAlgorithm};
+use codec::{Decode, Encode};
+use futures::{future, task::Poll};
+use futures_timer::Delay;
+use sp_consensus_pow::Seal;
+use sp_core::{blake2_256, U256};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::time::Duration;
+
+/// Number of nonces tried by [`run_dev_miner`] before checking for new work.
+const NONCES_PER_ROUND: u64 = 10_000;
+
+/// Whether the given work satisfies the difficulty, i.e. `work * difficulty` doesn't overflow.
+pub fn meets_difficulty(work: &[u8; 32], difficulty: U256) -> bool {
+	!U256::from_big_endian(work).overflowing_mul(difficulty).1
+}
+
+/// The work for the given pre-hash and nonce.
+pub fn work(pre_hash: &[u8], nonce: u64) -> [u8; 32] {
+	blake2_256(&(pre_hash, nonce).encode())
+}
+
+/// Try `rounds` nonces from `start`, returning the seal of the first one satisfying the
+/// difficulty.
+pub fn mine(pre_hash: &[u8], difficulty: U256, start: u64, rounds: u64) -> Option<Seal> {
+	(start..start.saturating_add(rounds))
+		.find(|nonce| meets_difficulty(&work(pre_hash, *nonce), difficulty))
+		.map(|nonce| nonce.encode())
+}
+
+/// A trivial [`PowAlgorithm`] with a fixed difficulty.
+///
+/// Wrap it into a [`DifficultyAdjusted`](crate::DifficultyAdjusted) to test the difficulty
+/// adjustment algorithms.
+#[derive(Debug, Clone)]
+pub struct DevPowAlgorithm {
+	difficulty: U256,
+}
+
+impl DevPowAlgorithm {
+	/// Create a new algorithm with the given difficulty.
+	pub fn new(difficulty: U256) -> Self {
+		Self { difficulty }
+	}
+}
+
+impl<B: BlockT> PowAlgorithm<B> for DevPowAlgorithm {
+	type Difficulty = U256;
+
+	fn difficulty(&self, _parent: B::Hash) -> Result<Self::Difficulty, Error<B>> {
+		Ok(self.difficulty)
+	}
+
+	fn verify(
+		&self,
+		_parent: &BlockId<B>,
+		pre_hash: &B::Hash,
+		_pre_digest: Option<&[u8]>,
+		seal: &Seal,
+		difficulty: Self::Difficulty,
+	) -> Result<bool, Error<B>> {
+		let Ok(nonce) = u64::decode(&mut &seal[..]) else { return Ok(false) };
+
+		Ok(meets_difficulty(&work(pre_hash.as_ref(), nonce), difficulty))
+	}
+}
+
+/// Yield to the executor once, so that other tasks can make progress.
+async fn yield_now() {
+	let mut yielded = false;
+	future::poll_fn(|cx| {
+		if yielded {
+			return Poll::Ready(())
+		}
+		yielded = true;
+		cx.waker().wake_by_ref();
+		Poll::Pending
+	})
+	.await
+}
+
+/// Mine blocks with the [`DevPowAlgorithm`] work on the given handle, forever.
+///
+/// The miner keeps the CPU busy, hence it should be spawned as a blocking task. It yields after
+/// every round of nonces, so that it doesn't starve the other tasks of the executor it runs on.
+pub async fn run_dev_miner<B, A, L, Proof>(handle: MiningHandle<B, A, L, Proof>)
+where
+	B: BlockT,
+	A: PowAlgorithm<B, Difficulty = U256>,
+	L: sc_consensus::JustificationSyncLink<B>,
+{
+	let mut nonce = 0u64;
+	loop {
+		let Some(metadata) = handle.metadata() else {
+			Delay::new(Duration::from_millis(100)).await;
+			continue
+		};
+
+		match mine(metadata.pre_hash.as_ref(), metadata.difficulty, nonce, NONCES_PER_ROUND) {
+			Some(seal) => {
+				handle.submit(seal).await;
+			},
+			None => nonce = nonce.wrapping_add(NONCES_PER_ROUND),
+		}
+		yield_now().await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+	use substrate_test_runtime_client::runtime::Block;
+
+	#[test]
+	fn mined_seal_is_valid() {
+		let pre_hash = H256::default();
+		let difficulty = U256::from(1_000);
+		let algorithm = DevPowAlgorithm::new(difficulty);
+
+		let seal = mine(pre_hash.as_ref(), difficulty, 0, u64::MAX).unwrap();
+		let verify = |seal: &Seal, difficulty| {
+			PowAlgorithm::<Block>::verify(
+				&algorithm,
+				&BlockId::Number(0),
+				&pre_hash,
+				None,
+				seal,
+				difficulty,
+			)
+			.unwrap()
+		};
+
+		assert!(verify(&seal, difficulty));
+		assert!(!verify(&seal, U256::MAX));
+		assert!(!verify(&vec![1, 2, 3], difficulty));
+	}
+}
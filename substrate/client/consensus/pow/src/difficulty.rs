@@ -0,0 +1,537 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Difficulty adjustment algorithms.
+//!
+//! [`DifficultyAdjusted`] wraps a [`PowAlgorithm`] and computes its
+//! [`PowAlgorithm::difficulty`] with the [`DifficultyAdjustment`] algorithm returned by the
+//! [`DifficultyAdjustmentApi`] runtime API at the parent block. The algorithms work on the
+//! difficulties stored in the auxiliary storage by the block import, and on the timestamps of the
+//! blocks, fetched with the [`TimestampApi`] runtime API. If the runtime doesn't configure an
+//! algorithm, or as long as there isn't enough history, e.g. right after genesis, the difficulty
+//! of the wrapped algorithm is used.
+
+use crate::{Error, PowAlgorithm, PowAux};
+use parking_lot::Mutex;
+use sc_client_api::backend::AuxStore;
+use schnellru::{ByLength, LruMap};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+pub use sp_consensus_pow::DifficultyAdjustment;
+use sp_consensus_pow::{DifficultyAdjustmentApi, Seal, TimestampApi};
+use sp_core::{U256, U512};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+	SaturatedConversion,
+};
+use std::sync::Arc;
+
+/// Number of blocks whose timestamp and difficulty are cached.
+///
+/// LWMA windows up to this size only fetch the data of the parent block for every new block.
+const BLOCK_DATA_CACHE_SIZE: u32 = 2048;
+
+/// Difficulty of the next block by LWMA, given the `(timestamp, difficulty)` of the last blocks,
+/// oldest first.
+///
+/// Returns `None` if less than two blocks are given.
+pub fn lwma(blocks: &[(u64, U256)], target_block_time: u64) -> Option<U256> {
+	let window = blocks.len().checked_sub(1).filter(|n| *n > 0)? as u128;
+	let target_block_time = target_block_time.max(1) as u128;
+
+	let mut weighted_solve_times = 0u128;
+	let mut difficulties = U256::zero();
+	for (i, pair) in blocks.windows(2).enumerate() {
+		// bound the solve times, so that a few bad timestamps can't move the difficulty too much.
+		let solve_time =
+			(pair[1].0.saturating_sub(pair[0].0) as u128).clamp(1, 6 * target_block_time);
+		weighted_solve_times += (i as u128 + 1) * solve_time;
+		difficulties = difficulties.saturating_add(pair[1].1);
+	}
+
+	let weights = window * (window + 1) / 2;
+	let weighted_solve_times = weighted_solve_times.max(weights * target_block_time / 10);
+
+	let next = (difficulties / U256::from(window))
+		.full_mul(U256::from(weights * target_block_time)) /
+		U512::from(weighted_solve_times);
+	Some(U256::try_from(next).unwrap_or(U256::MAX).max(U256::one()))
+}
+
+/// Difficulty of the next block by ASERT.
+///
+/// `time_delta` and `height_delta` are the time and the number of blocks between the anchor block
+/// of difficulty `anchor_difficulty` and the parent of the next block.
+pub fn asert(
+	anchor_difficulty: U256,
+	time_delta: i64,
+	height_delta: u64,
+	target_block_time: u64,
+	half_life: u64,
+) -> U256 {
+	let ahead_of_schedule = target_block_time as i128 * height_delta as i128 - time_delta as i128;
+	// the exponent in 16.16 fixed point.
+	let exponent = ahead_of_schedule * 65536 / half_life.max(1) as i128;
+	let shifts = exponent >> 16;
+	let frac = (exponent - (shifts << 16)) as u128;
+
+	// 2^(frac / 65536) * 65536, approximated by the cubic polynomial of `aserti3-2d`.
+	let factor = 65536 +
+		((195_766_423_245_049 * frac +
+			971_821_376 * frac * frac +
+			5_127 * frac * frac * frac +
+			(1 << 47)) >>
+			48);
+
+	let difficulty =
+		U256::try_from(anchor_difficulty.full_mul(U256::from(factor)) >> 16).unwrap_or(U256::MAX);
+	let difficulty = if shifts >= 0 {
+		if shifts >= difficulty.leading_zeros() as i128 {
+			U256::MAX
+		} else {
+			difficulty << shifts as usize
+		}
+	} else if -shifts >= 256 {
+		U256::zero()
+	} else {
+		difficulty >> (-shifts) as usize
+	};
+
+	difficulty.max(U256::one())
+}
+
+/// A [`PowAlgorithm`] whose difficulty is adjusted by a [`DifficultyAdjustment`] algorithm.
+///
+/// The seals are verified by the wrapped algorithm.
+pub struct DifficultyAdjusted<B: BlockT, C, A: PowAlgorithm<B>> {
+	inner: A,
+	client: Arc<C>,
+	/// The difficulty is queried twice for every block, so the last one is cached.
+	cache: Arc<Mutex<Option<(B::Hash, A::Difficulty)>>>,
+	/// The data the algorithms work on, by block hash.
+	block_data: Arc<Mutex<LruMap<B::Hash, BlockData<B>>>>,
+}
+
+/// The data of a block the difficulty adjustment algorithms work on.
+struct BlockData<B: BlockT> {
+	number: u64,
+	parent_hash: B::Hash,
+	timestamp: u64,
+	difficulty: U256,
+}
+
+impl<B: BlockT> Clone for BlockData<B> {
+	fn clone(&self) -> Self {
+		Self {
+			number: self.number,
+			parent_hash: self.parent_hash,
+			timestamp: self.timestamp,
+			difficulty: self.difficulty,
+		}
+	}
+}
+
+impl<B: BlockT, C, A: PowAlgorithm<B> + Clone> Clone for DifficultyAdjusted<B, C, A> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+			client: self.client.clone(),
+			cache: self.cache.clone(),
+			block_data: self.block_data.clone(),
+		}
+	}
+}
+
+impl<B, C, A> DifficultyAdjusted<B, C, A>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + AuxStore,
+	C::Api: TimestampApi<B, u64> + DifficultyAdjustmentApi<B>,
+	A: PowAlgorithm<B>,
+	A::Difficulty: Into<U256> + TryFrom<U256>,
+{
+	/// Adjust the difficulty of `inner` with the algorithm configured by the runtime.
+	pub fn new(inner: A, client: Arc<C>) -> Self {
+		Self {
+			inner,
+			client,
+			cache: Arc::new(Mutex::new(None)),
+			block_data: Arc::new(Mutex::new(LruMap::new(ByLength::new(BLOCK_DATA_CACHE_SIZE)))),
+		}
+	}
+
+	/// The number, parent, timestamp and difficulty of the given block.
+	fn block_data(&self, hash: B::Hash) -> Result<BlockData<B>, Error<B>> {
+		if let Some(data) = self.block_data.lock().get(&hash) {
+			return Ok(data.clone())
+		}
+
+		let header = self
+			.client
+			.header(hash)
+			.map_err(Error::Client)?
+			.ok_or_else(|| Error::Other(format!("Header {hash:?} not found")))?;
+		let timestamp = self
+			.client
+			.runtime_api()
+			.timestamp(hash)
+			.map_err(|e| Error::Runtime(e.to_string()))?;
+		let difficulty = PowAux::<A::Difficulty>::read::<_, B>(&*self.client, &hash)?.difficulty;
+		let data = BlockData {
+			number: (*header.number()).saturated_into(),
+			parent_hash: *header.parent_hash(),
+			timestamp,
+			difficulty: difficulty.into(),
+		};
+		self.block_data.lock().insert(hash, data.clone());
+
+		Ok(data)
+	}
+
+	/// The adjusted difficulty of the child of `parent`, `None` if there isn't enough history.
+	fn adjusted_difficulty(
+		&self,
+		parent: B::Hash,
+		adjustment: DifficultyAdjustment,
+	) -> Result<Option<U256>, Error<B>> {
+		match adjustment {
+			DifficultyAdjustment::Lwma { target_block_time, window } => {
+				let mut blocks = Vec::new();
+				let mut hash = parent;
+				while blocks.len() <= window as usize {
+					let data = self.block_data(hash)?;
+					// there is no difficulty recorded for genesis.
+					if data.number == 0 || data.difficulty.is_zero() {
+						return Ok(None)
+					}
+					blocks.push((data.timestamp, data.difficulty));
+					hash = data.parent_hash;
+				}
+				blocks.reverse();
+
+				Ok(lwma(&blocks, target_block_time))
+			},
+			DifficultyAdjustment::Asert { target_block_time, half_life, anchor } => {
+				let parent = self.block_data(parent)?;
+				if parent.number <= anchor {
+					return Ok(None)
+				}
+
+				let anchor_hash = self
+					.client
+					.hash(anchor.saturated_into())
+					.map_err(Error::Client)?
+					.ok_or_else(|| Error::Other(format!("Anchor block #{anchor} not found")))?;
+				let anchor_block = self.block_data(anchor_hash)?;
+				if anchor_block.difficulty.is_zero() {
+					return Ok(None)
+				}
+
+				Ok(Some(asert(
+					anchor_block.difficulty,
+					parent.timestamp as i64 - anchor_block.timestamp as i64,
+					parent.number - anchor,
+					target_block_time,
+					half_life,
+				)))
+			},
+		}
+	}
+}
+
+impl<B, C, A> PowAlgorithm<B> for DifficultyAdjusted<B, C, A>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + AuxStore,
+	C::Api: TimestampApi<B, u64> + DifficultyAdjustmentApi<B>,
+	A: PowAlgorithm<B>,
+	A::Difficulty: Into<U256> + TryFrom<U256>,
+{
+	type Difficulty = A::Difficulty;
+
+	fn difficulty(&self, parent: B::Hash) -> Result<Self::Difficulty, Error<B>> {
+		if let Some((hash, difficulty)) = *self.cache.lock() {
+			if hash == parent {
+				return Ok(difficulty)
+			}
+		}
+
+		let adjustment = self
+			.client
+			.runtime_api()
+			.difficulty_adjustment(parent)
+			.map_err(|e| Error::Runtime(e.to_string()))?;
+		let adjusted = match adjustment {
+			Some(adjustment) => self.adjusted_difficulty(parent, adjustment)?,
+			None => None,
+		};
+		let difficulty = match adjusted {
+			Some(difficulty) => <Self::Difficulty as TryFrom<U256>>::try_from(difficulty)
+				.map_err(|_| Error::Other(format!("Difficulty {difficulty} overflows")))?,
+			None => self.inner.difficulty(parent)?,
+		};
+		*self.cache.lock() = Some((parent, difficulty));
+
+		Ok(difficulty)
+	}
+
+	fn preliminary_verify(
+		&self,
+		pre_hash: &B::Hash,
+		seal: &Seal,
+	) -> Result<Option<bool>, Error<B>> {
+		self.inner.preliminary_verify(pre_hash, seal)
+	}
+
+	fn break_tie(&self, own_seal: &Seal, new_seal: &Seal) -> bool {
+		self.inner.break_tie(own_seal, new_seal)
+	}
+
+	fn verify(
+		&self,
+		parent: &BlockId<B>,
+		pre_hash: &B::Hash,
+		pre_digest: Option<&[u8]>,
+		seal: &Seal,
+		difficulty: Self::Difficulty,
+	) -> Result<bool, Error<B>> {
+		self.inner.verify(parent, pre_hash, pre_digest, seal, difficulty)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{aux_key, dev::DevPowAlgorithm};
+	use codec::Encode;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_api::{ApiError, ApiRef};
+	use sp_blockchain::{BlockStatus, Info};
+	use sp_consensus::BlockOrigin;
+	use sp_runtime::traits::NumberFor;
+	use std::collections::HashMap;
+	use substrate_test_runtime_client::{
+		prelude::*,
+		runtime::{Block, Hash},
+	};
+
+	const BLOCK_TIME: u64 = 6_000;
+
+	fn chain(solve_time: u64, difficulty: u64, len: u64) -> Vec<(u64, U256)> {
+		(0..len).map(|i| (i * solve_time, U256::from(difficulty))).collect()
+	}
+
+	/// What the runtime API of [`TestApi`] returns, and how often it was called.
+	#[derive(Default)]
+	struct RuntimeState {
+		adjustment: Option<DifficultyAdjustment>,
+		timestamps: HashMap<Hash, u64>,
+		calls: usize,
+	}
+
+	/// A real client, with a runtime API providing the timestamps and the difficulty adjustment.
+	struct TestApi {
+		client: Arc<TestClient>,
+		state: Arc<Mutex<RuntimeState>>,
+	}
+
+	impl TestApi {
+		fn new() -> Self {
+			Self {
+				client: Arc::new(substrate_test_runtime_client::new()),
+				state: Default::default(),
+			}
+		}
+
+		/// Import a child of the best block, with the given timestamp and difficulty.
+		fn push_block(&self, timestamp: u64, difficulty: U256) -> Hash {
+			let info = self.client.chain_info();
+			let block = BlockBuilderBuilder::new(&*self.client)
+				.on_parent_block(info.best_hash)
+				.with_parent_block_number(info.best_number)
+				.build()
+				.unwrap()
+				.build()
+				.unwrap()
+				.block;
+			let hash = block.hash();
+			futures::executor::block_on(self.client.import(BlockOrigin::Own, block)).unwrap();
+
+			let aux = PowAux { difficulty, total_difficulty: U256::zero() }.encode();
+			AuxStore::insert_aux(&*self.client, &[(&aux_key(&hash)[..], &aux[..])], &[]).unwrap();
+			self.state.lock().timestamps.insert(hash, timestamp);
+
+			hash
+		}
+	}
+
+	struct RuntimeApi {
+		state: Arc<Mutex<RuntimeState>>,
+	}
+
+	impl ProvideRuntimeApi<Block> for TestApi {
+		type Api = RuntimeApi;
+
+		fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+			RuntimeApi { state: self.state.clone() }.into()
+		}
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl TimestampApi<Block, u64> for RuntimeApi {
+			#[advanced]
+			fn timestamp(&self, at: <Block as BlockT>::Hash) -> Result<u64, ApiError> {
+				let mut state = self.state.lock();
+				state.calls += 1;
+
+				Ok(state.timestamps.get(&at).copied().unwrap_or_default())
+			}
+		}
+
+		impl DifficultyAdjustmentApi<Block> for RuntimeApi {
+			#[advanced]
+			fn difficulty_adjustment(
+				&self,
+				_at: <Block as BlockT>::Hash,
+			) -> Result<Option<DifficultyAdjustment>, ApiError> {
+				let mut state = self.state.lock();
+				state.calls += 1;
+
+				Ok(state.adjustment)
+			}
+		}
+	}
+
+	impl HeaderBackend<Block> for TestApi {
+		fn header(&self, hash: Hash) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
+			HeaderBackend::header(&*self.client, hash)
+		}
+
+		fn info(&self) -> Info<Block> {
+			HeaderBackend::info(&*self.client)
+		}
+
+		fn status(&self, hash: Hash) -> sp_blockchain::Result<BlockStatus> {
+			HeaderBackend::status(&*self.client, hash)
+		}
+
+		fn number(&self, hash: Hash) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+			HeaderBackend::number(&*self.client, hash)
+		}
+
+		fn hash(&self, number: NumberFor<Block>) -> sp_blockchain::Result<Option<Hash>> {
+			HeaderBackend::hash(&*self.client, number)
+		}
+	}
+
+	impl AuxStore for TestApi {
+		fn insert_aux<
+			'a,
+			'b: 'a,
+			'c: 'a,
+			I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+			D: IntoIterator<Item = &'a &'b [u8]>,
+		>(
+			&self,
+			insert: I,
+			delete: D,
+		) -> sp_blockchain::Result<()> {
+			AuxStore::insert_aux(&*self.client, insert, delete)
+		}
+
+		fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+			AuxStore::get_aux(&*self.client, key)
+		}
+	}
+
+	#[test]
+	fn difficulty_is_adjusted_as_configured_by_the_runtime() {
+		let api = Arc::new(TestApi::new());
+		let algorithm =
+			DifficultyAdjusted::new(DevPowAlgorithm::new(U256::from(1_000)), api.clone());
+		let difficulty = |parent| PowAlgorithm::<Block>::difficulty(&algorithm, parent).unwrap();
+
+		// Blocks are mined twice as fast as targeted, but the runtime doesn't adjust the
+		// difficulty yet.
+		let mut parent = api.client.chain_info().genesis_hash;
+		for n in 1..=5 {
+			assert_eq!(difficulty(parent), U256::from(1_000));
+			parent = api.push_block(n * BLOCK_TIME / 2, U256::from(1_000));
+		}
+
+		api.state.lock().adjustment =
+			Some(DifficultyAdjustment::Lwma { target_block_time: BLOCK_TIME, window: 4 });
+		let mut last = difficulty(parent);
+		assert_eq!(last, U256::from(2_000));
+
+		for n in 6..=10 {
+			parent = api.push_block(n * BLOCK_TIME / 2, last);
+
+			// Only the adjustment and the timestamp of the new parent are fetched, the rest of
+			// the window is cached.
+			api.state.lock().calls = 0;
+			let next = difficulty(parent);
+			assert_eq!(api.state.lock().calls, 2);
+			assert_eq!(difficulty(parent), next);
+			assert_eq!(api.state.lock().calls, 2);
+
+			assert!(next > last);
+			last = next;
+		}
+	}
+
+	#[test]
+	fn lwma_follows_solve_times() {
+		let on_target = lwma(&chain(BLOCK_TIME, 1_000_000, 61), BLOCK_TIME).unwrap();
+		assert_eq!(on_target, U256::from(1_000_000));
+
+		let fast = lwma(&chain(BLOCK_TIME / 2, 1_000_000, 61), BLOCK_TIME).unwrap();
+		assert_eq!(fast, U256::from(2_000_000));
+
+		let slow = lwma(&chain(BLOCK_TIME * 2, 1_000_000, 61), BLOCK_TIME).unwrap();
+		assert_eq!(slow, U256::from(500_000));
+
+		assert_eq!(lwma(&chain(BLOCK_TIME, 1_000_000, 1), BLOCK_TIME), None);
+	}
+
+	#[test]
+	fn asert_follows_schedule() {
+		let anchor = U256::from(1_000_000);
+		let half_life = 3_600_000;
+
+		assert_eq!(asert(anchor, 100 * BLOCK_TIME as i64, 100, BLOCK_TIME, half_life), anchor);
+		assert_eq!(
+			asert(anchor, 100 * BLOCK_TIME as i64 - half_life as i64, 100, BLOCK_TIME, half_life),
+			U256::from(2_000_000),
+		);
+		assert_eq!(
+			asert(anchor, 100 * BLOCK_TIME as i64 + half_life as i64, 100, BLOCK_TIME, half_life),
+			U256::from(500_000),
+		);
+
+		let half_ahead = asert(
+			anchor,
+			100 * BLOCK_TIME as i64 - half_life as i64 / 2,
+			100,
+			BLOCK_TIME,
+			half_life,
+		);
+		assert!(half_ahead > U256::from(1_414_000) && half_ahead < U256::from(1_415_000));
+	}
+}
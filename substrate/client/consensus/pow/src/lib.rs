@@ -38,10 +38,22 @@
 //! for the auxiliary storage. It is also possible to just use the runtime
 //! as the storage, but it is not recommended as it won't work well with light
 //! clients.
-
+//!
+//! The difficulty of an existing [`PowAlgorithm`] can be adjusted by one of the algorithms of
+//! [`DifficultyAdjustment`], selected by the runtime, by wrapping it into a
+//! [`DifficultyAdjusted`]. External miners can
+//! fetch work and submit seals over the RPC of the [`rpc`] module. The [`dev`] module provides a
+//! trivial algorithm and miner to run a PoW chain locally.
+
+pub mod dev;
+mod difficulty;
+pub mod rpc;
 mod worker;
 
-pub use crate::worker::{MiningBuild, MiningHandle, MiningMetadata};
+pub use crate::{
+	difficulty::{asert, lwma, DifficultyAdjusted, DifficultyAdjustment},
+	worker::{MiningBuild, MiningHandle, MiningMetadata},
+};
 
 use crate::worker::UntilImportedOrTimeout;
 use codec::{Decode, Encode};
@@ -0,0 +1,254 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC interface for external miners, backed by a [`MiningHandle`].
+
+use crate::{MiningHandle, PowAlgorithm};
+use jsonrpsee::{
+	core::async_trait,
+	proc_macros::rpc,
+	types::{ErrorObject, ErrorObjectOwned},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+const POW_ERROR: i32 = 9100;
+
+/// The work to be mined.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Work<Hash, Difficulty> {
+	/// The best block the work builds on.
+	pub best_hash: Hash,
+	/// The hash of the block to seal.
+	pub pre_hash: Hash,
+	/// The PoW pre-runtime digest of the block, if any.
+	pub pre_runtime: Option<Bytes>,
+	/// The difficulty the seal must satisfy.
+	pub difficulty: Difficulty,
+}
+
+/// Provides RPC methods for external miners.
+#[rpc(client, server)]
+pub trait PowApi<Hash, Difficulty> {
+	/// Returns the work to be mined, or `None` if the node is syncing or building a block.
+	#[method(name = "pow_getWork")]
+	fn get_work(&self) -> Result<Option<Work<Hash, Difficulty>>, Error>;
+
+	/// Submits the seal mined for the given pre-hash. Returns whether the block was imported.
+	#[method(name = "pow_submitWork")]
+	async fn submit_work(&self, pre_hash: Hash, seal: Bytes) -> Result<bool, Error>;
+}
+
+/// Provides RPC methods for external miners.
+pub struct Pow<
+	B: BlockT,
+	Algorithm: PowAlgorithm<B>,
+	L: sc_consensus::JustificationSyncLink<B>,
+	Proof,
+> {
+	handle: MiningHandle<B, Algorithm, L, Proof>,
+}
+
+impl<B, Algorithm, L, Proof> Pow<B, Algorithm, L, Proof>
+where
+	B: BlockT,
+	Algorithm: PowAlgorithm<B>,
+	L: sc_consensus::JustificationSyncLink<B>,
+{
+	/// Creates a new instance of the PoW RPC handler.
+	pub fn new(handle: MiningHandle<B, Algorithm, L, Proof>) -> Self {
+		Self { handle }
+	}
+}
+
+#[async_trait]
+impl<B, Algorithm, L, Proof> PowApiServer<B::Hash, Algorithm::Difficulty>
+	for Pow<B, Algorithm, L, Proof>
+where
+	B: BlockT,
+	Algorithm: PowAlgorithm<B> + Send + Sync + 'static,
+	Algorithm::Difficulty: Serialize + DeserializeOwned + Send + Sync + 'static,
+	L: sc_consensus::JustificationSyncLink<B> + 'static,
+	Proof: Send + 'static,
+{
+	fn get_work(&self) -> Result<Option<Work<B::Hash, Algorithm::Difficulty>>, Error> {
+		Ok(self.handle.metadata().map(|metadata| Work {
+			best_hash: metadata.best_hash,
+			pre_hash: metadata.pre_hash,
+			pre_runtime: metadata.pre_runtime.map(Into::into),
+			difficulty: metadata.difficulty,
+		}))
+	}
+
+	async fn submit_work(&self, pre_hash: B::Hash, seal: Bytes) -> Result<bool, Error> {
+		match self.handle.metadata() {
+			Some(metadata) if metadata.pre_hash == pre_hash => Ok(self.handle.submit(seal.0).await),
+			_ => Err(Error::StaleWork(format!("{pre_hash:?}"))),
+		}
+	}
+}
+
+/// Errors encountered by the PoW RPC.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The submitted work isn't the current one.
+	#[error("Work for {0} is stale or unknown")]
+	StaleWork(String),
+}
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::StaleWork(_) => ErrorObject::owned(POW_ERROR + 1, error.to_string(), None::<()>),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		dev::{mine, DevPowAlgorithm},
+		MiningBuild, MiningMetadata, PowBlockImport,
+	};
+	use assert_matches::assert_matches;
+	use jsonrpsee::{rpc_params, MethodsError, RpcModule};
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_consensus::Proposal;
+	use sp_core::{H256, U256};
+	use sp_runtime::traits::Header as HeaderT;
+	use std::sync::Arc;
+	use substrate_test_runtime_client::{prelude::*, runtime::Block, LongestChain};
+
+	const DIFFICULTY: u64 = 1_000;
+
+	type Handle = MiningHandle<Block, DevPowAlgorithm, (), ()>;
+
+	fn setup() -> (Arc<TestClient>, Handle, RpcModule<Pow<Block, DevPowAlgorithm, (), ()>>) {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let client = Arc::new(builder.build());
+		let algorithm = DevPowAlgorithm::new(U256::from(DIFFICULTY));
+		let block_import = PowBlockImport::new(
+			client.clone(),
+			client.clone(),
+			algorithm.clone(),
+			0,
+			LongestChain::new(backend),
+			|_, _| async { Ok(()) },
+		);
+
+		let handle = MiningHandle::new(algorithm, Box::new(block_import), ());
+		let rpc = Pow::new(handle.clone()).into_rpc();
+
+		(client, handle, rpc)
+	}
+
+	/// Build a child of the best block and hand it to the miners, returning its pre-hash.
+	fn build(client: &TestClient, handle: &Handle) -> H256 {
+		let info = client.chain_info();
+		let built = BlockBuilderBuilder::new(client)
+			.on_parent_block(info.best_hash)
+			.with_parent_block_number(info.best_number)
+			.build()
+			.unwrap()
+			.build()
+			.unwrap();
+		let pre_hash = built.block.header().hash();
+
+		handle.on_build(MiningBuild {
+			metadata: MiningMetadata {
+				best_hash: info.best_hash,
+				pre_hash,
+				pre_runtime: None,
+				difficulty: U256::from(DIFFICULTY),
+			},
+			proposal: Proposal {
+				block: built.block,
+				proof: (),
+				storage_changes: built.storage_changes,
+			},
+		});
+
+		pre_hash
+	}
+
+	async fn get_work(rpc: &RpcModule<impl Send + Sync + 'static>) -> Option<Work<H256, U256>> {
+		rpc.call("pow_getWork", rpc_params![]).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn get_work_returns_current_build() {
+		let (client, handle, rpc) = setup();
+		assert_eq!(get_work(&rpc).await, None);
+
+		let pre_hash = build(&client, &handle);
+		assert_eq!(
+			get_work(&rpc).await,
+			Some(Work {
+				best_hash: client.chain_info().genesis_hash,
+				pre_hash,
+				pre_runtime: None,
+				difficulty: U256::from(DIFFICULTY),
+			}),
+		);
+
+		handle.on_major_syncing();
+		assert_eq!(get_work(&rpc).await, None);
+	}
+
+	#[tokio::test]
+	async fn submit_work_imports_block() {
+		let (client, handle, rpc) = setup();
+		let pre_hash = build(&client, &handle);
+		let seal = mine(pre_hash.as_ref(), U256::from(DIFFICULTY), 0, u64::MAX).unwrap();
+
+		let err = rpc
+			.call::<_, bool>(
+				"pow_submitWork",
+				rpc_params![H256::repeat_byte(1), Bytes(seal.clone())],
+			)
+			.await
+			.unwrap_err();
+		assert_matches!(err, MethodsError::JsonRpc(err) if err.code() == POW_ERROR + 1);
+
+		let imported: bool = rpc
+			.call("pow_submitWork", rpc_params![pre_hash, Bytes(vec![1, 2, 3])])
+			.await
+			.unwrap();
+		assert!(!imported);
+		assert_eq!(client.chain_info().best_number, 0);
+
+		let imported: bool = rpc
+			.call("pow_submitWork", rpc_params![pre_hash, Bytes(seal.clone())])
+			.await
+			.unwrap();
+		assert!(imported);
+		assert_eq!(client.chain_info().best_number, 1);
+
+		// The work was consumed by the import.
+		assert_eq!(get_work(&rpc).await, None);
+		let err = rpc
+			.call::<_, bool>("pow_submitWork", rpc_params![pre_hash, Bytes(seal)])
+			.await
+			.unwrap_err();
+		assert_matches!(err, MethodsError::JsonRpc(err) if err.code() == POW_ERROR + 1);
+	}
+}
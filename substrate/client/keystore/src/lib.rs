@@ -26,6 +26,8 @@ use std::io;
 /// Local keystore implementation
 mod local;
 pub use local::LocalKeystore;
+pub mod remote;
+pub use remote::RemoteKeystore;
 pub use sp_keystore::Keystore;
 
 /// Keystore error.
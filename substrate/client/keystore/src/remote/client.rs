@@ -0,0 +1,521 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Keystore forwarding the requests to the signer.
+
+use super::{
+	read_message, write_message, AuthKey, Endpoint, Envelope, Hello, Request, Response, Stream,
+	PROTOCOL_VERSION,
+};
+use codec::Decode;
+use parking_lot::Mutex;
+use sp_core::{
+	crypto::{ByteArray, CryptoTypeId, KeyTypeId, Pair as CorePair, VrfPublic},
+	ecdsa, ed25519, sr25519,
+};
+use sp_keystore::{Error as TraitError, Keystore, KeystorePtr};
+use std::{io, net::TcpStream, sync::Arc, time::Duration};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+sp_keystore::bandersnatch_experimental_enabled! {
+use sp_core::bandersnatch;
+}
+
+sp_keystore::bls_experimental_enabled! {
+use sp_core::{bls381, ecdsa_bls381};
+}
+
+const LOG_TARGET: &str = "keystore::remote";
+
+/// Default timeout of a single request to the signer.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An established connection to the signer.
+struct Connection {
+	stream: Stream,
+	/// Challenge sent by the signer when connecting.
+	challenge: [u8; 32],
+	/// Number of requests sent over the connection.
+	counter: u64,
+}
+
+/// A keystore whose keys are held by a separate signer process.
+///
+/// The connection is established lazily and re-established once per request if it was lost.
+///
+/// VRF signatures returned by the signer are verified against the transcript of the caller,
+/// hence a transcript modified after its construction fails to be signed. Ring VRF signing isn't
+/// supported.
+pub struct RemoteKeystore {
+	endpoint: Endpoint,
+	auth_key: AuthKey,
+	timeout: Duration,
+	connection: Mutex<Option<Connection>>,
+}
+
+impl RemoteKeystore {
+	/// Create a keystore forwarding the requests to the signer at `endpoint`, authenticated with
+	/// `auth_key`.
+	pub fn new(endpoint: Endpoint, auth_key: AuthKey) -> Self {
+		Self { endpoint, auth_key, timeout: DEFAULT_TIMEOUT, connection: Mutex::new(None) }
+	}
+
+	/// Set the timeout of a single request to the signer.
+	pub fn with_timeout(self, timeout: Duration) -> Self {
+		Self { timeout, ..self }
+	}
+
+	fn connect(&self) -> io::Result<Connection> {
+		let mut stream = self.open_stream()?;
+		let hello = read_message::<Hello>(&mut stream)?;
+		if hello.version != PROTOCOL_VERSION {
+			return Err(io::Error::new(
+				io::ErrorKind::Unsupported,
+				format!(
+					"Signer speaks protocol version {}, expected {PROTOCOL_VERSION}",
+					hello.version
+				),
+			))
+		}
+		Ok(Connection { stream, challenge: hello.challenge, counter: 0 })
+	}
+
+	fn open_stream(&self) -> io::Result<Stream> {
+		match &self.endpoint {
+			#[cfg(unix)]
+			Endpoint::Unix(path) => {
+				let stream = Stream::Unix(UnixStream::connect(path)?);
+				stream.set_timeouts(self.timeout, self.timeout)?;
+				Ok(stream)
+			},
+			#[cfg(not(unix))]
+			Endpoint::Unix(_) => Err(io::Error::new(
+				io::ErrorKind::Unsupported,
+				"Unix sockets are not supported on this platform",
+			)),
+			Endpoint::Tcp(addr) => {
+				let stream = TcpStream::connect(addr)?;
+				stream.set_nodelay(true)?;
+				let stream = Stream::Tcp(stream);
+				stream.set_timeouts(self.timeout, self.timeout)?;
+				Ok(stream)
+			},
+		}
+	}
+
+	fn exchange(
+		&self,
+		connection: &mut Option<Connection>,
+		request: &Request,
+	) -> io::Result<Response> {
+		if connection.is_none() {
+			*connection = Some(self.connect()?);
+		}
+		let conn = connection.as_mut().expect("Connection established above; qed");
+		let envelope =
+			Envelope::new(request.clone(), conn.counter, &conn.challenge, &self.auth_key);
+		conn.counter += 1;
+		let result =
+			write_message(&mut conn.stream, &envelope).and_then(|_| read_message(&mut conn.stream));
+		if result.is_err() {
+			*connection = None;
+		}
+		result
+	}
+
+	fn request(&self, request: Request) -> Result<Response, TraitError> {
+		let mut connection = self.connection.lock();
+		let response = self
+			.exchange(&mut connection, &request)
+			.or_else(|e| {
+				log::debug!(
+					target: LOG_TARGET,
+					"Request to signer {} failed: {e}, retrying",
+					self.endpoint
+				);
+				self.exchange(&mut connection, &request)
+			})
+			.map_err(|e| {
+				log::error!(target: LOG_TARGET, "Signer {} is unavailable: {e}", self.endpoint);
+				TraitError::Unavailable
+			})?;
+
+		match response {
+			Response::Refused(reason) => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Signing refused by the slashing protection: {reason}"
+				);
+				Err(TraitError::Other(format!(
+					"Signing refused by the slashing protection: {reason}"
+				)))
+			},
+			Response::Error(e) => Err(TraitError::Other(e)),
+			Response::UnsupportedVersion(version) => Err(TraitError::Other(format!(
+				"Signer speaks protocol version {version}, expected {PROTOCOL_VERSION}"
+			))),
+			Response::Unauthorized => {
+				*connection = None;
+				log::error!(
+					target: LOG_TARGET,
+					"Signer {} refused the request, check the authentication key",
+					self.endpoint
+				);
+				Err(TraitError::Other("Request refused by the signer, unauthorized".into()))
+			},
+			response => Ok(response),
+		}
+	}
+
+	fn raw_public_keys(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+	) -> Result<Vec<Vec<u8>>, TraitError> {
+		match self.request(Request::PublicKeys { key_type, crypto })? {
+			Response::Keys(keys) => Ok(keys),
+			response => Err(unexpected(response)),
+		}
+	}
+
+	fn public_keys<T: CorePair>(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+	) -> Vec<T::Public> {
+		self.raw_public_keys(key_type, crypto)
+			.map(|keys| keys.iter().filter_map(|k| T::Public::from_slice(k).ok()).collect())
+			.unwrap_or_default()
+	}
+
+	fn sign<T: CorePair>(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+		public: &T::Public,
+		msg: &[u8],
+	) -> Result<Option<T::Signature>, TraitError> {
+		let request =
+			Request::Sign { key_type, crypto, public: public.to_raw_vec(), msg: msg.to_vec() };
+		into_signature::<T>(self.request(request)?)
+	}
+
+	/// Request a VRF signature, checking that it verifies for `data` with `public`.
+	fn vrf_sign<P: VrfPublic>(
+		&self,
+		request: Request,
+		public: &P,
+		data: &P::VrfSignData,
+	) -> Result<Option<P::VrfSignature>, TraitError>
+	where
+		P::VrfSignature: Decode,
+	{
+		let signature = match self.request(request)? {
+			Response::Signature(signature) => decode::<P::VrfSignature>(signature)?,
+			response => return Err(unexpected(response)),
+		};
+		match signature {
+			Some(signature) if !public.vrf_verify(data, &signature) => Err(TraitError::Other(
+				"VRF signature returned by the signer doesn't match the transcript".into(),
+			)),
+			signature => Ok(signature),
+		}
+	}
+
+	fn vrf_pre_output<T: Decode>(&self, request: Request) -> Result<Option<T>, TraitError> {
+		match self.request(request)? {
+			Response::VrfPreOutput(pre_output) => decode(pre_output),
+			response => Err(unexpected(response)),
+		}
+	}
+}
+
+fn unexpected(response: Response) -> TraitError {
+	TraitError::Other(format!("Unexpected response from the signer: {response:?}"))
+}
+
+fn into_signature<T: CorePair>(response: Response) -> Result<Option<T::Signature>, TraitError> {
+	match response {
+		Response::Signature(signature) => signature
+			.map(|s| {
+				T::Signature::from_slice(&s).map_err(|_| {
+					TraitError::Other("Invalid signature returned by the signer".into())
+				})
+			})
+			.transpose(),
+		response => Err(unexpected(response)),
+	}
+}
+
+fn decode<T: Decode>(encoded: Option<Vec<u8>>) -> Result<Option<T>, TraitError> {
+	encoded
+		.map(|encoded| {
+			T::decode(&mut &encoded[..])
+				.map_err(|_| TraitError::Other("Invalid data returned by the signer".into()))
+		})
+		.transpose()
+}
+
+fn generation_unsupported<T>() -> Result<T, TraitError> {
+	Err(TraitError::Other("Keys must be generated by the signer".into()))
+}
+
+impl Keystore for RemoteKeystore {
+	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys::<sr25519::Pair>(key_type, sr25519::CRYPTO_ID)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		_key_type: KeyTypeId,
+		_seed: Option<&str>,
+	) -> Result<sr25519::Public, TraitError> {
+		generation_unsupported()
+	}
+
+	fn sr25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		msg: &[u8],
+	) -> Result<Option<sr25519::Signature>, TraitError> {
+		self.sign::<sr25519::Pair>(key_type, sr25519::CRYPTO_ID, public, msg)
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		data: &sr25519::vrf::VrfSignData,
+	) -> Result<Option<sr25519::vrf::VrfSignature>, TraitError> {
+		let request = Request::Sr25519VrfSign {
+			key_type,
+			public: *public,
+			transcript: data.as_ref().data().clone(),
+			extra: data.extra().map(|extra| extra.data().clone()),
+		};
+		self.vrf_sign(request, public, data)
+	}
+
+	fn sr25519_vrf_pre_output(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		input: &sr25519::vrf::VrfInput,
+	) -> Result<Option<sr25519::vrf::VrfPreOutput>, TraitError> {
+		let request =
+			Request::Sr25519VrfPreOutput { key_type, public: *public, input: input.data().clone() };
+		self.vrf_pre_output(request)
+	}
+
+	fn ed25519_public_keys(&self, key_type: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys::<ed25519::Pair>(key_type, ed25519::CRYPTO_ID)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		_key_type: KeyTypeId,
+		_seed: Option<&str>,
+	) -> Result<ed25519::Public, TraitError> {
+		generation_unsupported()
+	}
+
+	fn ed25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ed25519::Public,
+		msg: &[u8],
+	) -> Result<Option<ed25519::Signature>, TraitError> {
+		self.sign::<ed25519::Pair>(key_type, ed25519::CRYPTO_ID, public, msg)
+	}
+
+	fn ecdsa_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys::<ecdsa::Pair>(key_type, ecdsa::CRYPTO_ID)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		_key_type: KeyTypeId,
+		_seed: Option<&str>,
+	) -> Result<ecdsa::Public, TraitError> {
+		generation_unsupported()
+	}
+
+	fn ecdsa_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8],
+	) -> Result<Option<ecdsa::Signature>, TraitError> {
+		self.sign::<ecdsa::Pair>(key_type, ecdsa::CRYPTO_ID, public, msg)
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, TraitError> {
+		let request = Request::EcdsaSignPrehashed { key_type, public: *public, msg: *msg };
+		into_signature::<ecdsa::Pair>(self.request(request)?)
+	}
+
+	sp_keystore::bandersnatch_experimental_enabled! {
+		fn bandersnatch_public_keys(&self, key_type: KeyTypeId) -> Vec<bandersnatch::Public> {
+			self.public_keys::<bandersnatch::Pair>(key_type, bandersnatch::CRYPTO_ID)
+		}
+
+		fn bandersnatch_generate_new(
+			&self,
+			_key_type: KeyTypeId,
+			_seed: Option<&str>,
+		) -> Result<bandersnatch::Public, TraitError> {
+			generation_unsupported()
+		}
+
+		fn bandersnatch_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			msg: &[u8],
+		) -> Result<Option<bandersnatch::Signature>, TraitError> {
+			self.sign::<bandersnatch::Pair>(key_type, bandersnatch::CRYPTO_ID, public, msg)
+		}
+
+		fn bandersnatch_vrf_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			data: &bandersnatch::vrf::VrfSignData,
+		) -> Result<Option<bandersnatch::vrf::VrfSignature>, TraitError> {
+			let request = Request::BandersnatchVrfSign {
+				key_type,
+				public: public.to_raw_vec(),
+				label: data.transcript_label().to_vec(),
+				transcript_data: data.transcript_data().to_vec(),
+				inputs: data
+					.inputs
+					.iter()
+					.map(|input| (input.domain().to_vec(), input.data().to_vec()))
+					.collect(),
+			};
+			self.vrf_sign(request, public, data)
+		}
+
+		fn bandersnatch_vrf_pre_output(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			input: &bandersnatch::vrf::VrfInput,
+		) -> Result<Option<bandersnatch::vrf::VrfPreOutput>, TraitError> {
+			let request = Request::BandersnatchVrfPreOutput {
+				key_type,
+				public: public.to_raw_vec(),
+				domain: input.domain().to_vec(),
+				data: input.data().to_vec(),
+			};
+			self.vrf_pre_output(request)
+		}
+
+		fn bandersnatch_ring_vrf_sign(
+			&self,
+			_key_type: KeyTypeId,
+			_public: &bandersnatch::Public,
+			_data: &bandersnatch::vrf::VrfSignData,
+			_prover: &bandersnatch::ring_vrf::RingProver,
+		) -> Result<Option<bandersnatch::ring_vrf::RingVrfSignature>, TraitError> {
+			Err(TraitError::Other("Ring VRF signing is not supported by the remote keystore".into()))
+		}
+	}
+
+	sp_keystore::bls_experimental_enabled! {
+		fn bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<bls381::Public> {
+			self.public_keys::<bls381::Pair>(key_type, bls381::CRYPTO_ID)
+		}
+
+		fn ecdsa_bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa_bls381::Public> {
+			self.public_keys::<ecdsa_bls381::Pair>(key_type, ecdsa_bls381::CRYPTO_ID)
+		}
+
+		fn bls381_generate_new(
+			&self,
+			_key_type: KeyTypeId,
+			_seed: Option<&str>,
+		) -> Result<bls381::Public, TraitError> {
+			generation_unsupported()
+		}
+
+		fn ecdsa_bls381_generate_new(
+			&self,
+			_key_type: KeyTypeId,
+			_seed: Option<&str>,
+		) -> Result<ecdsa_bls381::Public, TraitError> {
+			generation_unsupported()
+		}
+
+		fn bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bls381::Public,
+			msg: &[u8],
+		) -> Result<Option<bls381::Signature>, TraitError> {
+			self.sign::<bls381::Pair>(key_type, bls381::CRYPTO_ID, public, msg)
+		}
+
+		fn ecdsa_bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &ecdsa_bls381::Public,
+			msg: &[u8],
+		) -> Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			self.sign::<ecdsa_bls381::Pair>(key_type, ecdsa_bls381::CRYPTO_ID, public, msg)
+		}
+
+		fn ecdsa_bls381_sign_with_keccak256(
+			&self,
+			_key_type: KeyTypeId,
+			_public: &ecdsa_bls381::Public,
+			_msg: &[u8],
+		) -> Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			Err(TraitError::Other("Keccak256 signing is not supported by the remote keystore".into()))
+		}
+	}
+
+	fn insert(&self, _key_type: KeyTypeId, _suri: &str, _public: &[u8]) -> Result<(), ()> {
+		Err(())
+	}
+
+	fn keys(&self, key_type: KeyTypeId) -> Result<Vec<Vec<u8>>, TraitError> {
+		match self.request(Request::Keys(key_type))? {
+			Response::Keys(keys) => Ok(keys),
+			response => Err(unexpected(response)),
+		}
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		matches!(self.request(Request::HasKeys(public_keys.to_vec())), Ok(Response::HasKeys(true)))
+	}
+}
+
+impl Into<KeystorePtr> for RemoteKeystore {
+	fn into(self) -> KeystorePtr {
+		Arc::new(self)
+	}
+}
@@ -0,0 +1,611 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Remote keystore, forwarding the signing requests to a separate signer process.
+//!
+//! The node and the signer talk over a Unix socket or a TCP connection. Every message is SCALE
+//! encoded and prefixed with its length as a little endian `u32`. On every new connection the
+//! signer first sends a [`Hello`], holding a random challenge. Afterwards the node sends
+//! [`Envelope`]s, each answered with exactly one [`Response`].
+//!
+//! Requests are authenticated with an [`AuthKey`] shared by the signer and the node, on every
+//! transport: every envelope carries a MAC over the challenge of the connection, a strictly
+//! increasing counter and the request. A Unix socket is additionally only reachable by the user
+//! running the signer.
+//!
+//! Listing the keys, signing messages and VRF signing are supported. Merlin and ark transcripts
+//! can't be serialized, hence VRF requests carry the label and messages the transcript was built
+//! from (see [`sr25519::vrf::VrfTranscript::data`]) and the signer rebuilds it. The node verifies
+//! every returned VRF signature against its own transcript. Ring VRF signing, key generation and
+//! key insertion are not available through the remote keystore.
+
+use codec::{Decode, Encode};
+use sp_core::{
+	crypto::{CryptoTypeId, KeyTypeId},
+	ecdsa,
+	sr25519::{self, vrf::VrfTranscriptData},
+};
+use std::{
+	fmt, fs,
+	io::{self, Read, Write},
+	net::TcpStream,
+	path::{Path, PathBuf},
+	str::FromStr,
+	time::Duration,
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+mod client;
+mod protection;
+mod server;
+
+pub use client::RemoteKeystore;
+pub use protection::SlashingProtection;
+pub use server::{serve, Listener};
+
+/// Version of the protocol spoken between the remote keystore and the signer.
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Maximum size of a single message, in bytes.
+const MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Address of the signer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+	/// Unix socket at the given path.
+	Unix(PathBuf),
+	/// TCP address, as `host:port`.
+	Tcp(String),
+}
+
+impl FromStr for Endpoint {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Some(path) = s.strip_prefix("unix:") {
+			Ok(Self::Unix(path.into()))
+		} else if let Some(addr) = s.strip_prefix("tcp:") {
+			Ok(Self::Tcp(addr.into()))
+		} else {
+			Err(format!(
+				"Invalid signer endpoint `{s}`, expected `unix:<PATH>` or `tcp:<HOST:PORT>`"
+			))
+		}
+	}
+}
+
+impl fmt::Display for Endpoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Unix(path) => write!(f, "unix:{}", path.display()),
+			Self::Tcp(addr) => write!(f, "tcp:{addr}"),
+		}
+	}
+}
+
+/// Key authenticating the requests to the signer.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AuthKey([u8; 32]);
+
+impl AuthKey {
+	/// Create a key from its raw bytes.
+	pub fn new(key: [u8; 32]) -> Self {
+		Self(key)
+	}
+
+	/// Read a hex encoded key from the file at `path`.
+	pub fn from_file(path: &Path) -> io::Result<Self> {
+		let hex = fs::read_to_string(path)?;
+		array_bytes::hex2array(hex.trim()).map(Self).map_err(|_| {
+			io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("{} doesn't contain a hex encoded 32 bytes key", path.display()),
+			)
+		})
+	}
+
+	/// MAC of the `counter`th request sent over the connection with the given `challenge`.
+	fn mac(&self, challenge: &[u8; 32], counter: u64, request: &Request) -> [u8; 32] {
+		// BLAKE2 isn't subject to length extension, hence prefixing the key is a secure MAC.
+		let mut data = self.0.to_vec();
+		data.extend_from_slice(challenge);
+		data.extend_from_slice(&counter.to_le_bytes());
+		request.encode_to(&mut data);
+		sp_core::blake2_256(&data)
+	}
+
+	/// Check the MAC of a request in constant time.
+	fn verify(
+		&self,
+		challenge: &[u8; 32],
+		counter: u64,
+		request: &Request,
+		mac: &[u8; 32],
+	) -> bool {
+		self.mac(challenge, counter, request)
+			.iter()
+			.zip(mac)
+			.fold(0, |acc, (a, b)| acc | (a ^ b)) ==
+			0
+	}
+}
+
+impl fmt::Debug for AuthKey {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("AuthKey(..)")
+	}
+}
+
+/// First message of the signer on every connection.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Hello {
+	/// Protocol version of the signer.
+	pub version: u32,
+	/// Random challenge, covered by the MAC of every request sent over the connection.
+	pub challenge: [u8; 32],
+}
+
+/// A request to the signer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum Request {
+	/// List all the raw public keys of the given key type.
+	Keys(KeyTypeId),
+	/// Check that all the given keys are available.
+	HasKeys(Vec<(Vec<u8>, KeyTypeId)>),
+	/// List the raw public keys of the given key type and crypto scheme.
+	PublicKeys {
+		/// Key type of the keys.
+		key_type: KeyTypeId,
+		/// Crypto scheme of the keys.
+		crypto: CryptoTypeId,
+	},
+	/// Sign `msg` with the key of the given key type, crypto scheme and public key.
+	Sign {
+		/// Key type of the key.
+		key_type: KeyTypeId,
+		/// Crypto scheme of the key.
+		crypto: CryptoTypeId,
+		/// Raw public key.
+		public: Vec<u8>,
+		/// Message to sign.
+		msg: Vec<u8>,
+	},
+	/// Sign the pre-hashed `msg` with the given ecdsa key.
+	EcdsaSignPrehashed {
+		/// Key type of the key.
+		key_type: KeyTypeId,
+		/// Public key.
+		public: ecdsa::Public,
+		/// Hash of the message to sign.
+		msg: [u8; 32],
+	},
+	/// VRF sign the transcript with the given sr25519 key.
+	Sr25519VrfSign {
+		/// Key type of the key.
+		key_type: KeyTypeId,
+		/// Public key.
+		public: sr25519::Public,
+		/// Transcript contributing to the VRF output.
+		transcript: VrfTranscriptData,
+		/// Extra transcript signed along, not contributing to the VRF output.
+		extra: Option<VrfTranscriptData>,
+	},
+	/// Compute the VRF pre-output of the transcript with the given sr25519 key.
+	Sr25519VrfPreOutput {
+		/// Key type of the key.
+		key_type: KeyTypeId,
+		/// Public key.
+		public: sr25519::Public,
+		/// VRF input transcript.
+		input: VrfTranscriptData,
+	},
+	/// VRF sign the transcript and inputs with the given bandersnatch key.
+	BandersnatchVrfSign {
+		/// Key type of the key.
+		key_type: KeyTypeId,
+		/// Raw public key.
+		public: Vec<u8>,
+		/// Label of the transcript.
+		label: Vec<u8>,
+		/// Data appended to the transcript, in order.
+		transcript_data: Vec<Vec<u8>>,
+		/// Domain and data of the VRF inputs.
+		inputs: Vec<(Vec<u8>, Vec<u8>)>,
+	},
+	/// Compute the VRF pre-output of the input with the given bandersnatch key.
+	BandersnatchVrfPreOutput {
+		/// Key type of the key.
+		key_type: KeyTypeId,
+		/// Raw public key.
+		public: Vec<u8>,
+		/// Domain of the VRF input.
+		domain: Vec<u8>,
+		/// Data of the VRF input.
+		data: Vec<u8>,
+	},
+}
+
+/// Versioned and authenticated [`Request`], as sent on the wire.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Envelope {
+	/// Protocol version of the sender.
+	pub version: u32,
+	/// Number of the request on the connection, starting at zero.
+	pub counter: u64,
+	/// The request.
+	pub request: Request,
+	/// MAC of the request, see [`AuthKey`].
+	pub mac: [u8; 32],
+}
+
+impl Envelope {
+	/// Wrap the `counter`th request sent over the connection with the given `challenge`.
+	pub(crate) fn new(request: Request, counter: u64, challenge: &[u8; 32], key: &AuthKey) -> Self {
+		let mac = key.mac(challenge, counter, &request);
+		Self { version: PROTOCOL_VERSION, counter, request, mac }
+	}
+
+	/// Whether the request is the expected one and authenticated by `key`.
+	pub(crate) fn is_authorized(
+		&self,
+		expected_counter: u64,
+		challenge: &[u8; 32],
+		key: &AuthKey,
+	) -> bool {
+		self.counter == expected_counter &&
+			key.verify(challenge, self.counter, &self.request, &self.mac)
+	}
+}
+
+/// A response of the signer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum Response {
+	/// The requested public keys.
+	Keys(Vec<Vec<u8>>),
+	/// Whether all the requested keys are available.
+	HasKeys(bool),
+	/// SCALE encoded signature, `None` if the key isn't available.
+	Signature(Option<Vec<u8>>),
+	/// SCALE encoded VRF pre-output, `None` if the key isn't available.
+	VrfPreOutput(Option<Vec<u8>>),
+	/// Signing was refused by the slashing protection.
+	Refused(String),
+	/// The request failed.
+	Error(String),
+	/// The protocol version of the request isn't supported, the signer speaks the given one.
+	UnsupportedVersion(u32),
+	/// The request isn't authenticated. The signer closes the connection.
+	Unauthorized,
+}
+
+/// Connection to the signer.
+pub(crate) enum Stream {
+	#[cfg(unix)]
+	Unix(UnixStream),
+	Tcp(TcpStream),
+}
+
+impl Read for Stream {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		match self {
+			#[cfg(unix)]
+			Self::Unix(stream) => stream.read(buf),
+			Self::Tcp(stream) => stream.read(buf),
+		}
+	}
+}
+
+impl Stream {
+	/// Set the read and write timeouts of the connection.
+	pub(crate) fn set_timeouts(&self, read: Duration, write: Duration) -> io::Result<()> {
+		match self {
+			#[cfg(unix)]
+			Self::Unix(stream) => {
+				stream.set_read_timeout(Some(read))?;
+				stream.set_write_timeout(Some(write))
+			},
+			Self::Tcp(stream) => {
+				stream.set_read_timeout(Some(read))?;
+				stream.set_write_timeout(Some(write))
+			},
+		}
+	}
+}
+
+impl Write for Stream {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		match self {
+			#[cfg(unix)]
+			Self::Unix(stream) => stream.write(buf),
+			Self::Tcp(stream) => stream.write(buf),
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		match self {
+			#[cfg(unix)]
+			Self::Unix(stream) => stream.flush(),
+			Self::Tcp(stream) => stream.flush(),
+		}
+	}
+}
+
+/// Write a length prefixed message.
+pub(crate) fn write_message(stream: &mut impl Write, message: &impl Encode) -> io::Result<()> {
+	let encoded = message.encode();
+	let len = u32::try_from(encoded.len())
+		.ok()
+		.filter(|len| *len <= MAX_MESSAGE_SIZE)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Message too large"))?;
+	stream.write_all(&len.to_le_bytes())?;
+	stream.write_all(&encoded)?;
+	stream.flush()
+}
+
+/// Read a length prefixed message.
+pub(crate) fn read_message<T: Decode>(stream: &mut impl Read) -> io::Result<T> {
+	let mut len = [0u8; 4];
+	stream.read_exact(&mut len)?;
+	let len = u32::from_le_bytes(len);
+	if len > MAX_MESSAGE_SIZE {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "Message too large"))
+	}
+	let mut buf = vec![0u8; len as usize];
+	stream.read_exact(&mut buf)?;
+	T::decode(&mut &buf[..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::LocalKeystore;
+	use sp_core::{ed25519, sr25519, Pair};
+	use sp_keystore::Keystore;
+	use std::sync::Arc;
+	use tempfile::TempDir;
+
+	const BABE: KeyTypeId = KeyTypeId(*b"babe");
+	const GRANDPA: KeyTypeId = KeyTypeId(*b"gran");
+
+	fn auth_key() -> AuthKey {
+		AuthKey::new([7; 32])
+	}
+
+	fn spawn_signer(
+		endpoint: impl FnOnce(&Path) -> Endpoint,
+		protection: Option<SlashingProtection>,
+	) -> (TempDir, Endpoint, Arc<LocalKeystore>) {
+		let temp_dir = TempDir::new().unwrap();
+		let keystore = Arc::new(LocalKeystore::open(temp_dir.path().join("keys"), None).unwrap());
+		let listener = Listener::bind(&endpoint(temp_dir.path())).unwrap();
+		let endpoint = listener.local_endpoint().unwrap();
+		let signer_keystore = keystore.clone();
+		std::thread::spawn(move || {
+			serve(listener, signer_keystore, auth_key(), protection.map(Arc::new))
+		});
+		(temp_dir, endpoint, keystore)
+	}
+
+	fn unix_socket(dir: &Path) -> Endpoint {
+		Endpoint::Unix(dir.join("signer.sock"))
+	}
+
+	fn tcp_socket(_: &Path) -> Endpoint {
+		Endpoint::Tcp("127.0.0.1:0".into())
+	}
+
+	#[test]
+	fn remote_keystore_forwards_requests() {
+		let (_dir, endpoint, local) = spawn_signer(unix_socket, None);
+		let remote = RemoteKeystore::new(endpoint, auth_key());
+
+		let babe = local.sr25519_generate_new(BABE, None).unwrap();
+		let grandpa = local.ed25519_generate_new(GRANDPA, None).unwrap();
+
+		assert_eq!(remote.sr25519_public_keys(BABE), vec![babe]);
+		assert_eq!(remote.ed25519_public_keys(GRANDPA), vec![grandpa]);
+		assert!(remote.ed25519_public_keys(BABE).is_empty());
+		assert_eq!(remote.keys(BABE).unwrap(), vec![babe.to_vec()]);
+		assert!(remote.has_keys(&[(babe.to_vec(), BABE), (grandpa.to_vec(), GRANDPA)]));
+		assert!(!remote.has_keys(&[(babe.to_vec(), GRANDPA)]));
+
+		let msg = b"hello";
+		let signature = remote.sr25519_sign(BABE, &babe, msg).unwrap().unwrap();
+		assert!(sr25519::Pair::verify(&signature, msg, &babe));
+		let signature = remote.ed25519_sign(GRANDPA, &grandpa, msg).unwrap().unwrap();
+		assert!(ed25519::Pair::verify(&signature, msg, &grandpa));
+
+		// unknown keys are reported as missing
+		let unknown = sr25519::Pair::generate().0.public();
+		assert_eq!(remote.sr25519_sign(BABE, &unknown, msg).unwrap(), None);
+	}
+
+	#[test]
+	fn remote_keystore_signs_vrf() {
+		use sp_core::crypto::VrfPublic;
+		use sr25519::vrf::{VrfSignData, VrfTranscript};
+
+		let (_dir, endpoint, local) = spawn_signer(unix_socket, None);
+		let remote = RemoteKeystore::new(endpoint, auth_key());
+		let babe = local.sr25519_generate_new(BABE, None).unwrap();
+
+		let input = VrfTranscript::new(b"label", &[(b"domain", b"data"), (b"slot", &[1, 2])]);
+		let extra = VrfTranscript::new(b"extra", &[(b"domain", b"extra data")]);
+		let data = VrfSignData::new(input.clone()).with_extra(extra);
+
+		let signature = remote.sr25519_vrf_sign(BABE, &babe, &data).unwrap().unwrap();
+		assert!(babe.vrf_verify(&data, &signature));
+		let pre_output = remote.sr25519_vrf_pre_output(BABE, &babe, &input).unwrap().unwrap();
+		assert_eq!(pre_output, signature.pre_output);
+		assert_eq!(local.sr25519_vrf_pre_output(BABE, &babe, &input).unwrap(), Some(pre_output));
+
+		// a transcript modified after its construction can't be rebuilt by the signer
+		let mut modified = input.clone();
+		modified.0.append_message(b"more", b"data");
+		assert!(remote.sr25519_vrf_sign(BABE, &babe, &modified.into_sign_data()).is_err());
+
+		let unknown = sr25519::Pair::generate().0.public();
+		assert_eq!(remote.sr25519_vrf_sign(BABE, &unknown, &data).unwrap(), None);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn unix_socket_is_private() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let (dir, _endpoint, _local) = spawn_signer(unix_socket, None);
+		let metadata = std::fs::metadata(dir.path().join("signer.sock")).unwrap();
+		assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn unix_socket_requires_private_directory() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let dir = TempDir::new().unwrap();
+		let private = dir.path().join("private");
+		Listener::bind(&unix_socket(&private)).unwrap();
+		let metadata = std::fs::metadata(&private).unwrap();
+		assert_eq!(metadata.permissions().mode() & 0o777, 0o700);
+
+		let shared = dir.path().join("shared");
+		std::fs::create_dir(&shared).unwrap();
+		std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o755)).unwrap();
+		let err = Listener::bind(&unix_socket(&shared)).err().unwrap();
+		assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+		assert!(!shared.join("signer.sock").exists());
+	}
+
+	#[test]
+	fn remote_keystore_signs_ecdsa_prehashed() {
+		let (_dir, endpoint, local) = spawn_signer(unix_socket, None);
+		let remote = RemoteKeystore::new(endpoint, auth_key());
+
+		let key_type = KeyTypeId(*b"beef");
+		let public = local.ecdsa_generate_new(key_type, None).unwrap();
+		let msg = sp_core::keccak_256(b"hello");
+		let signature = remote.ecdsa_sign_prehashed(key_type, &public, &msg).unwrap().unwrap();
+		assert!(ecdsa::Pair::verify_prehashed(&signature, &msg, &public));
+	}
+
+	#[test]
+	fn requests_are_authenticated() {
+		let endpoints: [fn(&Path) -> Endpoint; 2] = [unix_socket, tcp_socket];
+		for endpoint in endpoints {
+			let (_dir, endpoint, local) = spawn_signer(endpoint, None);
+			let babe = local.sr25519_generate_new(BABE, None).unwrap();
+
+			let remote = RemoteKeystore::new(endpoint.clone(), auth_key());
+			assert!(remote.sr25519_sign(BABE, &babe, b"hello").unwrap().is_some());
+			assert!(remote.sr25519_sign(BABE, &babe, b"hello again").unwrap().is_some());
+
+			let remote = RemoteKeystore::new(endpoint, AuthKey::new([8; 32]));
+			assert!(remote.sr25519_sign(BABE, &babe, b"hello").is_err());
+			assert!(remote.sr25519_public_keys(BABE).is_empty());
+		}
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn connections_are_limited() {
+		let (_dir, endpoint, _local) = spawn_signer(unix_socket, None);
+		let Endpoint::Unix(path) = endpoint else { unreachable!() };
+
+		let connections = (0..server::MAX_CONNECTIONS)
+			.map(|_| {
+				let mut stream = Stream::Unix(UnixStream::connect(&path).unwrap());
+				read_message::<Hello>(&mut stream).unwrap();
+				stream
+			})
+			.collect::<Vec<_>>();
+		let mut stream = Stream::Unix(UnixStream::connect(&path).unwrap());
+		assert!(read_message::<Hello>(&mut stream).is_err());
+
+		drop(connections);
+		// the slots are released once the connections are closed
+		let remote = RemoteKeystore::new(Endpoint::Unix(path), auth_key());
+		let mut released = false;
+		for _ in 0..100 {
+			if remote.keys(BABE).is_ok() {
+				released = true;
+				break
+			}
+			std::thread::sleep(std::time::Duration::from_millis(10));
+		}
+		assert!(released);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn replayed_requests_are_refused() {
+		let auth_key = auth_key();
+		let (_dir, endpoint, local) = spawn_signer(unix_socket, None);
+		let babe = local.sr25519_generate_new(BABE, None).unwrap();
+		let Endpoint::Unix(path) = endpoint else { unreachable!() };
+
+		let mut stream = Stream::Unix(UnixStream::connect(path).unwrap());
+		let hello = read_message::<Hello>(&mut stream).unwrap();
+		let request = Request::Sign {
+			key_type: BABE,
+			crypto: sr25519::CRYPTO_ID,
+			public: babe.to_vec(),
+			msg: b"hello".to_vec(),
+		};
+		let envelope = Envelope::new(request, 0, &hello.challenge, &auth_key);
+
+		write_message(&mut stream, &envelope).unwrap();
+		assert!(matches!(read_message(&mut stream).unwrap(), Response::Signature(Some(_))));
+		write_message(&mut stream, &envelope).unwrap();
+		assert_eq!(read_message::<Response>(&mut stream).unwrap(), Response::Unauthorized);
+	}
+
+	#[test]
+	fn slashing_protection_refuses_conflicting_votes() {
+		let protection = SlashingProtection::new(GRANDPA, None).unwrap();
+		let (_dir, endpoint, local) = spawn_signer(unix_socket, Some(protection));
+		let remote = RemoteKeystore::new(endpoint, auth_key());
+
+		let babe = local.sr25519_generate_new(BABE, None).unwrap();
+		let grandpa = local.ed25519_generate_new(GRANDPA, None).unwrap();
+		let prevote = |target: [u8; 32], round: u64| {
+			let mut payload = vec![0u8];
+			(target, 10u32, round, 1u64).encode_to(&mut payload);
+			payload
+		};
+
+		assert!(remote.ed25519_sign(GRANDPA, &grandpa, &prevote([1; 32], 5)).unwrap().is_some());
+		// signing the same vote again is fine
+		assert!(remote.ed25519_sign(GRANDPA, &grandpa, &prevote([1; 32], 5)).unwrap().is_some());
+		// signing another vote in the same round is refused
+		assert!(remote.ed25519_sign(GRANDPA, &grandpa, &prevote([2; 32], 5)).is_err());
+		assert!(remote.ed25519_sign(GRANDPA, &grandpa, &prevote([2; 32], 6)).unwrap().is_some());
+		// other key types are not protected
+		assert!(remote.sr25519_sign(BABE, &babe, b"header 1").unwrap().is_some());
+		assert!(remote.sr25519_sign(BABE, &babe, b"header 2").unwrap().is_some());
+	}
+
+	#[test]
+	fn endpoint_parsing_works() {
+		assert_eq!("unix:/tmp/signer.sock".parse(), Ok(Endpoint::Unix("/tmp/signer.sock".into())));
+		assert_eq!("tcp:127.0.0.1:9955".parse(), Ok(Endpoint::Tcp("127.0.0.1:9955".into())));
+		assert!("127.0.0.1:9955".parse::<Endpoint>().is_err());
+	}
+}
@@ -0,0 +1,213 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Slashing protection of the signer.
+
+use crate::Result;
+use parking_lot::Mutex;
+use sp_core::crypto::KeyTypeId;
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+/// Number of rounds of the current set for which the signed votes are remembered.
+const KEPT_ROUNDS: u64 = 64;
+
+/// A GRANDPA vote, as decoded from the signed payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Vote {
+	set_id: u64,
+	round: u64,
+	/// Index of the message variant: prevote, precommit or primary propose.
+	stage: u8,
+}
+
+impl Vote {
+	/// Decode the vote from a GRANDPA payload, SCALE encoded as `(message, round, set_id)`.
+	fn decode(payload: &[u8]) -> Option<Self> {
+		let stage = *payload.first()?;
+		let trailer = payload.len().checked_sub(16).filter(|len| *len > 0)?;
+		let round = u64::from_le_bytes(payload[trailer..trailer + 8].try_into().ok()?);
+		let set_id = u64::from_le_bytes(payload[trailer + 8..].try_into().ok()?);
+		Some(Self { set_id, round, stage })
+	}
+}
+
+/// Map over `Raw public key` -> `Vote` -> `Hash of the signed payload`.
+type SignedVotes = BTreeMap<Vec<u8>, BTreeMap<Vote, [u8; 32]>>;
+
+/// Slashing protection refusing to sign two different GRANDPA votes of the same stage in a round.
+///
+/// The set id, round and stage of the vote are read from the signed payload, hence the protection
+/// doesn't depend on the clocks of the node or the signer. Votes for rounds which are older than
+/// the remembered ones are refused as well.
+///
+/// Slot based authoring (e.g. BABE or Aura) only sends the hash of the header to the signer, which
+/// doesn't reveal the slot. It is protected by the slashing protection database of the node.
+///
+/// The signed votes are optionally persisted to a file, so the protection holds across restarts
+/// of the signer.
+pub struct SlashingProtection {
+	key_type: KeyTypeId,
+	path: Option<PathBuf>,
+	signed: Mutex<SignedVotes>,
+}
+
+impl SlashingProtection {
+	/// Create a new slashing protection of the GRANDPA votes signed with the keys of `key_type`,
+	/// persisting its state at `path` if given.
+	pub fn new(key_type: KeyTypeId, path: Option<PathBuf>) -> Result<Self> {
+		let mut signed = SignedVotes::new();
+		if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+			let entries: Vec<(String, u64, u64, u8, String)> =
+				serde_json::from_slice(&fs::read(path)?)?;
+			for (public, set_id, round, stage, hash) in entries {
+				let (Ok(public), Ok(hash)) =
+					(array_bytes::hex2bytes(&public), array_bytes::hex2array(&hash))
+				else {
+					continue
+				};
+				signed.entry(public).or_default().insert(Vote { set_id, round, stage }, hash);
+			}
+		}
+		Ok(Self { key_type, path, signed: Mutex::new(signed) })
+	}
+
+	/// Check that `msg` may be signed with the given key, and record it as signed.
+	///
+	/// Returns the reason of the refusal otherwise.
+	pub fn check(
+		&self,
+		key_type: KeyTypeId,
+		public: &[u8],
+		msg: &[u8],
+	) -> std::result::Result<(), String> {
+		if key_type != self.key_type {
+			return Ok(())
+		}
+		let vote = Vote::decode(msg).ok_or_else(|| "Not a GRANDPA vote".to_string())?;
+		let hash = sp_core::blake2_256(msg);
+
+		let mut signed = self.signed.lock();
+		let votes = signed.entry(public.to_vec()).or_default();
+		match votes.get(&vote) {
+			Some(signed_hash) if *signed_hash == hash => return Ok(()),
+			Some(_) =>
+				return Err(format!(
+					"Already signed another vote of stage {} in round {} of set {}",
+					vote.stage, vote.round, vote.set_id
+				)),
+			None => {},
+		}
+		if let Some((oldest, _)) = votes.first_key_value() {
+			if (vote.set_id, vote.round) < (oldest.set_id, oldest.round) {
+				return Err(format!(
+					"Round {} of set {} is older than the remembered votes",
+					vote.round, vote.set_id
+				))
+			}
+		}
+
+		votes.insert(vote, hash);
+		// Only remember the recent rounds of the latest set.
+		let (latest_set, latest_round) = votes
+			.last_key_value()
+			.map(|(v, _)| (v.set_id, v.round))
+			.expect("Inserted above; qed");
+		votes.retain(|v, _| {
+			v.set_id == latest_set && v.round.saturating_add(KEPT_ROUNDS) > latest_round
+		});
+
+		self.persist(&signed)
+			.map_err(|e| format!("Failed to persist the slashing protection state: {e}"))
+	}
+
+	fn persist(&self, signed: &SignedVotes) -> Result<()> {
+		let Some(path) = &self.path else { return Ok(()) };
+		let entries = signed
+			.iter()
+			.flat_map(|(public, votes)| {
+				votes.iter().map(|(vote, hash)| {
+					(
+						array_bytes::bytes2hex("", public),
+						vote.set_id,
+						vote.round,
+						vote.stage,
+						array_bytes::bytes2hex("", hash),
+					)
+				})
+			})
+			.collect::<Vec<_>>();
+		let tmp = path.with_extension("tmp");
+		fs::write(&tmp, serde_json::to_vec(&entries)?)?;
+		fs::File::open(&tmp)?.sync_all()?;
+		fs::rename(tmp, path)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use tempfile::TempDir;
+
+	const GRANDPA: KeyTypeId = KeyTypeId(*b"gran");
+
+	/// Payload of a vote for the given target, like `sp_consensus_grandpa::localized_payload`.
+	fn vote(stage: u8, target: [u8; 32], round: u64, set_id: u64) -> Vec<u8> {
+		let mut payload = vec![stage];
+		(target, 10u32, round, set_id).encode_to(&mut payload);
+		payload
+	}
+
+	#[test]
+	fn conflicting_votes_are_refused() {
+		let protection = SlashingProtection::new(GRANDPA, None).unwrap();
+
+		assert_eq!(protection.check(GRANDPA, b"alice", &vote(0, [1; 32], 5, 1)), Ok(()));
+		// signing the same vote again is fine
+		assert_eq!(protection.check(GRANDPA, b"alice", &vote(0, [1; 32], 5, 1)), Ok(()));
+		// another prevote in the same round is refused
+		assert!(protection.check(GRANDPA, b"alice", &vote(0, [2; 32], 5, 1)).is_err());
+		// the precommit of the round and the votes of the next round are fine
+		assert_eq!(protection.check(GRANDPA, b"alice", &vote(1, [2; 32], 5, 1)), Ok(()));
+		assert_eq!(protection.check(GRANDPA, b"alice", &vote(0, [2; 32], 6, 1)), Ok(()));
+		// other keys and key types are not affected
+		assert_eq!(protection.check(GRANDPA, b"bob", &vote(0, [2; 32], 5, 1)), Ok(()));
+		assert_eq!(protection.check(KeyTypeId(*b"babe"), b"alice", b"header"), Ok(()));
+		// a new set forgets the votes of the previous one, which can't be signed anymore
+		assert_eq!(protection.check(GRANDPA, b"alice", &vote(0, [3; 32], 1, 2)), Ok(()));
+		assert!(protection.check(GRANDPA, b"alice", &vote(1, [3; 32], 6, 1)).is_err());
+		// garbage is refused
+		assert!(protection.check(GRANDPA, b"alice", b"header").is_err());
+	}
+
+	#[test]
+	fn state_is_persisted() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("protection.json");
+
+		let protection = SlashingProtection::new(GRANDPA, Some(path.clone())).unwrap();
+		assert_eq!(protection.check(GRANDPA, b"alice", &vote(0, [1; 32], 5, 1)), Ok(()));
+		drop(protection);
+
+		let protection = SlashingProtection::new(GRANDPA, Some(path)).unwrap();
+		assert_eq!(protection.check(GRANDPA, b"alice", &vote(0, [1; 32], 5, 1)), Ok(()));
+		assert!(protection.check(GRANDPA, b"alice", &vote(0, [2; 32], 5, 1)).is_err());
+		assert_eq!(protection.check(GRANDPA, b"bob", &vote(0, [2; 32], 5, 1)), Ok(()));
+	}
+}
@@ -0,0 +1,461 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Signer side of the remote keystore.
+
+use super::{
+	read_message, write_message, AuthKey, Endpoint, Envelope, Hello, Request, Response,
+	SlashingProtection, Stream, PROTOCOL_VERSION,
+};
+use codec::Encode;
+use sp_core::{
+	crypto::{ByteArray, CryptoTypeId, KeyTypeId},
+	ecdsa, ed25519,
+	sr25519::{
+		self,
+		vrf::{VrfSignData, VrfTranscript, VrfTranscriptData},
+	},
+};
+use sp_keystore::{Keystore, KeystorePtr};
+use std::{
+	collections::BTreeSet,
+	io,
+	net::TcpListener,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex, PoisonError,
+	},
+	time::Duration,
+};
+
+#[cfg(unix)]
+use std::os::unix::{
+	fs::{DirBuilderExt, PermissionsExt},
+	net::UnixListener,
+};
+
+const LOG_TARGET: &str = "keystore::signer";
+
+/// Maximum number of connections served at once. Further connections are closed right away.
+pub(super) const MAX_CONNECTIONS: usize = 64;
+
+/// Connections without any request for this long are closed.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Timeout of writing a message to a connection.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of distinct transcript labels kept by the signer.
+const MAX_LABELS: usize = 1024;
+
+/// Maximum length of a transcript label, in bytes.
+const MAX_LABEL_LEN: usize = 64;
+
+/// Listener accepting the connections of the remote keystores.
+pub enum Listener {
+	/// Listener on a Unix socket.
+	#[cfg(unix)]
+	Unix(UnixListener),
+	/// Listener on a TCP address.
+	Tcp(TcpListener),
+}
+
+impl Listener {
+	/// Listen on the given `endpoint`.
+	///
+	/// A Unix socket must be placed in a directory only accessible by the user running the
+	/// signer, so that no other user can connect to it, even before its own permissions are
+	/// restricted. The directory is created if missing. A stale socket left at the endpoint path is
+	/// removed. The nodes are expected to run as the same user as the signer.
+	pub fn bind(endpoint: &Endpoint) -> io::Result<Self> {
+		match endpoint {
+			#[cfg(unix)]
+			Endpoint::Unix(path) => {
+				let dir = path
+					.parent()
+					.filter(|dir| !dir.as_os_str().is_empty())
+					.unwrap_or(std::path::Path::new("."));
+				std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+				if std::fs::metadata(dir)?.permissions().mode() & 0o077 != 0 {
+					return Err(io::Error::new(
+						io::ErrorKind::PermissionDenied,
+						format!(
+							"The directory of the signer socket, {}, must only be accessible by \
+							 its owner (mode 0700)",
+							dir.display()
+						),
+					))
+				}
+				if path.exists() {
+					std::fs::remove_file(path)?;
+				}
+				let listener = UnixListener::bind(path)?;
+				std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+				Ok(Self::Unix(listener))
+			},
+			#[cfg(not(unix))]
+			Endpoint::Unix(_) => Err(io::Error::new(
+				io::ErrorKind::Unsupported,
+				"Unix sockets are not supported on this platform",
+			)),
+			Endpoint::Tcp(addr) => Ok(Self::Tcp(TcpListener::bind(addr)?)),
+		}
+	}
+
+	/// Returns the endpoint the listener is bound to.
+	pub fn local_endpoint(&self) -> io::Result<Endpoint> {
+		match self {
+			#[cfg(unix)]
+			Self::Unix(listener) => listener
+				.local_addr()?
+				.as_pathname()
+				.map(|path| Endpoint::Unix(path.into()))
+				.ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Unnamed Unix socket")),
+			Self::Tcp(listener) => Ok(Endpoint::Tcp(listener.local_addr()?.to_string())),
+		}
+	}
+
+	fn accept(&self) -> io::Result<Stream> {
+		match self {
+			#[cfg(unix)]
+			Self::Unix(listener) => listener.accept().map(|(stream, _)| Stream::Unix(stream)),
+			Self::Tcp(listener) => listener.accept().map(|(stream, _)| Stream::Tcp(stream)),
+		}
+	}
+}
+
+/// Serve the signing requests of the remote keystores connecting to `listener`, using the keys
+/// of `keystore`.
+///
+/// Only the requests authenticated with `auth_key` are served, whatever the transport.
+///
+/// Every connection is served by a dedicated thread, at most 64 at once. Connections idle for
+/// longer than a minute are closed. Returns only if accepting a connection fails.
+pub fn serve(
+	listener: Listener,
+	keystore: KeystorePtr,
+	auth_key: AuthKey,
+	protection: Option<Arc<SlashingProtection>>,
+) -> io::Result<()> {
+	let auth_key = Arc::new(auth_key);
+	let connections = Arc::new(AtomicUsize::new(0));
+	loop {
+		let stream = listener.accept()?;
+		let Some(slot) = ConnectionSlot::acquire(&connections) else {
+			log::warn!(
+				target: LOG_TARGET,
+				"Refusing connection, {MAX_CONNECTIONS} connections are served already"
+			);
+			continue
+		};
+		let keystore = keystore.clone();
+		let auth_key = auth_key.clone();
+		let protection = protection.clone();
+		std::thread::spawn(move || {
+			let _slot = slot;
+			log::debug!(target: LOG_TARGET, "Remote keystore connected");
+			if let Err(e) = serve_connection(stream, &*keystore, &auth_key, protection.as_deref()) {
+				log::warn!(target: LOG_TARGET, "Remote keystore connection failed: {e}");
+			}
+			log::debug!(target: LOG_TARGET, "Remote keystore disconnected");
+		});
+	}
+}
+
+/// One of the [`MAX_CONNECTIONS`] connections served at once, released when dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+	fn acquire(connections: &Arc<AtomicUsize>) -> Option<Self> {
+		connections
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+				(n < MAX_CONNECTIONS).then_some(n + 1)
+			})
+			.ok()
+			.map(|_| Self(connections.clone()))
+	}
+}
+
+impl Drop for ConnectionSlot {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+fn serve_connection(
+	mut stream: Stream,
+	keystore: &dyn Keystore,
+	auth_key: &AuthKey,
+	protection: Option<&SlashingProtection>,
+) -> io::Result<()> {
+	stream.set_timeouts(READ_TIMEOUT, WRITE_TIMEOUT)?;
+	let challenge = rand::random::<[u8; 32]>();
+	write_message(&mut stream, &Hello { version: PROTOCOL_VERSION, challenge })?;
+
+	for counter in 0.. {
+		let envelope = match read_message::<Envelope>(&mut stream) {
+			Ok(envelope) => envelope,
+			Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+			Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+				log::debug!(target: LOG_TARGET, "Closing idle remote keystore connection");
+				return Ok(())
+			},
+			Err(e) => return Err(e),
+		};
+		if envelope.version != PROTOCOL_VERSION {
+			write_message(&mut stream, &Response::UnsupportedVersion(PROTOCOL_VERSION))?;
+			continue
+		}
+		if !envelope.is_authorized(counter, &challenge, auth_key) {
+			log::warn!(target: LOG_TARGET, "Refusing unauthenticated request");
+			write_message(&mut stream, &Response::Unauthorized)?;
+			return Ok(())
+		}
+		let response = handle_request(keystore, protection, envelope.request);
+		write_message(&mut stream, &response)?;
+	}
+	Ok(())
+}
+
+fn public_keys(keystore: &dyn Keystore, key_type: KeyTypeId, crypto: CryptoTypeId) -> Vec<Vec<u8>> {
+	match crypto {
+		sr25519::CRYPTO_ID => keystore
+			.sr25519_public_keys(key_type)
+			.iter()
+			.map(ByteArray::to_raw_vec)
+			.collect(),
+		ed25519::CRYPTO_ID => keystore
+			.ed25519_public_keys(key_type)
+			.iter()
+			.map(ByteArray::to_raw_vec)
+			.collect(),
+		ecdsa::CRYPTO_ID =>
+			keystore.ecdsa_public_keys(key_type).iter().map(ByteArray::to_raw_vec).collect(),
+		#[cfg(feature = "bandersnatch-experimental")]
+		sp_core::bandersnatch::CRYPTO_ID => keystore
+			.bandersnatch_public_keys(key_type)
+			.iter()
+			.map(ByteArray::to_raw_vec)
+			.collect(),
+		#[cfg(feature = "bls-experimental")]
+		sp_core::bls381::CRYPTO_ID => keystore
+			.bls381_public_keys(key_type)
+			.iter()
+			.map(ByteArray::to_raw_vec)
+			.collect(),
+		#[cfg(feature = "bls-experimental")]
+		sp_core::ecdsa_bls381::CRYPTO_ID => keystore
+			.ecdsa_bls381_public_keys(key_type)
+			.iter()
+			.map(ByteArray::to_raw_vec)
+			.collect(),
+		_ => Vec::new(),
+	}
+}
+
+fn handle_request(
+	keystore: &dyn Keystore,
+	protection: Option<&SlashingProtection>,
+	request: Request,
+) -> Response {
+	let check = |key_type, public: &[u8], msg: &[u8]| {
+		protection.map_or(Ok(()), |protection| protection.check(key_type, public, msg))
+	};
+
+	let result = match request {
+		Request::Keys(key_type) => keystore.keys(key_type).map(Response::Keys),
+		Request::HasKeys(keys) => Ok(Response::HasKeys(keystore.has_keys(&keys))),
+		Request::PublicKeys { key_type, crypto } =>
+			Ok(Response::Keys(public_keys(keystore, key_type, crypto))),
+		Request::Sign { key_type, crypto, public, msg } => {
+			if let Err(reason) = check(key_type, &public, &msg) {
+				return Response::Refused(reason)
+			}
+			keystore.sign_with(key_type, crypto, &public, &msg).map(Response::Signature)
+		},
+		Request::EcdsaSignPrehashed { key_type, public, msg } => {
+			if let Err(reason) = check(key_type, public.as_ref(), &msg) {
+				return Response::Refused(reason)
+			}
+			keystore
+				.ecdsa_sign_prehashed(key_type, &public, &msg)
+				.map(|signature| Response::Signature(signature.map(|s| s.encode())))
+		},
+		Request::Sr25519VrfSign { key_type, public, transcript, extra } => {
+			let data = match sr25519_sign_data(&transcript, extra.as_ref()) {
+				Ok(data) => data,
+				Err(e) => return Response::Error(e),
+			};
+			keystore
+				.sr25519_vrf_sign(key_type, &public, &data)
+				.map(|signature| Response::Signature(signature.map(|s| s.encode())))
+		},
+		Request::Sr25519VrfPreOutput { key_type, public, input } => {
+			let input = match sr25519_transcript(&input) {
+				Ok(input) => input,
+				Err(e) => return Response::Error(e),
+			};
+			keystore
+				.sr25519_vrf_pre_output(key_type, &public, &input)
+				.map(|pre_output| Response::VrfPreOutput(pre_output.map(|p| p.encode())))
+		},
+		Request::BandersnatchVrfSign { key_type, public, label, transcript_data, inputs } =>
+			return bandersnatch::vrf_sign(
+				keystore,
+				key_type,
+				&public,
+				&label,
+				transcript_data,
+				inputs,
+			),
+		Request::BandersnatchVrfPreOutput { key_type, public, domain, data } =>
+			return bandersnatch::vrf_pre_output(keystore, key_type, &public, &domain, &data),
+	};
+
+	result.unwrap_or_else(|e| Response::Error(e.to_string()))
+}
+
+/// Returns a `'static` copy of a transcript label.
+///
+/// Transcripts only take `'static` labels, hence every distinct label received from the nodes is
+/// leaked once and reused afterwards. The VRFs of the protocols use a few fixed labels, the bounds
+/// only keep a misbehaving node from growing the memory of the signer.
+fn intern(label: &[u8]) -> Result<&'static [u8], String> {
+	static LABELS: Mutex<BTreeSet<&'static [u8]>> = Mutex::new(BTreeSet::new());
+
+	if label.len() > MAX_LABEL_LEN {
+		return Err(format!("Transcript label longer than {MAX_LABEL_LEN} bytes"))
+	}
+	let mut labels = LABELS.lock().unwrap_or_else(PoisonError::into_inner);
+	if let Some(label) = labels.get(label) {
+		return Ok(*label)
+	}
+	if labels.len() >= MAX_LABELS {
+		return Err(format!("More than {MAX_LABELS} distinct transcript labels"))
+	}
+	let label: &'static [u8] = Box::leak(label.to_vec().into_boxed_slice());
+	labels.insert(label);
+	Ok(label)
+}
+
+fn sr25519_transcript(data: &VrfTranscriptData) -> Result<VrfTranscript, String> {
+	let items = data
+		.items
+		.iter()
+		.map(|(label, msg)| Ok((intern(label)?, &msg[..])))
+		.collect::<Result<Vec<_>, String>>()?;
+	Ok(VrfTranscript::new(intern(&data.label)?, &items))
+}
+
+fn sr25519_sign_data(
+	transcript: &VrfTranscriptData,
+	extra: Option<&VrfTranscriptData>,
+) -> Result<VrfSignData, String> {
+	let data = VrfSignData::new(sr25519_transcript(transcript)?);
+	Ok(match extra {
+		Some(extra) => data.with_extra(sr25519_transcript(extra)?),
+		None => data,
+	})
+}
+
+#[cfg(feature = "bandersnatch-experimental")]
+mod bandersnatch {
+	use super::*;
+	use sp_core::bandersnatch::{
+		vrf::{VrfInput, VrfSignData, MAX_VRF_IOS},
+		Public,
+	};
+
+	fn public(public: &[u8]) -> Result<Public, Response> {
+		Public::from_slice(public)
+			.map_err(|_| Response::Error("Invalid bandersnatch public key".into()))
+	}
+
+	pub(super) fn vrf_sign(
+		keystore: &dyn Keystore,
+		key_type: KeyTypeId,
+		public_key: &[u8],
+		label: &[u8],
+		transcript_data: Vec<Vec<u8>>,
+		inputs: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> Response {
+		let public = match public(public_key) {
+			Ok(public) => public,
+			Err(response) => return response,
+		};
+		let label = match intern(label) {
+			Ok(label) => label,
+			Err(e) => return Response::Error(e),
+		};
+		let inputs = inputs.iter().map(|(domain, data)| VrfInput::new(domain, data));
+		let Ok(data) = VrfSignData::new(label, transcript_data, inputs) else {
+			return Response::Error(format!("More than {MAX_VRF_IOS} VRF inputs"))
+		};
+		keystore
+			.bandersnatch_vrf_sign(key_type, &public, &data)
+			.map(|signature| Response::Signature(signature.map(|s| s.encode())))
+			.unwrap_or_else(|e| Response::Error(e.to_string()))
+	}
+
+	pub(super) fn vrf_pre_output(
+		keystore: &dyn Keystore,
+		key_type: KeyTypeId,
+		public_key: &[u8],
+		domain: &[u8],
+		data: &[u8],
+	) -> Response {
+		let public = match public(public_key) {
+			Ok(public) => public,
+			Err(response) => return response,
+		};
+		keystore
+			.bandersnatch_vrf_pre_output(key_type, &public, &VrfInput::new(domain, data))
+			.map(|pre_output| Response::VrfPreOutput(pre_output.map(|p| p.encode())))
+			.unwrap_or_else(|e| Response::Error(e.to_string()))
+	}
+}
+
+#[cfg(not(feature = "bandersnatch-experimental"))]
+mod bandersnatch {
+	use super::*;
+
+	fn unsupported() -> Response {
+		Response::Error("Bandersnatch keys are not supported by the signer".into())
+	}
+
+	pub(super) fn vrf_sign(
+		_keystore: &dyn Keystore,
+		_key_type: KeyTypeId,
+		_public: &[u8],
+		_label: &[u8],
+		_transcript_data: Vec<Vec<u8>>,
+		_inputs: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> Response {
+		unsupported()
+	}
+
+	pub(super) fn vrf_pre_output(
+		_keystore: &dyn Keystore,
+		_key_type: KeyTypeId,
+		_public: &[u8],
+		_domain: &[u8],
+		_data: &[u8],
+	) -> Response {
+		unsupported()
+	}
+}
@@ -83,6 +83,10 @@ const POOL_NO_TAGS: i32 = POOL_INVALID_TX + 9;
 const POOL_INVALID_BLOCK_ID: i32 = POOL_INVALID_TX + 10;
 /// The pool is not accepting future transactions.
 const POOL_FUTURE_TX: i32 = POOL_INVALID_TX + 11;
+/// The priority increase is too low to replace existing transaction in the pool.
+const POOL_TOO_LOW_PRIORITY_BUMP: i32 = POOL_INVALID_TX + 12;
+/// The transaction was not included to the pool because of the per-sender limit.
+const POOL_SENDER_LIMIT_REACHED: i32 = POOL_INVALID_TX + 13;
 /// Other error.
 const OTHER_ERR: i32 = BASE_ERROR + 40;
 
@@ -140,6 +144,11 @@ impl From<Error> for ErrorObjectOwned {
 				format!("Priority is too low: ({} vs {})", old, new),
 				Some("The transaction has too low priority to replace another transaction already in the pool.")
 			),
+			Error::Pool(PoolError::TooLowPriorityBump { old, new, min_bump }) => ErrorObject::owned(
+				POOL_TOO_LOW_PRIORITY_BUMP,
+				format!("Priority bump is too low: ({} vs {}, required: {:?})", old, new, min_bump),
+				Some("The priority increase is not big enough to replace another transaction already in the pool.")
+			),
 			Error::Pool(PoolError::CycleDetected) =>
 				ErrorObject::owned(
 				POOL_CYCLE_DETECTED,
@@ -151,6 +160,11 @@ impl From<Error> for ErrorObjectOwned {
 				"Immediately Dropped",
 				Some("The transaction couldn't enter the pool because of the limit"),
 			),
+			Error::Pool(PoolError::SenderLimitReached) => ErrorObject::owned(
+				POOL_SENDER_LIMIT_REACHED,
+				"Sender Limit Reached",
+				Some("The transaction couldn't enter the pool because of the per-sender limit"),
+			),
 			Error::Pool(PoolError::Unactionable) => ErrorObject::owned(
 				POOL_UNACTIONABLE,
 				"Unactionable",
@@ -50,6 +50,9 @@ pub enum Error {
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
+	/// Querying the remote archive node for the pruned state failed.
+	#[error("Archive fallback error: {}", .0)]
+	ArchiveFallback(String),
 }
 
 /// Base code for all state errors.
@@ -62,6 +65,8 @@ impl From<Error> for ErrorObjectOwned {
 				ErrorObject::owned(BASE_ERROR + 1, e.to_string(), None::<()>),
 			Error::InvalidCount { .. } =>
 				ErrorObject::owned(BASE_ERROR + 2, e.to_string(), None::<()>),
+			Error::ArchiveFallback(_) =>
+				ErrorObject::owned(BASE_ERROR + 4, e.to_string(), None::<()>),
 			e => ErrorObject::owned(BASE_ERROR + 3, e.to_string(), None::<()>),
 		}
 	}
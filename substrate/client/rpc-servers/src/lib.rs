@@ -23,7 +23,10 @@
 pub mod middleware;
 pub mod utils;
 
-use std::{error::Error as StdError, net::SocketAddr, time::Duration};
+#[cfg(test)]
+mod tests;
+
+use std::{error::Error as StdError, net::SocketAddr, num::NonZeroU32, time::Duration};
 
 use jsonrpsee::{
 	core::BoxError,
@@ -42,11 +45,16 @@ pub use jsonrpsee::{
 	core::id_providers::{RandomIntegerIdProvider, RandomStringIdProvider},
 	server::{middleware::rpc::RpcServiceBuilder, BatchRequestConfig},
 };
-pub use middleware::{Metrics, MiddlewareLayer, NodeHealthProxyLayer, RpcMetrics};
+pub use middleware::{
+	IpQuota, MethodCost, MethodCosts, Metrics, MiddlewareLayer, NodeHealthProxyLayer, RpcMetrics,
+};
 pub use utils::{RpcEndpoint, RpcMethods};
 
 const MEGABYTE: u32 = 1024 * 1024;
 
+/// How often the IP addresses with replenished quota are forgotten.
+const IP_QUOTA_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Type to encapsulate the server handle and listening address.
 pub struct Server {
 	/// Handle to the rpc server
@@ -101,6 +109,11 @@ pub struct Config<M: Send + Sync + 'static> {
 	pub id_provider: Option<Box<dyn SubscriptionIdProvider>>,
 	/// Tokio runtime handle.
 	pub tokio_handle: tokio::runtime::Handle,
+	/// Quota of the RPC method costs per minute, shared by all the connections from the same IP
+	/// address.
+	pub ip_quota: Option<NonZeroU32>,
+	/// Costs of the RPC methods.
+	pub method_costs: MethodCosts,
 }
 
 #[derive(Debug, Clone)]
@@ -108,6 +121,8 @@ struct PerConnection {
 	methods: Methods,
 	stop_handle: StopHandle,
 	metrics: Option<RpcMetrics>,
+	ip_quota: Option<IpQuota>,
+	method_costs: MethodCosts,
 	tokio_handle: tokio::runtime::Handle,
 }
 
@@ -116,16 +131,32 @@ pub async fn start_server<M>(config: Config<M>) -> Result<Server, Box<dyn StdErr
 where
 	M: Send + Sync,
 {
-	let Config { endpoints, metrics, tokio_handle, rpc_api, id_provider } = config;
+	let Config { endpoints, metrics, tokio_handle, rpc_api, id_provider, ip_quota, method_costs } =
+		config;
 
 	let (stop_handle, server_handle) = stop_channel();
+	let ip_quota = ip_quota.map(IpQuota::per_minute);
 	let cfg = PerConnection {
 		methods: build_rpc_api(rpc_api).into(),
 		metrics,
+		ip_quota: ip_quota.clone(),
+		method_costs,
 		tokio_handle: tokio_handle.clone(),
-		stop_handle,
+		stop_handle: stop_handle.clone(),
 	};
 
+	if let Some(ip_quota) = ip_quota {
+		tokio_handle.spawn(async move {
+			let mut interval = tokio::time::interval(IP_QUOTA_CLEANUP_INTERVAL);
+			loop {
+				tokio::select! {
+					_ = interval.tick() => ip_quota.retain_recent(),
+					_ = stop_handle.clone().shutdown() => break,
+				}
+			}
+		});
+	}
+
 	let mut local_addrs = Vec::new();
 
 	for endpoint in endpoints {
@@ -217,42 +248,52 @@ where
 					tower::service_fn(move |mut req: http::Request<hyper::body::Incoming>| {
 						req.extensions_mut().insert(deny_unsafe);
 
-						let PerConnection { methods, metrics, tokio_handle, stop_handle } =
-							cfg2.clone();
+						let PerConnection {
+							methods,
+							metrics,
+							ip_quota,
+							method_costs,
+							tokio_handle,
+							stop_handle,
+						} = cfg2.clone();
 						let service_builder = service_builder2.clone();
 
 						let proxy_ip =
 							if rate_limit_trust_proxy_headers { get_proxy_ip(&req) } else { None };
 
-						let rate_limit_cfg = if rate_limit_whitelisted_ips
+						let is_trusted = rate_limit_whitelisted_ips
 							.iter()
-							.any(|ips| ips.contains(proxy_ip.unwrap_or(ip)))
-						{
+							.any(|ips| ips.contains(proxy_ip.unwrap_or(ip)));
+						let (rate_limit_cfg, ip_quota) = if is_trusted {
 							log::debug!(target: "rpc", "ip={ip}, proxy_ip={:?} is trusted, disabling rate-limit", proxy_ip);
-							None
+							(None, None)
 						} else {
 							if !rate_limit_whitelisted_ips.is_empty() {
 								log::debug!(target: "rpc", "ip={ip}, proxy_ip={:?} is not trusted, rate-limit enabled", proxy_ip);
 							}
-							rate_limit
+							(rate_limit, ip_quota)
 						};
 
 						let is_websocket = ws::is_upgrade_request(&req);
 						let transport_label = if is_websocket { "ws" } else { "http" };
 
-						let middleware_layer = match (metrics, rate_limit_cfg) {
-							(None, None) => None,
-							(Some(metrics), None) => Some(
-								MiddlewareLayer::new()
-									.with_metrics(Metrics::new(metrics, transport_label)),
-							),
-							(None, Some(rate_limit)) =>
-								Some(MiddlewareLayer::new().with_rate_limit_per_minute(rate_limit)),
-							(Some(metrics), Some(rate_limit)) => Some(
-								MiddlewareLayer::new()
-									.with_metrics(Metrics::new(metrics, transport_label))
-									.with_rate_limit_per_minute(rate_limit),
-							),
+						let middleware_layer = if metrics.is_none() &&
+							rate_limit_cfg.is_none() &&
+							ip_quota.is_none()
+						{
+							None
+						} else {
+							let mut layer = MiddlewareLayer::new().with_method_costs(method_costs);
+							if let Some(metrics) = metrics {
+								layer = layer.with_metrics(Metrics::new(metrics, transport_label));
+							}
+							if let Some(rate_limit) = rate_limit_cfg {
+								layer = layer.with_rate_limit_per_minute(rate_limit);
+							}
+							if let Some(ip_quota) = ip_quota {
+								layer = layer.with_ip_quota(ip_quota, proxy_ip.unwrap_or(ip));
+							}
+							Some(layer)
 						};
 
 						let rpc_middleware = RpcServiceBuilder::new()
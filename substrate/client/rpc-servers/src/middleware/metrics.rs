@@ -18,7 +18,7 @@
 
 //! RPC middleware to collect prometheus metrics on RPC calls.
 
-use std::time::Instant;
+use std::{num::NonZeroU32, time::Instant};
 
 use jsonrpsee::{types::Request, MethodResponse};
 use prometheus_endpoint::{
@@ -51,6 +51,10 @@ pub struct RpcMetrics {
 	calls_started: CounterVec<U64>,
 	/// Number of calls completed.
 	calls_finished: CounterVec<U64>,
+	/// Total cost of the calls completed.
+	calls_cost: CounterVec<U64>,
+	/// Number of calls rejected because of the exceeded quota.
+	calls_quota_exceeded: CounterVec<U64>,
 	/// Number of Websocket sessions opened.
 	ws_sessions_opened: Option<Counter<U64>>,
 	/// Number of Websocket sessions closed.
@@ -95,6 +99,26 @@ impl RpcMetrics {
 					)?,
 					metrics_registry,
 				)?,
+				calls_cost: register(
+					CounterVec::new(
+						Opts::new(
+							"substrate_rpc_calls_cost",
+							"Total cost of processed RPC calls (unique un-batched requests)",
+						),
+						&["protocol", "method"],
+					)?,
+					metrics_registry,
+				)?,
+				calls_quota_exceeded: register(
+					CounterVec::new(
+						Opts::new(
+							"substrate_rpc_calls_quota_exceeded",
+							"Number of RPC calls rejected because of the exceeded quota",
+						),
+						&["protocol", "method"],
+					)?,
+					metrics_registry,
+				)?,
 				ws_sessions_opened: register(
 					Counter::new(
 						"substrate_rpc_sessions_opened",
@@ -157,6 +181,7 @@ impl RpcMetrics {
 		req: &Request,
 		rp: &MethodResponse,
 		is_rate_limited: bool,
+		cost: NonZeroU32,
 		transport_label: &'static str,
 		now: Instant,
 	) {
@@ -187,6 +212,15 @@ impl RpcMetrics {
 				if is_rate_limited { "true" } else { "false" },
 			])
 			.inc();
+		self.calls_cost
+			.with_label_values(&[transport_label, req.method_name()])
+			.inc_by(cost.get().into());
+	}
+
+	pub(crate) fn on_quota_exceeded(&self, req: &Request, transport_label: &'static str) {
+		self.calls_quota_exceeded
+			.with_label_values(&[transport_label, req.method_name()])
+			.inc();
 	}
 }
 
@@ -220,8 +254,14 @@ impl Metrics {
 		req: &Request,
 		rp: &MethodResponse,
 		is_rate_limited: bool,
+		cost: NonZeroU32,
 		now: Instant,
 	) {
-		self.inner.on_response(req, rp, is_rate_limited, self.transport_label, now)
+		self.inner
+			.on_response(req, rp, is_rate_limited, cost, self.transport_label, now)
+	}
+
+	pub(crate) fn on_quota_exceeded(&self, req: &Request) {
+		self.inner.on_quota_exceeded(req, self.transport_label)
 	}
 }
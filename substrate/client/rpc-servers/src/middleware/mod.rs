@@ -19,6 +19,7 @@
 //! JSON-RPC specific middleware.
 
 use std::{
+	net::IpAddr,
 	num::NonZeroU32,
 	time::{Duration, Instant},
 };
@@ -33,10 +34,12 @@ use jsonrpsee::{
 
 mod metrics;
 mod node_health;
+mod quota;
 mod rate_limit;
 
 pub use metrics::*;
 pub use node_health::*;
+pub use quota::*;
 pub use rate_limit::*;
 
 const MAX_JITTER: Duration = Duration::from_millis(50);
@@ -46,6 +49,8 @@ const MAX_RETRIES: usize = 10;
 #[derive(Debug, Clone, Default)]
 pub struct MiddlewareLayer {
 	rate_limit: Option<RateLimit>,
+	ip_quota: Option<(IpQuota, IpAddr)>,
+	method_costs: MethodCosts,
 	metrics: Option<Metrics>,
 }
 
@@ -57,12 +62,22 @@ impl MiddlewareLayer {
 
 	/// Enable new rate limit middleware enforced per minute.
 	pub fn with_rate_limit_per_minute(self, n: NonZeroU32) -> Self {
-		Self { rate_limit: Some(RateLimit::per_minute(n)), metrics: self.metrics }
+		Self { rate_limit: Some(RateLimit::per_minute(n)), ..self }
+	}
+
+	/// Enable the quota shared by all the connections from the `ip` address.
+	pub fn with_ip_quota(self, quota: IpQuota, ip: IpAddr) -> Self {
+		Self { ip_quota: Some((quota, ip)), ..self }
+	}
+
+	/// Set the costs of the methods accounted against the quota and reported in the metrics.
+	pub fn with_method_costs(self, method_costs: MethodCosts) -> Self {
+		Self { method_costs, ..self }
 	}
 
 	/// Enable metrics middleware.
 	pub fn with_metrics(self, metrics: Metrics) -> Self {
-		Self { rate_limit: self.rate_limit, metrics: Some(metrics) }
+		Self { metrics: Some(metrics), ..self }
 	}
 
 	/// Register a new websocket connection.
@@ -80,12 +95,18 @@ impl<S> tower::Layer<S> for MiddlewareLayer {
 	type Service = Middleware<S>;
 
 	fn layer(&self, service: S) -> Self::Service {
-		Middleware { service, rate_limit: self.rate_limit.clone(), metrics: self.metrics.clone() }
+		Middleware {
+			service,
+			rate_limit: self.rate_limit.clone(),
+			ip_quota: self.ip_quota.clone(),
+			method_costs: self.method_costs.clone(),
+			metrics: self.metrics.clone(),
+		}
 	}
 }
 
-/// JSON-RPC middleware that handles metrics
-/// and rate-limiting.
+/// JSON-RPC middleware that handles metrics,
+/// rate-limiting and quotas.
 ///
 /// These are part of the same middleware
 /// because the metrics needs to know whether
@@ -94,6 +115,8 @@ impl<S> tower::Layer<S> for MiddlewareLayer {
 pub struct Middleware<S> {
 	service: S,
 	rate_limit: Option<RateLimit>,
+	ip_quota: Option<(IpQuota, IpAddr)>,
+	method_costs: MethodCosts,
 	metrics: Option<Metrics>,
 }
 
@@ -108,8 +131,11 @@ where
 
 		self.metrics.as_ref().map(|m| m.on_call(&req));
 
+		let cost = self.method_costs.cost(req.method_name());
+
 		let service = self.service.clone();
 		let rate_limit = self.rate_limit.clone();
+		let ip_quota = self.ip_quota.clone();
 		let metrics = self.metrics.clone();
 
 		async move {
@@ -136,8 +162,21 @@ where
 				}
 			}
 
+			// Only the calls let through by the rate limit are accounted against the quota.
+			if let Some((quota, ip)) = ip_quota.as_ref() {
+				if let Err(exceeded) = quota.check(ip, cost) {
+					log::debug!(
+						target: "rpc",
+						"ip={ip} exceeded the quota calling {} of cost {cost}",
+						req.method_name(),
+					);
+					metrics.as_ref().map(|m| m.on_quota_exceeded(&req));
+					return reject_quota_exceeded(req.id, exceeded);
+				}
+			}
+
 			let rp = service.call(req.clone()).await;
-			metrics.as_ref().map(|m| m.on_response(&req, &rp, is_rate_limited, now));
+			metrics.as_ref().map(|m| m.on_response(&req, &rp, is_rate_limited, cost, now));
 
 			rp
 		}
@@ -148,3 +187,58 @@ where
 fn reject_too_many_calls(id: Id) -> MethodResponse {
 	MethodResponse::error(id, ErrorObject::owned(-32999, "RPC rate limit exceeded", None::<()>))
 }
+
+fn reject_quota_exceeded(id: Id, exceeded: QuotaExceeded) -> MethodResponse {
+	let err = match exceeded {
+		QuotaExceeded::RetryAfter(retry_after) => ErrorObject::owned(
+			-32998,
+			"RPC quota exceeded",
+			Some(serde_json::json!({ "retryAfterMs": retry_after.as_millis() as u64 })),
+		),
+		QuotaExceeded::CostTooHigh =>
+			ErrorObject::owned(-32998, "RPC call cost exceeds the quota", None::<()>),
+	};
+	MethodResponse::error(id, err)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpsee::server::ResponsePayload;
+	use tower::Layer;
+
+	#[derive(Clone)]
+	struct OkService;
+
+	impl<'a> RpcServiceT<'a> for OkService {
+		type Future = futures::future::Ready<MethodResponse>;
+
+		fn call(&self, req: Request<'a>) -> Self::Future {
+			futures::future::ready(MethodResponse::response(
+				req.id,
+				ResponsePayload::success("ok"),
+				usize::MAX,
+			))
+		}
+	}
+
+	fn call(id: u64) -> Request<'static> {
+		Request::new("test_call".into(), None, Id::Number(id))
+	}
+
+	// The time is paused so that the retries of the rate limit elapse immediately, while the rate
+	// limit itself keeps rejecting the calls.
+	#[tokio::test(start_paused = true)]
+	async fn rate_limited_calls_are_not_charged_against_the_quota() {
+		let ip = "127.0.0.1".parse().unwrap();
+		let middleware = MiddlewareLayer::new()
+			.with_rate_limit_per_minute(NonZeroU32::new(1).unwrap())
+			.with_ip_quota(IpQuota::per_minute(NonZeroU32::new(2).unwrap()), ip)
+			.layer(OkService);
+
+		assert!(middleware.call(call(1)).await.is_success());
+		assert_eq!(middleware.call(call(2)).await.as_error_code(), Some(-32999));
+		// Had the previous call been charged, the quota would be exhausted by now.
+		assert_eq!(middleware.call(call(3)).await.as_error_code(), Some(-32999));
+	}
+}
@@ -0,0 +1,196 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC method costs and per-IP quotas.
+
+use governor::{
+	clock::{Clock, DefaultClock, QuantaClock},
+	middleware::NoOpMiddleware,
+	state::keyed::DefaultKeyedStateStore,
+	Quota,
+};
+use std::{
+	collections::HashMap, net::IpAddr, num::NonZeroU32, str::FromStr, sync::Arc, time::Duration,
+};
+
+type IpQuotaInner =
+	governor::RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock, NoOpMiddleware>;
+
+/// The cost of the methods not listed in the [`MethodCosts`].
+pub const DEFAULT_METHOD_COST: u32 = 1;
+
+/// The default costs of the methods known to be heavy.
+const DEFAULT_METHOD_COSTS: &[(&str, u32)] = &[
+	("archive_unstable_call", 50),
+	("archive_unstable_storage", 50),
+	("chainHead_v1_call", 50),
+	("chainHead_v1_storage", 20),
+	("state_call", 50),
+	("state_getKeysPaged", 20),
+	("state_getPairs", 100),
+	("state_getReadProof", 20),
+	("state_queryStorage", 100),
+	("state_queryStorageAt", 20),
+	("state_traceBlock", 100),
+	("system_dryRun", 50),
+];
+
+/// The cost of a single RPC method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodCost {
+	/// Name of the method.
+	pub method: String,
+	/// Cost of a single call of the method, in quota units.
+	pub cost: NonZeroU32,
+}
+
+impl FromStr for MethodCost {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (method, cost) = s
+			.split_once('=')
+			.ok_or_else(|| format!("Invalid method cost `{s}`, expected `<method>=<cost>`"))?;
+		let cost =
+			cost.parse().map_err(|_| format!("Invalid cost of method `{method}`: {cost}"))?;
+		Ok(Self { method: method.to_string(), cost })
+	}
+}
+
+/// Costs of the RPC methods, used to account the calls against the [`IpQuota`].
+///
+/// Batch requests are accounted per call, subscriptions are accounted once when opened.
+#[derive(Debug, Clone)]
+pub struct MethodCosts {
+	costs: Arc<HashMap<String, NonZeroU32>>,
+}
+
+impl Default for MethodCosts {
+	fn default() -> Self {
+		Self::new(std::iter::empty())
+	}
+}
+
+impl MethodCosts {
+	/// Create new method costs, overriding the defaults with the given `costs`.
+	pub fn new(costs: impl IntoIterator<Item = MethodCost>) -> Self {
+		let costs = DEFAULT_METHOD_COSTS
+			.iter()
+			.map(|(method, cost)| {
+				(
+					method.to_string(),
+					NonZeroU32::new(*cost).expect("Default costs are non-zero; qed"),
+				)
+			})
+			.chain(costs.into_iter().map(|MethodCost { method, cost }| (method, cost)))
+			.collect();
+		Self { costs: Arc::new(costs) }
+	}
+
+	/// Returns the cost of a single call of the `method`.
+	pub fn cost(&self, method: &str) -> NonZeroU32 {
+		self.costs
+			.get(method)
+			.copied()
+			.unwrap_or(NonZeroU32::new(DEFAULT_METHOD_COST).expect("Default cost is non-zero; qed"))
+	}
+}
+
+/// Why a call was rejected by the [`IpQuota`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QuotaExceeded {
+	/// The quota is exhausted, the call may be retried after the given duration.
+	RetryAfter(Duration),
+	/// The cost of the call exceeds the whole quota, the call will never be accepted.
+	CostTooHigh,
+}
+
+/// Quota of RPC cost units per minute, shared by all the connections from the same IP address.
+#[derive(Debug, Clone)]
+pub struct IpQuota {
+	inner: Arc<IpQuotaInner>,
+	clock: QuantaClock,
+}
+
+impl IpQuota {
+	/// Create a new `IpQuota` of cost units per minute.
+	pub fn per_minute(n: NonZeroU32) -> Self {
+		let clock = QuantaClock::default();
+		Self {
+			inner: Arc::new(IpQuotaInner::dashmap_with_clock(Quota::per_minute(n), &clock)),
+			clock,
+		}
+	}
+
+	/// Account a call of the given `cost` made from `ip`.
+	pub(crate) fn check(&self, ip: &IpAddr, cost: NonZeroU32) -> Result<(), QuotaExceeded> {
+		match self.inner.check_key_n(ip, cost) {
+			Ok(Ok(())) => Ok(()),
+			Ok(Err(not_until)) =>
+				Err(QuotaExceeded::RetryAfter(not_until.wait_time_from(self.clock.now()))),
+			Err(_) => Err(QuotaExceeded::CostTooHigh),
+		}
+	}
+
+	/// Forget the IP addresses whose quota is fully replenished.
+	pub(crate) fn retain_recent(&self) {
+		self.inner.retain_recent();
+		self.inner.shrink_to_fit();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn method_costs_override_defaults() {
+		let costs = MethodCosts::new(vec![
+			"state_call=7".parse().unwrap(),
+			"system_health=3".parse().unwrap(),
+		]);
+
+		assert_eq!(costs.cost("state_call").get(), 7);
+		assert_eq!(costs.cost("system_health").get(), 3);
+		assert_eq!(costs.cost("state_getPairs").get(), 100);
+		assert_eq!(costs.cost("chain_getHeader").get(), DEFAULT_METHOD_COST);
+	}
+
+	#[test]
+	fn invalid_method_cost_is_rejected() {
+		assert!("state_call".parse::<MethodCost>().is_err());
+		assert!("state_call=0".parse::<MethodCost>().is_err());
+		assert!("state_call=abc".parse::<MethodCost>().is_err());
+	}
+
+	#[test]
+	fn ip_quota_is_shared_per_ip() {
+		let quota = IpQuota::per_minute(NonZeroU32::new(10).unwrap());
+		let ip1: IpAddr = "127.0.0.1".parse().unwrap();
+		let ip2: IpAddr = "127.0.0.2".parse().unwrap();
+		let cost = NonZeroU32::new(6).unwrap();
+
+		assert_eq!(quota.check(&ip1, cost), Ok(()));
+		assert!(matches!(quota.check(&ip1, cost), Err(QuotaExceeded::RetryAfter(_))));
+		assert_eq!(quota.check(&ip2, cost), Ok(()));
+		assert_eq!(
+			quota.check(&ip2, NonZeroU32::new(11).unwrap()),
+			Err(QuotaExceeded::CostTooHigh)
+		);
+	}
+}
@@ -0,0 +1,105 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use jsonrpsee::{
+	core::client::{ClientT, Error as ClientError},
+	rpc_params,
+	ws_client::WsClientBuilder,
+};
+
+async fn start_with_quota(ip_quota: u32) -> Server {
+	let mut rpc_api = RpcModule::new(());
+	rpc_api.register_method("test_call", |_, _, _| "ok").unwrap();
+	rpc_api.register_method("test_heavyCall", |_, _, _| "ok").unwrap();
+
+	start_server(Config {
+		endpoints: vec![RpcEndpoint {
+			listen_addr: ([127, 0, 0, 1], 0).into(),
+			batch_config: BatchRequestConfig::Unlimited,
+			max_connections: 10,
+			max_payload_in_mb: 1,
+			max_payload_out_mb: 1,
+			max_subscriptions_per_connection: 10,
+			max_buffer_capacity_per_connection: 64,
+			rate_limit: None,
+			rate_limit_trust_proxy_headers: false,
+			rate_limit_whitelisted_ips: Vec::new(),
+			cors: None,
+			rpc_methods: RpcMethods::Unsafe,
+			is_optional: false,
+			retry_random_port: false,
+		}],
+		metrics: None,
+		rpc_api,
+		id_provider: None,
+		tokio_handle: tokio::runtime::Handle::current(),
+		ip_quota: NonZeroU32::new(ip_quota),
+		method_costs: MethodCosts::new(vec![
+			"test_call=6".parse().unwrap(),
+			"test_heavyCall=11".parse().unwrap(),
+		]),
+	})
+	.await
+	.unwrap()
+}
+
+#[tokio::test]
+async fn quota_exceeded_is_reported_with_retry_after() {
+	let server = start_with_quota(10).await;
+	let client = WsClientBuilder::default()
+		.build(format!("ws://{}", server.listen_addrs()[0]))
+		.await
+		.unwrap();
+
+	let res: String = client.request("test_call", rpc_params![]).await.unwrap();
+	assert_eq!(res, "ok");
+
+	let Err(ClientError::Call(err)) = client.request::<String, _>("test_call", rpc_params![]).await
+	else {
+		panic!("the second call must exceed the quota");
+	};
+	assert_eq!(err.code(), -32998);
+	assert_eq!(err.message(), "RPC quota exceeded");
+
+	let data: serde_json::Value = serde_json::from_str(err.data().unwrap().get()).unwrap();
+	let retry_after = data["retryAfterMs"].as_u64().unwrap();
+	assert!(retry_after > 0 && retry_after <= 60_000, "unexpected retryAfterMs {retry_after}");
+}
+
+#[tokio::test]
+async fn call_costing_more_than_the_quota_is_rejected() {
+	let server = start_with_quota(10).await;
+	let client = WsClientBuilder::default()
+		.build(format!("ws://{}", server.listen_addrs()[0]))
+		.await
+		.unwrap();
+
+	let Err(ClientError::Call(err)) =
+		client.request::<String, _>("test_heavyCall", rpc_params![]).await
+	else {
+		panic!("the call must cost more than the quota");
+	};
+	assert_eq!(err.code(), -32998);
+	assert_eq!(err.message(), "RPC call cost exceeds the quota");
+	assert!(err.data().is_none());
+
+	// The rejected call is not charged, the cheaper one still goes through.
+	let res: String = client.request("test_call", rpc_params![]).await.unwrap();
+	assert_eq!(res, "ok");
+}
@@ -18,7 +18,10 @@
 
 //! API trait for transactions.
 
-use crate::transaction::{error::ErrorBroadcast, event::TransactionEvent};
+use crate::transaction::{
+	error::ErrorBroadcast,
+	event::{PoolContent, PoolEvent, TransactionEvent},
+};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use sp_core::Bytes;
 
@@ -59,3 +62,34 @@ pub trait TransactionBroadcastApi {
 	#[method(name = "transaction_v1_stop", with_extensions)]
 	async fn stop_broadcast(&self, operation_id: String) -> Result<(), ErrorBroadcast>;
 }
+
+#[rpc(client, server)]
+pub trait TransactionPoolApi<Hash: Clone> {
+	/// Returns the ready and future transactions kept in the transaction pool.
+	///
+	/// This method is unsafe, since it exposes the transactions known to the node along with their
+	/// senders.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "transactionPool_unstable_content", with_extensions)]
+	fn content(&self) -> RpcResult<PoolContent<Hash>>;
+
+	/// Subscribe to the transactions entering and leaving the transaction pool.
+	///
+	/// See [`PoolEvent`](crate::transaction::event::PoolEvent) for details on reported events.
+	///
+	/// This method is unsafe, since it exposes the transactions known to the node.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[subscription(
+		name = "transactionPool_unstable_subscribe" => "transactionPool_unstable_event",
+		unsubscribe = "transactionPool_unstable_unsubscribe",
+		item = PoolEvent<Hash>,
+		with_extensions,
+	)]
+	fn subscribe(&self);
+}
@@ -73,6 +73,13 @@ impl<Hash> From<Error> for TransactionEvent<Hash> {
 						old, new
 					),
 				}),
+			Error::Pool(PoolError::TooLowPriorityBump { old, new, min_bump }) =>
+				TransactionEvent::Invalid(TransactionError {
+					error: format!(
+						"The priority increase of the transaction is too low (pool {} -> current {}, required bump {:?})",
+						old, new, min_bump
+					),
+				}),
 			Error::Pool(PoolError::CycleDetected) => TransactionEvent::Invalid(TransactionError {
 				error: "The transaction contains a cyclic dependency".into(),
 			}),
@@ -80,6 +87,11 @@ impl<Hash> From<Error> for TransactionEvent<Hash> {
 				TransactionEvent::Invalid(TransactionError {
 					error: "The transaction could not enter the pool because of the limit".into(),
 				}),
+			Error::Pool(PoolError::SenderLimitReached) =>
+				TransactionEvent::Invalid(TransactionError {
+					error: "The transaction could not enter the pool because of the per-sender limit"
+						.into(),
+				}),
 			Error::Pool(PoolError::Unactionable) => TransactionEvent::Invalid(TransactionError {
 				error: "Transaction cannot be propagated and the local node does not author blocks"
 					.into(),
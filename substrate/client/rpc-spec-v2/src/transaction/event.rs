@@ -192,6 +192,83 @@ impl<Hash> From<TransactionEventIR<Hash>> for TransactionEvent<Hash> {
 	}
 }
 
+/// The transaction kept in the pool, as reported by `transactionPool_unstable_content`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolTransaction<Hash> {
+	/// The hash of the transaction.
+	pub hash: Hash,
+	/// The priority of the transaction.
+	pub priority: u64,
+	/// The number of blocks the transaction is valid for.
+	pub longevity: u64,
+	/// The hex-encoded tags required by the transaction.
+	pub requires: Vec<String>,
+	/// The hex-encoded tags provided by the transaction.
+	pub provides: Vec<String>,
+	/// The hex-encoded sender of the transaction, if known.
+	pub sender: Option<String>,
+	/// Indicates if the transaction is propagated to other peers.
+	pub propagate: bool,
+}
+
+/// The content of the transaction pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolContent<Hash> {
+	/// The transactions ready to be included in the block, ordered by priority.
+	pub ready: Vec<PoolTransaction<Hash>>,
+	/// The transactions waiting for their requirements to be satisfied.
+	pub future: Vec<PoolTransaction<Hash>>,
+}
+
+/// Events describing transactions entering and leaving the pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "event")]
+pub enum PoolEvent<Hash> {
+	/// The transaction entered the pool.
+	Imported {
+		/// The hash of the transaction.
+		hash: Hash,
+	},
+	/// The transaction left the pool, as it was included in the finalized block.
+	Finalized {
+		/// The hash of the transaction.
+		hash: Hash,
+	},
+	/// The transaction left the pool, as it was replaced by other transaction.
+	Usurped {
+		/// The hash of the transaction.
+		hash: Hash,
+		/// The hash of the replacing transaction.
+		by: Hash,
+	},
+	/// The transaction left the pool because of the pool limits.
+	Dropped {
+		/// The hash of the transaction.
+		hash: Hash,
+	},
+	/// The transaction left the pool, as it is no longer valid.
+	Invalid {
+		/// The hash of the transaction.
+		hash: Hash,
+	},
+}
+
+impl<Hash> From<sc_transaction_pool_api::PoolEvent<Hash>> for PoolEvent<Hash> {
+	fn from(event: sc_transaction_pool_api::PoolEvent<Hash>) -> Self {
+		use sc_transaction_pool_api::PoolEvent as Event;
+		match event {
+			Event::Imported(hash) => PoolEvent::Imported { hash },
+			Event::Finalized(hash) => PoolEvent::Finalized { hash },
+			Event::Usurped { hash, by } => PoolEvent::Usurped { hash, by },
+			Event::Dropped(hash) => PoolEvent::Dropped { hash },
+			Event::Invalid(hash) => PoolEvent::Invalid { hash },
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -287,4 +364,23 @@ mod tests {
 		let event_dec: TransactionEvent<()> = serde_json::from_str(exp).unwrap();
 		assert_eq!(event_dec, event);
 	}
+
+	#[test]
+	fn pool_event() {
+		let event: PoolEvent<H256> =
+			PoolEvent::Usurped { hash: H256::from_low_u64_be(1), by: H256::from_low_u64_be(2) };
+		let ser = serde_json::to_string(&event).unwrap();
+
+		let exp = r#"{"event":"usurped","hash":"0x0000000000000000000000000000000000000000000000000000000000000001","by":"0x0000000000000000000000000000000000000000000000000000000000000002"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: PoolEvent<H256> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+
+		let event: PoolEvent<H256> = PoolEvent::Imported { hash: H256::from_low_u64_be(1) };
+		let ser = serde_json::to_string(&event).unwrap();
+
+		let exp = r#"{"event":"imported","hash":"0x0000000000000000000000000000000000000000000000000000000000000001"}"#;
+		assert_eq!(ser, exp);
+	}
 }
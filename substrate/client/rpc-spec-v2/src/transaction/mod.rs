@@ -21,9 +21,12 @@
 //! The transaction methods allow submitting a transaction and subscribing to
 //! its status updates generated by the chain.
 //!
+//! The transaction pool methods allow inspecting the content of the transaction pool and
+//! subscribing to transactions entering and leaving the pool.
+//!
 //! # Note
 //!
-//! Methods are prefixed by `transaction`.
+//! Methods are prefixed by `transaction` and `transactionPool`.
 
 #[cfg(test)]
 mod tests;
@@ -33,8 +36,13 @@ pub mod error;
 pub mod event;
 pub mod transaction;
 pub mod transaction_broadcast;
+pub mod transaction_pool;
 
-pub use api::{TransactionApiServer, TransactionBroadcastApiServer};
-pub use event::{TransactionBlock, TransactionDropped, TransactionError, TransactionEvent};
+pub use api::{TransactionApiServer, TransactionBroadcastApiServer, TransactionPoolApiServer};
+pub use event::{
+	PoolContent, PoolEvent, PoolTransaction, TransactionBlock, TransactionDropped,
+	TransactionError, TransactionEvent,
+};
 pub use transaction::Transaction;
 pub use transaction_broadcast::TransactionBroadcast;
+pub use transaction_pool::TransactionPoolInspect;
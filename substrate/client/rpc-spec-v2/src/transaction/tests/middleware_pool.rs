@@ -20,8 +20,8 @@ use async_trait::async_trait;
 use codec::Encode;
 use sc_transaction_pool::BasicPool;
 use sc_transaction_pool_api::{
-	ImportNotificationStream, PoolStatus, ReadyTransactions, TransactionFor, TransactionPool,
-	TransactionSource, TransactionStatusStreamFor, TxHash,
+	ImportNotificationStream, PoolEventStream, PoolStatus, ReadyTransactions, SenderExtractor,
+	TransactionFor, TransactionPool, TransactionSource, TransactionStatusStreamFor, TxHash,
 };
 
 use crate::hex_string;
@@ -149,6 +149,14 @@ impl TransactionPool for MiddlewarePool {
 		self.inner_pool.import_notification_stream()
 	}
 
+	fn pool_event_stream(&self) -> PoolEventStream<TxHash<Self>> {
+		self.inner_pool.pool_event_stream()
+	}
+
+	fn sender_extractor(&self) -> Option<SenderExtractor<Self::Block>> {
+		self.inner_pool.sender_extractor()
+	}
+
 	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
 		self.inner_pool.hash_of(xt)
 	}
@@ -22,4 +22,5 @@ mod middleware_pool;
 mod setup;
 
 mod transaction_broadcast_tests;
+mod transaction_pool_tests;
 mod transaction_tests;
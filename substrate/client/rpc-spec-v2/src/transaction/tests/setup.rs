@@ -19,11 +19,13 @@
 use crate::{
 	chain_head::test_utils::ChainHeadMockClient,
 	transaction::{
-		api::{TransactionApiServer, TransactionBroadcastApiServer},
+		api::{TransactionApiServer, TransactionBroadcastApiServer, TransactionPoolApiServer},
 		tests::executor::{TaskExecutorBroadcast, TaskExecutorState},
 		Transaction as RpcTransaction, TransactionBroadcast as RpcTransactionBroadcast,
+		TransactionPoolInspect as RpcTransactionPoolInspect,
 	},
 };
+use codec::Encode;
 use futures::Future;
 use jsonrpsee::RpcModule;
 use sc_transaction_pool::*;
@@ -120,6 +122,42 @@ pub fn setup_api_tx() -> (
 	(api, pool, client_mock, tx_api, executor_recv, pool_state)
 }
 
+pub fn setup_api_pool() -> (
+	Arc<TestApi>,
+	Arc<ForkAwareTxPool<TestApi, Block>>,
+	RpcModule<RpcTransactionPoolInspect<ForkAwareTxPool<TestApi, Block>>>,
+	TaskExecutorState,
+	futures::executor::ThreadPool,
+) {
+	let api = Arc::new(TestApi::with_alice_nonce(ALICE_NONCE));
+	let genesis_hash = api.genesis_hash();
+	// the RPC reports the senders determined by the pool.
+	let sender_extractor: sc_transaction_pool_api::SenderExtractor<Block> =
+		Arc::new(|xt: &substrate_test_runtime_client::runtime::Extrinsic| match &xt.preamble {
+			sp_runtime::generic::Preamble::Signed(address, ..) => Some(address.encode()),
+			_ => None,
+		});
+	let (pool, background_task) = ForkAwareTxPool::new_test_with_options(
+		api.clone(),
+		genesis_hash,
+		genesis_hash,
+		Default::default(),
+		usize::MAX,
+		Some(sender_extractor),
+	);
+	let thread_pool = futures::executor::ThreadPool::new().unwrap();
+	thread_pool.spawn_ok(background_task);
+	let pool = Arc::new(pool);
+
+	let (task_executor, executor_recv) = TaskExecutorBroadcast::new();
+
+	let mut tx_api =
+		RpcTransactionPoolInspect::new(pool.clone(), Arc::new(task_executor)).into_rpc();
+	tx_api.extensions_mut().insert(sc_rpc::DenyUnsafe::No);
+
+	(api, pool, tx_api, executor_recv, thread_pool)
+}
+
 /// Get the next event from the provided middleware in at most 5 seconds.
 macro_rules! get_next_event {
 	($middleware:expr) => {
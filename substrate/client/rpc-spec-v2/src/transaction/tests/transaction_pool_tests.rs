@@ -0,0 +1,134 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	hex_string,
+	transaction::{PoolContent, PoolEvent},
+};
+use codec::Encode;
+use jsonrpsee::rpc_params;
+use sc_transaction_pool_api::{
+	ChainEvent, MaintainedTransactionPool, TransactionPool, TransactionSource,
+};
+use sp_core::H256;
+use substrate_test_runtime_client::AccountKeyring::*;
+use substrate_test_runtime_transaction_pool::uxt;
+
+// Test helpers.
+use crate::transaction::tests::setup::{setup_api_pool, ALICE_NONCE};
+
+#[tokio::test]
+async fn pool_content_reports_ready_and_future() {
+	let (api, pool, tx_api, _exec_middleware, _thread_pool) = setup_api_pool();
+
+	let block_1_header = api.push_block(1, vec![], true);
+	let event = ChainEvent::NewBestBlock { hash: block_1_header.hash(), tree_route: None };
+	pool.maintain(event).await;
+
+	let xt_ready = uxt(Alice, ALICE_NONCE);
+	let xt_future = uxt(Alice, ALICE_NONCE + 2);
+	pool.submit_one(block_1_header.hash(), TransactionSource::External, xt_ready.clone())
+		.await
+		.unwrap();
+	pool.submit_one(block_1_header.hash(), TransactionSource::External, xt_future.clone())
+		.await
+		.unwrap();
+
+	let content: PoolContent<H256> =
+		tx_api.call("transactionPool_unstable_content", rpc_params![]).await.unwrap();
+
+	assert_eq!(content.ready.len(), 1);
+	assert_eq!(content.future.len(), 1);
+
+	let ready = &content.ready[0];
+	assert_eq!(ready.hash, api.hash_and_length(&xt_ready).0);
+	assert_eq!(ready.sender, Some(hex_string(&Alice.public().encode())));
+	assert!(ready.requires.is_empty());
+	assert_eq!(ready.provides.len(), 1);
+
+	let future = &content.future[0];
+	assert_eq!(future.hash, api.hash_and_length(&xt_future).0);
+	assert_eq!(future.requires.len(), 1);
+}
+
+#[tokio::test]
+async fn pool_content_is_unsafe() {
+	let (_api, _pool, mut tx_api, _exec_middleware, _thread_pool) = setup_api_pool();
+	tx_api.extensions_mut().insert(sc_rpc::DenyUnsafe::Yes);
+
+	let err = tx_api
+		.call::<_, PoolContent<H256>>("transactionPool_unstable_content", rpc_params![])
+		.await
+		.unwrap_err();
+	assert_matches::assert_matches!(
+		err,
+		jsonrpsee::MethodsError::JsonRpc(err) if err.message() == "RPC call is unsafe to be called externally"
+	);
+}
+
+#[tokio::test]
+async fn pool_subscription_is_unsafe() {
+	let (_api, _pool, mut tx_api, _exec_middleware, _thread_pool) = setup_api_pool();
+	tx_api.extensions_mut().insert(sc_rpc::DenyUnsafe::Yes);
+
+	let err = tx_api
+		.subscribe_unbounded("transactionPool_unstable_subscribe", rpc_params![])
+		.await
+		.unwrap_err();
+	assert_matches::assert_matches!(
+		err,
+		jsonrpsee::MethodsError::JsonRpc(err) if err.message() == "RPC call is unsafe to be called externally"
+	);
+}
+
+#[tokio::test]
+async fn pool_subscription_reports_entering_and_leaving() {
+	let (api, pool, tx_api, _exec_middleware, _thread_pool) = setup_api_pool();
+
+	let mut sub = tx_api
+		.subscribe_unbounded("transactionPool_unstable_subscribe", rpc_params![])
+		.await
+		.unwrap();
+
+	let block_1_header = api.push_block(1, vec![], true);
+	let event = ChainEvent::NewBestBlock { hash: block_1_header.hash(), tree_route: None };
+	pool.maintain(event).await;
+
+	let xt = uxt(Alice, ALICE_NONCE);
+	let xt_hash = api.hash_and_length(&xt).0;
+	pool.submit_one(block_1_header.hash(), TransactionSource::External, xt.clone())
+		.await
+		.unwrap();
+
+	let event: PoolEvent<H256> = get_next_event_sub!(&mut sub);
+	assert_eq!(event, PoolEvent::Imported { hash: xt_hash });
+
+	// Finalize the block including the transaction.
+	let block_2_header = api.push_block(2, vec![xt.clone()], true);
+	let block_2 = block_2_header.hash();
+	let event = ChainEvent::NewBestBlock { hash: block_2, tree_route: None };
+	pool.maintain(event).await;
+	let event = ChainEvent::Finalized {
+		hash: block_2,
+		tree_route: std::sync::Arc::from(vec![block_1_header.hash()]),
+	};
+	pool.maintain(event).await;
+
+	let event: PoolEvent<H256> = get_next_event_sub!(&mut sub);
+	assert_eq!(event, PoolEvent::Finalized { hash: xt_hash });
+}
@@ -0,0 +1,110 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API implementation for inspecting the transaction pool.
+
+use crate::{
+	hex_string,
+	transaction::{
+		api::TransactionPoolApiServer,
+		event::{PoolContent, PoolEvent, PoolTransaction},
+	},
+	SubscriptionTaskExecutor,
+};
+
+use futures::StreamExt;
+use jsonrpsee::{core::RpcResult, types::ErrorObject, Extensions, PendingSubscriptionSink};
+use sc_rpc::utils::{spawn_subscription_task, BoundedVecDeque, Subscription};
+use sc_rpc_api::check_if_safe;
+use sc_transaction_pool_api::{InPoolTransaction, SenderExtractor, TransactionPool, TxHash};
+use std::sync::Arc;
+
+/// An API for inspecting the transaction pool.
+pub struct TransactionPoolInspect<Pool: TransactionPool> {
+	/// Transactions pool.
+	pool: Arc<Pool>,
+	/// Provides the sender of the transaction.
+	sender_extractor: Option<SenderExtractor<Pool::Block>>,
+	/// Executor to spawn subscriptions.
+	executor: SubscriptionTaskExecutor,
+}
+
+impl<Pool: TransactionPool> TransactionPoolInspect<Pool> {
+	/// Creates a new [`TransactionPoolInspect`].
+	///
+	/// The sender of the pool transactions is determined by the extractor the pool was built with,
+	/// if any.
+	pub fn new(pool: Arc<Pool>, executor: SubscriptionTaskExecutor) -> Self {
+		let sender_extractor = pool.sender_extractor();
+		TransactionPoolInspect { pool, sender_extractor, executor }
+	}
+
+	/// Sets the means of determining the sender of the transaction, overriding the one of the pool.
+	///
+	/// If neither is provided, the sender of pool transactions is not reported.
+	pub fn with_sender_extractor(mut self, sender_extractor: SenderExtractor<Pool::Block>) -> Self {
+		self.sender_extractor = Some(sender_extractor);
+		self
+	}
+
+	/// Converts the in-pool transaction into its JSON compatible representation.
+	fn pool_transaction(&self, tx: &Pool::InPoolTransaction) -> PoolTransaction<TxHash<Pool>> {
+		PoolTransaction {
+			hash: tx.hash().clone(),
+			priority: *tx.priority(),
+			longevity: *tx.longevity(),
+			requires: tx.requires().iter().map(hex_string).collect(),
+			provides: tx.provides().iter().map(hex_string).collect(),
+			sender: self
+				.sender_extractor
+				.as_ref()
+				.and_then(|extractor| extractor(tx.data()))
+				.map(|sender| hex_string(&sender)),
+			propagate: tx.is_propagable(),
+		}
+	}
+}
+
+impl<Pool> TransactionPoolApiServer<TxHash<Pool>> for TransactionPoolInspect<Pool>
+where
+	Pool: TransactionPool + Sync + Send + 'static,
+{
+	fn content(&self, ext: &Extensions) -> RpcResult<PoolContent<TxHash<Pool>>> {
+		check_if_safe(ext)?;
+
+		let ready = self.pool.ready().map(|tx| self.pool_transaction(&tx)).collect();
+		let future = self.pool.futures().iter().map(|tx| self.pool_transaction(tx)).collect();
+		Ok(PoolContent { ready, future })
+	}
+
+	fn subscribe(&self, pending: PendingSubscriptionSink, ext: &Extensions) {
+		if let Err(err) = check_if_safe(ext) {
+			spawn_subscription_task(&self.executor, pending.reject(ErrorObject::from(err)));
+			return
+		}
+
+		let stream = self.pool.pool_event_stream().map(PoolEvent::from);
+
+		let fut = async move {
+			let Ok(sink) = pending.accept().await.map(Subscription::from) else { return };
+			sink.pipe_from_stream(stream, BoundedVecDeque::default()).await;
+		};
+
+		spawn_subscription_task(&self.executor, fut);
+	}
+}
@@ -0,0 +1,187 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Remote archive node queried for the state of the blocks pruned locally.
+//!
+//! Only the methods whose results can be checked against a storage proof fall back to the remote
+//! node: `state_getStorage`, `state_getStorageHash`, `state_getStorageSize` of existing entries and
+//! their `childstate_*` counterparts. `state_call` is only forwarded if unverified calls are
+//! enabled. Key listings, sizes of key prefixes, metadata, runtime versions, read proofs and
+//! storage queries over block ranges are not forwarded, as the remote node could omit entries
+//! without it being noticed.
+
+use super::error::Error;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use sc_rpc_api::{
+	child_state::ChildStateApiClient,
+	state::{ReadProof, StateApiClient},
+};
+use sp_core::{
+	storage::{ChildInfo, StorageData, StorageKey},
+	Bytes,
+};
+use sp_runtime::traits::{Block as BlockT, HashingFor, Header as HeaderT};
+use sp_state_machine::{read_child_proof_check, read_proof_check, StorageProof};
+use std::{collections::HashMap, marker::PhantomData, time::Duration};
+
+const LOG_TARGET: &str = "rpc::archive-fallback";
+
+/// The maximum time allowed for a single request to the remote archive node.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Configuration of the remote archive node fallback.
+#[derive(Debug, Clone)]
+pub struct ArchiveFallbackConfig {
+	/// The HTTP(S) endpoint of the remote archive node.
+	pub url: String,
+	/// Forward `state_call` requests for pruned blocks to the remote archive node.
+	///
+	/// Contrary to the storage queries, the results of runtime calls cannot be verified against
+	/// the locally known headers, so they are served as returned by the remote node.
+	pub unverified_calls: bool,
+}
+
+/// The remote archive node queried for the state of the blocks pruned locally.
+///
+/// The storage entries returned by the remote node are verified with the storage proof
+/// (`state_getReadProof`) against the state root of the locally known header.
+pub struct ArchiveFallback<Block: BlockT> {
+	client: HttpClient,
+	unverified_calls: bool,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block: BlockT> ArchiveFallback<Block> {
+	/// Creates a new [`ArchiveFallback`] from the given configuration.
+	pub fn new(config: &ArchiveFallbackConfig) -> Result<Self, Error> {
+		let client = HttpClientBuilder::default()
+			.request_timeout(REQUEST_TIMEOUT)
+			.build(&config.url)
+			.map_err(archive_err)?;
+		Ok(Self { client, unverified_calls: config.unverified_calls, _phantom: PhantomData })
+	}
+
+	/// Returns the storage entries for given keys at the block of given header.
+	///
+	/// The entries are verified against the state root of the header.
+	pub async fn storage(
+		&self,
+		header: &Block::Header,
+		keys: Vec<StorageKey>,
+	) -> Result<Vec<Option<StorageData>>, Error> {
+		let at = header.hash();
+		log::debug!(target: LOG_TARGET, "Fetching {} storage entries at {at:?}", keys.len());
+
+		let read_proof =
+			StateApiClient::<Block::Hash>::read_proof(&self.client, keys.clone(), Some(at))
+				.await
+				.map_err(archive_err)?;
+		let proof = storage_proof::<Block>(read_proof, at)?;
+
+		let entries = read_proof_check::<HashingFor<Block>, _>(
+			*header.state_root(),
+			proof,
+			keys.iter().map(|key| &key.0),
+		)
+		.map_err(|e| Error::ArchiveFallback(format!("Invalid storage proof: {e:?}")))?;
+
+		Ok(ordered_entries(keys, entries))
+	}
+
+	/// Returns the child storage entries for given keys at the block of given header.
+	///
+	/// The entries are verified against the state root of the header.
+	pub async fn child_storage(
+		&self,
+		header: &Block::Header,
+		child_info: &ChildInfo,
+		keys: Vec<StorageKey>,
+	) -> Result<Vec<Option<StorageData>>, Error> {
+		let at = header.hash();
+		log::debug!(target: LOG_TARGET, "Fetching {} child storage entries at {at:?}", keys.len());
+
+		let read_proof = ChildStateApiClient::<Block::Hash>::read_child_proof(
+			&self.client,
+			child_info.prefixed_storage_key(),
+			keys.clone(),
+			Some(at),
+		)
+		.await
+		.map_err(archive_err)?;
+		let proof = storage_proof::<Block>(read_proof, at)?;
+
+		let entries = read_child_proof_check::<HashingFor<Block>, _>(
+			*header.state_root(),
+			proof,
+			child_info,
+			keys.iter().map(|key| &key.0),
+		)
+		.map_err(|e| Error::ArchiveFallback(format!("Invalid child storage proof: {e:?}")))?;
+
+		Ok(ordered_entries(keys, entries))
+	}
+
+	/// Calls the runtime method at given block.
+	///
+	/// Fails unless forwarding of the unverified runtime calls was enabled.
+	pub async fn call(
+		&self,
+		at: Block::Hash,
+		method: String,
+		call_data: Bytes,
+	) -> Result<Bytes, Error> {
+		if !self.unverified_calls {
+			return Err(Error::ArchiveFallback(
+				"Forwarding of runtime calls for pruned blocks is disabled".into(),
+			))
+		}
+		log::debug!(target: LOG_TARGET, "Forwarding call {method} at {at:?}");
+
+		StateApiClient::<Block::Hash>::call(&self.client, method, call_data, Some(at))
+			.await
+			.map_err(archive_err)
+	}
+}
+
+/// Checks that the proof was generated at the requested block and converts it.
+fn storage_proof<Block: BlockT>(
+	read_proof: ReadProof<Block::Hash>,
+	at: Block::Hash,
+) -> Result<StorageProof, Error> {
+	if read_proof.at != at {
+		return Err(Error::ArchiveFallback(format!(
+			"Storage proof generated at {:?}, while requested at {at:?}",
+			read_proof.at
+		)))
+	}
+	Ok(StorageProof::new(read_proof.proof.into_iter().map(|node| node.0)))
+}
+
+/// Returns the verified entries in the order of the requested keys.
+fn ordered_entries(
+	keys: Vec<StorageKey>,
+	mut entries: HashMap<Vec<u8>, Option<Vec<u8>>>,
+) -> Vec<Option<StorageData>> {
+	keys.iter()
+		.map(|key| entries.remove(&key.0).flatten().map(StorageData))
+		.collect()
+}
+
+fn archive_err(err: impl std::fmt::Display) -> Error {
+	Error::ArchiveFallback(err.to_string())
+}
@@ -18,6 +18,7 @@
 
 //! Substrate state API.
 
+mod archive_fallback;
 mod state_full;
 mod utils;
 
@@ -40,6 +41,7 @@ use sp_runtime::traits::Block as BlockT;
 use sp_version::RuntimeVersion;
 use std::sync::Arc;
 
+pub use archive_fallback::{ArchiveFallback, ArchiveFallbackConfig};
 pub use sc_rpc_api::{child_state::*, state::*};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
@@ -183,9 +185,45 @@ where
 		+ 'static,
 	Client::Api: Metadata<Block>,
 {
-	let child_backend =
-		Box::new(self::state_full::FullState::new(client.clone(), executor.clone()));
-	let backend = Box::new(self::state_full::FullState::new(client, executor));
+	new_full_with_archive_fallback(client, executor, None)
+}
+
+/// Create new state API that works on full node, querying the state of the blocks pruned locally
+/// from the remote archive node.
+///
+/// The storage entries returned by the remote archive node are verified against the locally
+/// known headers before being served. Only storage and child storage entries, their hashes and
+/// sizes are queried remotely, and runtime calls if enabled in the [`ArchiveFallbackConfig`].
+pub fn new_full_with_archive_fallback<BE, Block: BlockT, Client>(
+	client: Arc<Client>,
+	executor: SubscriptionTaskExecutor,
+	archive_fallback: Option<Arc<ArchiveFallback<Block>>>,
+) -> (State<Block, Client>, ChildState<Block, Client>)
+where
+	Block: BlockT + 'static,
+	Block::Hash: Unpin,
+	BE: Backend<Block> + 'static,
+	Client: ExecutorProvider<Block>
+		+ StorageProvider<Block, BE>
+		+ ProofProvider<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ BlockchainEvents<Block>
+		+ CallApiAt<Block>
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: Metadata<Block>,
+{
+	let child_backend = Box::new(
+		self::state_full::FullState::new(client.clone(), executor.clone())
+			.with_archive_fallback(archive_fallback.clone()),
+	);
+	let backend = Box::new(
+		self::state_full::FullState::new(client, executor).with_archive_fallback(archive_fallback),
+	);
 	(State { backend }, ChildState { backend: child_backend })
 }
 
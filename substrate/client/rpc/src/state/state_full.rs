@@ -21,6 +21,7 @@
 use std::{collections::HashMap, marker::PhantomData, sync::Arc, time::Duration};
 
 use super::{
+	archive_fallback::ArchiveFallback,
 	client_err,
 	error::{Error, Result},
 	ChildStateBackend, StateBackend,
@@ -44,12 +45,13 @@ use sp_blockchain::{
 };
 use sp_core::{
 	storage::{
-		ChildInfo, ChildType, PrefixedStorageKey, StorageChangeSet, StorageData, StorageKey,
+		well_known_keys, ChildInfo, ChildType, PrefixedStorageKey, StorageChangeSet, StorageData,
+		StorageKey,
 	},
 	traits::CallContext,
 	Bytes,
 };
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, Hash as HashT, HashingFor};
 use sp_version::RuntimeVersion;
 
 /// The maximum time allowed for an RPC call when running without unsafe RPC enabled.
@@ -65,6 +67,8 @@ struct QueryStorageRange<Block: BlockT> {
 pub struct FullState<BE, Block: BlockT, Client> {
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
+	/// Remote archive node queried for the state of the blocks pruned locally.
+	archive_fallback: Option<Arc<ArchiveFallback<Block>>>,
 	_phantom: PhantomData<(BE, Block)>,
 }
 
@@ -79,7 +83,35 @@ where
 {
 	/// Create new state API backend for full nodes.
 	pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
-		Self { client, executor, _phantom: PhantomData }
+		Self { client, executor, archive_fallback: None, _phantom: PhantomData }
+	}
+
+	/// Sets the remote archive node queried for the state of the blocks pruned locally.
+	pub fn with_archive_fallback(
+		mut self,
+		archive_fallback: Option<Arc<ArchiveFallback<Block>>>,
+	) -> Self {
+		self.archive_fallback = archive_fallback;
+		self
+	}
+
+	/// Returns the archive fallback and the header of given block, if the state of the block is
+	/// not available locally and shall be queried from the remote archive node.
+	///
+	/// Only the blocks with the header known locally are queried remotely, as the header is
+	/// required to verify the returned values.
+	fn archive_fallback_for(
+		&self,
+		block: Block::Hash,
+	) -> Option<(&ArchiveFallback<Block>, Block::Header)> {
+		let archive_fallback = self.archive_fallback.as_deref()?;
+		let header = self.client.header(block).ok().flatten()?;
+		// Every state contains the runtime code, so failure means the state was pruned.
+		let code_key = StorageKey(well_known_keys::CODE.to_vec());
+		if self.client.storage_hash(block, &code_key).is_ok() {
+			return None
+		}
+		Some((archive_fallback, header))
 	}
 
 	/// Returns given block hash or best block hash if None is passed.
@@ -194,14 +226,15 @@ where
 		method: String,
 		call_data: Bytes,
 	) -> std::result::Result<Bytes, Error> {
-		self.block_or_best(block)
-			.and_then(|block| {
-				self.client
-					.executor()
-					.call(block, &method, &call_data, CallContext::Offchain)
-					.map(Into::into)
-			})
-			.map_err(client_err)
+		let block = self.block_or_best(block).map_err(client_err)?;
+		match self.client.executor().call(block, &method, &call_data, CallContext::Offchain) {
+			Ok(result) => Ok(result.into()),
+			Err(e) => match self.archive_fallback_for(block) {
+				Some((archive_fallback, _)) =>
+					block_on_fallback(archive_fallback.call(block, method, call_data)),
+				None => Err(client_err(e)),
+			},
+		}
 	}
 
 	// TODO: This is horribly broken; either remove it, or make it streaming.
@@ -248,9 +281,16 @@ where
 		block: Option<Block::Hash>,
 		key: StorageKey,
 	) -> std::result::Result<Option<StorageData>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| self.client.storage(block, &key))
-			.map_err(client_err)
+		let block = self.block_or_best(block).map_err(client_err)?;
+		match self.client.storage(block, &key) {
+			Ok(value) => Ok(value),
+			Err(e) => match self.archive_fallback_for(block) {
+				Some((archive_fallback, header)) =>
+					block_on_fallback(archive_fallback.storage(&header, vec![key]))
+						.map(|mut values| values.pop().flatten()),
+				None => Err(client_err(e)),
+			},
+		}
 	}
 
 	async fn storage_size(
@@ -264,6 +304,17 @@ where
 			Err(e) => return Err(client_err(e)),
 		};
 
+		// Only the sizes of existing entries are verifiable, the remote node could leave out keys
+		// of a prefix.
+		if let Some((archive_fallback, header)) = self.archive_fallback_for(block) {
+			return match archive_fallback.storage(&header, vec![key]).await?.pop().flatten() {
+				Some(value) => Ok(Some(value.0.len() as u64)),
+				None => Err(Error::ArchiveFallback(
+					"The size of a key prefix at a pruned block is not available".into(),
+				)),
+			}
+		}
+
 		let client = self.client.clone();
 		let timeout = match deny_unsafe {
 			DenyUnsafe::Yes => Some(MAXIMUM_SAFE_RPC_CALL_TIMEOUT),
@@ -307,9 +358,19 @@ where
 		block: Option<Block::Hash>,
 		key: StorageKey,
 	) -> std::result::Result<Option<Block::Hash>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| self.client.storage_hash(block, &key))
-			.map_err(client_err)
+		let block = self.block_or_best(block).map_err(client_err)?;
+		match self.client.storage_hash(block, &key) {
+			Ok(hash) => Ok(hash),
+			Err(e) => match self.archive_fallback_for(block) {
+				Some((archive_fallback, header)) => block_on_fallback(
+					archive_fallback.storage(&header, vec![key]),
+				)
+				.map(|mut values| {
+					values.pop().flatten().map(|value| HashingFor::<Block>::hash(&value.0))
+				}),
+				None => Err(client_err(e)),
+			},
+		}
 	}
 
 	fn metadata(&self, block: Option<Block::Hash>) -> std::result::Result<Bytes, Error> {
@@ -580,16 +641,24 @@ where
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 	) -> std::result::Result<Option<StorageData>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| {
-				let child_info = match ChildType::from_prefixed_key(&storage_key) {
-					Some((ChildType::ParentKeyId, storage_key)) =>
-						ChildInfo::new_default(storage_key),
-					None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
-				};
-				self.client.child_storage(block, &child_info, &key)
+		let (block, child_info) = self
+			.block_or_best(block)
+			.and_then(|block| match ChildType::from_prefixed_key(&storage_key) {
+				Some((ChildType::ParentKeyId, storage_key)) =>
+					Ok((block, ChildInfo::new_default(storage_key))),
+				None => Err(sp_blockchain::Error::InvalidChildStorageKey),
 			})
-			.map_err(client_err)
+			.map_err(client_err)?;
+		match self.client.child_storage(block, &child_info, &key) {
+			Ok(value) => Ok(value),
+			Err(e) => match self.archive_fallback_for(block) {
+				Some((archive_fallback, header)) => block_on_fallback(
+					archive_fallback.child_storage(&header, &child_info, vec![key]),
+				)
+				.map(|mut values| values.pop().flatten()),
+				None => Err(client_err(e)),
+			},
+		}
 	}
 
 	fn storage_entries(
@@ -606,13 +675,18 @@ where
 			return Err(client_err(sp_blockchain::Error::InvalidChildStorageKey))
 		};
 		let block = self.block_or_best(block).map_err(client_err)?;
-		let client = self.client.clone();
-
-		keys.into_iter()
-			.map(move |key| {
-				client.clone().child_storage(block, &child_info, &key).map_err(client_err)
-			})
-			.collect()
+		let entries = keys
+			.iter()
+			.map(|key| self.client.child_storage(block, &child_info, key))
+			.collect::<sp_blockchain::Result<Vec<_>>>();
+		match entries {
+			Ok(entries) => Ok(entries),
+			Err(e) => match self.archive_fallback_for(block) {
+				Some((archive_fallback, header)) =>
+					block_on_fallback(archive_fallback.child_storage(&header, &child_info, keys)),
+				None => Err(client_err(e)),
+			},
+		}
 	}
 
 	fn storage_hash(
@@ -621,16 +695,26 @@ where
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 	) -> std::result::Result<Option<Block::Hash>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| {
-				let child_info = match ChildType::from_prefixed_key(&storage_key) {
-					Some((ChildType::ParentKeyId, storage_key)) =>
-						ChildInfo::new_default(storage_key),
-					None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
-				};
-				self.client.child_storage_hash(block, &child_info, &key)
+		let (block, child_info) = self
+			.block_or_best(block)
+			.and_then(|block| match ChildType::from_prefixed_key(&storage_key) {
+				Some((ChildType::ParentKeyId, storage_key)) =>
+					Ok((block, ChildInfo::new_default(storage_key))),
+				None => Err(sp_blockchain::Error::InvalidChildStorageKey),
 			})
-			.map_err(client_err)
+			.map_err(client_err)?;
+		match self.client.child_storage_hash(block, &child_info, &key) {
+			Ok(hash) => Ok(hash),
+			Err(e) => match self.archive_fallback_for(block) {
+				Some((archive_fallback, header)) => block_on_fallback(
+					archive_fallback.child_storage(&header, &child_info, vec![key]),
+				)
+				.map(|mut values| {
+					values.pop().flatten().map(|value| HashingFor::<Block>::hash(&value.0))
+				}),
+				None => Err(client_err(e)),
+			},
+		}
 	}
 }
 
@@ -647,3 +731,13 @@ fn invalid_block_range<B: BlockT>(
 fn invalid_block<B: BlockT>(from: B::Hash, to: Option<B::Hash>, details: String) -> Error {
 	Error::InvalidBlockRange { from: format!("{:?}", from), to: format!("{:?}", to), details }
 }
+
+/// Blocks on the request to the remote archive node.
+///
+/// The blocking state RPC methods are executed on the blocking threads of the tokio runtime, so
+/// it is fine to block here.
+fn block_on_fallback<T>(
+	request: impl std::future::Future<Output = std::result::Result<T, Error>>,
+) -> std::result::Result<T, Error> {
+	tokio::runtime::Handle::current().block_on(request)
+}
@@ -25,10 +25,11 @@ use jsonrpsee::{core::EmptyServerParams as EmptyParams, MethodsError as RpcError
 use sc_block_builder::BlockBuilderBuilder;
 use sp_consensus::BlockOrigin;
 use sp_core::{hash::H256, storage::ChildInfo};
+use sp_runtime::traits::{BlakeTwo256, Hash as _};
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	prelude::*,
-	runtime::{ExtrinsicBuilder, Transfer},
+	runtime::{Block, ExtrinsicBuilder, Transfer},
 };
 
 const STORAGE_KEY: &[u8] = b"child";
@@ -538,3 +539,139 @@ async fn concrete_storage_subscriptions_are_rpc_safe() {
 
 	assert!(sub.is_ok());
 }
+
+#[tokio::test]
+async fn archive_fallback_should_verify_storage_proofs() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let genesis_hash = client.genesis_hash();
+	let (api, _child) = new_full(client.clone(), test_executor());
+
+	let server = jsonrpsee::server::Server::builder().build("127.0.0.1:0").await.unwrap();
+	let url = format!("http://{}", server.local_addr().unwrap());
+	let _handle = server.start(api.into_rpc());
+
+	let fallback =
+		ArchiveFallback::<Block>::new(&ArchiveFallbackConfig { url, unverified_calls: false })
+			.unwrap();
+	let header = client.header(genesis_hash).unwrap().unwrap();
+	let code_key = StorageKey(sp_core::storage::well_known_keys::CODE.to_vec());
+	let code = client.storage(genesis_hash, &code_key).unwrap();
+	assert!(code.is_some());
+
+	assert_eq!(
+		fallback
+			.storage(&header, vec![code_key, StorageKey(b":missing".to_vec())])
+			.await
+			.unwrap(),
+		vec![code, None],
+	);
+	assert_matches!(
+		fallback.call(genesis_hash, "Core_version".into(), Bytes(vec![])).await,
+		Err(Error::ArchiveFallback(_))
+	);
+}
+
+#[tokio::test]
+async fn pruned_state_is_served_from_archive_fallback() {
+	const KEY: &[u8] = b":mock";
+	const VALUE: &[u8] = b"hello world";
+	const CHILD_VALUE: &[u8] = b"hello world !";
+
+	let child_info = ChildInfo::new_default(STORAGE_KEY);
+	let archive = Arc::new(
+		TestClientBuilder::new()
+			.add_extra_storage(KEY.to_vec(), VALUE.to_vec())
+			.add_extra_child_storage(&child_info, KEY.to_vec(), CHILD_VALUE.to_vec())
+			.build(),
+	);
+	let pruned = Arc::new(
+		TestClientBuilder::with_pruning_window(1)
+			.add_extra_storage(KEY.to_vec(), VALUE.to_vec())
+			.add_extra_child_storage(&child_info, KEY.to_vec(), CHILD_VALUE.to_vec())
+			.build(),
+	);
+	let genesis_hash = archive.genesis_hash();
+	assert_eq!(pruned.genesis_hash(), genesis_hash);
+
+	for _ in 0..2 {
+		let block = BlockBuilderBuilder::new(&*archive)
+			.on_parent_block(archive.chain_info().best_hash)
+			.with_parent_block_number(archive.chain_info().best_number)
+			.build()
+			.unwrap()
+			.build()
+			.unwrap()
+			.block;
+		archive.import(BlockOrigin::Own, block.clone()).await.unwrap();
+		pruned.import_as_final(BlockOrigin::Own, block).await.unwrap();
+	}
+	let key = StorageKey(KEY.to_vec());
+	assert!(pruned.storage(genesis_hash, &key).is_err());
+
+	let (archive_api, archive_child) = new_full(archive, test_executor());
+	let mut archive_rpc = jsonrpsee::RpcModule::new(());
+	archive_rpc.merge(archive_api.into_rpc()).unwrap();
+	archive_rpc.merge(archive_child.into_rpc()).unwrap();
+	let server = jsonrpsee::server::Server::builder().build("127.0.0.1:0").await.unwrap();
+	let url = format!("http://{}", server.local_addr().unwrap());
+	let _handle = server.start(archive_rpc);
+
+	let fallback = Arc::new(
+		ArchiveFallback::<Block>::new(&ArchiveFallbackConfig { url, unverified_calls: false })
+			.unwrap(),
+	);
+	let (api, child) = new_full_with_archive_fallback(pruned, test_executor(), Some(fallback));
+	let mut api_rpc = api.into_rpc();
+	api_rpc.extensions_mut().insert(DenyUnsafe::No);
+	let child_rpc = child.into_rpc();
+
+	let value: Option<StorageData> =
+		api_rpc.call("state_getStorage", (key.clone(), genesis_hash)).await.unwrap();
+	assert_eq!(value, Some(StorageData(VALUE.to_vec())));
+	let hash: Option<H256> =
+		api_rpc.call("state_getStorageHash", (key.clone(), genesis_hash)).await.unwrap();
+	assert_eq!(hash, Some(BlakeTwo256::hash(VALUE)));
+	let size: Option<u64> =
+		api_rpc.call("state_getStorageSize", (key.clone(), genesis_hash)).await.unwrap();
+	assert_eq!(size, Some(VALUE.len() as u64));
+	// Sizes of key prefixes can't be verified.
+	assert_matches!(
+		api_rpc
+			.call::<_, Option<u64>>(
+				"state_getStorageSize",
+				(StorageKey(b":mo".to_vec()), genesis_hash)
+			)
+			.await,
+		Err(RpcError::JsonRpc(_))
+	);
+	// Runtime calls are not forwarded unless enabled.
+	assert_matches!(
+		api_rpc
+			.call::<_, Bytes>("state_call", ("Core_version", Bytes(vec![]), genesis_hash))
+			.await,
+		Err(RpcError::JsonRpc(_))
+	);
+
+	let value: Option<StorageData> = child_rpc
+		.call("childstate_getStorage", (prefixed_storage_key(), key.clone(), genesis_hash))
+		.await
+		.unwrap();
+	assert_eq!(value, Some(StorageData(CHILD_VALUE.to_vec())));
+	let values: Vec<Option<StorageData>> = child_rpc
+		.call(
+			"childstate_getStorageEntries",
+			(
+				prefixed_storage_key(),
+				vec![key.clone(), StorageKey(b":missing".to_vec())],
+				genesis_hash,
+			),
+		)
+		.await
+		.unwrap();
+	assert_eq!(values, vec![Some(StorageData(CHILD_VALUE.to_vec())), None]);
+	let size: Option<u64> = child_rpc
+		.call("childstate_getStorageSize", (prefixed_storage_key(), key, genesis_hash))
+		.await
+		.unwrap();
+	assert_eq!(size, Some(CHILD_VALUE.len() as u64));
+}
@@ -40,7 +40,7 @@ use sc_executor::{
 	sp_wasm_interface::HostFunctions, HeapAllocStrategy, NativeExecutionDispatch, RuntimeVersionOf,
 	WasmExecutor, DEFAULT_HEAP_ALLOC_STRATEGY,
 };
-use sc_keystore::LocalKeystore;
+use sc_keystore::{LocalKeystore, RemoteKeystore};
 use sc_network::{
 	config::{FullNetworkConfiguration, ProtocolId, SyncMode},
 	multiaddr::Protocol,
@@ -77,7 +77,7 @@ use sc_rpc_spec_v2::{
 	archive::ArchiveApiServer,
 	chain_head::ChainHeadApiServer,
 	chain_spec::ChainSpecApiServer,
-	transaction::{TransactionApiServer, TransactionBroadcastApiServer},
+	transaction::{TransactionApiServer, TransactionBroadcastApiServer, TransactionPoolApiServer},
 };
 use sc_telemetry::{telemetry, ConnectionMessage, Telemetry, TelemetryHandle, SUBSTRATE_INFO};
 use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool};
@@ -106,28 +106,41 @@ type TFullParts<TBl, TRtApi, TExec> =
 	(TFullClient<TBl, TRtApi, TExec>, Arc<TFullBackend<TBl>>, KeystoreContainer, TaskManager);
 
 /// Construct a local keystore shareable container
-pub struct KeystoreContainer(Arc<LocalKeystore>);
+pub struct KeystoreContainer {
+	keystore: KeystorePtr,
+	local: Option<Arc<LocalKeystore>>,
+}
 
 impl KeystoreContainer {
 	/// Construct KeystoreContainer
 	pub fn new(config: &KeystoreConfig) -> Result<Self, Error> {
-		let keystore = Arc::new(match config {
+		let local = match config {
 			KeystoreConfig::Path { path, password } =>
-				LocalKeystore::open(path.clone(), password.clone())?,
-			KeystoreConfig::InMemory => LocalKeystore::in_memory(),
-		});
+				Arc::new(LocalKeystore::open(path.clone(), password.clone())?),
+			KeystoreConfig::InMemory => Arc::new(LocalKeystore::in_memory()),
+			KeystoreConfig::Remote { endpoint, auth_key } => {
+				info!("🔑 Using remote signer at {endpoint}");
+				let keystore = RemoteKeystore::new(endpoint.clone(), auth_key.clone());
+				return Ok(Self { keystore: Arc::new(keystore), local: None })
+			},
+		};
 
-		Ok(Self(keystore))
+		Ok(Self { keystore: local.clone(), local: Some(local) })
 	}
 
 	/// Returns a shared reference to a dynamic `Keystore` trait implementation.
 	pub fn keystore(&self) -> KeystorePtr {
-		self.0.clone()
+		self.keystore.clone()
 	}
 
 	/// Returns a shared reference to the local keystore .
-	pub fn local_keystore(&self) -> Arc<LocalKeystore> {
-		self.0.clone()
+	///
+	/// Fails if a remote signer is used, since its keys can't be accessed locally. Services which
+	/// only sign should use [`Self::keystore`] instead, which works with both.
+	pub fn local_keystore(&self) -> Result<Arc<LocalKeystore>, Error> {
+		self.local.clone().ok_or_else(|| {
+			Error::Other("The local keystore isn't available with a remote signer".into())
+		})
 	}
 }
 
@@ -510,6 +523,17 @@ where
 
 	let rpc_id_provider = config.rpc.id_provider.take();
 
+	let archive_fallback = config
+		.rpc
+		.archive_fallback
+		.as_ref()
+		.map(|config| {
+			sc_rpc::state::ArchiveFallback::new(config)
+				.map(Arc::new)
+				.map_err(|e| Error::Other(format!("Failed to set up RPC archive fallback: {e}")))
+		})
+		.transpose()?;
+
 	// jsonrpsee RPC
 	let gen_rpc_module = || {
 		gen_rpc_module(
@@ -524,6 +548,7 @@ where
 			&config.state_pruning,
 			config.blocks_pruning,
 			backend.clone(),
+			archive_fallback.clone(),
 			&*rpc_builder,
 		)
 	};
@@ -651,6 +676,7 @@ pub fn gen_rpc_module<TBl, TBackend, TCl, TRpc, TExPool>(
 	state_pruning: &Option<PruningMode>,
 	blocks_pruning: BlocksPruning,
 	backend: Arc<TBackend>,
+	archive_fallback: Option<Arc<sc_rpc::state::ArchiveFallback<TBl>>>,
 	rpc_builder: &(dyn Fn(SubscriptionTaskExecutor) -> Result<RpcModule<TRpc>, Error>),
 ) -> Result<RpcModule<()>, Error>
 where
@@ -686,7 +712,11 @@ where
 
 	let (chain, state, child_state) = {
 		let chain = sc_rpc::chain::new_full(client.clone(), task_executor.clone()).into_rpc();
-		let (state, child_state) = sc_rpc::state::new_full(client.clone(), task_executor.clone());
+		let (state, child_state) = sc_rpc::state::new_full_with_archive_fallback(
+			client.clone(),
+			task_executor.clone(),
+			archive_fallback,
+		);
 		let state = state.into_rpc();
 		let child_state = child_state.into_rpc();
 
@@ -710,6 +740,12 @@ where
 	)
 	.into_rpc();
 
+	let transaction_pool_v2 = sc_rpc_spec_v2::transaction::TransactionPoolInspect::new(
+		transaction_pool.clone(),
+		task_executor.clone(),
+	)
+	.into_rpc();
+
 	let chain_head_v2 = sc_rpc_spec_v2::chain_head::ChainHead::new(
 		client.clone(),
 		backend.clone(),
@@ -767,6 +803,7 @@ where
 	rpc_api
 		.merge(transaction_broadcast_rpc_v2)
 		.map_err(|e| Error::Application(e.into()))?;
+	rpc_api.merge(transaction_pool_v2).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(chain_head_v2).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(chain_spec_v2).map_err(|e| Error::Application(e.into()))?;
 
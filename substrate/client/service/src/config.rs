@@ -23,6 +23,7 @@ use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
 pub use sc_client_db::{BlocksPruning, Database, DatabaseSource, PruningMode};
 pub use sc_executor::{WasmExecutionMethod, WasmtimeInstantiationStrategy};
+pub use sc_keystore::remote::{AuthKey as RemoteSignerAuthKey, Endpoint as RemoteSignerEndpoint};
 pub use sc_network::{
 	config::{
 		MultiaddrWithPeerId, NetworkConfiguration, NodeKeyConfig, NonDefaultSetConfig, ProtocolId,
@@ -33,8 +34,10 @@ pub use sc_network::{
 	},
 	Multiaddr,
 };
+pub use sc_rpc::state::ArchiveFallbackConfig;
 pub use sc_rpc_server::{
-	IpNetwork, RpcEndpoint, RpcMethods, SubscriptionIdProvider as RpcSubscriptionIdProvider,
+	IpNetwork, MethodCost as RpcMethodCost, MethodCosts as RpcMethodCosts, RpcEndpoint, RpcMethods,
+	SubscriptionIdProvider as RpcSubscriptionIdProvider,
 };
 pub use sc_telemetry::TelemetryEndpoints;
 pub use sc_transaction_pool::TransactionPoolOptions;
@@ -96,6 +99,10 @@ pub struct Configuration {
 	pub force_authoring: bool,
 	/// Disable GRANDPA when running in validator mode
 	pub disable_grandpa: bool,
+	/// Path of the slashing protection database consulted before signing blocks and votes.
+	///
+	/// `None` if disabled.
+	pub slashing_protection: Option<PathBuf>,
 	/// Development key seed.
 	///
 	/// When running in development mode, the seed will be used to generate authority keys by the
@@ -136,6 +143,16 @@ pub enum KeystoreConfig {
 	},
 	/// In-memory keystore. Recommended for in-browser nodes.
 	InMemory,
+	/// Keystore whose keys are held by a separate signer process.
+	///
+	/// Only the consumers of the [`Keystore`](sc_keystore::Keystore) trait can use the signer,
+	/// there is no local keystore.
+	Remote {
+		/// Endpoint of the signer.
+		endpoint: RemoteSignerEndpoint,
+		/// Key authenticating the requests to the signer.
+		auth_key: RemoteSignerAuthKey,
+	},
 }
 
 impl KeystoreConfig {
@@ -143,7 +160,7 @@ impl KeystoreConfig {
 	pub fn path(&self) -> Option<&Path> {
 		match self {
 			Self::Path { path, .. } => Some(path),
-			Self::InMemory => None,
+			Self::InMemory | Self::Remote { .. } => None,
 		}
 	}
 }
@@ -321,6 +338,12 @@ pub struct RpcConfiguration {
 	pub rate_limit_whitelisted_ips: Vec<IpNetwork>,
 	/// RPC rate limit trust proxy headers.
 	pub rate_limit_trust_proxy_headers: bool,
+	/// RPC method costs per minute allowed for a single IP address, across all connections.
+	pub ip_quota: Option<NonZeroU32>,
+	/// Costs of the RPC methods accounted against the `ip_quota`.
+	pub method_costs: RpcMethodCosts,
+	/// Remote archive node used to serve state queries for blocks pruned locally.
+	pub archive_fallback: Option<ArchiveFallbackConfig>,
 }
 
 /// Runtime executor configuration.
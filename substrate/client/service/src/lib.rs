@@ -447,6 +447,8 @@ where
 		metrics,
 		id_provider: rpc_id_provider,
 		tokio_handle: tokio_handle.clone(),
+		ip_quota: rpc_configuration.ip_quota,
+		method_costs: rpc_configuration.method_costs.clone(),
 	};
 
 	// TODO: https://github.com/paritytech/substrate/issues/13773
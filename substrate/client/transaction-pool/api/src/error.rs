@@ -18,8 +18,11 @@
 
 //! Transaction pool errors.
 
-use sp_runtime::transaction_validity::{
-	InvalidTransaction, TransactionPriority as Priority, UnknownTransaction,
+use sp_runtime::{
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority as Priority, UnknownTransaction,
+	},
+	Percent,
 };
 
 /// Transaction pool result.
@@ -55,12 +58,26 @@ pub enum Error {
 		/// Transaction entering the pool.
 		new: Priority,
 	},
+
+	#[error("Too low priority bump ({} -> {}, required: {:?})", old, new, min_bump)]
+	TooLowPriorityBump {
+		/// Transaction already in the pool.
+		old: Priority,
+		/// Transaction entering the pool.
+		new: Priority,
+		/// The minimal required priority increase.
+		min_bump: Percent,
+	},
+
 	#[error("Transaction with cyclic dependency")]
 	CycleDetected,
 
 	#[error("Transaction couldn't enter the pool because of the limit")]
 	ImmediatelyDropped,
 
+	#[error("Transaction couldn't enter the pool because of the per-sender limit")]
+	SenderLimitReached,
+
 	#[error("Transaction cannot be propagated and the local node does not author blocks")]
 	Unactionable,
 
@@ -83,6 +100,8 @@ impl Error {
 			Error::TemporarilyBanned |
 			// The pool is full at the moment.
 			Error::ImmediatelyDropped |
+			// The sender has too many transactions in the pool at the moment.
+			Error::SenderLimitReached |
 			// The block id is not known to the pool.
 			// The node might be lagging behind, or during a warp sync.
 			Error::InvalidBlockId(_) |
@@ -195,6 +195,56 @@ pub type TransactionStatusStream<Hash, BlockHash> =
 /// The import notification event stream.
 pub type ImportNotificationStream<H> = futures::channel::mpsc::Receiver<H>;
 
+/// An event describing a transaction entering or leaving the pool.
+///
+/// Contrary to [`TransactionStatus`] events, these are emitted for all the transactions in the
+/// pool, not only for the watched ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolEvent<Hash> {
+	/// Transaction was imported into the pool.
+	Imported(Hash),
+	/// Transaction was included in the finalized block and removed from the pool.
+	Finalized(Hash),
+	/// Transaction was replaced in the pool by the other transaction providing the same tags.
+	Usurped {
+		/// Hash of the replaced transaction.
+		hash: Hash,
+		/// Hash of the transaction replacing it.
+		by: Hash,
+	},
+	/// Transaction was dropped from the pool because of the limits.
+	Dropped(Hash),
+	/// Transaction was removed from the pool as it is no longer valid.
+	Invalid(Hash),
+}
+
+impl<Hash> PoolEvent<Hash> {
+	/// Returns the hash of the transaction this event refers to.
+	pub fn hash(&self) -> &Hash {
+		match self {
+			Self::Imported(hash) |
+			Self::Finalized(hash) |
+			Self::Usurped { hash, .. } |
+			Self::Dropped(hash) |
+			Self::Invalid(hash) => hash,
+		}
+	}
+}
+
+/// The stream of [`PoolEvent`]s.
+pub type PoolEventStream<Hash> = Pin<Box<dyn Stream<Item = PoolEvent<Hash>> + Send>>;
+
+/// An opaque identifier of the transaction sender (e.g. the encoded account id).
+pub type SenderId = Vec<u8>;
+
+/// Extracts the [`SenderId`] from the extrinsic.
+///
+/// Used e.g. to enforce the per-sender limit in the pool, or to expose the sender of in-pool
+/// transactions over RPC. The transactions for which `None` is returned (e.g. unsigned
+/// transactions) have no known sender.
+pub type SenderExtractor<Block> =
+	Arc<dyn Fn(&<Block as BlockT>::Extrinsic) -> Option<SenderId> + Send + Sync>;
+
 /// Transaction hash type for a pool.
 pub type TxHash<P> = <P as TransactionPool>::Hash;
 /// Block hash type for a pool.
@@ -304,6 +354,22 @@ pub trait TransactionPool: Send + Sync {
 	/// Return an event stream of transactions imported to the pool.
 	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>>;
 
+	// *** RPC
+	/// Return an event stream of transactions entering and leaving the pool.
+	///
+	/// Pools not supporting this kind of notifications return an empty stream. The pool may close
+	/// the stream of a consumer not keeping up with the events.
+	fn pool_event_stream(&self) -> PoolEventStream<TxHash<Self>> {
+		Box::pin(futures::stream::empty())
+	}
+
+	/// Returns the function determining the sender of the pool transactions.
+	///
+	/// `None` if the pool wasn't given one.
+	fn sender_extractor(&self) -> Option<SenderExtractor<Self::Block>> {
+		None
+	}
+
 	// *** networking
 	/// Notify the pool about transactions broadcast.
 	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>);
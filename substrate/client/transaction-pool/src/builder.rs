@@ -20,7 +20,7 @@
 
 use crate::{
 	common::api::FullChainApi,
-	fork_aware_txpool::ForkAwareTxPool as ForkAwareFullPool,
+	fork_aware_txpool::{ForkAwareTxPool as ForkAwareFullPool, SenderExtractor},
 	graph::{base_pool::Transaction, ChainApi, ExtrinsicFor, ExtrinsicHash, IsValidator, Options},
 	single_state_txpool::BasicPool as SingleStateFullPool,
 	TransactionPoolWrapper, LOG_TARGET,
@@ -28,7 +28,7 @@ use crate::{
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_transaction_pool_api::{LocalTransactionPool, MaintainedTransactionPool};
 use sp_core::traits::SpawnEssentialNamed;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{traits::Block as BlockT, PerThing, Percent};
 use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 /// The type of transaction pool.
@@ -84,6 +84,26 @@ impl TransactionPoolOptions {
 		TransactionPoolOptions { options, txpool_type }
 	}
 
+	/// Sets the minimal priority increase (in percent) required to replace the transactions
+	/// providing the same tags.
+	pub fn with_min_priority_bump(mut self, min_priority_bump: Percent) -> Self {
+		self.options.min_priority_bump = min_priority_bump;
+		self
+	}
+
+	/// Sets the maximum number of transactions from a single sender kept in the pool.
+	///
+	/// Only respected by the fork-aware transaction pool, if the sender extractor was provided
+	/// with [`Builder::with_sender_extractor`]. Otherwise a warning is logged when the pool is
+	/// built.
+	pub fn with_max_transactions_per_sender(
+		mut self,
+		max_transactions_per_sender: Option<usize>,
+	) -> Self {
+		self.options.max_transactions_per_sender = max_transactions_per_sender;
+		self
+	}
+
 	/// Creates predefined options for benchmarking
 	pub fn new_for_benchmarks() -> TransactionPoolOptions {
 		TransactionPoolOptions {
@@ -98,6 +118,8 @@ impl TransactionPoolOptions {
 				},
 				reject_future_transactions: false,
 				ban_time: Duration::from_secs(30 * 60),
+				min_priority_bump: Percent::zero(),
+				max_transactions_per_sender: None,
 			},
 			txpool_type: TransactionPoolType::SingleState,
 		}
@@ -175,6 +197,7 @@ pub struct Builder<'a, Block, Client> {
 	prometheus: Option<&'a PrometheusRegistry>,
 	client: Arc<Client>,
 	spawner: Box<dyn SpawnEssentialNamed>,
+	sender_extractor: Option<SenderExtractor<Block>>,
 	_phantom: PhantomData<(Client, Block)>,
 }
 
@@ -207,6 +230,7 @@ where
 			client,
 			is_validator,
 			prometheus: None,
+			sender_extractor: None,
 		}
 	}
 
@@ -222,9 +246,35 @@ where
 		self
 	}
 
+	/// Sets the function determining the sender of the transaction.
+	///
+	/// Used by the fork-aware transaction pool to enforce the per-sender limit.
+	pub fn with_sender_extractor(
+		mut self,
+		sender_extractor: impl Fn(&Block::Extrinsic) -> Option<Vec<u8>> + Send + Sync + 'static,
+	) -> Self {
+		self.sender_extractor = Some(Arc::new(sender_extractor));
+		self
+	}
+
 	/// Creates an instance of transaction pool.
 	pub fn build(self) -> TransactionPoolHandle<Block, Client> {
 		log::info!(target:LOG_TARGET, " creating {:?} txpool {:?}/{:?}.", self.options.txpool_type, self.options.options.ready, self.options.options.future);
+		if let Some(limit) = self.options.options.max_transactions_per_sender {
+			match self.options.txpool_type {
+				TransactionPoolType::SingleState => log::warn!(
+					target: LOG_TARGET,
+					"The per-sender limit of {limit} transactions is ignored by the single-state \
+					 transaction pool, use the fork-aware transaction pool to enforce it."
+				),
+				TransactionPoolType::ForkAware if self.sender_extractor.is_none() => log::warn!(
+					target: LOG_TARGET,
+					"The per-sender limit of {limit} transactions is ignored, this node doesn't \
+					 determine the sender of transactions."
+				),
+				TransactionPoolType::ForkAware => (),
+			}
+		}
 		TransactionPoolWrapper::<Block, Client>(match self.options.txpool_type {
 			TransactionPoolType::SingleState => Box::new(SingleStateFullPool::new_full(
 				self.options.options,
@@ -239,6 +289,7 @@ where
 				self.prometheus,
 				self.spawner,
 				self.client,
+				self.sender_extractor,
 			)),
 		})
 	}
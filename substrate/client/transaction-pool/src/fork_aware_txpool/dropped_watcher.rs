@@ -47,7 +47,39 @@ type ViewStream<C> = Pin<Box<dyn futures::Stream<Item = ViewStreamEvent<C>> + Se
 
 /// Stream of extrinsic hashes that were dropped by the views and have no references by existing
 /// views.
-pub(crate) type StreamOfDropped<C> = Pin<Box<dyn futures::Stream<Item = ExtrinsicHash<C>> + Send>>;
+pub(crate) type StreamOfDropped<C> =
+	Pin<Box<dyn futures::Stream<Item = DroppedTransaction<ExtrinsicHash<C>>> + Send>>;
+
+/// The reason for which the transaction was dropped from all the views.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DroppedReason<Hash> {
+	/// Transaction was replaced by the other transaction (e.g. providing the same tags with higher
+	/// priority).
+	Usurped(Hash),
+	/// Transaction was dropped because of the view's limits.
+	LimitsEnforced,
+}
+
+/// Represents the transaction that was dropped from all the views.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DroppedTransaction<Hash> {
+	/// Hash of the dropped transaction.
+	pub tx_hash: Hash,
+	/// The reason of dropping the transaction.
+	pub reason: DroppedReason<Hash>,
+}
+
+impl<Hash> DroppedTransaction<Hash> {
+	/// Creates a new instance with the reason set to `DroppedReason::Usurped(by)`.
+	pub fn new_usurped(tx_hash: Hash, by: Hash) -> Self {
+		Self { tx_hash, reason: DroppedReason::Usurped(by) }
+	}
+
+	/// Creates a new instance with the reason set to `DroppedReason::LimitsEnforced`.
+	pub fn new_enforced_by_limits(tx_hash: Hash) -> Self {
+		Self { tx_hash, reason: DroppedReason::LimitsEnforced }
+	}
+}
 
 /// A type alias for a sender used as the controller of the [`MultiViewDropWatcherContext`].
 /// Used to send control commands from the [`MultiViewDroppedWatcherController`] to
@@ -120,12 +152,13 @@ where
 	/// accordingly.
 	///
 	/// If the event indicates that a transaction has been dropped and is no longer referenced by
-	/// any active views, the transaction hash is returned. Otherwise `None` is returned.
+	/// any active views, the transaction hash together with the reason of dropping is returned.
+	/// Otherwise `None` is returned.
 	fn handle_event(
 		&mut self,
 		block_hash: BlockHash<C>,
 		event: ViewStreamEvent<C>,
-	) -> Option<ExtrinsicHash<C>> {
+	) -> Option<DroppedTransaction<ExtrinsicHash<C>>> {
 		trace!(
 			target: LOG_TARGET,
 			"dropped_watcher: handle_event: event:{:?} views:{:?}, ",
@@ -138,6 +171,10 @@ where
 				self.transaction_states.entry(tx_hash).or_default().insert(block_hash);
 			},
 			TransactionStatus::Dropped | TransactionStatus::Usurped(_) => {
+				let dropped = match status {
+					TransactionStatus::Usurped(by) => DroppedTransaction::new_usurped(tx_hash, by),
+					_ => DroppedTransaction::new_enforced_by_limits(tx_hash),
+				};
 				if let Entry::Occupied(mut views_keeping_tx_valid) =
 					self.transaction_states.entry(tx_hash)
 				{
@@ -148,11 +185,11 @@ where
 							.iter()
 							.all(|h| !self.stream_map.contains_key(h))
 					{
-						return Some(tx_hash)
+						return Some(dropped)
 					}
 				} else {
 					debug!("[{:?}] dropped_watcher: removing (non-tracked) tx", tx_hash);
-					return Some(tx_hash)
+					return Some(dropped)
 				}
 			},
 			_ => {},
@@ -298,7 +335,10 @@ mod dropped_watcher_tests {
 
 		watcher.add_view(block_hash, view_stream);
 		let handle = tokio::spawn(async move { output_stream.take(1).collect::<Vec<_>>().await });
-		assert_eq!(handle.await.unwrap(), vec![tx_hash]);
+		assert_eq!(
+			handle.await.unwrap(),
+			vec![DroppedTransaction::new_enforced_by_limits(tx_hash)]
+		);
 	}
 
 	#[tokio::test]
@@ -348,7 +388,10 @@ mod dropped_watcher_tests {
 		watcher.add_view(block_hash0, view_stream0);
 		watcher.add_view(block_hash1, view_stream1);
 		let handle = tokio::spawn(async move { output_stream.take(1).collect::<Vec<_>>().await });
-		assert_eq!(handle.await.unwrap(), vec![tx_hash1]);
+		assert_eq!(
+			handle.await.unwrap(),
+			vec![DroppedTransaction::new_enforced_by_limits(tx_hash1)]
+		);
 	}
 
 	#[tokio::test]
@@ -376,7 +419,10 @@ mod dropped_watcher_tests {
 
 		watcher.add_view(block_hash1, view_stream1);
 		let handle = tokio::spawn(async move { output_stream.take(1).collect::<Vec<_>>().await });
-		assert_eq!(handle.await.unwrap(), vec![tx_hash]);
+		assert_eq!(
+			handle.await.unwrap(),
+			vec![DroppedTransaction::new_enforced_by_limits(tx_hash)]
+		);
 	}
 
 	#[tokio::test]
@@ -419,6 +465,39 @@ mod dropped_watcher_tests {
 		let block_hash2 = H256::repeat_byte(0x03);
 		watcher.add_view(block_hash2, view_stream2);
 		let handle = tokio::spawn(async move { output_stream.take(1).collect::<Vec<_>>().await });
-		assert_eq!(handle.await.unwrap(), vec![tx_hash]);
+		assert_eq!(
+			handle.await.unwrap(),
+			vec![DroppedTransaction::new_enforced_by_limits(tx_hash)]
+		);
+	}
+
+	#[tokio::test]
+	async fn test06() {
+		sp_tracing::try_init_simple();
+		let (watcher, output_stream) = MultiViewDroppedWatcher::new();
+
+		let block_hash0 = H256::repeat_byte(0x01);
+		let block_hash1 = H256::repeat_byte(0x02);
+		let tx_hash = H256::repeat_byte(0x0a);
+		let usurper_hash = H256::repeat_byte(0x0b);
+
+		let view_stream0 = futures::stream::iter(vec![
+			(tx_hash, TransactionStatus::Ready),
+			(tx_hash, TransactionStatus::Usurped(usurper_hash)),
+		])
+		.boxed();
+		let view_stream1 = futures::stream::iter(vec![
+			(tx_hash, TransactionStatus::Future),
+			(tx_hash, TransactionStatus::Usurped(usurper_hash)),
+		])
+		.boxed();
+
+		watcher.add_view(block_hash0, view_stream0);
+		watcher.add_view(block_hash1, view_stream1);
+		let handle = tokio::spawn(async move { output_stream.take(1).collect::<Vec<_>>().await });
+		assert_eq!(
+			handle.await.unwrap(),
+			vec![DroppedTransaction::new_usurped(tx_hash, usurper_hash)]
+		);
 	}
 }
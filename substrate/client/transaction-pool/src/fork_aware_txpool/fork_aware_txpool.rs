@@ -45,8 +45,10 @@ use futures::{
 use parking_lot::Mutex;
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_transaction_pool_api::{
-	ChainEvent, ImportNotificationStream, MaintainedTransactionPool, PoolStatus, TransactionFor,
-	TransactionPool, TransactionSource, TransactionStatusStreamFor, TxHash,
+	error::{Error as TxPoolApiError, IntoPoolError},
+	ChainEvent, ImportNotificationStream, MaintainedTransactionPool, PoolEventStream, PoolStatus,
+	SenderExtractor, TransactionFor, TransactionPool, TransactionSource,
+	TransactionStatusStreamFor, TxHash,
 };
 use sp_blockchain::{HashAndNumber, TreeRoute};
 use sp_core::traits::SpawnEssentialNamed;
@@ -182,18 +184,41 @@ where
 		ready_limits: crate::PoolLimit,
 		future_limits: crate::PoolLimit,
 		mempool_max_transactions_count: usize,
+	) -> (Self, ForkAwareTxPoolTask) {
+		Self::new_test_with_options(
+			pool_api,
+			best_block_hash,
+			finalized_hash,
+			Options { ready: ready_limits, future: future_limits, ..Default::default() },
+			mempool_max_transactions_count,
+			None,
+		)
+	}
+
+	/// Create new fork aware transaction pool with given options and with provided shared
+	/// instance of `ChainApi` intended for tests.
+	pub fn new_test_with_options(
+		pool_api: Arc<ChainApi>,
+		best_block_hash: Block::Hash,
+		finalized_hash: Block::Hash,
+		options: Options,
+		mempool_max_transactions_count: usize,
+		sender_extractor: Option<SenderExtractor<Block>>,
 	) -> (Self, ForkAwareTxPoolTask) {
 		let listener = Arc::from(MultiViewListener::new());
 		let (import_notification_sink, import_notification_sink_task) =
 			MultiViewImportNotificationSink::new_with_worker();
 
-		let mempool = Arc::from(TxMemPool::new(
-			pool_api.clone(),
-			listener.clone(),
-			Default::default(),
-			mempool_max_transactions_count,
-			ready_limits.total_bytes + future_limits.total_bytes,
-		));
+		let mempool = Arc::from(
+			TxMemPool::new(
+				pool_api.clone(),
+				listener.clone(),
+				Default::default(),
+				mempool_max_transactions_count,
+				options.ready.total_bytes + options.future.total_bytes,
+			)
+			.with_per_sender_limit(options.max_transactions_per_sender, sender_extractor),
+		);
 
 		let (dropped_stream_controller, dropped_stream) =
 			MultiViewDroppedWatcherController::<ChainApi>::new();
@@ -211,8 +236,6 @@ where
 		}
 		.boxed();
 
-		let options = Options { ready: ready_limits, future: future_limits, ..Default::default() };
-
 		(
 			Self {
 				mempool,
@@ -251,9 +274,9 @@ where
 				log::debug!(target: LOG_TARGET, "fatp::dropped_monitor_task: terminated...");
 				break;
 			};
-			log::trace!(target: LOG_TARGET, "[{:?}] fatp::dropped notification, removing", dropped);
-			mempool.remove_dropped_transactions(&[dropped]).await;
-			import_notification_sink.clean_notified_items(&[dropped]);
+			log::trace!(target: LOG_TARGET, "[{:?}] fatp::dropped notification {:?}, removing", dropped.tx_hash, dropped.reason);
+			mempool.remove_dropped_transaction(&dropped).await;
+			import_notification_sink.clean_notified_items(&[dropped.tx_hash]);
 		}
 	}
 
@@ -261,6 +284,9 @@ where
 	///
 	/// The txpool essential tasks (including a revalidation worker) are spawned using provided
 	/// spawner.
+	///
+	/// The optional `sender_extractor` is used to enforce the per-sender limit (given in
+	/// [`Options::max_transactions_per_sender`]) in the internal *mempool*.
 	pub fn new_with_background_worker(
 		options: Options,
 		is_validator: IsValidator,
@@ -269,6 +295,7 @@ where
 		spawner: impl SpawnEssentialNamed,
 		best_block_hash: Block::Hash,
 		finalized_hash: Block::Hash,
+		sender_extractor: Option<SenderExtractor<Block>>,
 	) -> Self {
 		let metrics = PrometheusMetrics::new(prometheus);
 		let listener = Arc::from(MultiViewListener::new());
@@ -278,13 +305,17 @@ where
 		let (import_notification_sink, import_notification_sink_task) =
 			MultiViewImportNotificationSink::new_with_worker();
 
-		let mempool = Arc::from(TxMemPool::new(
-			pool_api.clone(),
-			listener.clone(),
-			metrics.clone(),
-			TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER * (options.ready.count + options.future.count),
-			options.ready.total_bytes + options.future.total_bytes,
-		));
+		let mempool = Arc::from(
+			TxMemPool::new(
+				pool_api.clone(),
+				listener.clone(),
+				metrics.clone(),
+				TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER *
+					(options.ready.count + options.future.count),
+				options.ready.total_bytes + options.future.total_bytes,
+			)
+			.with_per_sender_limit(options.max_transactions_per_sender, sender_extractor),
+		);
 
 		let (dropped_stream_controller, dropped_stream) =
 			MultiViewDroppedWatcherController::<ChainApi>::new();
@@ -366,6 +397,25 @@ where
 		self.mempool.unwatched_and_watched_count()
 	}
 
+	/// Sends out the `Imported` pool event for all the successfully submitted transactions.
+	fn notify_imported(&self, results: &[Result<ExtrinsicHash<ChainApi>, ChainApi::Error>]) {
+		let imported = results.iter().filter_map(|r| r.as_ref().ok().copied()).collect::<Vec<_>>();
+		self.view_store.listener.transactions_imported(&imported);
+	}
+
+	/// Updates the metrics for the transaction rejected by the views.
+	fn report_rejected(&self, error: ChainApi::Error) -> ChainApi::Error {
+		match error.into_pool_error() {
+			Ok(error) => {
+				if matches!(error, TxPoolApiError::TooLowPriorityBump { .. }) {
+					self.metrics.report(|metrics| metrics.too_low_priority_bump_txs.inc());
+				}
+				error.into()
+			},
+			Err(error) => error,
+		}
+	}
+
 	/// Returns a best-effort set of ready transactions for a given block, without executing full
 	/// maintain process.
 	///
@@ -600,6 +650,7 @@ where
 		let mempool_results = self.mempool.extend_unwatched(source, &xts);
 
 		if view_store.is_empty() {
+			self.notify_imported(&mempool_results);
 			return Ok(mempool_results)
 		}
 
@@ -616,7 +667,7 @@ where
 		let results_map = view_store.submit(source, to_be_submitted.into_iter()).await;
 		let mut submission_results = reduce_multiview_result(results_map).into_iter();
 
-		Ok(mempool_results
+		let results = mempool_results
 				.into_iter()
 				.map(|result| {
 					result.and_then(|xt_hash| {
@@ -626,9 +677,12 @@ where
 							.inspect_err(|_|
 								mempool.remove(xt_hash)
 							)
+							.map_err(|e| self.report_rejected(e))
 					})
 				})
-				.collect::<Vec<_>>())
+				.collect::<Vec<_>>();
+		self.notify_imported(&results);
+		Ok(results)
 	}
 
 	/// Submits a single transaction and returns a future resolving to the submission results.
@@ -672,7 +726,9 @@ where
 		view_store
 			.submit_and_watch(at, source, xt)
 			.await
+			.inspect(|_| self.view_store.listener.transactions_imported(&[xt_hash]))
 			.inspect_err(|_| mempool.remove(xt_hash))
+			.map_err(|e| self.report_rejected(e))
 	}
 
 	/// Intended to remove transactions identified by the given hashes, and any dependent
@@ -713,6 +769,19 @@ where
 		self.import_notification_sink.event_stream()
 	}
 
+	/// Return an event stream of transactions entering and leaving the pool.
+	///
+	/// The events are emitted for both watched and unwatched transactions, and are provided by the
+	/// multi view listener.
+	fn pool_event_stream(&self) -> PoolEventStream<ExtrinsicHash<ChainApi>> {
+		self.view_store.listener.create_pool_event_stream()
+	}
+
+	/// Returns the function determining the sender of the transactions, as given to the pool.
+	fn sender_extractor(&self) -> Option<SenderExtractor<Block>> {
+		self.mempool.sender_extractor()
+	}
+
 	/// Returns the hash of a given transaction.
 	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
 		self.api().hash_and_length(xt).0
@@ -1352,6 +1421,7 @@ where
 		prometheus: Option<&PrometheusRegistry>,
 		spawner: impl SpawnEssentialNamed,
 		client: Arc<Client>,
+		sender_extractor: Option<SenderExtractor<Block>>,
 	) -> Self {
 		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
 		let pool = Self::new_with_background_worker(
@@ -1362,6 +1432,7 @@ where
 			spawner,
 			client.usage_info().chain.best_hash,
 			client.usage_info().chain.finalized_hash,
+			sender_extractor,
 		);
 
 		pool
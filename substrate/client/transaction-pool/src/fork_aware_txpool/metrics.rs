@@ -59,6 +59,14 @@ pub struct Metrics {
 	pub view_revalidation_duration: Histogram,
 	/// Total number of the views created w/o cloning existing view.
 	pub non_cloned_views: Counter<U64>,
+	/// Total number of transactions replaced by other transactions in all the views.
+	pub usurped_txs: Counter<U64>,
+	/// Total number of transactions dropped by all the views because of the limits.
+	pub dropped_by_limits_txs: Counter<U64>,
+	/// Total number of transactions rejected by mempool because of the per-sender limit.
+	pub sender_limit_rejected_txs: Counter<U64>,
+	/// Total number of transactions rejected because of the too low priority bump.
+	pub too_low_priority_bump_txs: Counter<U64>,
 }
 
 impl MetricsRegistrant for Metrics {
@@ -171,6 +179,34 @@ impl MetricsRegistrant for Metrics {
 				)?,
 				registry,
 			)?,
+			usurped_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_usurped_txs_total",
+					"Total number of transactions replaced by other transactions in all the views.",
+				)?,
+				registry,
+			)?,
+			dropped_by_limits_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_dropped_by_limits_txs_total",
+					"Total number of transactions dropped by all the views because of the limits.",
+				)?,
+				registry,
+			)?,
+			sender_limit_rejected_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_sender_limit_rejected_txs_total",
+					"Total number of transactions rejected by mempool because of the per-sender limit.",
+				)?,
+				registry,
+			)?,
+			too_low_priority_bump_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_too_low_priority_bump_txs_total",
+					"Total number of transactions rejected because of the too low priority bump.",
+				)?,
+				registry,
+			)?,
 		}))
 	}
 }
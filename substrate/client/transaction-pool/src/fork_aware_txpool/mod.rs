@@ -97,6 +97,10 @@
 //! More information about it is provided in [transaction
 //! route](#transaction-route-submit_and_watch) section.
 //!
+//! The [`MultiViewListener`] also provides the [stream of pool events][`PoolEvent`], which
+//! reports all (also unwatched) transactions entering the pool (after successful submission) and
+//! leaving the pool (when finalized, usurped, dropped or invalidated).
+//!
 //!
 //! ### Intermediate transactions buffer: [`TxMemPool`]
 //! The main purpose of an internal [`TxMemPool`] (referred to as *mempool*) is to prevent a
@@ -285,6 +289,18 @@
 //! while not referenced by the others), what means that transaction can also be
 //! [removed][`dropped_monitor_task`] from the *mempool*.
 //!
+//! Every dropped transaction is accompanied with the [reason][`DroppedReason`]. If the transaction
+//! was replaced by the other transaction (providing the same tags with the priority higher at least
+//! by [`min_priority_bump`]), the [`Usurped`] event is sent to the external watcher. Otherwise the
+//! transaction was dropped because of the limits and the [`Dropped`] event is sent.
+//!
+//! ### Per-sender limit
+//! The *mempool* can also limit the [number of transactions][`max_transactions_per_sender`] that a
+//! single sender can keep in the pool, so one account cannot fill the *mempool* with e.g. valid
+//! future transactions. The pool is not able to determine the sender on its own, so the
+//! [`SenderExtractor`] needs to be provided when the pool is built. Transactions exceeding the
+//! limit are rejected with [`SenderLimitReached`] error.
+//!
 //!
 //! ## API Considerations
 //! Refer to github issue: <https://github.com/paritytech/polkadot-sdk/issues/5491>
@@ -340,6 +356,14 @@
 //! [`MultiViewImportNotificationSink`]: crate::fork_aware_txpool::import_notification_sink::MultiViewImportNotificationSink
 //! [`RevalidationQueue`]: crate::fork_aware_txpool::revalidation_worker::RevalidationQueue
 //! [`StreamOfDropped`]: crate::fork_aware_txpool::dropped_watcher::StreamOfDropped
+//! [`DroppedReason`]: crate::fork_aware_txpool::dropped_watcher::DroppedReason
+//! [`SenderExtractor`]: sc_transaction_pool_api::SenderExtractor
+//! [`PoolEvent`]: sc_transaction_pool_api::PoolEvent
+//! [`min_priority_bump`]: crate::graph::Options::min_priority_bump
+//! [`max_transactions_per_sender`]: crate::graph::Options::max_transactions_per_sender
+//! [`Usurped`]:sc_transaction_pool_api::TransactionStatus::Usurped
+//! [`Dropped`]:sc_transaction_pool_api::TransactionStatus::Dropped
+//! [`SenderLimitReached`]: sc_transaction_pool_api::error::Error::SenderLimitReached
 //! [`Arc`]: std::sync::Arc
 
 mod dropped_watcher;
@@ -353,6 +377,7 @@ mod view;
 mod view_store;
 
 pub use fork_aware_txpool::{ForkAwareTxPool, ForkAwareTxPoolTask};
+pub use sc_transaction_pool_api::{SenderExtractor, SenderId};
 
 mod stream_map_util {
 	use futures::Stream;
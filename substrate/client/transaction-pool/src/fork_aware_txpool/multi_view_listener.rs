@@ -25,9 +25,11 @@ use crate::{
 	graph::{self, BlockHash, ExtrinsicHash},
 	LOG_TARGET,
 };
-use futures::StreamExt;
-use log::{debug, trace};
-use sc_transaction_pool_api::{TransactionStatus, TransactionStatusStream, TxIndex};
+use futures::{channel::mpsc::Sender as PoolEventSink, StreamExt};
+use log::{debug, trace, warn};
+use sc_transaction_pool_api::{
+	PoolEvent, PoolEventStream, TransactionStatus, TransactionStatusStream, TxIndex,
+};
 use sc_utils::mpsc;
 use sp_runtime::traits::Block as BlockT;
 use std::{
@@ -42,6 +44,14 @@ use tokio_stream::StreamMap;
 /// Set of instances of [`Controller`] lives within the [`MultiViewListener`].
 type Controller<T> = mpsc::TracingUnboundedSender<T>;
 
+/// The capacity of a single [`PoolEvent`]s stream.
+///
+/// The stream of a consumer falling behind by this many events is closed.
+const POOL_EVENT_STREAM_BUFFER_SIZE: usize = 1024;
+
+/// The maximum number of [`PoolEvent`]s streams open at the same time.
+const MAX_POOL_EVENT_STREAMS: usize = 64;
+
 /// A receiver of [`ControllerCommand`] instances allowing to control the external stream.
 ///
 /// Lives within the [`ExternalWatcherContext`] instance.
@@ -80,6 +90,11 @@ enum ControllerCommand<ChainApi: graph::ChainApi> {
 	///
 	/// If all preconditions are met, an external dropped event will be sent out.
 	TransactionDropped,
+
+	/// Notifies that a transaction was replaced by the other transaction.
+	///
+	/// Sends out an external usurped event.
+	TransactionUsurped(ExtrinsicHash<ChainApi>),
 }
 
 impl<ChainApi> std::fmt::Debug for ControllerCommand<ChainApi>
@@ -102,6 +117,9 @@ where
 			ControllerCommand::TransactionDropped => {
 				write!(f, "ListenerAction::TransactionDropped")
 			},
+			ControllerCommand::TransactionUsurped(by) => {
+				write!(f, "ListenerAction::TransactionUsurped({by})")
+			},
 		}
 	}
 }
@@ -116,12 +134,18 @@ where
 ///
 /// The listener provides a side channel that allows triggering specific events (finalized, dropped,
 /// invalid) independently of the view's stream.
+///
+/// Additionally the listener provides the stream of [`PoolEvent`]s, describing all (including
+/// unwatched) transactions entering and leaving the pool.
 pub struct MultiViewListener<ChainApi: graph::ChainApi> {
 	/// Provides the set of controllers for the events streams corresponding to individual
 	/// transactions identified by transaction hashes.
 	controllers: parking_lot::RwLock<
 		HashMap<ExtrinsicHash<ChainApi>, Controller<ControllerCommand<ChainApi>>>,
 	>,
+
+	/// The sinks of the pool events streams.
+	pool_event_sinks: parking_lot::Mutex<Vec<PoolEventSink<PoolEvent<ExtrinsicHash<ChainApi>>>>>,
 }
 
 /// The external stream unfolding context.
@@ -279,7 +303,67 @@ where
 {
 	/// Creates new instance of `MultiViewListener`.
 	pub fn new() -> Self {
-		Self { controllers: Default::default() }
+		Self { controllers: Default::default(), pool_event_sinks: Default::default() }
+	}
+
+	/// Creates a new stream of [`PoolEvent`]s describing transactions entering and leaving the
+	/// pool.
+	///
+	/// The stream is closed if its consumer doesn't keep up with the events. If
+	/// [`MAX_POOL_EVENT_STREAMS`] streams are already open, the returned stream is closed right
+	/// away.
+	pub(crate) fn create_pool_event_stream(&self) -> PoolEventStream<ExtrinsicHash<ChainApi>> {
+		let mut sinks = self.pool_event_sinks.lock();
+		sinks.retain(|sink| !sink.is_closed());
+		if sinks.len() >= MAX_POOL_EVENT_STREAMS {
+			warn!(
+				target: LOG_TARGET,
+				"mvl: refusing pool events stream, {MAX_POOL_EVENT_STREAMS} streams already open"
+			);
+			return futures::stream::empty().boxed()
+		}
+
+		let (sink, stream) = futures::channel::mpsc::channel(POOL_EVENT_STREAM_BUFFER_SIZE);
+		sinks.push(sink);
+		stream.boxed()
+	}
+
+	/// Sends out the given pool events to all the pool events streams.
+	///
+	/// The sinks of closed streams, and of the streams not consumed fast enough, are removed.
+	fn send_pool_events(
+		&self,
+		events: impl IntoIterator<Item = PoolEvent<ExtrinsicHash<ChainApi>>>,
+	) {
+		let mut sinks = self.pool_event_sinks.lock();
+		if sinks.is_empty() {
+			return
+		}
+		for event in events {
+			trace!(target: LOG_TARGET, "[{:?}] mvl sending out pool event: {:?}", event.hash(), event);
+			sinks.retain_mut(|sink| match sink.try_send(event.clone()) {
+				Ok(()) => true,
+				Err(e) => {
+					if e.is_full() {
+						warn!(target: LOG_TARGET, "mvl: closing pool events stream lagging behind");
+					}
+					false
+				},
+			});
+		}
+	}
+
+	/// Send `Imported` pool event for given transactions.
+	pub(crate) fn transactions_imported(&self, imported: &[ExtrinsicHash<ChainApi>]) {
+		self.send_pool_events(imported.iter().map(|tx_hash| PoolEvent::Imported(*tx_hash)));
+	}
+
+	/// Send `Finalized` pool event for given transactions.
+	///
+	/// Shall be called only for the transactions actually removed from the pool, it does not affect
+	/// the external watchers (see [`Self::finalize_transaction`]).
+	pub(crate) fn transactions_finalized(&self, finalized: &[ExtrinsicHash<ChainApi>]) {
+		self.send_pool_events(finalized.iter().map(|tx_hash| PoolEvent::Finalized(*tx_hash)));
 	}
 
 	/// Creates an external aggregated stream of events for given transaction.
@@ -351,6 +435,11 @@ where
 									ctx.terminate = true;
 									return Some((TransactionStatus::Dropped, ctx))
 								},
+								ControllerCommand::TransactionUsurped(by) => {
+									log::trace!(target: LOG_TARGET, "[{:?}] mvl sending out: Usurped({:?})", ctx.tx_hash, by);
+									ctx.terminate = true;
+									return Some((TransactionStatus::Usurped(by), ctx))
+								},
 							}
 						},
 					};
@@ -407,6 +496,7 @@ where
 	/// The external event will be sent if no view is referencing the transaction as `Ready` or
 	/// `Future`.
 	pub(crate) fn invalidate_transactions(&self, invalid_hashes: &[ExtrinsicHash<ChainApi>]) {
+		self.send_pool_events(invalid_hashes.iter().map(|tx_hash| PoolEvent::Invalid(*tx_hash)));
 		let mut controllers = self.controllers.write();
 		invalid_hashes.iter().for_each(|tx_hash| {
 			if let Entry::Occupied(mut tx) = controllers.entry(*tx_hash) {
@@ -446,6 +536,7 @@ where
 	/// This method sends a `TransactionDropped` command to the controller of each requested
 	/// transaction prompting and external `Broadcasted` event.
 	pub(crate) fn transactions_dropped(&self, dropped: &[ExtrinsicHash<ChainApi>]) {
+		self.send_pool_events(dropped.iter().map(|tx_hash| PoolEvent::Dropped(*tx_hash)));
 		let mut controllers = self.controllers.write();
 		debug!(target: LOG_TARGET, "mvl::transactions_dropped: {:?}", dropped);
 		for tx_hash in dropped {
@@ -458,6 +549,25 @@ where
 		}
 	}
 
+	/// Send `Usurped` event to listener of the transaction.
+	///
+	/// This method sends a `TransactionUsurped` command to the controller of the given
+	/// transaction prompting the external `Usurped` event.
+	pub(crate) fn transaction_usurped(
+		&self,
+		tx_hash: ExtrinsicHash<ChainApi>,
+		by: ExtrinsicHash<ChainApi>,
+	) {
+		self.send_pool_events([PoolEvent::Usurped { hash: tx_hash, by }]);
+		let mut controllers = self.controllers.write();
+		if let Some(tx) = controllers.remove(&tx_hash) {
+			debug!(target: LOG_TARGET, "[{:?}] transaction_usurped by {:?}", tx_hash, by);
+			if let Err(e) = tx.unbounded_send(ControllerCommand::TransactionUsurped(by)) {
+				trace!(target: LOG_TARGET, "[{:?}] transaction_usurped: send message failed: {:?}", tx_hash, e);
+			};
+		}
+	}
+
 	/// Send `Finalized` event for given transaction at given block.
 	///
 	/// This will send `Finalized` event to the external watcher.
@@ -733,4 +843,78 @@ mod tests {
 		assert!(out.iter().all(|v| vec![TransactionStatus::Invalid].contains(v)));
 		assert_eq!(out.len(), 1);
 	}
+
+	#[tokio::test]
+	async fn test06() {
+		sp_tracing::try_init_simple();
+		let listener = MultiViewListener::new();
+
+		let tx_hash0 = H256::repeat_byte(0x0a);
+		let tx_hash1 = H256::repeat_byte(0x0b);
+		let tx_hash2 = H256::repeat_byte(0x0c);
+
+		let pool_events = listener.create_pool_event_stream();
+
+		listener.transactions_imported(&[tx_hash0, tx_hash1, tx_hash2]);
+		listener.transaction_usurped(tx_hash0, tx_hash2);
+		listener.transactions_dropped(&[tx_hash1]);
+		listener.invalidate_transactions(&[tx_hash2]);
+		listener.transactions_finalized(&[tx_hash1]);
+		drop(listener);
+
+		let out = pool_events.collect::<Vec<_>>().await;
+		log::debug!("out: {:#?}", out);
+
+		assert_eq!(
+			out,
+			vec![
+				PoolEvent::Imported(tx_hash0),
+				PoolEvent::Imported(tx_hash1),
+				PoolEvent::Imported(tx_hash2),
+				PoolEvent::Usurped { hash: tx_hash0, by: tx_hash2 },
+				PoolEvent::Dropped(tx_hash1),
+				PoolEvent::Invalid(tx_hash2),
+				PoolEvent::Finalized(tx_hash1),
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn test07() {
+		sp_tracing::try_init_simple();
+		let listener = MultiViewListener::new();
+
+		// The stream is not consumed while the events are sent, so it lags behind and is closed.
+		let pool_events = listener.create_pool_event_stream();
+		let imported = (0..2 * POOL_EVENT_STREAM_BUFFER_SIZE as u64)
+			.map(H256::from_low_u64_be)
+			.collect::<Vec<_>>();
+		listener.transactions_imported(&imported);
+		assert!(listener.pool_event_sinks.lock().is_empty());
+
+		let out = pool_events.collect::<Vec<_>>().await;
+		assert!(out.len() < imported.len());
+		assert_eq!(out[0], PoolEvent::Imported(imported[0]));
+	}
+
+	#[tokio::test]
+	async fn test08() {
+		sp_tracing::try_init_simple();
+		let listener = MultiViewListener::new();
+
+		let mut pool_events = (0..MAX_POOL_EVENT_STREAMS)
+			.map(|_| listener.create_pool_event_stream())
+			.collect::<Vec<_>>();
+
+		// No more streams are handed out while the limit is reached.
+		let refused = listener.create_pool_event_stream();
+		assert!(refused.collect::<Vec<_>>().await.is_empty());
+
+		// Closing a stream frees a slot.
+		drop(pool_events.pop());
+		let tx_hash = H256::repeat_byte(0x0a);
+		let mut accepted = listener.create_pool_event_stream();
+		listener.transactions_imported(&[tx_hash]);
+		assert_eq!(accepted.next().await, Some(PoolEvent::Imported(tx_hash)));
+	}
 }
@@ -26,16 +26,21 @@
 //!   it), while on other forks tx can be valid. Depending on which view is chosen to be cloned,
 //!   such transaction could not be present in the newly created view.
 
-use super::{metrics::MetricsLink as PrometheusMetrics, multi_view_listener::MultiViewListener};
+use super::{
+	dropped_watcher::{DroppedReason, DroppedTransaction},
+	metrics::MetricsLink as PrometheusMetrics,
+	multi_view_listener::MultiViewListener,
+};
 use crate::{
 	common::log_xt::log_xt_trace,
 	graph,
-	graph::{tracked_map::Size, ExtrinsicFor, ExtrinsicHash},
+	graph::{tracked_map::Size, ExtrinsicFor, ExtrinsicHash, RawExtrinsicFor},
 	LOG_TARGET,
 };
 use futures::FutureExt;
 use itertools::Itertools;
-use sc_transaction_pool_api::TransactionSource;
+use parking_lot::RwLock;
+use sc_transaction_pool_api::{SenderExtractor, SenderId, TransactionSource};
 use sp_blockchain::HashAndNumber;
 use sp_runtime::{
 	traits::Block as BlockT,
@@ -75,6 +80,8 @@ where
 	bytes: usize,
 	/// Transaction source.
 	source: TransactionSource,
+	/// The sender of the transaction, if known.
+	sender: Option<SenderId>,
 	/// When the transaction was revalidated, used to periodically revalidate the mem pool buffer.
 	validated_at: AtomicU64,
 	//todo: we need to add future / ready status at finalized block.
@@ -100,13 +107,23 @@ where
 	}
 
 	/// Creates a new instance of wrapper for unwatched transaction.
-	fn new_unwatched(source: TransactionSource, tx: ExtrinsicFor<ChainApi>, bytes: usize) -> Self {
-		Self { watched: false, tx, source, validated_at: AtomicU64::new(0), bytes }
+	fn new_unwatched(
+		source: TransactionSource,
+		tx: ExtrinsicFor<ChainApi>,
+		bytes: usize,
+		sender: Option<SenderId>,
+	) -> Self {
+		Self { watched: false, tx, source, sender, validated_at: AtomicU64::new(0), bytes }
 	}
 
 	/// Creates a new instance of wrapper for watched transaction.
-	fn new_watched(source: TransactionSource, tx: ExtrinsicFor<ChainApi>, bytes: usize) -> Self {
-		Self { watched: true, tx, source, validated_at: AtomicU64::new(0), bytes }
+	fn new_watched(
+		source: TransactionSource,
+		tx: ExtrinsicFor<ChainApi>,
+		bytes: usize,
+		sender: Option<SenderId>,
+	) -> Self {
+		Self { watched: true, tx, source, sender, validated_at: AtomicU64::new(0), bytes }
 	}
 
 	/// Provides a clone of actual transaction body.
@@ -172,6 +189,18 @@ where
 
 	/// Maximal size of encodings of all transactions in the memory pool.
 	max_transactions_total_bytes: usize,
+
+	/// Indicates the maximum number of transactions from a single sender that can be maintained
+	/// in the memory pool.
+	max_transactions_per_sender: Option<usize>,
+
+	/// Provides the sender of the transaction, used to enforce the per-sender limit.
+	sender_extractor: Option<SenderExtractor<Block>>,
+
+	/// The number of transactions in the memory pool per sender.
+	///
+	/// Only transactions with known sender are counted. Shall be locked after `transactions`.
+	per_sender_count: RwLock<HashMap<SenderId, usize>>,
 }
 
 impl<ChainApi, Block> TxMemPool<ChainApi, Block>
@@ -196,9 +225,31 @@ where
 			metrics,
 			max_transactions_count,
 			max_transactions_total_bytes,
+			max_transactions_per_sender: None,
+			sender_extractor: None,
+			per_sender_count: Default::default(),
 		}
 	}
 
+	/// Sets the maximum number of transactions from a single sender and the means of determining
+	/// the sender of the transaction.
+	///
+	/// The limit is only enforced if both the limit and the extractor are provided.
+	pub(super) fn with_per_sender_limit(
+		mut self,
+		max_transactions_per_sender: Option<usize>,
+		sender_extractor: Option<SenderExtractor<Block>>,
+	) -> Self {
+		self.max_transactions_per_sender = max_transactions_per_sender;
+		self.sender_extractor = sender_extractor;
+		self
+	}
+
+	/// Returns the function determining the sender of the transactions, if any.
+	pub(super) fn sender_extractor(&self) -> Option<SenderExtractor<Block>> {
+		self.sender_extractor.clone()
+	}
+
 	/// Creates a new `TxMemPool` instance for testing purposes.
 	#[allow(dead_code)]
 	fn new_test(
@@ -213,6 +264,9 @@ where
 			metrics: Default::default(),
 			max_transactions_count,
 			max_transactions_total_bytes,
+			max_transactions_per_sender: None,
+			sender_extractor: None,
+			per_sender_count: Default::default(),
 		}
 	}
 
@@ -237,14 +291,76 @@ where
 		return self.transactions.bytes()
 	}
 
+	/// Returns the number of transactions from the given sender in the pool.
+	#[cfg(test)]
+	pub fn sender_count(&self, sender: &SenderId) -> usize {
+		self.per_sender_count.read().get(sender).copied().unwrap_or_default()
+	}
+
 	/// Returns true if provided values would exceed defined limits.
 	fn is_limit_exceeded(&self, length: usize, current_total_bytes: usize) -> bool {
 		length > self.max_transactions_count ||
 			current_total_bytes > self.max_transactions_total_bytes
 	}
 
+	/// Returns true if adding another transaction from the given sender would exceed the
+	/// per-sender limit.
+	fn is_sender_limit_exceeded(
+		&self,
+		per_sender_count: &HashMap<SenderId, usize>,
+		sender: &Option<SenderId>,
+	) -> bool {
+		match (sender, self.max_transactions_per_sender) {
+			(Some(sender), Some(max)) =>
+				per_sender_count.get(sender).map_or(false, |count| *count >= max),
+			_ => false,
+		}
+	}
+
+	/// Returns the sender of the given extrinsic, if the sender extractor is provided.
+	fn sender_of(&self, xt: &RawExtrinsicFor<ChainApi>) -> Option<SenderId> {
+		self.sender_extractor.as_ref().and_then(|extractor| extractor(xt))
+	}
+
+	/// Decreases the per-sender counter for the removed transaction.
+	fn on_removed(
+		per_sender_count: &mut HashMap<SenderId, usize>,
+		tx: &TxInMemPool<ChainApi, Block>,
+	) {
+		if let Some(sender) = &tx.sender {
+			if let std::collections::hash_map::Entry::Occupied(mut count) =
+				per_sender_count.entry(sender.clone())
+			{
+				*count.get_mut() -= 1;
+				if *count.get() == 0 {
+					count.remove();
+				}
+			}
+		}
+	}
+
+	/// Removes transactions with given hashes from the memory pool.
+	///
+	/// Returns the hashes of transactions that were actually removed.
+	fn remove_transactions<'a>(
+		&self,
+		hashes: impl IntoIterator<Item = &'a ExtrinsicHash<ChainApi>>,
+	) -> Vec<ExtrinsicHash<ChainApi>> {
+		let mut transactions = self.transactions.write();
+		let mut per_sender_count = self.per_sender_count.write();
+		hashes
+			.into_iter()
+			.filter_map(|hash| {
+				let tx = transactions.remove(hash)?;
+				Self::on_removed(&mut per_sender_count, &tx);
+				Some(*hash)
+			})
+			.collect()
+	}
+
 	/// Attempts to insert a transaction into the memory pool, ensuring it does not
-	/// exceed the maximum allowed transaction count.
+	/// exceed the maximum allowed transaction count, nor the maximum allowed transaction count
+	/// per sender.
 	fn try_insert(
 		&self,
 		hash: ExtrinsicHash<ChainApi>,
@@ -252,17 +368,26 @@ where
 	) -> Result<ExtrinsicHash<ChainApi>, ChainApi::Error> {
 		let bytes = self.transactions.bytes();
 		let mut transactions = self.transactions.write();
+		let mut per_sender_count = self.per_sender_count.write();
 		let result = match (
 			!self.is_limit_exceeded(transactions.len() + 1, bytes + tx.bytes),
 			transactions.contains_key(&hash),
+			self.is_sender_limit_exceeded(&per_sender_count, &tx.sender),
 		) {
-			(true, false) => {
+			(true, false, false) => {
+				if let Some(sender) = &tx.sender {
+					*per_sender_count.entry(sender.clone()).or_default() += 1;
+				}
 				transactions.insert(hash, Arc::from(tx));
 				Ok(hash)
 			},
-			(_, true) =>
+			(_, true, _) =>
 				Err(sc_transaction_pool_api::error::Error::AlreadyImported(Box::new(hash)).into()),
-			(false, _) => Err(sc_transaction_pool_api::error::Error::ImmediatelyDropped.into()),
+			(false, _, _) => Err(sc_transaction_pool_api::error::Error::ImmediatelyDropped.into()),
+			(true, false, true) => {
+				self.metrics.report(|metrics| metrics.sender_limit_rejected_txs.inc());
+				Err(sc_transaction_pool_api::error::Error::SenderLimitReached.into())
+			},
 		};
 		log::trace!(target: LOG_TARGET, "[{:?}] mempool::try_insert: {:?}", hash, result);
 
@@ -282,7 +407,11 @@ where
 			.iter()
 			.map(|xt| {
 				let (hash, length) = self.api.hash_and_length(&xt);
-				self.try_insert(hash, TxInMemPool::new_unwatched(source, xt.clone(), length))
+				let sender = self.sender_of(&xt);
+				self.try_insert(
+					hash,
+					TxInMemPool::new_unwatched(source, xt.clone(), length, sender),
+				)
 			})
 			.collect::<Vec<_>>();
 		result
@@ -296,7 +425,8 @@ where
 		xt: ExtrinsicFor<ChainApi>,
 	) -> Result<ExtrinsicHash<ChainApi>, ChainApi::Error> {
 		let (hash, length) = self.api.hash_and_length(&xt);
-		self.try_insert(hash, TxInMemPool::new_watched(source, xt.clone(), length))
+		let sender = self.sender_of(&xt);
+		self.try_insert(hash, TxInMemPool::new_watched(source, xt.clone(), length, sender))
 	}
 
 	/// Removes transactions from the memory pool which are specified by the given list of hashes
@@ -307,14 +437,32 @@ where
 	) {
 		log::debug!(target: LOG_TARGET, "remove_dropped_transactions count:{:?}", to_be_removed.len());
 		log_xt_trace!(target: LOG_TARGET, to_be_removed, "[{:?}] mempool::remove_dropped_transactions");
-		let mut transactions = self.transactions.write();
-		to_be_removed.iter().for_each(|t| {
-			transactions.remove(t);
-		});
+		self.remove_transactions(to_be_removed);
 
 		self.listener.transactions_dropped(to_be_removed);
 	}
 
+	/// Removes the transaction which was dropped by all the views from the memory pool and sends
+	/// the event matching the reason of dropping (`Usurped` or `Dropped`) to the listeners of
+	/// this transaction.
+	pub(super) async fn remove_dropped_transaction(
+		&self,
+		dropped: &DroppedTransaction<ExtrinsicHash<ChainApi>>,
+	) {
+		match dropped.reason {
+			DroppedReason::Usurped(by) => {
+				log::trace!(target: LOG_TARGET, "[{:?}] mempool::remove_dropped_transaction: usurped by {:?}", dropped.tx_hash, by);
+				self.remove_transactions(&[dropped.tx_hash]);
+				self.metrics.report(|metrics| metrics.usurped_txs.inc());
+				self.listener.transaction_usurped(dropped.tx_hash, by);
+			},
+			DroppedReason::LimitsEnforced => {
+				self.metrics.report(|metrics| metrics.dropped_by_limits_txs.inc());
+				self.remove_dropped_transactions(&[dropped.tx_hash]).await;
+			},
+		}
+	}
+
 	/// Clones and returns a `HashMap` of references to all unwatched transactions in the memory
 	/// pool.
 	pub(super) fn clone_unwatched(
@@ -340,7 +488,7 @@ where
 
 	/// Removes a transaction from the memory pool based on a given hash.
 	pub(super) fn remove(&self, hash: ExtrinsicHash<ChainApi>) {
-		let _ = self.transactions.write().remove(&hash);
+		self.remove_transactions(&[hash]);
 	}
 
 	/// Revalidates a batch of transactions against the provided finalized block.
@@ -416,10 +564,8 @@ where
 	) {
 		log::debug!(target: LOG_TARGET, "purge_finalized_transactions count:{:?}", finalized_xts.len());
 		log_xt_trace!(target: LOG_TARGET, finalized_xts, "[{:?}] purged finalized transactions");
-		let mut transactions = self.transactions.write();
-		finalized_xts.iter().for_each(|t| {
-			transactions.remove(t);
-		});
+		let removed = self.remove_transactions(finalized_xts);
+		self.listener.transactions_finalized(&removed);
 	}
 
 	/// Revalidates transactions in the memory pool against a given finalized block and removes
@@ -432,10 +578,7 @@ where
 			metrics.mempool_revalidation_invalid_txs.inc_by(invalid_hashes.len() as _)
 		});
 
-		let mut transactions = self.transactions.write();
-		invalid_hashes.iter().for_each(|i| {
-			transactions.remove(i);
-		});
+		self.remove_transactions(&invalid_hashes);
 		self.listener.invalidate_transactions(&invalid_hashes);
 	}
 }
@@ -444,8 +587,9 @@ where
 mod tx_mem_pool_tests {
 	use super::*;
 	use crate::{common::tests::TestApi, graph::ChainApi};
+	use codec::Encode;
 	use substrate_test_runtime::{AccountId, Extrinsic, ExtrinsicBuilder, Transfer, H256};
-	use substrate_test_runtime_client::AccountKeyring::*;
+	use substrate_test_runtime_client::{AccountKeyring, AccountKeyring::*};
 	fn uxt(nonce: u64) -> Extrinsic {
 		crate::common::tests::uxt(Transfer {
 			from: Alice.into(),
@@ -514,6 +658,58 @@ mod tx_mem_pool_tests {
 		));
 	}
 
+	fn signer_extractor() -> SenderExtractor<substrate_test_runtime::Block> {
+		Arc::new(|xt: &Extrinsic| match &xt.preamble {
+			sp_runtime::generic::Preamble::Signed(address, ..) => Some(address.encode()),
+			_ => None,
+		})
+	}
+
+	fn uxt_from(from: AccountKeyring, nonce: u64) -> Extrinsic {
+		crate::common::tests::uxt(Transfer {
+			from: from.into(),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		})
+	}
+
+	#[test]
+	fn extend_unwatched_obeys_per_sender_limit() {
+		sp_tracing::try_init_simple();
+		let max_per_sender = 3;
+		let api = Arc::from(TestApi::default());
+		let mempool = TxMemPool::new_test(api, usize::MAX, usize::MAX)
+			.with_per_sender_limit(Some(max_per_sender), Some(signer_extractor()));
+
+		let xts = (0..max_per_sender + 1)
+			.map(|x| Arc::from(uxt_from(Alice, x as _)))
+			.collect::<Vec<_>>();
+
+		let results = mempool.extend_unwatched(TransactionSource::External, &xts);
+		assert!(results.iter().take(max_per_sender).all(Result::is_ok));
+		assert!(matches!(
+			results.into_iter().last().unwrap().unwrap_err(),
+			sc_transaction_pool_api::error::Error::SenderLimitReached
+		));
+
+		// other senders are not affected
+		let result = mempool.push_watched(TransactionSource::External, Arc::from(uxt_from(Bob, 0)));
+		assert!(result.is_ok());
+
+		let alice = Alice.public().encode();
+		assert_eq!(mempool.sender_count(&alice), max_per_sender);
+		assert_eq!(mempool.sender_count(&Bob.public().encode()), 1);
+
+		// removing transaction frees the slot for the sender
+		let (hash, _) = mempool.api.hash_and_length(&xts[0]);
+		mempool.remove(hash);
+		assert_eq!(mempool.sender_count(&alice), max_per_sender - 1);
+		let result = mempool.push_watched(TransactionSource::External, xts[max_per_sender].clone());
+		assert!(result.is_ok());
+		assert_eq!(mempool.sender_count(&alice), max_per_sender);
+	}
+
 	#[test]
 	fn push_detects_already_imported() {
 		let max = 10;
@@ -33,6 +33,7 @@ use sp_runtime::{
 		TransactionLongevity as Longevity, TransactionPriority as Priority,
 		TransactionSource as Source, TransactionTag as Tag,
 	},
+	Percent,
 };
 
 use super::{
@@ -238,6 +239,13 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		}
 	}
 
+	/// Sets the minimal priority increase required to replace the ready transactions providing
+	/// the same tags.
+	pub fn with_min_priority_bump(mut self, min_priority_bump: Percent) -> Self {
+		self.ready.set_min_priority_bump(min_priority_bump);
+		self
+	}
+
 	/// Clears buffer keeping recently pruned transaction.
 	pub fn clear_recently_pruned(&mut self) {
 		self.recently_pruned = Default::default();
@@ -29,7 +29,7 @@ use super::{watcher, BlockHash, ChainApi, ExtrinsicHash};
 
 static LOG_TARGET: &str = "txpool::watcher";
 
-/// Single event used in dropped by limits stream. It is one of Ready/Future/Dropped.
+/// Single event used in dropped by limits stream. It is one of Ready/Future/Dropped/Usurped.
 pub type DroppedByLimitsEvent<H, BH> = (H, TransactionStatus<H, BH>);
 /// Stream of events used to determine if a transaction was dropped.
 pub type DroppedByLimitsStream<H, BH> = TracingUnboundedReceiver<DroppedByLimitsEvent<H, BH>>;
@@ -136,10 +136,16 @@ impl<H: hash::Hash + traits::Member + Serialize + Clone, C: ChainApi> Listener<H
 		});
 
 		//note: LimitEnforced could be introduced as new status to get rid of this flag.
-		if limits_enforced {
+		let status = match by {
+			Some(t) => Some(TransactionStatus::Usurped(t.clone())),
+			None if limits_enforced => Some(TransactionStatus::Dropped),
+			None => None,
+		};
+
+		if let Some(status) = status {
 			if let Some(ref sink) = self.dropped_by_limits_sink {
-				if let Err(e) = sink.unbounded_send((tx.clone(), TransactionStatus::Dropped)) {
-					trace!(target: LOG_TARGET, "[{:?}] dropped_sink/future: send message failed: {:?}", tx, e);
+				if let Err(e) = sink.unbounded_send((tx.clone(), status)) {
+					trace!(target: LOG_TARGET, "[{:?}] dropped_sink/dropped: send message failed: {:?}", tx, e);
 				}
 			}
 		}
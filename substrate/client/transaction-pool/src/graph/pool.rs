@@ -27,6 +27,7 @@ use sp_runtime::{
 	transaction_validity::{
 		TransactionSource, TransactionTag as Tag, TransactionValidity, TransactionValidityError,
 	},
+	PerThing, Percent,
 };
 use std::{
 	collections::HashMap,
@@ -145,6 +146,13 @@ pub struct Options {
 	pub reject_future_transactions: bool,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// The minimal priority increase required to replace transactions providing the same tags.
+	pub min_priority_bump: Percent,
+	/// The maximum number of transactions from a single sender kept in the pool.
+	///
+	/// Only respected by the fork-aware transaction pool, and only for the transactions which
+	/// sender can be determined.
+	pub max_transactions_per_sender: Option<usize>,
 }
 
 impl Default for Options {
@@ -154,6 +162,8 @@ impl Default for Options {
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
+			min_priority_bump: Percent::zero(),
+			max_transactions_per_sender: None,
 		}
 	}
 }
@@ -27,7 +27,7 @@ use crate::LOG_TARGET;
 use log::trace;
 use sc_transaction_pool_api::error;
 use serde::Serialize;
-use sp_runtime::{traits::Member, transaction_validity::TransactionTag as Tag};
+use sp_runtime::{traits::Member, transaction_validity::TransactionTag as Tag, PerThing, Percent};
 
 use super::{
 	base_pool::Transaction,
@@ -118,6 +118,10 @@ pub struct ReadyTransactions<Hash: hash::Hash + Eq, Ex> {
 	/// Best transactions that are ready to be included to the block without any other previous
 	/// transaction.
 	best: BTreeSet<TransactionRef<Hash, Ex>>,
+	/// The minimal priority increase (relative to the collective priority of the replaced
+	/// transactions) required for a transaction to replace the transactions providing the same
+	/// tags.
+	min_priority_bump: Percent,
 }
 
 impl<Hash, Ex> tracked_map::Size for ReadyTx<Hash, Ex> {
@@ -133,6 +137,7 @@ impl<Hash: hash::Hash + Eq, Ex> Default for ReadyTransactions<Hash, Ex> {
 			provided_tags: Default::default(),
 			ready: Default::default(),
 			best: Default::default(),
+			min_priority_bump: Percent::zero(),
 		}
 	}
 }
@@ -143,6 +148,12 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 		&self.provided_tags
 	}
 
+	/// Sets the minimal priority increase required to replace transactions providing the same
+	/// tags.
+	pub fn set_min_priority_bump(&mut self, min_priority_bump: Percent) {
+		self.min_priority_bump = min_priority_bump;
+	}
+
 	/// Returns an iterator of ready transactions.
 	///
 	/// Transactions are returned in order:
@@ -451,6 +462,17 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 				return Err(error::Error::TooLowPriority { old: old_priority, new: tx.priority })
 			}
 
+			// bail - the priority increase is not big enough to replace the old ones
+			let min_priority =
+				old_priority.saturating_add(self.min_priority_bump.mul_ceil(old_priority));
+			if min_priority > tx.priority {
+				return Err(error::Error::TooLowPriorityBump {
+					old: old_priority,
+					new: tx.priority,
+					min_bump: self.min_priority_bump,
+				})
+			}
+
 			// construct a list of unlocked transactions
 			let unlocks = {
 				let ready = self.ready.read();
@@ -641,6 +663,36 @@ mod tests {
 		assert_eq!(ready.get().count(), 1);
 	}
 
+	#[test]
+	fn should_require_min_priority_bump_to_replace_transaction() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		ready.set_min_priority_bump(Percent::from_percent(10));
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.provides = vec![vec![3]];
+		tx1.priority = 100;
+		let mut tx2 = tx(2);
+		tx2.requires.clear();
+		tx2.provides = vec![vec![3]];
+		tx2.priority = 109;
+
+		// when
+		import(&mut ready, tx1).unwrap();
+
+		// then
+		assert!(matches!(
+			import(&mut ready, tx2.clone()),
+			Err(error::Error::TooLowPriorityBump { old: 100, new: 109, .. })
+		));
+
+		tx2.priority = 110;
+		let replaced = import(&mut ready, tx2).unwrap();
+		assert_eq!(replaced.len(), 1);
+		assert_eq!(replaced[0].hash, 1);
+		assert_eq!(ready.get().count(), 1);
+	}
+
 	#[test]
 	fn should_replace_multiple_transactions_correctly() {
 		// given
@@ -129,7 +129,8 @@ impl<B: ChainApi> Clone for ValidatedPool<B> {
 impl<B: ChainApi> ValidatedPool<B> {
 	/// Create a new transaction pool.
 	pub fn new(options: Options, is_validator: IsValidator, api: Arc<B>) -> Self {
-		let base_pool = base::BasePool::new(options.reject_future_transactions);
+		let base_pool = base::BasePool::new(options.reject_future_transactions)
+			.with_min_priority_bump(options.min_priority_bump);
 		let ban_time = options.ban_time;
 		Self {
 			is_validator,
@@ -35,7 +35,7 @@ use std::sync::Arc;
 pub use api::FullChainApi;
 pub use builder::{Builder, TransactionPoolHandle, TransactionPoolOptions, TransactionPoolType};
 pub use common::notification_future;
-pub use fork_aware_txpool::{ForkAwareTxPool, ForkAwareTxPoolTask};
+pub use fork_aware_txpool::{ForkAwareTxPool, ForkAwareTxPoolTask, SenderExtractor, SenderId};
 pub use graph::{base_pool::Limit as PoolLimit, ChainApi, Options, Pool};
 use single_state_txpool::prune_known_txs_for_block;
 pub use single_state_txpool::{BasicPool, RevalidationType};
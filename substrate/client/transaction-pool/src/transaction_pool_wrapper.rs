@@ -27,8 +27,8 @@ use crate::{
 use async_trait::async_trait;
 use sc_transaction_pool_api::{
 	ChainEvent, ImportNotificationStream, LocalTransactionFor, LocalTransactionPool,
-	MaintainedTransactionPool, PoolStatus, ReadyTransactions, TransactionFor, TransactionPool,
-	TransactionSource, TransactionStatusStreamFor, TxHash,
+	MaintainedTransactionPool, PoolEventStream, PoolStatus, ReadyTransactions, SenderExtractor,
+	TransactionFor, TransactionPool, TransactionSource, TransactionStatusStreamFor, TxHash,
 };
 use sp_runtime::traits::Block as BlockT;
 use std::{collections::HashMap, pin::Pin, sync::Arc};
@@ -123,6 +123,14 @@ where
 		self.0.import_notification_stream()
 	}
 
+	fn pool_event_stream(&self) -> PoolEventStream<TxHash<Self>> {
+		self.0.pool_event_stream()
+	}
+
+	fn sender_extractor(&self) -> Option<SenderExtractor<Self::Block>> {
+		self.0.sender_extractor()
+	}
+
 	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
 		self.0.on_broadcasted(propagations)
 	}
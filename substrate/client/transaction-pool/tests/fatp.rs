@@ -26,7 +26,7 @@ use futures::{executor::block_on, task::Poll, FutureExt, StreamExt};
 use sc_transaction_pool::ChainApi;
 use sc_transaction_pool_api::{
 	error::{Error as TxPoolError, IntoPoolError},
-	ChainEvent, MaintainedTransactionPool, TransactionPool, TransactionStatus,
+	ChainEvent, MaintainedTransactionPool, PoolEvent, TransactionPool, TransactionStatus,
 };
 use sp_runtime::transaction_validity::InvalidTransaction;
 use std::{sync::Arc, time::Duration};
@@ -2615,3 +2615,36 @@ fn fatp_ready_at_with_timeout_works_for_misc_scenarios() {
 	assert_eq!(ready_at2.next().unwrap().hash, api.hash_and_length(&xt2).0);
 	assert!(ready_at2.next().is_none());
 }
+
+#[test]
+fn fatp_pool_event_stream_reports_imported_and_finalized() {
+	sp_tracing::try_init_simple();
+
+	let (pool, api, _) = pool();
+	let pool_events = pool.pool_event_stream();
+
+	let header01 = api.push_block(1, vec![], true);
+	let event = new_best_block_event(&pool, None, header01.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt(Bob, 200);
+	block_on(pool.submit_one(invalid_hash(), SOURCE, xt0.clone())).unwrap();
+	block_on(pool.submit_one(invalid_hash(), SOURCE, xt1.clone())).unwrap();
+
+	let header02 = api.push_block_with_parent(header01.hash(), vec![xt0.clone()], true);
+	let event = finalized_block_event(&pool, api.genesis_hash(), header02.hash());
+	block_on(pool.maintain(event));
+
+	let xt0_hash = api.hash_and_length(&xt0).0;
+	let xt1_hash = api.hash_and_length(&xt1).0;
+	let events = futures::executor::block_on_stream(pool_events).take(3).collect::<Vec<_>>();
+	assert_eq!(
+		events,
+		vec![
+			PoolEvent::Imported(xt0_hash),
+			PoolEvent::Imported(xt1_hash),
+			PoolEvent::Finalized(xt0_hash)
+		]
+	);
+}
@@ -18,9 +18,9 @@
 
 //! Tests for fork-aware transaction pool.
 
-use sc_transaction_pool::{ChainApi, PoolLimit};
+use sc_transaction_pool::{ChainApi, Options, PoolLimit, SenderExtractor};
 use sc_transaction_pool_api::ChainEvent;
-use sp_runtime::transaction_validity::TransactionSource;
+use sp_runtime::{transaction_validity::TransactionSource, Percent};
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	runtime::{Block, Hash, Header},
@@ -70,6 +70,9 @@ pub struct TestPoolBuilder {
 	ready_limits: sc_transaction_pool::PoolLimit,
 	future_limits: sc_transaction_pool::PoolLimit,
 	mempool_max_transactions_count: usize,
+	min_priority_bump: Percent,
+	max_transactions_per_sender: Option<usize>,
+	sender_extractor: Option<SenderExtractor<Block>>,
 }
 
 impl Default for TestPoolBuilder {
@@ -80,6 +83,9 @@ impl Default for TestPoolBuilder {
 			ready_limits: PoolLimit { count: 8192, total_bytes: 20 * 1024 * 1024 },
 			future_limits: PoolLimit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			mempool_max_transactions_count: usize::MAX,
+			min_priority_bump: Percent::zero(),
+			max_transactions_per_sender: None,
+			sender_extractor: None,
 		}
 	}
 }
@@ -124,6 +130,23 @@ impl TestPoolBuilder {
 		self
 	}
 
+	pub fn with_min_priority_bump(mut self, min_priority_bump: Percent) -> Self {
+		self.min_priority_bump = min_priority_bump;
+		self.use_default_limits = false;
+		self
+	}
+
+	pub fn with_max_transactions_per_sender(
+		mut self,
+		max_transactions_per_sender: usize,
+		sender_extractor: SenderExtractor<Block>,
+	) -> Self {
+		self.max_transactions_per_sender = Some(max_transactions_per_sender);
+		self.sender_extractor = Some(sender_extractor);
+		self.use_default_limits = false;
+		self
+	}
+
 	pub fn build(
 		self,
 	) -> (ForkAwareTxPool<TestApi, Block>, Arc<TestApi>, futures::executor::ThreadPool) {
@@ -142,13 +165,19 @@ impl TestPoolBuilder {
 		let (pool, txpool_task) = if self.use_default_limits {
 			ForkAwareTxPool::new_test(api.clone(), genesis_hash, genesis_hash)
 		} else {
-			ForkAwareTxPool::new_test_with_limits(
+			ForkAwareTxPool::new_test_with_options(
 				api.clone(),
 				genesis_hash,
 				genesis_hash,
-				self.ready_limits,
-				self.future_limits,
+				Options {
+					ready: self.ready_limits,
+					future: self.future_limits,
+					min_priority_bump: self.min_priority_bump,
+					max_transactions_per_sender: self.max_transactions_per_sender,
+					..Default::default()
+				},
 				self.mempool_max_transactions_count,
+				self.sender_extractor,
 			)
 		};
 
@@ -0,0 +1,192 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests of priorities for fork-aware transaction pool.
+
+pub mod fatp_common;
+
+use codec::Encode;
+use fatp_common::{finalized_block_event, new_best_block_event, TestPoolBuilder, SOURCE};
+use futures::executor::block_on;
+use sc_transaction_pool::{ChainApi, SenderExtractor};
+use sc_transaction_pool_api::{
+	error::Error as TxPoolError, MaintainedTransactionPool, TransactionPool, TransactionStatus,
+};
+use sp_runtime::{generic::Preamble, traits::TrailingZeroInput, Percent};
+use std::sync::Arc;
+use substrate_test_runtime::{Block, Extrinsic, ExtrinsicBuilder, Transfer};
+use substrate_test_runtime_client::{AccountKeyring, AccountKeyring::*};
+use substrate_test_runtime_transaction_pool::uxt;
+
+/// Builds the transfer with given amount, so it provides the same tags as `uxt(who, nonce)` while
+/// having a different hash.
+fn uxt_with_amount(who: AccountKeyring, nonce: u64, amount: u64) -> Extrinsic {
+	let dummy = codec::Decode::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+	let transfer = Transfer { from: who.into(), to: dummy, nonce, amount };
+	ExtrinsicBuilder::new_transfer(transfer).build()
+}
+
+/// Uses the signer of the transaction as its sender.
+fn signer_extractor() -> SenderExtractor<Block> {
+	Arc::new(|xt: &Extrinsic| match &xt.preamble {
+		Preamble::Signed(address, ..) => Some(address.encode()),
+		_ => None,
+	})
+}
+
+#[test]
+fn fatp_prio_watcher_usurped_by_higher_priority() {
+	sp_tracing::try_init_simple();
+
+	let builder = TestPoolBuilder::new();
+	let (pool, api, _) = builder.build();
+
+	let header01 = api.push_block(1, vec![], true);
+	let event = new_best_block_event(&pool, None, header01.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt_with_amount(Alice, 200, 2);
+	api.set_priority(&xt0, 2);
+	api.set_priority(&xt1, 3);
+
+	let xt0_watcher =
+		block_on(pool.submit_and_watch(header01.hash(), SOURCE, xt0.clone())).unwrap();
+	let xt1_watcher =
+		block_on(pool.submit_and_watch(header01.hash(), SOURCE, xt1.clone())).unwrap();
+
+	assert_pool_status!(header01.hash(), &pool, 1, 0);
+	assert_ready_iterator!(header01.hash(), pool, [xt1]);
+
+	let xt0_status = futures::executor::block_on_stream(xt0_watcher).take(2).collect::<Vec<_>>();
+	log::debug!("xt0_status: {:#?}", xt0_status);
+	assert_eq!(
+		xt0_status,
+		vec![TransactionStatus::Ready, TransactionStatus::Usurped(api.hash_and_length(&xt1).0)]
+	);
+
+	let xt1_status = futures::executor::block_on_stream(xt1_watcher).take(1).collect::<Vec<_>>();
+	assert_eq!(xt1_status, vec![TransactionStatus::Ready]);
+
+	// usurped transaction shall be also removed from the mempool
+	assert_eq!(pool.mempool_len(), (0, 1));
+}
+
+#[test]
+fn fatp_prio_lower_priority_cannot_replace() {
+	sp_tracing::try_init_simple();
+
+	let builder = TestPoolBuilder::new();
+	let (pool, api, _) = builder.build();
+
+	let header01 = api.push_block(1, vec![], true);
+	let event = new_best_block_event(&pool, None, header01.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt_with_amount(Alice, 200, 2);
+	api.set_priority(&xt0, 3);
+	api.set_priority(&xt1, 2);
+
+	block_on(pool.submit_one(header01.hash(), SOURCE, xt0.clone())).unwrap();
+	let result = block_on(pool.submit_one(header01.hash(), SOURCE, xt1.clone()));
+
+	assert!(matches!(result.unwrap_err().0, TxPoolError::TooLowPriority { old: 3, new: 2 }));
+	assert_pool_status!(header01.hash(), &pool, 1, 0);
+	assert_ready_iterator!(header01.hash(), pool, [xt0]);
+}
+
+#[test]
+fn fatp_prio_replacement_requires_min_priority_bump() {
+	sp_tracing::try_init_simple();
+
+	let builder = TestPoolBuilder::new().with_min_priority_bump(Percent::from_percent(10));
+	let (pool, api, _) = builder.build();
+
+	let header01 = api.push_block(1, vec![], true);
+	let event = new_best_block_event(&pool, None, header01.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt_with_amount(Alice, 200, 2);
+	let xt2 = uxt_with_amount(Alice, 200, 3);
+	api.set_priority(&xt0, 100);
+	api.set_priority(&xt1, 109);
+	api.set_priority(&xt2, 110);
+
+	let xt0_watcher =
+		block_on(pool.submit_and_watch(header01.hash(), SOURCE, xt0.clone())).unwrap();
+
+	let result = block_on(pool.submit_one(header01.hash(), SOURCE, xt1.clone()));
+	assert!(matches!(
+		result.unwrap_err().0,
+		TxPoolError::TooLowPriorityBump { old: 100, new: 109, .. }
+	));
+	assert_pool_status!(header01.hash(), &pool, 1, 0);
+	assert_ready_iterator!(header01.hash(), pool, [xt0]);
+
+	block_on(pool.submit_one(header01.hash(), SOURCE, xt2.clone())).unwrap();
+	assert_pool_status!(header01.hash(), &pool, 1, 0);
+	assert_ready_iterator!(header01.hash(), pool, [xt2]);
+
+	let xt0_status = futures::executor::block_on_stream(xt0_watcher).take(2).collect::<Vec<_>>();
+	assert_eq!(
+		xt0_status,
+		vec![TransactionStatus::Ready, TransactionStatus::Usurped(api.hash_and_length(&xt2).0)]
+	);
+}
+
+#[test]
+fn fatp_prio_per_sender_limit_is_enforced() {
+	sp_tracing::try_init_simple();
+
+	let builder = TestPoolBuilder::new().with_max_transactions_per_sender(2, signer_extractor());
+	let (pool, api, _) = builder.build();
+
+	let header01 = api.push_block(1, vec![], true);
+	let event = new_best_block_event(&pool, None, header01.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt(Alice, 201);
+	let xt2 = uxt(Alice, 202);
+	let xt3 = uxt(Bob, 0);
+
+	block_on(pool.submit_one(header01.hash(), SOURCE, xt0.clone())).unwrap();
+	block_on(pool.submit_and_watch(header01.hash(), SOURCE, xt1.clone())).unwrap();
+
+	let result = block_on(pool.submit_one(header01.hash(), SOURCE, xt2.clone()));
+	assert!(matches!(result.unwrap_err().0, TxPoolError::SenderLimitReached));
+	let result = block_on(pool.submit_and_watch(header01.hash(), SOURCE, xt2.clone()));
+	assert!(matches!(result.map(|_| ()).unwrap_err().0, TxPoolError::SenderLimitReached));
+
+	// other senders are not affected
+	block_on(pool.submit_one(header01.hash(), SOURCE, xt3.clone())).unwrap();
+
+	assert_pool_status!(header01.hash(), &pool, 3, 0);
+	assert_eq!(pool.mempool_len(), (2, 1));
+
+	// finalizing the transaction frees the slot for the sender
+	let header02 = api.push_block_with_parent(header01.hash(), vec![xt0.clone()], true);
+	let event = finalized_block_event(&pool, api.genesis_hash(), header02.hash());
+	block_on(pool.maintain(event));
+	assert_eq!(pool.mempool_len(), (1, 1));
+
+	block_on(pool.submit_one(header02.hash(), SOURCE, xt2.clone())).unwrap();
+	assert_pool_status!(header02.hash(), &pool, 3, 0);
+}
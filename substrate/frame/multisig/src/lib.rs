@@ -483,15 +483,27 @@ pub mod pallet {
 	}
 }
 
+/// Derive a multi-account ID from the sorted list of accounts and the threshold that are required.
+///
+/// Does not depend on the runtime configuration, so that off-chain tools can derive the address.
+///
+/// NOTE: `who` must be sorted. If it is not, then you'll get the wrong answer.
+pub fn multi_account_id<AccountId: Encode + Decode>(
+	who: &[AccountId],
+	threshold: u16,
+) -> AccountId {
+	let entropy = (b"modlpy/utilisuba", who, threshold).using_encoded(blake2_256);
+	Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
+
 impl<T: Config> Pallet<T> {
 	/// Derive a multi-account ID from the sorted list of accounts and the threshold that are
 	/// required.
 	///
 	/// NOTE: `who` must be sorted. If it is not, then you'll get the wrong answer.
 	pub fn multi_account_id(who: &[T::AccountId], threshold: u16) -> T::AccountId {
-		let entropy = (b"modlpy/utilisuba", who, threshold).using_encoded(blake2_256);
-		Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
-			.expect("infinite length input; no invalid inputs for type; qed")
+		multi_account_id(who, threshold)
 	}
 
 	fn operate(
@@ -586,6 +586,23 @@ pub mod pallet {
 	>;
 }
 
+/// Calculate the address of a pure account.
+///
+/// `height` and `ext_index` are the block number and extrinsic index of the `create_pure` call.
+/// See [`Pallet::pure_account`] for the other parameters.
+pub fn pure_account<AccountId: Encode + Decode, ProxyType: Encode, BlockNumber: Encode>(
+	who: &AccountId,
+	proxy_type: &ProxyType,
+	index: u16,
+	height: BlockNumber,
+	ext_index: u32,
+) -> AccountId {
+	let entropy =
+		(b"modlpy/proxy____", who, height, ext_index, proxy_type, index).using_encoded(blake2_256);
+	Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
+
 impl<T: Config> Pallet<T> {
 	/// Public function to proxies storage.
 	pub fn proxies(
@@ -630,10 +647,7 @@ impl<T: Config> Pallet<T> {
 				frame_system::Pallet::<T>::extrinsic_index().unwrap_or_default(),
 			)
 		});
-		let entropy = (b"modlpy/proxy____", who, height, ext_index, proxy_type, index)
-			.using_encoded(blake2_256);
-		Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
-			.expect("infinite length input; no invalid inputs for type; qed")
+		pure_account(who, proxy_type, index, height, ext_index)
 	}
 
 	/// Register a proxy account for the delegator that is able to make calls on its behalf.
@@ -24,7 +24,7 @@ use futures::{pin_mut, FutureExt};
 use jsonrpsee::server::RpcModule;
 use sc_cli::{PrometheusParams, RpcParams, SharedParams, Signals};
 use sc_service::{
-	config::{PrometheusConfig, RpcConfiguration},
+	config::{PrometheusConfig, RpcConfiguration, RpcMethodCosts},
 	start_rpc_servers, TaskManager,
 };
 
@@ -102,6 +102,9 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		rate_limit: rpc_params.rpc_rate_limit,
 		rate_limit_whitelisted_ips: rpc_params.rpc_rate_limit_whitelisted_ips,
 		rate_limit_trust_proxy_headers: rpc_params.rpc_rate_limit_trust_proxy_headers,
+		archive_fallback: None,
+		ip_quota: rpc_params.rpc_ip_quota,
+		method_costs: RpcMethodCosts::new(rpc_params.rpc_method_cost),
 	};
 
 	let prometheus_config =
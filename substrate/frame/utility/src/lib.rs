@@ -486,11 +486,18 @@ impl TypeId for IndexedUtilityPalletId {
 	const TYPE_ID: [u8; 4] = *b"suba";
 }
 
+/// Derive a derivative account ID from the owner account and the sub-account index.
+///
+/// This is the runtime independent version of [`Pallet::derivative_account_id`].
+pub fn derivative_account_id<AccountId: Encode + Decode>(who: &AccountId, index: u16) -> AccountId {
+	let entropy = (b"modlpy/utilisuba", who, index).using_encoded(blake2_256);
+	Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
+
 impl<T: Config> Pallet<T> {
 	/// Derive a derivative account ID from the owner account and the sub-account index.
 	pub fn derivative_account_id(who: T::AccountId, index: u16) -> T::AccountId {
-		let entropy = (b"modlpy/utilisuba", who, index).using_encoded(blake2_256);
-		Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
-			.expect("infinite length input; no invalid inputs for type; qed")
+		derivative_account_id(&who, index)
 	}
 }
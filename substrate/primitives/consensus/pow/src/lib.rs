@@ -23,7 +23,8 @@ extern crate alloc;
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
-use codec::Decode;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
 use sp_runtime::ConsensusEngineId;
 
 /// The `ConsensusEngineId` of PoW.
@@ -51,6 +52,33 @@ impl TotalDifficulty for u128 {
 	}
 }
 
+/// A difficulty adjustment algorithm, together with its parameters.
+///
+/// The node computes the difficulty with the algorithm returned by the
+/// [`DifficultyAdjustmentApi`] at the parent block, so that it can be changed by the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum DifficultyAdjustment {
+	/// Linearly weighted moving average of the solve times of the last `window` blocks (LWMA-1).
+	Lwma {
+		/// Target time between two blocks, in milliseconds.
+		target_block_time: u64,
+		/// Number of blocks to average over.
+		window: u32,
+	},
+	/// Absolutely scheduled exponentially rising targets (ASERT, as in `aserti3-2d`).
+	///
+	/// The difficulty doubles for every `half_life` the chain is ahead of the schedule set by the
+	/// anchor block, and halves for every `half_life` it is behind.
+	Asert {
+		/// Target time between two blocks, in milliseconds.
+		target_block_time: u64,
+		/// Half life of the difficulty, in milliseconds.
+		half_life: u64,
+		/// Number of the anchor block. Blocks up to its child use the fallback difficulty.
+		anchor: u64,
+	},
+}
+
 sp_api::decl_runtime_apis! {
 	/// API necessary for timestamp-based difficulty adjustment algorithms.
 	pub trait TimestampApi<Moment: Decode> {
@@ -65,4 +93,12 @@ sp_api::decl_runtime_apis! {
 		/// Return the target difficulty of the next block.
 		fn difficulty() -> Difficulty;
 	}
+
+	/// API for those chains that compute the difficulty on the node, but configure the
+	/// difficulty adjustment algorithm in the runtime.
+	pub trait DifficultyAdjustmentApi {
+		/// Return the algorithm adjusting the difficulty of the next block, `None` to keep the
+		/// difficulty of the PoW algorithm.
+		fn difficulty_adjustment() -> Option<DifficultyAdjustment>;
+	}
 }
@@ -188,14 +188,27 @@ pub mod vrf {
 	pub type VrfIosVec<T> = BoundedVec<T, ConstU32<MAX_VRF_IOS>>;
 
 	/// VRF input to construct a [`VrfPreOutput`] instance and embeddable in [`VrfSignData`].
+	///
+	/// The domain and data the input was built from are kept, so that it can be rebuilt by a
+	/// signer holding the secret key in another process.
 	#[derive(Clone, Debug)]
-	pub struct VrfInput(pub(super) bandersnatch_vrfs::VrfInput);
+	pub struct VrfInput(pub(super) bandersnatch_vrfs::VrfInput, (Vec<u8>, Vec<u8>));
 
 	impl VrfInput {
 		/// Construct a new VRF input.
 		pub fn new(domain: impl AsRef<[u8]>, data: impl AsRef<[u8]>) -> Self {
 			let msg = Message { domain: domain.as_ref(), message: data.as_ref() };
-			VrfInput(msg.into_vrf_input())
+			VrfInput(msg.into_vrf_input(), (domain.as_ref().to_vec(), data.as_ref().to_vec()))
+		}
+
+		/// Domain the input was built from.
+		pub fn domain(&self) -> &[u8] {
+			&self.1 .0
+		}
+
+		/// Data the input was built from.
+		pub fn data(&self) -> &[u8] {
+			&self.1 .1
 		}
 	}
 
@@ -261,12 +274,19 @@ pub mod vrf {
 	///   object doesn't influence the `VrfPreOutput`s values.
 	/// - *Vrf inputs* is some additional data which is used to produce *vrf pre-outputs*. This data
 	///   will contribute to the signature as well.
+	///
+	/// The label and data the transcript was built from are kept along with it, see
+	/// [`VrfSignData::transcript_label`] and [`VrfSignData::transcript_data`].
 	#[derive(Clone)]
 	pub struct VrfSignData {
 		/// Associated protocol transcript.
 		pub transcript: Transcript,
 		/// VRF inputs to be signed.
 		pub inputs: VrfIosVec<VrfInput>,
+		/// Label the transcript was built with.
+		transcript_label: Vec<u8>,
+		/// Data appended to the transcript, in order.
+		transcript_data: Vec<Vec<u8>>,
 	}
 
 	impl VrfSignData {
@@ -300,13 +320,34 @@ pub mod vrf {
 			let inputs: Vec<VrfInput> = inputs.into_iter().collect();
 			let inputs = VrfIosVec::truncate_from(inputs);
 			let mut transcript = Transcript::new_labeled(transcript_label);
-			transcript_data.into_iter().for_each(|data| transcript.append(data.as_ref()));
-			VrfSignData { transcript, inputs }
+			let transcript_data: Vec<Vec<u8>> =
+				transcript_data.into_iter().map(|data| data.as_ref().to_vec()).collect();
+			transcript_data.iter().for_each(|data| transcript.append(data.as_slice()));
+			VrfSignData {
+				transcript,
+				inputs,
+				transcript_label: transcript_label.to_vec(),
+				transcript_data,
+			}
 		}
 
 		/// Append a message to the transcript.
 		pub fn push_transcript_data(&mut self, data: &[u8]) {
 			self.transcript.append(data);
+			self.transcript_data.push(data.to_vec());
+		}
+
+		/// Label the transcript was built with.
+		pub fn transcript_label(&self) -> &[u8] {
+			&self.transcript_label
+		}
+
+		/// Data appended to the transcript with [`VrfSignData::new`] and
+		/// [`VrfSignData::push_transcript_data`], in order.
+		///
+		/// Data appended directly to [`VrfSignData::transcript`] is not part of it.
+		pub fn transcript_data(&self) -> &[Vec<u8>] {
+			&self.transcript_data
 		}
 
 		/// Tries to append a [`VrfInput`] to the vrf inputs list.
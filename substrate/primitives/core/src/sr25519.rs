@@ -313,8 +313,20 @@ pub mod vrf {
 	const DEFAULT_EXTRA_DATA_LABEL: &[u8] = b"VRF";
 
 	/// Transcript ready to be used for VRF related operations.
+	///
+	/// Besides the transcript itself, the label and messages it was built from are kept, so that
+	/// it can be rebuilt by a signer holding the secret key in another process.
 	#[derive(Clone)]
-	pub struct VrfTranscript(pub merlin::Transcript);
+	pub struct VrfTranscript(pub merlin::Transcript, VrfTranscriptData);
+
+	/// Label and messages a [`VrfTranscript`] was built from.
+	#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+	pub struct VrfTranscriptData {
+		/// Label of the transcript.
+		pub label: Vec<u8>,
+		/// The `(domain, message)` tuples appended to the transcript, in order.
+		pub items: Vec<(Vec<u8>, Vec<u8>)>,
+	}
 
 	impl VrfTranscript {
 		/// Build a new transcript instance.
@@ -323,7 +335,18 @@ pub mod vrf {
 		pub fn new(label: &'static [u8], data: &[(&'static [u8], &[u8])]) -> Self {
 			let mut transcript = merlin::Transcript::new(label);
 			data.iter().for_each(|(l, b)| transcript.append_message(l, b));
-			VrfTranscript(transcript)
+			let data = VrfTranscriptData {
+				label: label.to_vec(),
+				items: data.iter().map(|(l, b)| (l.to_vec(), b.to_vec())).collect(),
+			};
+			VrfTranscript(transcript, data)
+		}
+
+		/// Label and messages the transcript was built from.
+		///
+		/// Messages appended to the inner transcript afterwards are not part of it.
+		pub fn data(&self) -> &VrfTranscriptData {
+			&self.1
 		}
 
 		/// Map transcript to `VrfSignData`.
@@ -374,6 +397,11 @@ pub mod vrf {
 			self.extra = Some(extra);
 			self
 		}
+
+		/// Extra data to be signed, if any.
+		pub fn extra(&self) -> Option<&VrfTranscript> {
+			self.extra.as_ref()
+		}
 	}
 
 	/// VRF signature data
@@ -862,6 +890,24 @@ mod tests {
 		assert!(public.vrf_verify(&data, &signature));
 	}
 
+	#[test]
+	fn vrf_transcript_keeps_its_data() {
+		let transcript = VrfTranscript::new(b"label", &[(b"domain1", b"data1"), (b"domain2", b"")]);
+
+		assert_eq!(
+			transcript.data(),
+			&VrfTranscriptData {
+				label: b"label".to_vec(),
+				items: vec![
+					(b"domain1".to_vec(), b"data1".to_vec()),
+					(b"domain2".to_vec(), Vec::new())
+				],
+			}
+		);
+		let encoded = transcript.data().encode();
+		assert_eq!(VrfTranscriptData::decode(&mut &encoded[..]).unwrap(), *transcript.data());
+	}
+
 	#[test]
 	fn vrf_make_bytes_matches() {
 		let pair = Pair::from_seed(b"12345678901234567890123456789012");
@@ -15,7 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{borrow::Cow, process::Command};
+use std::{borrow::Cow, env, fs, path::PathBuf, process::Command};
 
 /// Generate the `cargo:` key output
 pub fn generate_cargo_keys() {
@@ -59,3 +59,96 @@ fn get_version(impl_commit: &str) -> String {
 		impl_commit
 	)
 }
+
+/// Generate `SUBSTRATE_WASMTIME_VERSION` with the version of `wasmtime` used in the workspace.
+///
+/// The version is read from the `Cargo.lock` found searching from the `CARGO_MANIFEST_DIR` and
+/// then from the `OUT_DIR` upwards. The variable is not set if the version can not be determined,
+/// e.g. when `wasmtime` is pulled in with several versions, so it must be read with
+/// `option_env!`.
+pub fn generate_wasmtime_version() {
+	generate_dependency_version("wasmtime", "SUBSTRATE_WASMTIME_VERSION");
+}
+
+fn generate_dependency_version(dep: &str, env_var: &str) {
+	let version = match find_lock_file() {
+		Some(lock_file) => {
+			println!("cargo:rerun-if-changed={}", lock_file.display());
+
+			match fs::read_to_string(&lock_file) {
+				Ok(lock) => {
+					let mut versions = locked_versions(&lock, dep);
+					versions.dedup();
+					match &versions[..] {
+						[version] => Some(version.to_owned()),
+						[] => {
+							println!(
+								"cargo:warning=`{}` not found in `{}`",
+								dep,
+								lock_file.display(),
+							);
+							None
+						},
+						_ => {
+							println!(
+								"cargo:warning=Several versions of `{}` in `{}`: {}",
+								dep,
+								lock_file.display(),
+								versions.join(", "),
+							);
+							None
+						},
+					}
+				},
+				Err(err) => {
+					println!("cargo:warning=Failed to read `{}`: {}", lock_file.display(), err);
+					None
+				},
+			}
+		},
+		None => {
+			println!("cargo:warning=Could not find `Cargo.lock` to read the version of `{dep}`");
+			None
+		},
+	};
+
+	if let Some(version) = version {
+		println!("cargo:rustc-env={env_var}={version}");
+	}
+}
+
+/// The `Cargo.lock` of the workspace being built.
+///
+/// A dependency fetched from a registry is outside of the workspace, but its `OUT_DIR` is in the
+/// target directory, usually inside the workspace.
+fn find_lock_file() -> Option<PathBuf> {
+	["CARGO_MANIFEST_DIR", "OUT_DIR"]
+		.into_iter()
+		.filter_map(|var| env::var_os(var).map(PathBuf::from))
+		.find_map(|dir| {
+			dir.ancestors().map(|dir| dir.join("Cargo.lock")).find(|path| path.is_file())
+		})
+}
+
+/// The sorted versions of the package `name` in the given `Cargo.lock`.
+fn locked_versions(lock: &str, name: &str) -> Vec<String> {
+	let name = format!("name = \"{name}\"");
+	let mut versions = Vec::new();
+	// Each package is a `[[package]]` table, starting with its name and version.
+	for package in lock.split("[[package]]").skip(1) {
+		let mut lines = package.lines().map(str::trim).filter(|line| !line.is_empty());
+		if lines.next() != Some(name.as_str()) {
+			continue
+		}
+		if let Some(version) = lines
+			.next()
+			.and_then(|line| line.strip_prefix("version = \""))
+			.and_then(|line| line.strip_suffix('"'))
+		{
+			versions.push(version.to_owned());
+		}
+	}
+	versions.sort();
+
+	versions
+}
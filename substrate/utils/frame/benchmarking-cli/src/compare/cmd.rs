@@ -0,0 +1,296 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains the [`CompareCmd`] as entry point for the CLI to compare two benchmark runs.
+
+use clap::Parser;
+use comfy_table::Table;
+use frame_benchmarking::AnalysisChoice;
+use log::info;
+use sc_cli::{CliConfiguration, Result, SharedParams};
+use serde::Serialize;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+use super::weights::{self, Metric, Weights};
+
+/// Compare the weights of two benchmark runs.
+///
+/// Each run is either the JSON output of `benchmark pallet --json-file`, a weight module generated
+/// by `benchmark pallet --output` or a directory of such weight modules. The base value and the
+/// slope of every component are compared for the ref time, proof size, reads and writes of every
+/// extrinsic. Example:
+///
+/// $ substrate benchmark compare old/weights new/weights --threshold 30
+///
+/// An increase above the threshold is a regression, the command fails if any is found.
+#[derive(Debug, Parser)]
+pub struct CompareCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	/// The results of the baseline run.
+	pub old: PathBuf,
+
+	/// The results of the run to check.
+	pub new: PathBuf,
+
+	/// Maximal increase of the ref time, reads and writes that is not a regression.
+	#[arg(long, default_value_t = 30.0, value_name = "PERCENT")]
+	pub threshold: f64,
+
+	/// Maximal increase of the proof size that is not a regression.
+	///
+	/// Defaults to `--threshold`.
+	#[arg(long, value_name = "PERCENT")]
+	pub proof_size_threshold: Option<f64>,
+
+	/// Which analysis function to use for JSON inputs:
+	/// * min-squares (default)
+	/// * median-slopes
+	/// * max (max of min squares and median slopes for each value)
+	#[arg(long)]
+	pub analysis: Option<String>,
+
+	/// Print the report as JSON to stdout.
+	#[arg(long = "json")]
+	pub json_output: bool,
+
+	/// Write the report as JSON into the given file.
+	#[arg(long, conflicts_with = "json_output")]
+	pub json_file: Option<PathBuf>,
+
+	/// Do not return an error if any regression is found.
+	#[arg(long)]
+	pub allow_regressions: bool,
+}
+
+/// The change of a base value or a component slope of an extrinsic.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Change {
+	pub(crate) pallet: String,
+	pub(crate) extrinsic: String,
+	pub(crate) metric: Metric,
+	/// The component of the slope, `None` for the base value.
+	pub(crate) component: Option<String>,
+	pub(crate) old: u128,
+	pub(crate) new: u128,
+	/// The relative change in percent, `None` if the old value is zero.
+	pub(crate) change: Option<f64>,
+	pub(crate) regression: bool,
+}
+
+/// The result of the comparison.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct Report {
+	/// Values that changed between both runs.
+	pub(crate) changes: Vec<Change>,
+	/// Extrinsics only present in the new run, as `pallet::extrinsic`.
+	pub(crate) added: Vec<String>,
+	/// Extrinsics only present in the old run, as `pallet::extrinsic`.
+	pub(crate) removed: Vec<String>,
+	/// Number of changes that are regressions.
+	pub(crate) regressions: usize,
+}
+
+impl CompareCmd {
+	/// Compare both runs and print the report.
+	pub fn run(&self) -> Result<()> {
+		let (threshold, proof_size_threshold) = self.thresholds()?;
+		let analysis_choice: AnalysisChoice = self.analysis.clone().try_into()?;
+
+		let old = weights::load(&self.old, &analysis_choice)?;
+		let new = weights::load(&self.new, &analysis_choice)?;
+		let report = compare(&old, &new, |metric| match metric {
+			Metric::ProofSize => proof_size_threshold,
+			_ => threshold,
+		});
+
+		if self.json_output || self.json_file.is_some() {
+			let json = serde_json::to_string_pretty(&report)
+				.map_err(|e| format!("Serializing into JSON: {:?}", e))?;
+			match &self.json_file {
+				Some(path) => fs::write(path, json)?,
+				None => println!("{json}"),
+			}
+		} else {
+			print_report(&report);
+		}
+
+		if report.regressions > 0 && !self.allow_regressions {
+			return Err(format!("Found {} weight regression(s)", report.regressions).into())
+		}
+		Ok(())
+	}
+
+	fn thresholds(&self) -> Result<(f64, f64)> {
+		let threshold = self.threshold;
+		let proof_size_threshold = self.proof_size_threshold.unwrap_or(threshold);
+		if threshold < 0.0 || proof_size_threshold < 0.0 {
+			return Err("The thresholds must not be negative".into())
+		}
+		Ok((threshold, proof_size_threshold))
+	}
+}
+
+/// Compare the weights of both runs, an increase above the threshold of its metric (in percent) is
+/// a regression.
+pub(crate) fn compare(old: &Weights, new: &Weights, threshold: impl Fn(Metric) -> f64) -> Report {
+	let mut report = Report::default();
+
+	for key in old.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+		let (pallet, extrinsic) = key;
+		let (old_weight, new_weight) = match (old.get(key), new.get(key)) {
+			(Some(old), Some(new)) => (old, new),
+			(None, _) => {
+				report.added.push(format!("{pallet}::{extrinsic}"));
+				continue
+			},
+			(_, None) => {
+				report.removed.push(format!("{pallet}::{extrinsic}"));
+				continue
+			},
+		};
+
+		for metric in Metric::ALL {
+			let old = old_weight.get(&metric).cloned().unwrap_or_default();
+			let new = new_weight.get(&metric).cloned().unwrap_or_default();
+
+			let slopes = old.slopes.keys().chain(new.slopes.keys()).collect::<BTreeSet<_>>();
+			let values =
+				std::iter::once((None, old.base, new.base)).chain(slopes.into_iter().map(|c| {
+					let slope = |f: &weights::Formula| f.slopes.get(c).copied().unwrap_or_default();
+					(Some(c.clone()), slope(&old), slope(&new))
+				}));
+
+			for (component, old, new) in values.filter(|(_, old, new)| old != new) {
+				let change = (old != 0).then(|| (new as f64 - old as f64) / old as f64 * 100.0);
+				let regression = new > old && change.map_or(true, |c| c > threshold(metric));
+				report.regressions += regression as usize;
+				report.changes.push(Change {
+					pallet: pallet.clone(),
+					extrinsic: extrinsic.clone(),
+					metric,
+					component,
+					old,
+					new,
+					change,
+					regression,
+				});
+			}
+		}
+	}
+
+	report
+}
+
+/// Prints a human-readable report.
+fn print_report(report: &Report) {
+	let mut table = Table::new();
+	table.set_header(["Pallet", "Extrinsic", "Metric", "Component", "Old", "New", "Change", ""]);
+	for change in &report.changes {
+		table.add_row([
+			change.pallet.clone(),
+			change.extrinsic.clone(),
+			change.metric.to_string(),
+			change.component.clone().unwrap_or_else(|| "base".into()),
+			change.old.to_string(),
+			change.new.to_string(),
+			change.change.map_or_else(|| "new".into(), |c| format!("{c:+.2}%")),
+			if change.regression { "REGRESSION".into() } else { String::new() },
+		]);
+	}
+
+	info!("\n{table}");
+	for extrinsic in &report.added {
+		info!("Added: {extrinsic}");
+	}
+	for extrinsic in &report.removed {
+		info!("Removed: {extrinsic}");
+	}
+	info!(
+		"{} changed value(s), {} regression(s), {} added and {} removed extrinsic(s)",
+		report.changes.len(),
+		report.regressions,
+		report.added.len(),
+		report.removed.len(),
+	);
+}
+
+impl CliConfiguration for CompareCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::compare::weights::{ExtrinsicWeight, Formula};
+
+	fn weights(ref_time: u128, slope: u128, proof_size: u128) -> Weights {
+		let weight = ExtrinsicWeight::from([
+			(
+				Metric::RefTime,
+				Formula { base: ref_time, slopes: [("n".to_string(), slope)].into() },
+			),
+			(Metric::ProofSize, Formula { base: proof_size, slopes: Default::default() }),
+		]);
+		[(("pallet".to_string(), "call".to_string()), weight)].into()
+	}
+
+	#[test]
+	fn compare_reports_regressions_above_threshold() {
+		let old = weights(1000, 100, 1000);
+		let new = weights(1200, 200, 1500);
+
+		let report = compare(&old, &new, |_| 30.0);
+		let changes = report
+			.changes
+			.iter()
+			.map(|c| (c.metric, c.component.clone(), c.change, c.regression))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			changes,
+			vec![
+				(Metric::RefTime, None, Some(20.0), false),
+				(Metric::RefTime, Some("n".into()), Some(100.0), true),
+				(Metric::ProofSize, None, Some(50.0), true),
+			]
+		);
+		assert_eq!(report.regressions, 2);
+
+		let report = compare(&old, &new, |m| if m == Metric::ProofSize { 60.0 } else { 200.0 });
+		assert_eq!(report.regressions, 0);
+	}
+
+	#[test]
+	fn compare_reports_new_components_and_extrinsics() {
+		let old = weights(1000, 0, 1000);
+		let mut new = weights(900, 10, 1000);
+		new.insert(("pallet".into(), "other".into()), Default::default());
+
+		let report = compare(&old, &new, |_| 30.0);
+		assert_eq!(report.added, vec!["pallet::other".to_string()]);
+		assert!(report.removed.is_empty());
+		// Only the new component is a regression, the base got lighter.
+		assert_eq!(report.regressions, 1);
+		assert_eq!(report.changes[1].change, None);
+		assert!(report.changes[1].regression);
+		assert!(!report.changes[0].regression);
+	}
+}
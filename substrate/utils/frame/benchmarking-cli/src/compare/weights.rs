@@ -0,0 +1,317 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loads the weight formulas of extrinsics, either by analyzing the JSON output of the `pallet`
+//! command or by parsing generated weight modules.
+
+use frame_benchmarking::{
+	Analysis, AnalysisChoice, BenchmarkBatchSplitResults, BenchmarkResult, BenchmarkSelector,
+};
+use sc_cli::Result;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// A weight metric of an extrinsic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Metric {
+	/// Execution time in picoseconds.
+	RefTime,
+	/// Proof size in bytes.
+	ProofSize,
+	/// Number of database reads.
+	Reads,
+	/// Number of database writes.
+	Writes,
+}
+
+impl Metric {
+	pub(crate) const ALL: [Metric; 4] =
+		[Metric::RefTime, Metric::ProofSize, Metric::Reads, Metric::Writes];
+}
+
+impl std::fmt::Display for Metric {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::RefTime => write!(f, "ref_time"),
+			Self::ProofSize => write!(f, "proof_size"),
+			Self::Reads => write!(f, "reads"),
+			Self::Writes => write!(f, "writes"),
+		}
+	}
+}
+
+/// A linear formula: a base value plus a slope per component.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Formula {
+	pub(crate) base: u128,
+	pub(crate) slopes: BTreeMap<String, u128>,
+}
+
+/// The formula of every metric of an extrinsic.
+pub(crate) type ExtrinsicWeight = BTreeMap<Metric, Formula>;
+
+/// Weights of extrinsics, keyed by pallet and extrinsic name.
+pub(crate) type Weights = BTreeMap<(String, String), ExtrinsicWeight>;
+
+/// Load the weights from a file or a directory.
+///
+/// `.json` files are expected to be the output of `benchmark pallet --json-file`, other files to be
+/// weight modules generated by `benchmark pallet --output`. All weight modules of a directory are
+/// loaded.
+pub(crate) fn load(path: &Path, analysis_choice: &AnalysisChoice) -> Result<Weights> {
+	if path.is_dir() {
+		let mut weights = Weights::new();
+		let mut files = fs::read_dir(path)?
+			.map(|entry| entry.map(|e| e.path()))
+			.collect::<std::io::Result<Vec<_>>>()?;
+		files.sort();
+		for file in files.iter().filter(|f| f.extension().map_or(false, |e| e == "rs")) {
+			if file.file_name().map_or(false, |name| name == "mod.rs") {
+				continue
+			}
+			weights.extend(parse_weight_file(file)?);
+		}
+		return Ok(weights)
+	}
+
+	if path.extension().map_or(false, |e| e == "json") {
+		let batches: Vec<BenchmarkBatchSplitResults> = serde_json::from_slice(&fs::read(path)?)
+			.map_err(|e| format!("Failed to deserialize {:?}: {}", path, e))?;
+		analyze(&batches, analysis_choice)
+	} else {
+		parse_weight_file(path)
+	}
+}
+
+/// Analyze the raw benchmark results the same way the `pallet` command does.
+///
+/// The proof size is the measured one, since the storage info needed to estimate it isn't part of
+/// the results.
+pub(crate) fn analyze(
+	batches: &[BenchmarkBatchSplitResults],
+	analysis_choice: &AnalysisChoice,
+) -> Result<Weights> {
+	let analysis_function = match analysis_choice {
+		AnalysisChoice::MinSquares => Analysis::min_squares_iqr,
+		AnalysisChoice::MedianSlopes => Analysis::median_slopes,
+		AnalysisChoice::Max => Analysis::max,
+	};
+
+	let mut weights = Weights::new();
+	for batch in batches.iter().filter(|b| !b.time_results.is_empty()) {
+		let pallet = String::from_utf8(batch.pallet.clone())
+			.map_err(|_| "Pallet name is not valid UTF-8")?;
+		let benchmark = String::from_utf8(batch.benchmark.clone())
+			.map_err(|_| "Benchmark name is not valid UTF-8")?;
+
+		let analyze_metric = |results: &Vec<BenchmarkResult>, selector| -> Result<Formula> {
+			let analysis = analysis_function(results, selector)
+				.ok_or_else(|| format!("Failed to analyze the results of {pallet}::{benchmark}"))?;
+			let slopes = analysis
+				.names
+				.into_iter()
+				.zip(analysis.slopes)
+				.filter(|(_, slope)| *slope != 0)
+				.collect();
+			Ok(Formula { base: analysis.base, slopes })
+		};
+
+		let weight = ExtrinsicWeight::from([
+			(
+				Metric::RefTime,
+				analyze_metric(&batch.time_results, BenchmarkSelector::ExtrinsicTime)?,
+			),
+			(Metric::ProofSize, analyze_metric(&batch.db_results, BenchmarkSelector::ProofSize)?),
+			(Metric::Reads, analyze_metric(&batch.db_results, BenchmarkSelector::Reads)?),
+			(Metric::Writes, analyze_metric(&batch.db_results, BenchmarkSelector::Writes)?),
+		]);
+		weights.insert((pallet, benchmark), weight);
+	}
+
+	Ok(weights)
+}
+
+/// Parse a weight module generated with the default template of the `pallet` command.
+pub(crate) fn parse_weight_file(path: &Path) -> Result<Weights> {
+	let content = fs::read_to_string(path)?;
+	let fallback = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+	Ok(parse_weights(&content, &fallback))
+}
+
+/// Parse the content of a weight module, `fallback_pallet` is used if it doesn't name the pallet.
+pub(crate) fn parse_weights(content: &str, fallback_pallet: &str) -> Weights {
+	let mut weights = Weights::new();
+	let mut pallet = fallback_pallet.to_string();
+	let mut current: Option<(String, ExtrinsicWeight)> = None;
+
+	for line in content.lines().map(str::trim) {
+		if let Some(name) = line
+			.strip_prefix("/// Weight functions for `")
+			.and_then(|l| l.strip_suffix("`."))
+		{
+			pallet = name.to_string();
+			continue
+		}
+
+		if let Some(signature) = line.strip_prefix("fn ") {
+			if let Some((name, _)) = signature.split_once('(') {
+				if signature.ends_with("-> Weight {") {
+					current = Some((name.trim().to_string(), ExtrinsicWeight::new()));
+				}
+			}
+			continue
+		}
+
+		let Some((_, weight)) = current.as_mut() else { continue };
+		if line == "}" {
+			let (name, weight) = current.take().expect("checked above; qed");
+			// Pallets' own weight modules implement `WeightInfo` twice, keep the first one.
+			weights.entry((pallet.clone(), name)).or_insert(weight);
+			continue
+		}
+		parse_weight_line(line, weight);
+	}
+
+	weights
+}
+
+/// Parse a line of a weight function body, as written by the default template.
+fn parse_weight_line(line: &str, weight: &mut ExtrinsicWeight) {
+	let line = line.strip_prefix(".saturating_add(").unwrap_or(line);
+
+	if let Some(rest) = line.strip_prefix("Weight::from_parts(") {
+		let Some((parts, rest)) = rest.split_once(')') else { return };
+		let Some((ref_time, proof_size)) = parts.split_once(',') else { return };
+		let (Some(ref_time), Some(proof_size)) = (number(ref_time), number(proof_size)) else {
+			return
+		};
+		match component(rest) {
+			Some(component) if proof_size == 0 => {
+				formula(weight, Metric::RefTime).slopes.insert(component, ref_time);
+			},
+			Some(component) => {
+				formula(weight, Metric::ProofSize).slopes.insert(component, proof_size);
+			},
+			None => {
+				formula(weight, Metric::RefTime).base += ref_time;
+				formula(weight, Metric::ProofSize).base += proof_size;
+			},
+		}
+		return
+	}
+
+	for (prefix, metric) in [
+		("T::DbWeight::get().reads(", Metric::Reads),
+		("T::DbWeight::get().writes(", Metric::Writes),
+	] {
+		let Some(rest) = line.strip_prefix(prefix) else { continue };
+		match rest.strip_prefix('(') {
+			// `reads((1_u64).saturating_mul(c.into()))`
+			Some(rest) => {
+				let Some((slope, rest)) = rest.split_once(')') else { return };
+				let (Some(slope), Some(component)) = (number(slope), component(rest)) else {
+					return
+				};
+				formula(weight, metric).slopes.insert(component, slope);
+			},
+			// `reads(1)`
+			None =>
+				if let Some(base) = rest.split_once(')').and_then(|(base, _)| number(base)) {
+					formula(weight, metric).base += base;
+				},
+		}
+	}
+}
+
+fn formula(weight: &mut ExtrinsicWeight, metric: Metric) -> &mut Formula {
+	weight.entry(metric).or_default()
+}
+
+/// Parse a number as written in weight files, e.g. `1_000` or `1_u64`.
+fn number(s: &str) -> Option<u128> {
+	let s = s.trim().replace('_', "");
+	s.strip_suffix("u64").unwrap_or(&s).parse().ok()
+}
+
+/// The component of a `.saturating_mul(c.into())` suffix.
+fn component(rest: &str) -> Option<String> {
+	rest.trim_start_matches(')')
+		.strip_prefix(".saturating_mul(")
+		.and_then(|r| r.split_once(".into()"))
+		.map(|(c, _)| c.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const WEIGHTS: &str = r#"
+/// Weight functions for `pallet_balances`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_balances::WeightInfo for WeightInfo<T> {
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	fn transfer_allow_death() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `3593`
+		// Minimum execution time: 45_000_000 picoseconds.
+		Weight::from_parts(46_329_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// The range of component `u` is `[1, 1000]`.
+	fn upgrade_accounts(u: u32, ) -> Weight {
+		Weight::from_parts(16_534_000, 990)
+			// Standard Error: 10_000
+			.saturating_add(Weight::from_parts(13_327_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(u.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(u.into()))
+	}
+}
+"#;
+
+	#[test]
+	fn parse_weights_works() {
+		let weights = parse_weights(WEIGHTS, "fallback");
+		assert_eq!(weights.len(), 2);
+
+		let transfer = &weights[&("pallet_balances".into(), "transfer_allow_death".into())];
+		assert_eq!(transfer[&Metric::RefTime], Formula { base: 46_329_000, slopes: [].into() });
+		assert_eq!(transfer[&Metric::ProofSize], Formula { base: 3593, slopes: [].into() });
+		assert_eq!(transfer[&Metric::Reads], Formula { base: 1, slopes: [].into() });
+		assert_eq!(transfer[&Metric::Writes], Formula { base: 1, slopes: [].into() });
+
+		let upgrade = &weights[&("pallet_balances".into(), "upgrade_accounts".into())];
+		let slope = |value| BTreeMap::from([("u".to_string(), value)]);
+		assert_eq!(
+			upgrade[&Metric::RefTime],
+			Formula { base: 16_534_000, slopes: slope(13_327_000) }
+		);
+		assert_eq!(upgrade[&Metric::ProofSize], Formula { base: 990, slopes: slope(2603) });
+		assert_eq!(upgrade[&Metric::Reads], Formula { base: 0, slopes: slope(1) });
+		assert_eq!(upgrade[&Metric::Writes], Formula { base: 0, slopes: slope(1) });
+	}
+
+	#[test]
+	fn parse_weights_falls_back_to_file_name() {
+		let weights = parse_weights(&WEIGHTS.replace("/// Weight functions", "//"), "pallet_x");
+		assert!(weights.keys().all(|(pallet, _)| pallet == "pallet_x"));
+	}
+}
@@ -16,6 +16,7 @@
 // limitations under the License.
 
 use super::{
+	pov_audit,
 	types::{ComponentRange, ComponentRangeMap},
 	writer, ListOutput, PalletCmd,
 };
@@ -29,8 +30,8 @@ use crate::{
 use clap::{error::ErrorKind, CommandFactory};
 use codec::{Decode, Encode};
 use frame_benchmarking::{
-	Analysis, BenchmarkBatch, BenchmarkBatchSplitResults, BenchmarkList, BenchmarkParameter,
-	BenchmarkResult, BenchmarkSelector,
+	Analysis, AnalysisChoice, BenchmarkBatch, BenchmarkBatchSplitResults, BenchmarkList,
+	BenchmarkParameter, BenchmarkResult, BenchmarkSelector,
 };
 use frame_support::traits::StorageInfo;
 use linked_hash_map::LinkedHashMap;
@@ -733,6 +734,10 @@ impl PalletCmd {
 			self.print_summary(&batches, &storage_info, pov_modes.clone())
 		}
 
+		if self.audit_pov {
+			self.print_pov_audit(batches, storage_info, component_ranges, &pov_modes)?;
+		}
+
 		// Create the weights.rs file.
 		if let Some(output_path) = &self.output {
 			writer::write_results(
@@ -749,6 +754,39 @@ impl PalletCmd {
 		Ok(())
 	}
 
+	/// Prints the audit of the estimated proof sizes, see `--audit-pov`.
+	fn print_pov_audit(
+		&self,
+		batches: &[BenchmarkBatchSplitResults],
+		storage_info: &[StorageInfo],
+		component_ranges: &ComponentRangeMap,
+		pov_modes: &PovModesMap,
+	) -> Result<()> {
+		if storage_info.is_empty() {
+			log::warn!(
+				target: LOG_TARGET,
+				"Skipping the PoV audit since the storage info of the runtime is not available"
+			);
+			return Ok(())
+		}
+
+		let analysis_choice: AnalysisChoice = self.output_analysis.clone().try_into()?;
+		let pov_analysis_choice: AnalysisChoice = self.output_pov_analysis.clone().try_into()?;
+		let audits = pov_audit::audit_pov(
+			batches,
+			storage_info,
+			component_ranges,
+			pov_modes,
+			self.default_pov_mode,
+			&analysis_choice,
+			&pov_analysis_choice,
+			self.worst_case_map_values,
+			self.additional_trie_layers,
+		);
+		pov_audit::print_pov_audit(&audits);
+		Ok(())
+	}
+
 	/// Re-analyze a batch historic benchmark timing data. Will not take the PoV into account.
 	fn output_from_results(&self, batches: &[BenchmarkBatchSplitResults]) -> Result<()> {
 		let mut component_ranges = HashMap::<(String, String), HashMap<String, (u32, u32)>>::new();
@@ -16,6 +16,7 @@
 // limitations under the License.
 
 mod command;
+mod pov_audit;
 mod types;
 mod writer;
 
@@ -218,6 +219,15 @@ pub struct PalletCmd {
 	#[arg(long)]
 	pub no_storage_info: bool,
 
+	/// Audit the estimated proof sizes of the benchmarks.
+	///
+	/// Prints a table that compares the largest measured proof size of every extrinsic with the
+	/// estimate of the weight file at the maximum of all components. Storage items without a
+	/// `MaxEncodedLen` bound, items with the `Ignored` PoV mode and unrealistic estimates are
+	/// flagged.
+	#[arg(long)]
+	pub audit_pov: bool,
+
 	/// The assumed default maximum size of any `StorageMap`.
 	///
 	/// When the maximum size of a map is not defined by the runtime developer,
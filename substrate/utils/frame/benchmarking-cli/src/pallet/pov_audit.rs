@@ -0,0 +1,305 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compares the measured proof sizes with the estimates of the weight files, see `--audit-pov`.
+
+use std::collections::{BTreeSet, HashMap};
+
+use comfy_table::Table;
+use frame_benchmarking::{AnalysisChoice, BenchmarkBatchSplitResults};
+use frame_support::traits::StorageInfo;
+use sp_core::hexdisplay::HexDisplay;
+
+use crate::pallet::{
+	command::{PovEstimationMode, PovModesMap},
+	types::ComponentRangeMap,
+	writer,
+};
+
+/// An estimate that is this many times larger than the measured proof size is most likely based on
+/// an unrealistic `MaxEncodedLen` bound.
+const UNREALISTIC_ESTIMATE_FACTOR: u128 = 10;
+
+/// The proof size audit of one extrinsic.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PovAudit {
+	pub(crate) pallet: String,
+	pub(crate) extrinsic: String,
+	/// The largest proof size that was recorded while benchmarking.
+	pub(crate) measured: u128,
+	/// The proof size of the weight file with all components at their maximum.
+	pub(crate) estimated: u128,
+	/// Everything that makes the estimate questionable.
+	pub(crate) issues: Vec<String>,
+}
+
+/// Audits the proof size estimate of every benchmarked extrinsic.
+pub(crate) fn audit_pov(
+	batches: &[BenchmarkBatchSplitResults],
+	storage_info: &[StorageInfo],
+	component_ranges: &ComponentRangeMap,
+	pov_modes: &PovModesMap,
+	default_pov_mode: PovEstimationMode,
+	analysis_choice: &AnalysisChoice,
+	pov_analysis_choice: &AnalysisChoice,
+	worst_case_map_values: u32,
+	additional_trie_layers: u8,
+) -> Vec<PovAudit> {
+	let storage_info_map = storage_info
+		.iter()
+		.map(|info| (info.prefix.as_slice(), info))
+		.collect::<HashMap<_, _>>();
+
+	batches
+		.iter()
+		.filter(|batch| !batch.time_results.is_empty())
+		.map(|batch| {
+			let pallet = String::from_utf8(batch.pallet.clone()).expect("Encoded from String; qed");
+			let extrinsic =
+				String::from_utf8(batch.benchmark.clone()).expect("Encoded from String; qed");
+			let pov_mode =
+				pov_modes.get(&(pallet.clone(), extrinsic.clone())).cloned().unwrap_or_default();
+
+			let data = writer::get_benchmark_data(
+				batch,
+				storage_info,
+				component_ranges,
+				pov_modes.clone(),
+				default_pov_mode,
+				analysis_choice,
+				pov_analysis_choice,
+				worst_case_map_values,
+				additional_trie_layers,
+			);
+			let measured =
+				batch.db_results.iter().map(|r| r.proof_size as u128).max().unwrap_or_default();
+			let estimated = data.worst_case_calculated_proof_size();
+
+			let mut issues = storage_issues(batch, &storage_info_map, &pov_mode, default_pov_mode);
+			if estimated < measured {
+				issues.push(format!(
+					"estimate is {} bytes below the measurement",
+					measured - estimated
+				));
+			} else if measured > 0 && estimated / measured >= UNREALISTIC_ESTIMATE_FACTOR {
+				issues.push(format!(
+					"estimate is {}x the measurement, check the `MaxEncodedLen` bounds",
+					estimated / measured
+				));
+			}
+
+			PovAudit { pallet, extrinsic, measured, estimated, issues }
+		})
+		.collect()
+}
+
+/// Flags the storage items touched by a benchmark that are not properly accounted for.
+fn storage_issues(
+	batch: &BenchmarkBatchSplitResults,
+	storage_info: &HashMap<&[u8], &StorageInfo>,
+	pov_mode: &HashMap<(String, String), PovEstimationMode>,
+	default_pov_mode: PovEstimationMode,
+) -> Vec<String> {
+	let mut prefixes = BTreeSet::new();
+	for result in &batch.db_results {
+		for (key, _, _, whitelisted) in &result.keys {
+			if !whitelisted {
+				prefixes.insert(&key[..key.len().min(32)]);
+			}
+		}
+	}
+
+	let mut issues = Vec::new();
+	for prefix in prefixes {
+		// Markers that `process_storage_results` reports as `Skipped::Metadata` and
+		// `Benchmark::Override`.
+		if prefix == b"Skipped Metadata" || prefix == b"Benchmark Override" {
+			continue
+		}
+		let Some(info) = storage_info.get(prefix) else {
+			issues.push(format!(
+				"UNKNOWN KEY `0x{}` is not accounted for",
+				HexDisplay::from(&prefix)
+			));
+			continue
+		};
+
+		let pallet = String::from_utf8_lossy(&info.pallet_name).into_owned();
+		let storage = String::from_utf8_lossy(&info.storage_name).into_owned();
+		let mode = [
+			(pallet.clone(), storage.clone()),
+			(pallet.clone(), "ALL".into()),
+			("ALL".into(), "ALL".into()),
+		]
+		.iter()
+		.find_map(|key| pov_mode.get(key))
+		.copied()
+		.unwrap_or(default_pov_mode);
+
+		match (mode, info.max_size) {
+			(PovEstimationMode::Ignored, _) =>
+				issues.push(format!("`{pallet}::{storage}` uses the `Ignored` PoV mode")),
+			(PovEstimationMode::MaxEncodedLen, None) => issues.push(format!(
+				"`{pallet}::{storage}` has no `MaxEncodedLen` bound, only its measured size is used"
+			)),
+			(PovEstimationMode::Measured, None) =>
+				issues.push(format!("`{pallet}::{storage}` has no `MaxEncodedLen` bound")),
+			_ => {},
+		}
+	}
+
+	issues
+}
+
+/// Prints a table with the audit of every extrinsic.
+pub(crate) fn print_pov_audit(audits: &[PovAudit]) {
+	let max_pov_size = polkadot_primitives::MAX_POV_SIZE as u128;
+
+	let mut table = Table::new();
+	table.set_header([
+		"Pallet",
+		"Extrinsic",
+		"Measured",
+		"Estimated",
+		"Estimated / Measured",
+		"% of max PoV",
+		"Issues",
+	]);
+	for audit in audits {
+		let ratio = match audit.measured {
+			0 => "-".into(),
+			measured => format!("{:.2}", audit.estimated as f64 / measured as f64),
+		};
+		table.add_row([
+			audit.pallet.clone(),
+			audit.extrinsic.clone(),
+			audit.measured.to_string(),
+			audit.estimated.to_string(),
+			ratio,
+			format!("{:.2}", audit.estimated as f64 * 100.0 / max_pov_size as f64),
+			audit.issues.join("\n"),
+		]);
+	}
+
+	println!("PoV Audit\n========");
+	println!("{table}");
+	println!(
+		"{} of {} extrinsic(s) have issues",
+		audits.iter().filter(|a| !a.issues.is_empty()).count(),
+		audits.len()
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::pallet::types::ComponentRange;
+	use frame_benchmarking::{BenchmarkParameter, BenchmarkResult};
+
+	fn batch(keys: Vec<Vec<u8>>) -> BenchmarkBatchSplitResults {
+		let results = (0..5)
+			.map(|i| BenchmarkResult {
+				components: vec![(BenchmarkParameter::n, i)],
+				extrinsic_time: 1000,
+				storage_root_time: 1000,
+				reads: 1,
+				writes: 1,
+				proof_size: 100 + i * 10,
+				keys: keys.iter().map(|k| (k.clone(), 1, 1, false)).collect(),
+				..Default::default()
+			})
+			.collect::<Vec<_>>();
+
+		BenchmarkBatchSplitResults {
+			pallet: b"pallet".to_vec(),
+			instance: b"instance".to_vec(),
+			benchmark: b"call".to_vec(),
+			time_results: results.clone(),
+			db_results: results,
+		}
+	}
+
+	fn info(name: &[u8], max_size: Option<u32>) -> StorageInfo {
+		StorageInfo {
+			pallet_name: b"Pallet".to_vec(),
+			storage_name: name.to_vec(),
+			prefix: name.to_vec(),
+			max_values: Some(1),
+			max_size,
+		}
+	}
+
+	fn audit(
+		batch: BenchmarkBatchSplitResults,
+		storage_info: &[StorageInfo],
+		pov_modes: &PovModesMap,
+	) -> PovAudit {
+		let ranges = ComponentRangeMap::from([(
+			("pallet".into(), "call".into()),
+			vec![ComponentRange { name: "n".into(), min: 0, max: 4 }],
+		)]);
+		let mut audits = audit_pov(
+			&[batch],
+			storage_info,
+			&ranges,
+			pov_modes,
+			PovEstimationMode::MaxEncodedLen,
+			&AnalysisChoice::MinSquares,
+			&AnalysisChoice::MedianSlopes,
+			16,
+			0,
+		);
+		assert_eq!(audits.len(), 1);
+		audits.remove(0)
+	}
+
+	#[test]
+	fn audit_pov_flags_unbounded_unknown_and_ignored_items() {
+		let storage_info = vec![info(b"Bounded", Some(8)), info(b"Unbounded", None)];
+		let keys = vec![b"Bounded".to_vec(), b"Unbounded".to_vec(), b"Unknown".to_vec()];
+		let audit = audit(batch(keys.clone()), &storage_info, &Default::default());
+
+		assert_eq!(audit.measured, 140);
+		assert_eq!(
+			audit.issues,
+			vec![
+				"`Pallet::Unbounded` has no `MaxEncodedLen` bound, only its measured size is used"
+					.to_string(),
+				format!("UNKNOWN KEY `0x{}` is not accounted for", HexDisplay::from(&b"Unknown")),
+			]
+		);
+
+		let pov_modes = PovModesMap::from([(
+			("pallet".into(), "call".into()),
+			HashMap::from([(("Pallet".into(), "Bounded".into()), PovEstimationMode::Ignored)]),
+		)]);
+		let audit = audit(batch(keys[..1].to_vec()), &storage_info, &pov_modes);
+		// An ignored item only contributes its measured proof size.
+		assert_eq!(audit.estimated, audit.measured);
+		assert_eq!(audit.issues, vec!["`Pallet::Bounded` uses the `Ignored` PoV mode".to_string()]);
+	}
+
+	#[test]
+	fn audit_pov_flags_unrealistic_estimates() {
+		let storage_info = vec![info(b"Huge", Some(1 << 20))];
+		let audit = audit(batch(vec![b"Huge".to_vec()]), &storage_info, &Default::default());
+
+		assert!(audit.estimated >= 1 << 20);
+		assert_eq!(audit.issues.len(), 1);
+		assert!(audit.issues[0].contains("check the `MaxEncodedLen` bounds"), "{:?}", audit.issues);
+	}
+}
@@ -62,7 +62,7 @@ struct TemplateData {
 
 // This was the final data we have about each benchmark.
 #[derive(Serialize, Default, Debug, Clone, PartialEq)]
-struct BenchmarkData {
+pub(crate) struct BenchmarkData {
 	name: String,
 	components: Vec<Component>,
 	#[serde(serialize_with = "string_serialize")]
@@ -186,8 +186,25 @@ fn extract_errors(errors: &Option<Vec<u128>>) -> impl Iterator<Item = u128> + '_
 		.chain(std::iter::repeat(0))
 }
 
+impl BenchmarkData {
+	/// The calculated proof size with every component at the maximum of its range.
+	pub(crate) fn worst_case_calculated_proof_size(&self) -> u128 {
+		self.component_calculated_proof_size.iter().fold(
+			self.base_calculated_proof_size,
+			|size, component| {
+				let max = self
+					.component_ranges
+					.iter()
+					.find(|range| range.name == component.name)
+					.map_or(0, |range| range.max);
+				size.saturating_add(component.slope.saturating_mul(max.into()))
+			},
+		)
+	}
+}
+
 // Analyze and return the relevant results for a given benchmark.
-fn get_benchmark_data(
+pub(crate) fn get_benchmark_data(
 	batch: &BenchmarkBatchSplitResults,
 	storage_info: &[StorageInfo],
 	// Per extrinsic component ranges.
@@ -23,16 +23,21 @@ use sp_blockchain::HeaderBackend;
 use sp_database::{ColumnId, Database};
 use sp_runtime::traits::{Block as BlockT, HashingFor};
 use sp_state_machine::Storage;
-use sp_storage::{ChildInfo, ChildType, PrefixedStorageKey, StateVersion};
+use sp_storage::{
+	well_known_keys, ChildInfo, ChildType, PrefixedStorageKey, StateVersion, StorageKey,
+};
 
 use clap::{Args, Parser};
-use log::info;
+use log::{info, warn};
 use rand::prelude::*;
 use serde::Serialize;
 use sp_runtime::generic::BlockId;
-use std::{fmt::Debug, path::PathBuf, sync::Arc};
+use std::{collections::BTreeMap, fmt::Debug, path::PathBuf, sync::Arc};
 
-use super::template::TemplateData;
+use super::{
+	prefix::{prefix_names, print_prefix_stats},
+	template::TemplateData,
+};
 use crate::shared::{new_rng, HostInfoParams, WeightParams};
 
 /// Benchmark the storage speed of a chain snapshot.
@@ -116,6 +121,20 @@ pub struct StorageParams {
 	/// Include child trees in benchmark.
 	#[arg(long)]
 	pub include_child_trees: bool,
+
+	/// Group the `read` results by storage prefix.
+	///
+	/// Reports the trie depth and the time of a cold and a warm read of the keys of every storage
+	/// item. The warm read immediately repeats the cold one. Child trees are not grouped.
+	///
+	/// The read benchmark skips the `--warmups` in this mode, so that the first read of every key
+	/// misses the trie cache. The cache of the operating system is not cleared.
+	#[arg(long, conflicts_with = "skip_read")]
+	pub per_prefix: bool,
+
+	/// Add a read weight constant for every storage prefix to the weight file.
+	#[arg(long, requires = "per_prefix")]
+	pub prefix_weights: bool,
 }
 
 impl StorageCmd {
@@ -139,14 +158,28 @@ impl StorageCmd {
 		template.set_block_number(block_id.to_string());
 
 		if !self.params.skip_read {
-			self.bench_warmup(&client)?;
-			let record = self.bench_read(client.clone())?;
+			// Warming up would turn the cold reads of `--per-prefix` into warm ones.
+			if self.params.per_prefix {
+				info!("Skipping warmups of the read benchmark to measure cold reads");
+			} else {
+				self.bench_warmup(&client)?;
+			}
+			let (record, prefixes) = self.bench_read(client.clone(), storage.clone())?;
 			if let Some(path) = &self.params.json_read_path {
 				record.save_json(&cfg, path, "read")?;
 			}
 			let stats = record.calculate_stats()?;
 			info!("Time summary [ns]:\n{:?}\nValue size summary:\n{:?}", stats.0, stats.1);
 			template.set_stats(Some(stats), None)?;
+
+			if let Some(prefixes) = prefixes {
+				let names = self.prefix_names(&client)?;
+				let stats = prefixes.calculate_stats(&names, &self.params.weight_params)?;
+				print_prefix_stats(&stats);
+				if self.params.prefix_weights {
+					template.set_prefix_stats(stats);
+				}
+			}
 		}
 
 		if !self.params.skip_write {
@@ -182,6 +215,26 @@ impl StorageCmd {
 		None
 	}
 
+	/// Returns the `Pallet::Item` names of the storage prefixes known to the runtime.
+	///
+	/// Falls back to no names if the metadata can not be fetched.
+	fn prefix_names<B, BA, C>(&self, client: &Arc<C>) -> Result<BTreeMap<Vec<u8>, String>>
+	where
+		C: UsageProvider<B> + StorageProvider<B, BA>,
+		B: BlockT,
+		BA: ClientBackend<B>,
+	{
+		let hash = client.usage_info().chain.best_hash;
+		let code = client
+			.storage(hash, &StorageKey(well_known_keys::CODE.to_vec()))?
+			.ok_or("Runtime code not found in state")?;
+
+		Ok(prefix_names(&code.0).unwrap_or_else(|e| {
+			warn!("Could not fetch the metadata, prefixes will not be named: {}", e);
+			Default::default()
+		}))
+	}
+
 	/// Run some rounds of the (read) benchmark as warmup.
 	/// See `frame_benchmarking_cli::storage::read::bench_read` for detailed comments.
 	fn bench_warmup<B, BA, C>(&self, client: &Arc<C>) -> Result<()>
@@ -16,6 +16,7 @@
 // limitations under the License.
 
 pub mod cmd;
+mod prefix;
 pub mod read;
 pub mod template;
 pub mod write;
@@ -0,0 +1,255 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Groups the results of the `read` benchmark by storage prefix.
+
+use sc_cli::Result;
+use sc_executor::WasmExecutor;
+
+use comfy_table::Table;
+use inflector::Inflector;
+use log::info;
+use serde::Serialize;
+use std::{borrow::Cow, collections::BTreeMap, time::Duration};
+
+use crate::{
+	overhead::{
+		command::ParachainHostFunctions, runtime_utilities::fetch_latest_metadata_from_code_blob,
+	},
+	shared::{BenchRecord, Stats, WeightParams},
+};
+
+/// Length of the prefix of a storage item: `twox128(pallet) ++ twox128(item)`.
+const PREFIX_LEN: usize = 32;
+
+/// Well-known keys that are used as prefix of child trie roots.
+const CHILD_STORAGE_PREFIX: &[u8] = b":child_storage:";
+
+/// Returns the storage prefix that a key belongs to.
+///
+/// Well-known keys are their own prefix, except for the roots of child tries which are grouped
+/// together.
+pub(crate) fn key_prefix(key: &[u8]) -> &[u8] {
+	if key.starts_with(CHILD_STORAGE_PREFIX) {
+		CHILD_STORAGE_PREFIX
+	} else if key.starts_with(b":") {
+		key
+	} else {
+		&key[..key.len().min(PREFIX_LEN)]
+	}
+}
+
+/// Resolves the `Pallet::Item` names of all storage prefixes from the metadata of `code`.
+pub(crate) fn prefix_names(code: &[u8]) -> Result<BTreeMap<Vec<u8>, String>> {
+	let executor = WasmExecutor::<ParachainHostFunctions>::builder()
+		.with_allow_missing_host_functions(true)
+		.build();
+	let metadata = fetch_latest_metadata_from_code_blob(&executor, Cow::Borrowed(code))?;
+
+	let mut names = BTreeMap::new();
+	for pallet in metadata.pallets() {
+		let Some(storage) = pallet.storage() else { continue };
+		let pallet_hash = sp_crypto_hashing::twox_128(storage.prefix().as_bytes());
+		for entry in storage.entries() {
+			let item_hash = sp_crypto_hashing::twox_128(entry.name().as_bytes());
+			let prefix = [pallet_hash, item_hash].concat();
+			names.insert(prefix, format!("{}::{}", pallet.name(), entry.name()));
+		}
+	}
+	Ok(names)
+}
+
+/// Raw measurements of the keys of one storage prefix.
+#[derive(Debug, Default, Clone)]
+struct PrefixRecord {
+	/// Number of trie nodes that need to be read to access each key.
+	depths: Vec<u64>,
+	/// First read of each key.
+	cold: BenchRecord,
+	/// Immediate second read of each key.
+	warm: BenchRecord,
+}
+
+/// Raw output of the `read` benchmark, grouped by storage prefix.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct PrefixRecords(BTreeMap<Vec<u8>, PrefixRecord>);
+
+/// Statistics about the keys of one storage prefix.
+#[derive(Serialize, Default, Debug, Clone)]
+pub(crate) struct PrefixStats {
+	/// `Pallet::Item` or the hex encoded prefix if the runtime does not know it.
+	pub name: String,
+	/// Name of the weight constant of this prefix.
+	pub const_name: String,
+	/// Number of keys with this prefix.
+	pub keys: usize,
+	/// Number of trie nodes that need to be read to access a key.
+	pub depth: Stats,
+	/// Time of a cold read in nanoseconds.
+	pub cold: Stats,
+	/// Time of a warm read in nanoseconds.
+	pub warm: Stats,
+	/// Size of the values in bytes.
+	pub size: Stats,
+	/// The weight of a cold read.
+	pub read_weight: u64,
+	/// The weight of a warm read.
+	pub warm_read_weight: u64,
+}
+
+impl PrefixRecords {
+	/// Appends the measurements of a single key.
+	pub fn append(
+		&mut self,
+		key: &[u8],
+		depth: usize,
+		size: usize,
+		cold: Duration,
+		warm: Duration,
+	) -> Result<()> {
+		let record = self.0.entry(key_prefix(key).to_vec()).or_default();
+		record.depths.push(depth as u64);
+		record.cold.append(size, cold)?;
+		record.warm.append(size, warm)
+	}
+
+	/// Returns the statistics of every prefix, sorted by name.
+	///
+	/// Prefixes that are missing from `names` are named by their hex encoding.
+	pub fn calculate_stats(
+		self,
+		names: &BTreeMap<Vec<u8>, String>,
+		weight_params: &WeightParams,
+	) -> Result<Vec<PrefixStats>> {
+		let mut stats = self
+			.0
+			.into_iter()
+			.map(|(prefix, record)| {
+				let (name, const_name) = match names.get(&prefix) {
+					Some(name) => (name.clone(), name.replace("::", "_").to_screaming_snake_case()),
+					None if prefix.starts_with(b":") => (
+						String::from_utf8_lossy(&prefix).into_owned(),
+						format!("WELL_KNOWN_{}", String::from_utf8_lossy(&prefix))
+							.to_screaming_snake_case(),
+					),
+					None => (
+						array_bytes::bytes2hex("0x", &prefix),
+						format!("PREFIX_{}", array_bytes::bytes2hex("", &prefix).to_uppercase()),
+					),
+				};
+				let keys = record.depths.len();
+				let depth = Stats::new(&record.depths)?;
+				let (cold, size) = record.cold.calculate_stats()?;
+				let (warm, _) = record.warm.calculate_stats()?;
+
+				Ok(PrefixStats {
+					name,
+					const_name,
+					keys,
+					depth,
+					read_weight: weight_params.calc_weight(&cold)?,
+					warm_read_weight: weight_params.calc_weight(&warm)?,
+					cold,
+					warm,
+					size,
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		stats.sort_by(|a, b| a.name.cmp(&b.name));
+		Ok(stats)
+	}
+}
+
+/// Prints a table with the statistics of every prefix.
+pub(crate) fn print_prefix_stats(stats: &[PrefixStats]) {
+	let mut table = Table::new();
+	table.set_header([
+		"Prefix",
+		"Keys",
+		"Depth avg",
+		"Depth max",
+		"Cold avg [ns]",
+		"Cold p99 [ns]",
+		"Warm avg [ns]",
+		"Size avg [B]",
+	]);
+	for s in stats {
+		table.add_row([
+			s.name.clone(),
+			s.keys.to_string(),
+			s.depth.avg.to_string(),
+			s.depth.max.to_string(),
+			s.cold.avg.to_string(),
+			s.cold.p99.to_string(),
+			s.warm.avg.to_string(),
+			s.size.avg.to_string(),
+		]);
+	}
+	info!("Read summary per storage prefix:\n{table}");
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn key_prefix_works() {
+		let key = [[1u8; 32].as_slice(), &[2u8; 40]].concat();
+		assert_eq!(key_prefix(&key), &[1u8; 32]);
+		assert_eq!(key_prefix(&[1u8; 16]), &[1u8; 16]);
+		assert_eq!(key_prefix(b":code"), b":code");
+		assert_eq!(key_prefix(b":child_storage:default:a"), CHILD_STORAGE_PREFIX);
+	}
+
+	#[test]
+	fn calculate_stats_groups_by_prefix() {
+		let mut records = PrefixRecords::default();
+		let account = |i: u8| [[1u8; 32].as_slice(), &[i; 48]].concat();
+		for (i, depth) in [(1, 4), (2, 6)] {
+			let cold = Duration::from_nanos(1000 * i as u64);
+			records.append(&account(i), depth, 80, cold, Duration::from_nanos(100)).unwrap();
+		}
+		records
+			.append(b":code", 2, 1000, Duration::from_nanos(500), Duration::from_nanos(50))
+			.unwrap();
+		records
+			.append(&[2u8; 32], 3, 4, Duration::from_nanos(700), Duration::from_nanos(70))
+			.unwrap();
+
+		let names = BTreeMap::from([(vec![1u8; 32], "System::Account".to_string())]);
+		let stats = records
+			.calculate_stats(&names, &WeightParams { weight_mul: 1.0, ..Default::default() })
+			.unwrap();
+
+		let summary = stats
+			.iter()
+			.map(|s| (s.name.as_str(), s.const_name.as_str(), s.keys, s.depth.max, s.cold.avg))
+			.collect::<Vec<_>>();
+		let (unknown, unknown_const) =
+			(format!("0x{}", "02".repeat(32)), format!("PREFIX_{}", "02".repeat(32)));
+		assert_eq!(
+			summary,
+			vec![
+				(unknown.as_str(), unknown_const.as_str(), 1, 3, 700),
+				(":code", "WELL_KNOWN_CODE", 1, 2, 500),
+				("System::Account", "SYSTEM_ACCOUNT", 2, 6, 1500),
+			]
+		);
+		assert_eq!(stats[2].warm.avg, 100);
+	}
+}
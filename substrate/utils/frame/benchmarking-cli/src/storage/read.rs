@@ -17,21 +17,32 @@
 
 use sc_cli::Result;
 use sc_client_api::{Backend as ClientBackend, StorageProvider, UsageProvider};
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sc_client_db::DbStateBuilder;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, HashingFor, Header as HeaderT};
+use sp_state_machine::{prove_read_on_trie_backend, Storage};
 
 use log::info;
 use rand::prelude::*;
 use std::{fmt::Debug, sync::Arc, time::Instant};
 
-use super::cmd::StorageCmd;
+use super::{cmd::StorageCmd, prefix::PrefixRecords};
 use crate::shared::{new_rng, BenchRecord};
 
 impl StorageCmd {
 	/// Benchmarks the time it takes to read a single Storage item.
 	/// Uses the latest state that is available for the given client.
-	pub(crate) fn bench_read<B, BA, C>(&self, client: Arc<C>) -> Result<BenchRecord>
+	///
+	/// With `--per-prefix` every key is read a second time right after the first read, and the
+	/// number of trie nodes that need to be read to access it is recorded. These results are
+	/// returned grouped by storage prefix.
+	pub(crate) fn bench_read<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		storage: Arc<dyn Storage<HashingFor<B>>>,
+	) -> Result<(BenchRecord, Option<PrefixRecords>)>
 	where
-		C: UsageProvider<B> + StorageProvider<B, BA>,
+		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B>,
 		B: BlockT + Debug,
 		BA: ClientBackend<B>,
 		<<B as BlockT>::Header as HeaderT>::Number: From<u32>,
@@ -39,6 +50,15 @@ impl StorageCmd {
 		let mut record = BenchRecord::default();
 		let best_hash = client.usage_info().chain.best_hash;
 
+		// The trie is only used to find out how deep each key is.
+		let mut per_prefix = if self.params.per_prefix {
+			let header = client.header(best_hash)?.ok_or("Header not found")?;
+			let trie = DbStateBuilder::<HashingFor<B>>::new(storage, *header.state_root()).build();
+			Some((trie, PrefixRecords::default()))
+		} else {
+			None
+		};
+
 		info!("Preparing keys from block {}", best_hash);
 		// Load all keys and randomly shuffle them.
 		let mut keys: Vec<_> = client.storage_keys(best_hash, None, None)?.collect();
@@ -64,7 +84,20 @@ impl StorageCmd {
 						.storage(best_hash, &key)
 						.expect("Checked above to exist")
 						.ok_or("Value unexpectedly empty")?;
-					record.append(v.0.len(), start.elapsed())?;
+					let cold = start.elapsed();
+					record.append(v.0.len(), cold)?;
+
+					if let Some((trie, prefixes)) = per_prefix.as_mut() {
+						// Read it again while all caches are hot.
+						let start = Instant::now();
+						let _ = client.storage(best_hash, &key).expect("Checked above to exist");
+						let warm = start.elapsed();
+
+						let depth = prove_read_on_trie_backend(trie, [&key.0])
+							.map_err(|e| format!("Proving read of key: {e}"))?
+							.len();
+						prefixes.append(&key.0, depth, v.0.len(), cold, warm)?;
+					}
 				},
 			}
 		}
@@ -82,6 +115,6 @@ impl StorageCmd {
 				record.append(v.0.len(), start.elapsed())?;
 			}
 		}
-		Ok(record)
+		Ok((record, per_prefix.map(|(_, prefixes)| prefixes)))
 	}
 }
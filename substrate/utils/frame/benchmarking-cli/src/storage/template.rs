@@ -22,7 +22,7 @@ use log::info;
 use serde::Serialize;
 use std::{env, fs, path::PathBuf};
 
-use super::cmd::StorageParams;
+use super::{cmd::StorageParams, prefix::PrefixStats};
 use crate::shared::{Stats, UnderscoreHelper};
 
 static VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -61,6 +61,8 @@ pub(crate) struct TemplateData {
 	/// Stats about a `write` benchmark. Contains *time* and *value size* stats.
 	/// The *value size* stats are currently not used in the template.
 	write: Option<(Stats, Stats)>,
+	/// Stats about the `read` benchmark per storage prefix. Only set with `--prefix-weights`.
+	prefixes: Vec<PrefixStats>,
 }
 
 impl TemplateData {
@@ -104,6 +106,11 @@ impl TemplateData {
 		Ok(())
 	}
 
+	/// Sets the stats per storage prefix, which adds a read weight for each of them.
+	pub(crate) fn set_prefix_stats(&mut self, prefixes: Vec<PrefixStats>) {
+		self.prefixes = prefixes;
+	}
+
 	/// Sets the block id that was used.
 	pub fn set_block_number(&mut self, block_number: String) {
 		self.block_number = block_number
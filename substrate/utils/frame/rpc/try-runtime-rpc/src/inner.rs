@@ -0,0 +1,267 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of the try-runtime RPC, see the crate documentation.
+
+use codec::{Decode, Encode};
+use frame_try_runtime::{TryRuntime, UpgradeCheckSelect};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorCode, ErrorObject, ErrorObjectOwned},
+	Extensions,
+};
+use sc_client_api::{backend::Backend, HeaderBackend};
+use sc_executor_common::runtime_blob::RuntimeBlob;
+use sc_rpc_api::check_if_safe;
+use serde::{Deserialize, Serialize};
+use sp_api::RuntimeApiInfo;
+use sp_core::{
+	storage::well_known_keys,
+	traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+	Bytes,
+};
+use sp_runtime::traits::{Block as BlockT, HashingFor};
+use sp_state_machine::{
+	backend::AsTrieBackend, OverlayedChanges, StateMachine, TrieBackendBuilder,
+};
+use sp_trie::recorder::Recorder;
+use sp_weights::Weight;
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "rpc::try-runtime";
+
+/// Name of the runtime method dry-running the runtime upgrade.
+const ON_RUNTIME_UPGRADE: &str = "TryRuntime_on_runtime_upgrade";
+
+/// Summary of the storage changes made by the runtime upgrade.
+///
+/// The override of the runtime code itself is not accounted.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct StorageChangesSummary {
+	/// Number of top keys set.
+	pub top_set: u32,
+	/// Number of top keys removed.
+	pub top_removed: u32,
+	/// Number of child tries changed.
+	pub child_tries: u32,
+	/// Number of child keys set or removed.
+	pub child_changes: u32,
+	/// Total size of the written values, in bytes.
+	pub bytes_written: u64,
+}
+
+/// Outcome of the runtime upgrade dry-run.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeUpgradeDryRun {
+	/// Spec version of the candidate runtime.
+	pub spec_version: u32,
+	/// Weight consumed by the runtime upgrade, `None` if the upgrade failed.
+	pub weight: Option<Weight>,
+	/// Maximum weight of the block, as declared by the candidate runtime.
+	pub max_block_weight: Option<Weight>,
+	/// Storage changes made by the runtime upgrade.
+	pub storage_changes: StorageChangesSummary,
+	/// Estimated size of the storage proof of the runtime upgrade, in bytes.
+	pub pov_size: u64,
+	/// Reason of the failure, e.g. a failed `pre_upgrade`, `post_upgrade` or `try_state` check.
+	pub failure: Option<String>,
+}
+
+/// Dry-run the runtime upgrade to `code` on top of the given `state`.
+///
+/// Failures of the runtime upgrade itself are reported in [`RuntimeUpgradeDryRun::failure`],
+/// while the returned error means that the dry-run could not be performed at all.
+pub fn dry_run_runtime_upgrade<Block, B, E>(
+	state: &B::State,
+	executor: &E,
+	code: &[u8],
+	checks: UpgradeCheckSelect,
+) -> Result<RuntimeUpgradeDryRun, String>
+where
+	Block: BlockT,
+	B: Backend<Block>,
+	E: CodeExecutor + Clone + 'static,
+{
+	let blob = RuntimeBlob::uncompress_if_needed(code)
+		.map_err(|e| format!("Invalid runtime code: {e}"))?;
+	let version = sc_executor::read_embedded_version(&blob)
+		.map_err(|e| format!("Failed to read the runtime version: {e}"))?
+		.ok_or("The runtime code does not embed its version")?;
+	if !version.has_api_with(&<dyn TryRuntime<Block>>::ID, |_| true) {
+		return Err("The runtime does not implement the `TryRuntime` API, make sure it is built \
+			with the `try-runtime` feature"
+			.into())
+	}
+
+	let recorder = Recorder::<HashingFor<Block>>::default();
+	let trie_backend = state.as_trie_backend();
+	let backend = TrieBackendBuilder::wrap(trie_backend).with_recorder(recorder.clone()).build();
+
+	let mut overlay = OverlayedChanges::default();
+	overlay.set_storage(well_known_keys::CODE.to_vec(), Some(code.to_vec()));
+
+	let runtime_code = RuntimeCode {
+		code_fetcher: &WrappedRuntimeCode(code.into()),
+		heap_pages: None,
+		hash: sp_core::blake2_256(code).to_vec(),
+	};
+
+	log::info!(
+		target: LOG_TARGET,
+		"Dry-running runtime upgrade to spec version {} with {checks:?} checks",
+		version.spec_version,
+	);
+	let result = StateMachine::new(
+		&backend,
+		&mut overlay,
+		executor,
+		ON_RUNTIME_UPGRADE,
+		&checks.encode(),
+		&mut Default::default(),
+		&runtime_code,
+		CallContext::Offchain,
+	)
+	.execute();
+
+	let (weight, max_block_weight, failure) = match result {
+		Ok(output) => {
+			let (weight, max_block_weight) = <(Weight, Weight)>::decode(&mut &output[..])
+				.map_err(|e| format!("Failed to decode the runtime upgrade result: {e}"))?;
+			(Some(weight), Some(max_block_weight), None)
+		},
+		Err(e) => {
+			log::debug!(target: LOG_TARGET, "Runtime upgrade dry-run failed: {e}");
+			(None, None, Some(e.to_string()))
+		},
+	};
+
+	// Draining computes the new storage root, which accounts the touched trie nodes in the proof.
+	let changes = overlay
+		.drain_storage_changes(&backend, version.state_version())
+		.map_err(|e| format!("Failed to collect the storage changes: {e}"))?;
+
+	let mut storage_changes = StorageChangesSummary::default();
+	for (key, value) in &changes.main_storage_changes {
+		if key == well_known_keys::CODE {
+			continue
+		}
+		match value {
+			Some(value) => {
+				storage_changes.top_set += 1;
+				storage_changes.bytes_written += value.len() as u64;
+			},
+			None => storage_changes.top_removed += 1,
+		}
+	}
+	for (_, child_changes) in &changes.child_storage_changes {
+		storage_changes.child_tries += 1;
+		storage_changes.child_changes += child_changes.len() as u32;
+		storage_changes.bytes_written += child_changes
+			.iter()
+			.filter_map(|(_, v)| v.as_ref())
+			.map(|v| v.len() as u64)
+			.sum::<u64>();
+	}
+
+	Ok(RuntimeUpgradeDryRun {
+		spec_version: version.spec_version,
+		weight,
+		max_block_weight,
+		storage_changes,
+		pov_size: recorder.estimate_encoded_size() as u64,
+		failure,
+	})
+}
+
+/// Try-runtime RPC methods.
+#[rpc(server)]
+pub trait TryRuntimeApi<BlockHash> {
+	/// Dry-run the upgrade to the given runtime `code` on top of the state of the block `at`, the
+	/// best block by default.
+	///
+	/// `checks` selects the `try-runtime` checks to run, one of `none`, `all`, `pre-and-post` or
+	/// `try-state`, `all` by default.
+	///
+	/// This call is performed locally without submitting any transactions. Thus executing this
+	/// won't change any state. Nonetheless it is a VERY costly call that should be
+	/// only exposed to trusted peers.
+	#[method(name = "tryRuntime_dryRunUpgrade", with_extensions, blocking)]
+	fn dry_run_upgrade(
+		&self,
+		code: Bytes,
+		checks: Option<String>,
+		at: Option<BlockHash>,
+	) -> RpcResult<RuntimeUpgradeDryRun>;
+}
+
+/// An implementation of try-runtime specific RPC methods.
+pub struct TryRuntimeRpc<C, B, BA, E> {
+	client: Arc<C>,
+	backend: Arc<BA>,
+	executor: E,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B, BA, E> TryRuntimeRpc<C, B, BA, E> {
+	/// Create new try-runtime rpc for the given reference to the client.
+	///
+	/// The `executor` must provide the host functions required by the candidate runtimes.
+	pub fn new(client: Arc<C>, backend: Arc<BA>, executor: E) -> Self {
+		TryRuntimeRpc { client, backend, executor, _marker: Default::default() }
+	}
+}
+
+impl<C, B, BA, E> TryRuntimeApiServer<<B as BlockT>::Hash> for TryRuntimeRpc<C, B, BA, E>
+where
+	B: BlockT,
+	C: Send + Sync + 'static + HeaderBackend<B>,
+	BA: 'static + Backend<B>,
+	E: CodeExecutor + Clone + Send + Sync + 'static,
+{
+	fn dry_run_upgrade(
+		&self,
+		ext: &Extensions,
+		code: Bytes,
+		checks: Option<String>,
+		at: Option<<B as BlockT>::Hash>,
+	) -> RpcResult<RuntimeUpgradeDryRun> {
+		check_if_safe(ext)?;
+
+		let checks = checks
+			.map(|checks| checks.parse())
+			.transpose()
+			.map_err(|e: &str| ErrorObject::owned(ErrorCode::InvalidParams.code(), e, None::<()>))?
+			.unwrap_or(UpgradeCheckSelect::All);
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let state = self.backend.state_at(hash).map_err(error_into_rpc_err)?;
+		dry_run_runtime_upgrade::<B, BA, E>(&state, &self.executor, &code, checks)
+			.map_err(error_into_rpc_err)
+	}
+}
+
+fn error_into_rpc_err(err: impl std::fmt::Display) -> ErrorObjectOwned {
+	ErrorObject::owned(
+		ErrorCode::InternalError.code(),
+		"Error while dry-running the runtime upgrade",
+		Some(err.to_string()),
+	)
+}
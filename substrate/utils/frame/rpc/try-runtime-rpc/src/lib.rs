@@ -0,0 +1,35 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rpc for dry-running runtime upgrades.
+//!
+//! The candidate runtime is executed on top of the node's own state, using the `TryRuntime`
+//! runtime API of [`frame_try_runtime`]. Hence the candidate runtime must be built with the
+//! `try-runtime` feature enabled. All the changes are kept in an overlay and discarded
+//! afterwards.
+//!
+//! Everything is behind the `try-runtime` feature of this crate, as it enables the `TryRuntime`
+//! API in [`frame_try_runtime`], which must not leak into production runtimes through feature
+//! unification.
+
+#[cfg(feature = "try-runtime")]
+mod inner;
+#[cfg(feature = "try-runtime")]
+pub use inner::*;
+
+#[cfg(all(test, feature = "try-runtime"))]
+mod tests;
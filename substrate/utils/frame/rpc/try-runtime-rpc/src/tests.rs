@@ -0,0 +1,92 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use assert_matches::assert_matches;
+use jsonrpsee::{rpc_params, types::error::ErrorCode, MethodsError, RpcModule};
+use sc_rpc_api::DenyUnsafe;
+use sp_core::{Bytes, H256};
+use std::sync::Arc;
+use substrate_test_runtime_client::{prelude::*, runtime::Block};
+
+fn rpc(deny_unsafe: DenyUnsafe) -> RpcModule<impl Sized> {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+	let executor = sc_executor::WasmExecutor::<sp_io::SubstrateHostFunctions>::builder().build();
+
+	let mut rpc = TryRuntimeRpc::<_, Block, _, _>::new(client, backend, executor).into_rpc();
+	rpc.extensions_mut().insert(deny_unsafe);
+	rpc
+}
+
+fn test_runtime_code() -> Bytes {
+	Bytes(substrate_test_runtime_client::runtime::wasm_binary_unwrap().to_vec())
+}
+
+#[tokio::test]
+async fn dry_run_upgrade_is_unsafe() {
+	let rpc = rpc(DenyUnsafe::Yes);
+
+	let err = rpc
+		.call::<_, RuntimeUpgradeDryRun>(
+			"tryRuntime_dryRunUpgrade",
+			rpc_params![test_runtime_code(), None::<String>, None::<H256>],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(
+		err,
+		MethodsError::JsonRpc(err) if err.message() == "RPC call is unsafe to be called externally"
+	);
+}
+
+#[tokio::test]
+async fn dry_run_upgrade_rejects_invalid_checks() {
+	let rpc = rpc(DenyUnsafe::No);
+
+	let err = rpc
+		.call::<_, RuntimeUpgradeDryRun>(
+			"tryRuntime_dryRunUpgrade",
+			rpc_params![test_runtime_code(), Some("everything"), None::<H256>],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(
+		err,
+		MethodsError::JsonRpc(err) if err.code() == ErrorCode::InvalidParams.code()
+	);
+}
+
+#[tokio::test]
+async fn dry_run_upgrade_requires_try_runtime_api() {
+	let rpc = rpc(DenyUnsafe::No);
+
+	// The test runtime does not implement the `TryRuntime` API.
+	let err = rpc
+		.call::<_, RuntimeUpgradeDryRun>(
+			"tryRuntime_dryRunUpgrade",
+			rpc_params![test_runtime_code(), Some("none"), None::<H256>],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(
+		err,
+		MethodsError::JsonRpc(err) if err.code() == ErrorCode::InternalError.code() &&
+			err.data().map_or(false, |data| data.get().contains("TryRuntime"))
+	);
+}
@@ -23,8 +23,9 @@
 #![warn(missing_docs)]
 
 use jsonrpsee::RpcModule;
-use minimal_template_runtime::interface::{AccountId, Nonce, OpaqueBlock};
+use minimal_template_runtime::interface::{AccountId, Hash, Nonce, OpaqueBlock};
 use polkadot_sdk::{
+	sc_consensus_manual_seal::EngineCommand,
 	sc_transaction_pool_api::TransactionPool,
 	sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata},
 	*,
@@ -37,6 +38,8 @@ pub struct FullDeps<C, P> {
 	pub client: Arc<C>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
+	/// Channel to the manual seal authorship task, if the node uses manual seal.
+	pub manual_seal: Option<futures::channel::mpsc::Sender<EngineCommand<Hash>>>,
 }
 
 #[docify::export]
@@ -56,11 +59,17 @@ where
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<OpaqueBlock, AccountId, Nonce>,
 	P: TransactionPool + 'static,
 {
-	use polkadot_sdk::substrate_frame_rpc_system::{System, SystemApiServer};
+	use polkadot_sdk::{
+		sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer},
+		substrate_frame_rpc_system::{System, SystemApiServer},
+	};
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool } = deps;
+	let FullDeps { client, pool, manual_seal } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
+	if let Some(manual_seal) = manual_seal {
+		module.merge(ManualSeal::new(manual_seal).into_rpc())?;
+	}
 
 	Ok(module)
 }
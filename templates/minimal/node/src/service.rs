@@ -169,12 +169,22 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 		);
 	}
 
+	// Commands of the manual seal authorship task, sent by the block timer and the `engine_*` RPCs.
+	let (manual_seal_sink, manual_seal_stream) = futures::channel::mpsc::channel(1024);
+	let time_control = sc_consensus_manual_seal::TimeControl::new();
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let manual_seal =
+			matches!(consensus, Consensus::ManualSeal(_)).then(|| manual_seal_sink.clone());
 
 		Box::new(move |_| {
-			let deps = crate::rpc::FullDeps { client: client.clone(), pool: pool.clone() };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				manual_seal: manual_seal.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -227,7 +237,7 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 			);
 		},
 		Consensus::ManualSeal(block_time) => {
-			let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+			let mut sink = manual_seal_sink;
 			task_manager.spawn_handle().spawn("block_authoring", None, async move {
 				loop {
 					futures_timer::Delay::new(std::time::Duration::from_millis(block_time)).await;
@@ -247,13 +257,20 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 				client,
 				pool: transaction_pool,
 				select_chain,
-				commands_stream: Box::pin(commands_stream),
+				commands_stream: Box::pin(manual_seal_stream),
 				consensus_data_provider: None,
-				create_inherent_data_providers: move |_, ()| async move {
-					Ok(sp_timestamp::InherentDataProvider::from_system_time())
+				create_inherent_data_providers: {
+					let time_control = time_control.clone();
+					move |_, ()| {
+						let timestamp = time_control.inherent_data_provider();
+						async move { Ok(timestamp) }
+					}
 				},
 			};
-			let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
+			let controls =
+				sc_consensus_manual_seal::ManualSealControls::new().with_time_control(time_control);
+			let authorship_future =
+				sc_consensus_manual_seal::run_manual_seal_with_controls(params, controls);
 
 			task_manager.spawn_essential_handle().spawn_blocking(
 				"manual-seal",
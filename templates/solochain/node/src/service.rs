@@ -210,6 +210,12 @@ pub fn new_full<
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
+	let slashing_protection = config
+		.slashing_protection
+		.as_ref()
+		.map(|path| sc_consensus::SlashingProtection::open(path, prometheus_registry.as_ref()))
+		.transpose()
+		.map_err(|e| ServiceError::Other(format!("Slashing protection error: {e}")))?;
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
@@ -274,6 +280,7 @@ pub fn new_full<
 				max_block_proposal_slot_portion: None,
 				telemetry: telemetry.as_ref().map(|x| x.handle()),
 				compatibility_mode: Default::default(),
+				slashing_protection: slashing_protection.clone(),
 			},
 		)?;
 
@@ -299,6 +306,7 @@ pub fn new_full<
 			local_role: role,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			protocol_name: grandpa_protocol_name,
+			slashing_protection,
 		};
 
 		// start the full GRANDPA voter
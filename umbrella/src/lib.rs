@@ -1519,6 +1519,10 @@ pub use substrate_rpc_client;
 #[cfg(feature = "substrate-state-trie-migration-rpc")]
 pub use substrate_state_trie_migration_rpc;
 
+/// Node-specific RPC methods for dry-running runtime upgrades against the node's own state.
+#[cfg(feature = "substrate-try-runtime-rpc")]
+pub use substrate_try_runtime_rpc;
+
 /// Utility for building WASM binaries.
 #[cfg(feature = "substrate-wasm-builder")]
 pub use substrate_wasm_builder;